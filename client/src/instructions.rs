@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+
+//Builds any lending protocol instruction from its typed accounts (lending_accounts::WithdrawTokens, lending_accounts::BorrowTokens, ...)
+//and typed arguments (lending_instruction::WithdrawTokens, lending_instruction::BorrowTokens, ...).
+//Optional accounts left as None are filled in with the program ID the way Anchor expects, and the remaining accounts go on the end in order
+pub fn build_instruction<A: ToAccountMetas, D: InstructionData>(accounts: A, arguments: D, remaining_accounts: Vec<AccountMeta>) -> Instruction
+{
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(remaining_accounts);
+
+    Instruction
+    {
+        program_id: lending_protocol::ID,
+        accounts: account_metas,
+        data: arguments.data()
+    }
+}
@@ -0,0 +1,16 @@
+//Rust client for the lending protocol, so bots and backends don't have to hand roll seeds and account lists.
+//Every instruction's typed accounts and arguments come from the program's own Anchor generated accounts and instruction modules,
+//which are re-exported here, and build_instruction turns any pair of them into an Instruction
+pub mod instructions;
+pub mod merkle;
+pub mod pda;
+pub mod remaining_accounts;
+
+pub use instructions::*;
+pub use merkle::*;
+pub use pda::*;
+pub use remaining_accounts::*;
+
+pub use lending_protocol::accounts as lending_accounts;
+pub use lending_protocol::instruction as lending_instruction;
+pub use lending_protocol::ID as LENDING_PROTOCOL_PROGRAM_ID;
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use lending_protocol::lending_helpers::{get_merkle_leaf_hash, get_merkle_node_hash};
+
+//Builds the Merkle tree for a Merkle Distribution the same way claim_merkle_distribution checks it.
+//A node without a sibling is carried up to the next level as is, so it just has one less hash in its proofs
+pub struct MerkleDistributionTree
+{
+    levels: Vec<Vec<[u8; 32]>> //Leaves first, the root's level last
+}
+
+impl MerkleDistributionTree
+{
+    //Allocations are (claimant wallet, amount). Each wallet can only be in a distribution once since it can only claim once
+    pub fn new(allocations: &[(Pubkey, u64)]) -> Self
+    {
+        let mut levels = vec![allocations.iter().map(|(claimant_address, amount)| get_merkle_leaf_hash(claimant_address, *amount)).collect::<Vec<_>>()];
+
+        while levels[levels.len() - 1].len() > 1
+        {
+            let next_level = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { get_merkle_node_hash(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+            levels.push(next_level);
+        }
+
+        MerkleDistributionTree { levels }
+    }
+
+    //The root to publish with create_merkle_distribution. An empty distribution has an all zero root that no proof can match
+    pub fn get_merkle_root(&self) -> [u8; 32]
+    {
+        self.levels[self.levels.len() - 1].first().copied().unwrap_or([0; 32])
+    }
+
+    //The proof for the allocation at allocation_index, to pass to claim_merkle_distribution
+    pub fn get_merkle_proof(&self, allocation_index: usize) -> Vec<[u8; 32]>
+    {
+        let mut merkle_proof = Vec::new();
+        let mut node_index = allocation_index;
+
+        for level in &self.levels[..self.levels.len() - 1]
+        {
+            if let Some(sibling_hash) = level.get(node_index ^ 1)
+            {
+                merkle_proof.push(*sibling_hash);
+            }
+
+            node_index /= 2;
+        }
+
+        merkle_proof
+    }
+}
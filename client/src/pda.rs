@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+
+//PDA derivations matching the seeds in the program's account contexts. Each one returns the address and its bump
+
+fn find_lending_protocol_address(seeds: &[&[u8]]) -> (Pubkey, u8)
+{
+    Pubkey::find_program_address(seeds, &lending_protocol::ID)
+}
+
+//The original Token Reserve of a mint is version 0 and doesn't have a version seed
+fn get_token_reserve_version_seed(reserve_version: &u8) -> &[u8]
+{
+    if *reserve_version == 0
+    {
+        &[]
+    }
+    else
+    {
+        std::slice::from_ref(reserve_version)
+    }
+}
+
+pub fn get_lending_protocol_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingProtocol".as_ref()])
+}
+
+pub fn get_lending_protocol_ceo_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingProtocolCEO".as_ref()])
+}
+
+pub fn get_lending_stats_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingStats".as_ref()])
+}
+
+pub fn get_lending_user_stats_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserStats".as_ref()])
+}
+
+pub fn get_token_reserve_stats_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"tokenReserveStats".as_ref()])
+}
+
+pub fn get_sub_market_stats_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"subMarketStats".as_ref()])
+}
+
+pub fn get_solvency_treasurer_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"solvencyTreasurer".as_ref()])
+}
+
+pub fn get_liquidation_treasurer_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"liquidationTreasurer".as_ref()])
+}
+
+pub fn get_oracle_price_validator_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"oraclePriceValidator".as_ref()])
+}
+
+pub fn get_protocol_tvl_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"protocolTvl".as_ref()])
+}
+
+pub fn get_keeper_bounty_vault_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"keeperBountyVault".as_ref()])
+}
+
+pub fn get_staking_pool_pda() -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"stakingPool".as_ref()])
+}
+
+pub fn get_e_mode_category_pda(asset_category: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"eModeCategory".as_ref(), asset_category.to_le_bytes().as_ref()])
+}
+
+//The temporary price account the Oracle posts verified prices to for a signer
+pub fn get_price_account_pda(signer_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"oraclePriceData".as_ref(), signer_address.as_ref()])
+}
+
+pub fn get_temporary_wsol_account_pda(signer_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"temporaryWsolAccount".as_ref(), signer_address.as_ref()])
+}
+
+pub fn get_token_reserve_pda(token_mint_address: &Pubkey, reserve_version: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"tokenReserve".as_ref(), token_mint_address.as_ref(), get_token_reserve_version_seed(&reserve_version)])
+}
+
+pub fn get_token_reserve_listing_pda(token_mint_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"tokenReserveListing".as_ref(), token_mint_address.as_ref()])
+}
+
+pub fn get_token_reserve_audit_snapshot_pda(token_mint_address: &Pubkey, reserve_version: u8, snapshot_index: u32) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"tokenReserveAuditSnapshot".as_ref(),
+        token_mint_address.as_ref(),
+        get_token_reserve_version_seed(&reserve_version),
+        snapshot_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_insurance_fund_pda(token_mint_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"insuranceFund".as_ref(), token_mint_address.as_ref()])
+}
+
+//Deposit Reward Emissions don't have a side seed, borrow Reward Emissions have a side seed of 1
+pub fn get_reward_emissions_pda(token_reserve_address: &Pubkey, borrow_rewards: bool) -> (Pubkey, u8)
+{
+    let side_seed: &[u8] = if borrow_rewards { &[1] } else { &[] };
+    find_lending_protocol_address(&[b"rewardEmissions".as_ref(), token_reserve_address.as_ref(), side_seed])
+}
+
+pub fn get_reward_vesting_schedule_pda(reward_emissions_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"rewardVestingSchedule".as_ref(), reward_emissions_address.as_ref()])
+}
+
+pub fn get_merkle_distribution_pda(distribution_index: u32) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"merkleDistribution".as_ref(), distribution_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_merkle_claim_receipt_pda(merkle_distribution_address: &Pubkey, claimant_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"merkleClaimReceipt".as_ref(), merkle_distribution_address.as_ref(), claimant_address.as_ref()])
+}
+
+pub fn get_sub_market_pda(token_id: u8, sub_market_owner_address: &Pubkey, sub_market_index: u16) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"subMarket".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner_address.as_ref(),
+        sub_market_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_sub_market_reward_emissions_pda(sub_market_address: &Pubkey, reward_mint_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"subMarketRewardEmissions".as_ref(), sub_market_address.as_ref(), reward_mint_address.as_ref()])
+}
+
+pub fn get_sub_market_owner_look_up_table_pda(sub_market_owner_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"subMarketOwnerLookUpTable".as_ref(), sub_market_owner_address.as_ref()])
+}
+
+pub fn get_sub_market_allowlist_entry_pda(sub_market_address: &Pubkey, user_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"subMarketAllowlist".as_ref(), sub_market_address.as_ref(), user_address.as_ref()])
+}
+
+pub fn get_lending_user_account_pda(lending_user_address: &Pubkey, user_account_index: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserAccount".as_ref(), lending_user_address.as_ref(), user_account_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_user_health_pda(lending_user_address: &Pubkey, user_account_index: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"userHealth".as_ref(), lending_user_address.as_ref(), user_account_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_lending_user_stake_pda(lending_user_address: &Pubkey, user_account_index: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserStake".as_ref(), lending_user_address.as_ref(), user_account_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_lending_user_activity_pda(lending_user_address: &Pubkey, user_account_index: u8) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserActivity".as_ref(), lending_user_address.as_ref(), user_account_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_credit_delegation_pda(lending_user_address: &Pubkey, user_account_index: u8, token_mint_address: &Pubkey, delegate_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"creditDelegation".as_ref(),
+        lending_user_address.as_ref(),
+        user_account_index.to_le_bytes().as_ref(),
+        token_mint_address.as_ref(),
+        delegate_address.as_ref()])
+}
+
+pub fn get_lending_user_tab_account_pda(token_id: u8,
+    sub_market_owner_address: &Pubkey,
+    sub_market_index: u16,
+    lending_user_address: &Pubkey,
+    user_account_index: u8
+) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserTabAccount".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner_address.as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_address.as_ref(),
+        user_account_index.to_le_bytes().as_ref()])
+}
+
+pub fn get_lending_user_referral_pda(lending_user_tab_account_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserReferral".as_ref(), lending_user_tab_account_address.as_ref()])
+}
+
+pub fn get_lending_user_reward_account_pda(reward_emissions_address: &Pubkey, lending_user_tab_account_address: &Pubkey) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"lendingUserReward".as_ref(), reward_emissions_address.as_ref(), lending_user_tab_account_address.as_ref()])
+}
+
+//Monthly Statements are seeded with the Lending Protocol's current statement month and year
+pub fn get_lending_user_monthly_statement_account_pda(statement_month: u8,
+    statement_year: u16,
+    token_id: u8,
+    sub_market_owner_address: &Pubkey,
+    sub_market_index: u16,
+    lending_user_address: &Pubkey,
+    user_account_index: u8
+) -> (Pubkey, u8)
+{
+    find_lending_protocol_address(&[b"userMonthlyStatementAccount".as_ref(),
+        statement_month.to_le_bytes().as_ref(),
+        statement_year.to_le_bytes().as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner_address.as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_address.as_ref(),
+        user_account_index.to_le_bytes().as_ref()])
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::pda::get_price_account_pda;
+
+//Where withdraws and borrows get their prices from
+pub enum PriceSource
+{
+    //The signer's temporary price account posted by the Oracle in the same transaction. The Oracle account gets the price account's rent refunded
+    PriceAccounts { oracle_address: Pubkey },
+    //The verified prices attested in the User Health by refresh_user_health, until it expires
+    AttestedUserHealth,
+    //Withdrawals from Lending User Accounts with no debt and no co-signer don't need prices
+    NotNeeded
+}
+
+//Remaining accounts for withdraw_tokens, borrow_tokens, and close_position, in the order the program reads them:
+//the price accounts when they're passed in, followed by the token mint's transfer hook accounts if it has any.
+//close_position doesn't read the User Health attestation, so it needs the price accounts whenever the user has debt or a co-signer
+pub fn get_withdraw_or_borrow_remaining_accounts(signer_address: &Pubkey, price_source: PriceSource, transfer_hook_accounts: &[AccountMeta]) -> Vec<AccountMeta>
+{
+    let mut remaining_accounts = Vec::with_capacity(2 + transfer_hook_accounts.len());
+
+    if let PriceSource::PriceAccounts { oracle_address } = price_source
+    {
+        remaining_accounts.push(AccountMeta::new(get_price_account_pda(signer_address).0, false));
+        remaining_accounts.push(AccountMeta::new(oracle_address, false));
+    }
+
+    remaining_accounts.extend_from_slice(transfer_hook_accounts);
+    remaining_accounts
+}
+
+//Remaining accounts for refresh_user_health. Passing the price accounts attests the verified prices so the withdraw or borrow after it can leave them out
+pub fn get_refresh_user_health_remaining_accounts(signer_address: &Pubkey, oracle_address: Option<Pubkey>) -> Vec<AccountMeta>
+{
+    match oracle_address
+    {
+        Some(oracle_address) => vec![AccountMeta::new(get_price_account_pda(signer_address).0, false), AccountMeta::new(oracle_address, false)],
+        None => Vec::new()
+    }
+}
@@ -0,0 +1,194 @@
+use lending_protocol::structs::{LendingUserActivityAccount, LendingUserMonthlyStatementAccount};
+use lending_protocol::shared_constants::MAX_LENDING_USER_ACTIVITY_RECORDS;
+use rusqlite::{params, Connection, Result};
+
+//Amounts are stored as text since u64 amounts and u128 values don't fit in SQLite's signed 64 bit integers
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS monthly_statements
+    (
+        address TEXT PRIMARY KEY,
+        owner TEXT NOT NULL,
+        user_account_index INTEGER NOT NULL,
+        token_id INTEGER NOT NULL,
+        sub_market_owner_address TEXT NOT NULL,
+        sub_market_index INTEGER NOT NULL,
+        statement_month INTEGER NOT NULL,
+        statement_year INTEGER NOT NULL,
+        snap_shot_balance_amount TEXT NOT NULL,
+        snap_shot_debt_amount TEXT NOT NULL,
+        monthly_deposited_amount TEXT NOT NULL,
+        monthly_interest_earned_amount TEXT NOT NULL,
+        monthly_fees_generated_amount TEXT NOT NULL,
+        monthly_withdrawal_amount TEXT NOT NULL,
+        monthly_borrowed_amount TEXT NOT NULL,
+        monthly_interest_accrued_amount TEXT NOT NULL,
+        monthly_repaid_debt_amount TEXT NOT NULL,
+        monthly_liquidated_amount TEXT NOT NULL,
+        monthly_liquidator_amount TEXT NOT NULL,
+        last_lending_activity_type INTEGER NOT NULL,
+        last_lending_activity_time_stamp INTEGER NOT NULL,
+        slot INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS activities
+    (
+        owner TEXT NOT NULL,
+        user_account_index INTEGER NOT NULL,
+        activity_number INTEGER NOT NULL,
+        activity_type INTEGER NOT NULL,
+        token_mint_address TEXT NOT NULL,
+        amount TEXT NOT NULL,
+        time_stamp INTEGER NOT NULL,
+        PRIMARY KEY (owner, user_account_index, activity_number)
+    );
+    CREATE TABLE IF NOT EXISTS transactions
+    (
+        signature TEXT NOT NULL,
+        instruction_number INTEGER NOT NULL,
+        slot INTEGER NOT NULL,
+        block_time INTEGER,
+        instruction_name TEXT NOT NULL,
+        summary TEXT,
+        succeeded INTEGER NOT NULL,
+        PRIMARY KEY (signature, instruction_number)
+    );
+    CREATE TABLE IF NOT EXISTS events
+    (
+        signature TEXT NOT NULL,
+        event_number INTEGER NOT NULL,
+        slot INTEGER NOT NULL,
+        event_name TEXT NOT NULL,
+        event_data_base64 TEXT NOT NULL,
+        PRIMARY KEY (signature, event_number)
+    );
+    CREATE TABLE IF NOT EXISTS backfill_progress
+    (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        oldest_signature TEXT NOT NULL
+    );";
+
+pub fn open_database(path: &str) -> Result<Connection>
+{
+    let connection = Connection::open(path)?;
+    connection.execute_batch(SCHEMA)?;
+    Ok(connection)
+}
+
+//Statements keep changing until their month ends, so the latest slot wins. Statements stay in the database after they're garbage collected on chain
+pub fn upsert_monthly_statement(connection: &Connection, address: &str, statement: &LendingUserMonthlyStatementAccount, slot: u64) -> Result<()>
+{
+    connection.execute("
+        INSERT INTO monthly_statements VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
+        ON CONFLICT(address) DO UPDATE SET
+            snap_shot_balance_amount = excluded.snap_shot_balance_amount,
+            snap_shot_debt_amount = excluded.snap_shot_debt_amount,
+            monthly_deposited_amount = excluded.monthly_deposited_amount,
+            monthly_interest_earned_amount = excluded.monthly_interest_earned_amount,
+            monthly_fees_generated_amount = excluded.monthly_fees_generated_amount,
+            monthly_withdrawal_amount = excluded.monthly_withdrawal_amount,
+            monthly_borrowed_amount = excluded.monthly_borrowed_amount,
+            monthly_interest_accrued_amount = excluded.monthly_interest_accrued_amount,
+            monthly_repaid_debt_amount = excluded.monthly_repaid_debt_amount,
+            monthly_liquidated_amount = excluded.monthly_liquidated_amount,
+            monthly_liquidator_amount = excluded.monthly_liquidator_amount,
+            last_lending_activity_type = excluded.last_lending_activity_type,
+            last_lending_activity_time_stamp = excluded.last_lending_activity_time_stamp,
+            slot = excluded.slot
+        WHERE excluded.slot >= monthly_statements.slot",
+        params![
+            address,
+            statement.owner.to_string(),
+            statement.user_account_index,
+            statement.token_id,
+            statement.sub_market_owner_address.to_string(),
+            statement.sub_market_index,
+            statement.statement_month,
+            statement.statement_year,
+            statement.snap_shot_balance_amount.to_string(),
+            statement.snap_shot_debt_amount.to_string(),
+            statement.monthly_deposited_amount.to_string(),
+            statement.monthly_interest_earned_amount.to_string(),
+            statement.monthly_fees_generated_amount.to_string(),
+            statement.monthly_withdrawal_amount.to_string(),
+            statement.monthly_borrowed_amount.to_string(),
+            statement.monthly_interest_accrued_amount.to_string(),
+            statement.monthly_repaid_debt_amount.to_string(),
+            statement.monthly_liquidated_amount.to_string(),
+            statement.monthly_liquidator_amount.to_string(),
+            statement.last_lending_activity_type,
+            statement.last_lending_activity_time_stamp as i64,
+            slot as i64
+        ])?;
+
+    Ok(())
+}
+
+//The on chain ring buffer only keeps the most recent activities. Each one is numbered by its place in the account's lifetime activity count,
+//so activities already in the database are kept when the buffer wraps around
+pub fn insert_activities(connection: &Connection, activity_account: &LendingUserActivityAccount) -> Result<()>
+{
+    let recorded_activity_count = std::cmp::min(activity_account.activity_count, MAX_LENDING_USER_ACTIVITY_RECORDS as u64);
+
+    for activities_back in 1..=recorded_activity_count
+    {
+        let activity_index = (activity_account.next_activity_index as usize + MAX_LENDING_USER_ACTIVITY_RECORDS - activities_back as usize) % MAX_LENDING_USER_ACTIVITY_RECORDS;
+        let activity = &activity_account.activities[activity_index];
+
+        connection.execute("INSERT OR IGNORE INTO activities VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                activity_account.owner.to_string(),
+                activity_account.user_account_index,
+                (activity_account.activity_count - activities_back) as i64,
+                activity.activity_type,
+                activity.token_mint_address.to_string(),
+                activity.amount.to_string(),
+                activity.time_stamp as i64
+            ])?;
+    }
+
+    Ok(())
+}
+
+pub fn insert_transaction_instruction(connection: &Connection,
+    signature: &str,
+    instruction_number: usize,
+    slot: u64,
+    block_time: Option<i64>,
+    instruction_name: &str,
+    summary: Option<&str>,
+    succeeded: bool
+) -> Result<()>
+{
+    connection.execute("INSERT OR IGNORE INTO transactions VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![signature, instruction_number as i64, slot as i64, block_time, instruction_name, summary, succeeded])?;
+
+    Ok(())
+}
+
+pub fn insert_event(connection: &Connection, signature: &str, event_number: usize, slot: u64, event_name: &str, event_data_base64: &str) -> Result<()>
+{
+    connection.execute("INSERT OR IGNORE INTO events VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![signature, event_number as i64, slot as i64, event_name, event_data_base64])?;
+
+    Ok(())
+}
+
+//Backfills walk back from the newest transaction, so the oldest signature reached is where the next backfill picks up
+pub fn get_backfill_oldest_signature(connection: &Connection) -> Result<Option<String>>
+{
+    let mut statement = connection.prepare("SELECT oldest_signature FROM backfill_progress WHERE id = 0")?;
+    let mut rows = statement.query([])?;
+
+    match rows.next()?
+    {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None)
+    }
+}
+
+pub fn set_backfill_oldest_signature(connection: &Connection, oldest_signature: &str) -> Result<()>
+{
+    connection.execute("INSERT INTO backfill_progress VALUES (0, ?1) ON CONFLICT(id) DO UPDATE SET oldest_signature = excluded.oldest_signature",
+        params![oldest_signature])?;
+
+    Ok(())
+}
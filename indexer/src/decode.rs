@@ -0,0 +1,137 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use lending_protocol::events::*;
+use lending_protocol::structs::{LendingUserActivityAccount, LendingUserMonthlyStatementAccount};
+
+pub enum IndexedAccount
+{
+    MonthlyStatement(LendingUserMonthlyStatementAccount),
+    Activity(LendingUserActivityAccount)
+}
+
+//Only Monthly Statements and Lending User Activity accounts are indexed. Everything else the program owns is skipped
+pub fn decode_indexed_account(data: &[u8]) -> Option<IndexedAccount>
+{
+    if data.starts_with(LendingUserMonthlyStatementAccount::DISCRIMINATOR)
+    {
+        LendingUserMonthlyStatementAccount::try_deserialize(&mut &data[..]).ok().map(IndexedAccount::MonthlyStatement)
+    }
+    else if data.starts_with(LendingUserActivityAccount::DISCRIMINATOR)
+    {
+        LendingUserActivityAccount::try_deserialize(&mut &data[..]).ok().map(IndexedAccount::Activity)
+    }
+    else
+    {
+        None
+    }
+}
+
+//Anchor events are logged as base64 after "Program data: ", starting with the event's discriminator
+fn get_event_name(event_data: &[u8]) -> &'static str
+{
+    let event_discriminators: [(&[u8], &'static str); 26] = [
+        (LendingProtocolCEOChanged::DISCRIMINATOR, "LendingProtocolCEOChanged"),
+        (LendingProtocolRolesUpdated::DISCRIMINATOR, "LendingProtocolRolesUpdated"),
+        (BorrowsPaused::DISCRIMINATOR, "BorrowsPaused"),
+        (TimelockedChangeQueued::DISCRIMINATOR, "TimelockedChangeQueued"),
+        (TimelockedChangeExecuted::DISCRIMINATOR, "TimelockedChangeExecuted"),
+        (TimelockedChangeCancelled::DISCRIMINATOR, "TimelockedChangeCancelled"),
+        (AdminActionCreated::DISCRIMINATOR, "AdminActionCreated"),
+        (AdminActionExecuted::DISCRIMINATOR, "AdminActionExecuted"),
+        (AdminActionCancelled::DISCRIMINATOR, "AdminActionCancelled"),
+        (TimelockDelayUpdated::DISCRIMINATOR, "TimelockDelayUpdated"),
+        (StatementMonthAndYearUpdated::DISCRIMINATOR, "StatementMonthAndYearUpdated"),
+        (TokenReserveAdded::DISCRIMINATOR, "TokenReserveAdded"),
+        (TokenReserveListed::DISCRIMINATOR, "TokenReserveListed"),
+        (TokenReserveListingVetoed::DISCRIMINATOR, "TokenReserveListingVetoed"),
+        (TokenReserveUpdated::DISCRIMINATOR, "TokenReserveUpdated"),
+        (TokenReserveBorrowIntentSettingsUpdated::DISCRIMINATOR, "TokenReserveBorrowIntentSettingsUpdated"),
+        (ReserveInvariantDrift::DISCRIMINATOR, "ReserveInvariantDrift"),
+        (SubMarketCreated::DISCRIMINATOR, "SubMarketCreated"),
+        (SubMarketEdited::DISCRIMINATOR, "SubMarketEdited"),
+        (SubMarketRetired::DISCRIMINATOR, "SubMarketRetired"),
+        (SubMarketFeeCollectorChanged::DISCRIMINATOR, "SubMarketFeeCollectorChanged"),
+        (TabAccountHealthSnapShot::DISCRIMINATOR, "TabAccountHealthSnapShot"),
+        (FlashLoanRepaid::DISCRIMINATOR, "FlashLoanRepaid"),
+        (RewardsClaimed::DISCRIMINATOR, "RewardsClaimed"),
+        (ReferralFeesClaimed::DISCRIMINATOR, "ReferralFeesClaimed"),
+        (MerkleDistributionClaimed::DISCRIMINATOR, "MerkleDistributionClaimed")
+    ];
+
+    event_discriminators
+        .iter()
+        .find(|(discriminator, _)| event_data.starts_with(discriminator))
+        .map(|(_, event_name)| *event_name)
+        .unwrap_or("Unknown")
+}
+
+pub struct LoggedInstruction
+{
+    pub instruction_name: String,
+    pub summary: Option<String> //The instruction's summary msg! line. Detail lines are only logged by verbose-logs builds
+}
+
+pub struct LoggedEvent
+{
+    pub event_name: &'static str,
+    pub event_data_base64: String
+}
+
+//Walks a transaction's logs for the Lending Protocol's top level and CPI'd instructions, pulling out each instruction's name, its summary log line, and its events
+pub fn parse_program_logs(log_messages: &[String], program_id: &str) -> (Vec<LoggedInstruction>, Vec<LoggedEvent>)
+{
+    let mut logged_instructions: Vec<LoggedInstruction> = Vec::new();
+    let mut logged_events = Vec::new();
+    let invoke_prefix = format!("Program {} invoke", program_id);
+    let mut program_stack: Vec<bool> = Vec::new(); //Whether each program on the invoke stack is the Lending Protocol
+
+    for log_message in log_messages
+    {
+        if log_message.starts_with("Program ") && log_message.contains(" invoke [")
+        {
+            program_stack.push(log_message.starts_with(&invoke_prefix));
+            continue;
+        }
+
+        if log_message.starts_with("Program ") && (log_message.ends_with(" success") || log_message.contains(" failed"))
+        {
+            program_stack.pop();
+            continue;
+        }
+
+        if program_stack.last() != Some(&true)
+        {
+            continue;
+        }
+
+        if let Some(instruction_name) = log_message.strip_prefix("Program log: Instruction: ")
+        {
+            logged_instructions.push(LoggedInstruction
+            {
+                instruction_name: instruction_name.to_string(),
+                summary: None
+            });
+        }
+        else if let Some(log) = log_message.strip_prefix("Program log: ")
+        {
+            if let Some(logged_instruction) = logged_instructions.last_mut()
+            {
+                if logged_instruction.summary.is_none()
+                {
+                    logged_instruction.summary = Some(log.to_string());
+                }
+            }
+        }
+        else if let Some(event_data_base64) = log_message.strip_prefix("Program data: ")
+        {
+            let event_name = STANDARD.decode(event_data_base64).map(|event_data| get_event_name(&event_data)).unwrap_or("Unknown");
+            logged_events.push(LoggedEvent
+            {
+                event_name,
+                event_data_base64: event_data_base64.to_string()
+            });
+        }
+    }
+
+    (logged_instructions, logged_events)
+}
@@ -0,0 +1,199 @@
+mod database;
+mod decode;
+
+use std::error::Error;
+use std::str::FromStr;
+use anchor_lang::Discriminator;
+use decode::{decode_indexed_account, parse_program_logs, IndexedAccount};
+use lending_protocol::structs::{LendingUserActivityAccount, LendingUserMonthlyStatementAccount};
+use rusqlite::Connection;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::pubsub_client::PubsubClient;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+const USAGE: &str = "Usage: lending_protocol_indexer <snapshot|follow|backfill> <rpc url> <database path> [websocket url]
+
+    snapshot   Index every Monthly Statement and Lending User Activity account the program currently owns
+    follow     Snapshot, then keep the database up to date from a program account subscription (needs the websocket url)
+    backfill   Index the program's historical transactions, picking up where the last backfill stopped.
+               Transactions newer than the first backfill aren't picked up, start from a fresh database to backfill them";
+
+const SIGNATURES_PER_PAGE: usize = 1000;
+
+fn index_account(connection: &Connection, address: &str, data: &[u8], slot: u64) -> Result<(), Box<dyn Error>>
+{
+    match decode_indexed_account(data)
+    {
+        Some(IndexedAccount::MonthlyStatement(statement)) => database::upsert_monthly_statement(connection, address, &statement, slot)?,
+        Some(IndexedAccount::Activity(activity_account)) => database::insert_activities(connection, &activity_account)?,
+        None => {}
+    }
+
+    Ok(())
+}
+
+//Without a discriminator every account the program owns is returned, and the ones that aren't indexed are skipped when they're decoded
+fn get_program_accounts_config(discriminator: Option<&[u8]>) -> RpcProgramAccountsConfig
+{
+    RpcProgramAccountsConfig
+    {
+        filters: discriminator.map(|discriminator| vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, discriminator))]),
+        account_config: RpcAccountInfoConfig
+        {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    }
+}
+
+fn snapshot(rpc_client: &RpcClient, connection: &Connection) -> Result<(), Box<dyn Error>>
+{
+    let slot = rpc_client.get_slot()?;
+
+    for discriminator in [LendingUserMonthlyStatementAccount::DISCRIMINATOR, LendingUserActivityAccount::DISCRIMINATOR]
+    {
+        let program_accounts = rpc_client.get_program_accounts_with_config(&lending_protocol::ID, get_program_accounts_config(Some(discriminator)))?;
+
+        for (address, account) in program_accounts
+        {
+            index_account(connection, &address.to_string(), &account.data, slot)?;
+        }
+    }
+
+    println!("Snapshot indexed at slot {}", slot);
+    Ok(())
+}
+
+//The subscription only reports accounts as they change, so a snapshot is taken first to catch everything that changed while the indexer was down
+fn follow(rpc_client: &RpcClient, connection: &Connection, websocket_url: &str) -> Result<(), Box<dyn Error>>
+{
+    snapshot(rpc_client, connection)?;
+
+    let (_subscription, receiver) = PubsubClient::program_subscribe(websocket_url, &lending_protocol::ID, Some(get_program_accounts_config(None)))?;
+
+    println!("Following program accounts");
+
+    for response in receiver
+    {
+        //Accounts closed by garbage collection come through with no data and are left in the database
+        if let Some(data) = response.value.account.data.decode()
+        {
+            index_account(connection, &response.value.pubkey, &data, response.context.slot)?;
+        }
+    }
+
+    Err("Program account subscription closed".into())
+}
+
+//Walks back through the program's transactions from the newest, or from where the last backfill stopped, until there are none left
+fn backfill(rpc_client: &RpcClient, connection: &Connection) -> Result<(), Box<dyn Error>>
+{
+    let program_id = lending_protocol::ID.to_string();
+    let mut before = database::get_backfill_oldest_signature(connection)?;
+    let mut transaction_count: usize = 0;
+
+    loop
+    {
+        let signatures = rpc_client.get_signatures_for_address_with_config(&lending_protocol::ID, GetConfirmedSignaturesForAddress2Config
+        {
+            before: before.as_deref().map(Signature::from_str).transpose()?,
+            limit: Some(SIGNATURES_PER_PAGE),
+            ..GetConfirmedSignaturesForAddress2Config::default()
+        })?;
+
+        if signatures.is_empty()
+        {
+            break;
+        }
+
+        for signature_status in &signatures
+        {
+            let transaction = rpc_client.get_transaction_with_config(&Signature::from_str(&signature_status.signature)?, RpcTransactionConfig
+            {
+                encoding: Some(UiTransactionEncoding::Json),
+                max_supported_transaction_version: Some(0),
+                ..RpcTransactionConfig::default()
+            })?;
+
+            let Some(meta) = transaction.transaction.meta else
+            {
+                continue;
+            };
+
+            let log_messages: Vec<String> = Option::from(meta.log_messages).unwrap_or_default();
+            let (logged_instructions, logged_events) = parse_program_logs(&log_messages, &program_id);
+
+            for (instruction_number, logged_instruction) in logged_instructions.iter().enumerate()
+            {
+                database::insert_transaction_instruction(connection,
+                    &signature_status.signature,
+                    instruction_number,
+                    transaction.slot,
+                    transaction.block_time,
+                    &logged_instruction.instruction_name,
+                    logged_instruction.summary.as_deref(),
+                    meta.err.is_none())?;
+            }
+
+            for (event_number, logged_event) in logged_events.iter().enumerate()
+            {
+                database::insert_event(connection,
+                    &signature_status.signature,
+                    event_number,
+                    transaction.slot,
+                    logged_event.event_name,
+                    &logged_event.event_data_base64)?;
+            }
+        }
+
+        transaction_count += signatures.len();
+        let oldest_signature = signatures[signatures.len() - 1].signature.clone();
+        database::set_backfill_oldest_signature(connection, &oldest_signature)?;
+        before = Some(oldest_signature);
+
+        println!("Backfilled {} transactions", transaction_count);
+    }
+
+    println!("Backfill complete");
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>>
+{
+    let arguments: Vec<String> = std::env::args().collect();
+
+    if arguments.len() < 4
+    {
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    }
+
+    let rpc_client = RpcClient::new(arguments[2].clone());
+    let connection = database::open_database(&arguments[3])?;
+
+    match arguments[1].as_str()
+    {
+        "snapshot" => snapshot(&rpc_client, &connection),
+        "follow" =>
+        {
+            let Some(websocket_url) = arguments.get(4) else
+            {
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            };
+
+            follow(&rpc_client, &connection, websocket_url)
+        },
+        "backfill" => backfill(&rpc_client, &connection),
+        _ =>
+        {
+            eprintln!("{}", USAGE);
+            std::process::exit(1);
+        }
+    }
+}
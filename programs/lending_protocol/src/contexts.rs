@@ -1,11 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenInterface, TokenAccount};
-use core::mem::size_of;
 use crate::structs as Structs;
-
-//Lending User Account need atleast 4 extra bytes of space to pass with full load(Longest name possible)
-const LENDING_USER_ACCOUNT_EXTRA_SIZE: usize = 4;
+use crate::lending_helpers::{get_token_reserve_version_seed, get_reward_emissions_side_seed, ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS};
 
 //Derived Accounts
 #[derive(Accounts)]
@@ -19,7 +16,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"lendingProtocol".as_ref()],
         bump,
-        space = size_of::<Structs::LendingProtocol>() + 8)]
+        space = Structs::LendingProtocol::INIT_SPACE + 8)]
     pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
@@ -27,7 +24,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"lendingProtocolCEO".as_ref()],
         bump,
-        space = size_of::<Structs::LendingProtocolCEO>() + 8)]
+        space = Structs::LendingProtocolCEO::INIT_SPACE + 8)]
     pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
@@ -35,7 +32,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"solvencyTreasurer".as_ref()],
         bump,
-        space = size_of::<Structs::SolvencyTreasurer>() + 8)]
+        space = Structs::SolvencyTreasurer::INIT_SPACE + 8)]
     pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
 
     #[account(
@@ -43,7 +40,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"liquidationTreasurer".as_ref()],
         bump,
-        space = size_of::<Structs::LiquidationTreasurer>() + 8)]
+        space = Structs::LiquidationTreasurer::INIT_SPACE + 8)]
     pub liquidation_treasurer: Account<'info, Structs::LiquidationTreasurer>,
 
     #[account(
@@ -51,7 +48,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"oraclePriceValidator".as_ref()],
         bump,
-        space = size_of::<Structs::OraclePriceValidator>() + 8)]
+        space = Structs::OraclePriceValidator::INIT_SPACE + 8)]
     pub price_validator: Account<'info, Structs::OraclePriceValidator>,
 
     #[account(
@@ -59,7 +56,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"lendingStats".as_ref()],
         bump,
-        space = size_of::<Structs::LendingStats>() + 8)]
+        space = Structs::LendingStats::INIT_SPACE + 8)]
     pub lending_stats: Account<'info, Structs::LendingStats>,
 
     #[account(
@@ -67,7 +64,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"lendingUserStats".as_ref()],
         bump,
-        space = size_of::<Structs::LendingUserStats>() + 8)]
+        space = Structs::LendingUserStats::INIT_SPACE + 8)]
     pub lending_user_stats: Account<'info, Structs::LendingUserStats>,
 
     #[account(
@@ -75,7 +72,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"tokenReserveStats".as_ref()],
         bump,
-        space = size_of::<Structs::TokenReserveStats>() + 8)]
+        space = Structs::TokenReserveStats::INIT_SPACE + 8)]
     pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
 
     #[account(
@@ -83,7 +80,7 @@ pub struct InitializeLendingProtocol<'info>
         payer = signer,
         seeds = [b"subMarketStats".as_ref()],
         bump,
-        space = size_of::<Structs::SubMarketStats>() + 8)]
+        space = Structs::SubMarketStats::INIT_SPACE + 8)]
     pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
 
     #[account(mut)]
@@ -108,6 +105,35 @@ pub struct PassOnLendingProtocolCEO<'info>
     pub system_program: Program<'info, System>
 }
 
+#[derive(Accounts)]
+pub struct PassOnLendingProtocolCEOToGovernance<'info> 
+{
+    ///CHECK: This is the SPL Governance program the Realm was deployed under. Realms can run their own copy of the program so it isn't hardcoded
+    #[account(executable)]
+    pub governance_program: UncheckedAccount<'info>,
+
+    ///CHECK: This is the Governance account whose Native Treasury becomes the new CEO
+    #[account(owner = governance_program.key())]
+    pub governance: UncheckedAccount<'info>,
+
+    ///CHECK: This is the Native Treasury PDA of the Governance account, the address proposals sign with
+    #[account(
+        seeds = [b"native-treasury".as_ref(), governance.key().as_ref()],
+        seeds::program = governance_program.key(),
+        bump)]
+    pub native_treasury: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
 #[derive(Accounts)]
 pub struct PassOnSolvencyTreasurer<'info> 
 {
@@ -142,12 +168,61 @@ pub struct PassOnLiquidationTreasurer<'info>
     pub system_program: Program<'info, System>
 }
 
+#[derive(Accounts)]
+pub struct SetLendingProtocolRoles<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump, 
+        space = Structs::LendingProtocolRoles::INIT_SPACE + 8)]
+    pub roles: Account<'info, Structs::LendingProtocolRoles>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct PauseBorrows<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    //Only needed when the signer is calling as the Guardian instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
+
+    pub signer: Signer<'info>
+}
+
 #[derive(Accounts)]
 pub struct SetOraclePriceValidator<'info> 
 {
     ///CHECK: This is the address of the new Oracle Price Validator
     pub new_price_validator_address: UncheckedAccount<'info>,
 
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
     #[account(
         seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
@@ -181,8 +256,8 @@ pub struct CreateTempOraclePriceData<'info>
         payer = signer,
         seeds = [b"oraclePriceData".as_ref(), lending_user_address.key().as_ref()], 
         bump,
-        space = (payload.data.len() * 17) + 1 + 4 + 8 + 8)]//Token Prices Count * (token_id(1byte) + normalized_price_18_decimals(16bytes) = 17bytes)
-        //1(Bump) + 4(Borsh Vector Prefix) + 8(slot) + 8(Anchor Discriminator)
+        space = (payload.data.len() * 17) + 1 + 4 + 8 + 1 + 8)]//Token Prices Count * (token_id(1byte) + normalized_price_18_decimals(16bytes) = 17bytes)
+        //1(Bump) + 4(Borsh Vector Prefix) + 8(slot) + 1(Version) + 8(Anchor Discriminator)
     pub temp_price_account: Account<'info, Structs::TempOraclePriceAccount>,
 
     #[account(mut)]
@@ -224,19 +299,25 @@ pub struct UpdateLendingProtocol<'info>
         bump)]
     pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
+
     #[account(mut)]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>
 }
 
 #[derive(Accounts)]
-pub struct AddTokenReserve<'info> 
+pub struct AddLiquidatorToAllowlist<'info> 
 {
-    #[account(
-        mut,
-        seeds = [b"tokenReserveStats".as_ref()],
-        bump)]
-    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
+    ///CHECK: This is the token mint address of the Token Reserve the liquidator is being allowlisted for
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    ///CHECK: This is the wallet address of the liquidator being allowlisted
+    pub liquidator_address: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocolCEO".as_ref()],
@@ -244,25 +325,17 @@ pub struct AddTokenReserve<'info>
     pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
-        init, 
-        payer = signer,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump, 
-        space = size_of::<Structs::TokenReserve>() + 8)]
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Account<'info, Structs::TokenReserve>,
 
     #[account(
         init, 
         payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = token_reserve,
-        associated_token::token_program = token_program
-    )]
-    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
-
-    pub token_mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+        space = 8 + Structs::LiquidatorAllowlistEntry::INIT_SPACE,
+        seeds = [b"liquidatorAllowlist".as_ref(), token_reserve.key().as_ref(), liquidator_address.key().as_ref()], 
+        bump)]
+    pub liquidator_allowlist_entry: Account<'info, Structs::LiquidatorAllowlistEntry>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -270,27 +343,30 @@ pub struct AddTokenReserve<'info>
 }
 
 #[derive(Accounts)]
-pub struct UpdateTokenReserve<'info> 
+pub struct RemoveLiquidatorFromAllowlist<'info> 
 {
-    ///CHECK: This is the token mint address of the Token Reserve the CEO wants to update
+    ///CHECK: This is the token mint address of the Token Reserve the liquidator is being removed from
     pub token_mint_address: UncheckedAccount<'info>,
 
-    #[account(
-        mut,
-        seeds = [b"tokenReserveStats".as_ref()],
-        bump)]
-    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
+    ///CHECK: This is the wallet address of the liquidator being removed
+    pub liquidator_address: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
     pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
+
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()], 
+        close = signer,
+        seeds = [b"liquidatorAllowlist".as_ref(), token_reserve.key().as_ref(), liquidator_address.key().as_ref()], 
         bump)]
-    pub token_reserve: Account<'info, Structs::TokenReserve>,
+    pub liquidator_allowlist_entry: Account<'info, Structs::LiquidatorAllowlistEntry>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -298,42 +374,24 @@ pub struct UpdateTokenReserve<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16)]
-pub struct CreateSubMarket<'info> 
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct AddSubMarketAllowlistEntry<'info> 
 {
-    ///CHECK: This is the token mint address of the Token Reserve the user wants to create a Sub Market under
-    pub token_mint_address: UncheckedAccount<'info>,
-
-    ///CHECK: This is the fee collector address that the Sub Market owner wants to designate to be able to collect fees from this Sub Market
-    pub fee_collector_address: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user being allowlisted
+    pub user_address: UncheckedAccount<'info>,
 
     #[account(
-        mut,
-        seeds = [b"subMarketStats".as_ref()],
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
-
-    #[account(
-        init,
-        payer = signer,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::SubMarket>() + 8)]
-    pub sub_market: Account<'info, Structs::SubMarket>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
     #[account(
-        init_if_needed,
+        init, 
         payer = signer,
-        seeds = [b"subMarketOwnerLookUpTable".as_ref(), signer.key().as_ref()], 
-        bump, 
-        space = size_of::<Structs::SubMarketOwnerLookUpTable>() + 8)]
-    pub sub_market_owner_look_up_table: Account<'info, Structs::SubMarketOwnerLookUpTable>,
-
-    //The Token Reserve must exist to create a SubMarket. Only the ceo can create a Token Reserve.
-    #[account(
-        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()], 
+        space = 8 + Structs::SubMarketAllowlistEntry::INIT_SPACE,
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), user_address.key().as_ref()], 
         bump)]
-    pub token_reserve: Account<'info, Structs::TokenReserve>,
+    pub sub_market_allowlist_entry: Account<'info, Structs::SubMarketAllowlistEntry>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -342,152 +400,157 @@ pub struct CreateSubMarket<'info>
 
 #[derive(Accounts)]
 #[instruction(token_id: u8, sub_market_index: u16)]
-pub struct EditSubMarket<'info> 
+pub struct RemoveSubMarketAllowlistEntry<'info> 
 {
-    ///CHECK: This is the fee collector address that the Sub Market owner wants to designate to be able to collect fees from this Sub Market
-    pub fee_collector_address: UncheckedAccount<'info>,
-    
+    ///CHECK: This is the wallet address of the user being removed from the allowlist
+    pub user_address: UncheckedAccount<'info>,
+
     #[account(
-        mut,
-        seeds = [b"subMarketStats".as_ref()],
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
     #[account(
         mut,
-        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        close = signer,
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), user_address.key().as_ref()], 
         bump)]
-    pub sub_market: Account<'info, Structs::SubMarket>,
+    pub sub_market_allowlist_entry: Account<'info, Structs::SubMarketAllowlistEntry>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>
 }
 
-/*#[derive(Accounts)]
-#[instruction(user_account_index: u8)]
-pub struct UpdateLendingUserLookUpTableAddress<'info> 
+#[derive(Accounts)]
+pub struct QueueTimelockedChange<'info> 
 {
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_user_account: Account<'info, LendingUserAccount>,
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init, 
+        payer = signer,
+        space = 8 + Structs::TimelockedChange::INIT_SPACE,
+        seeds = [b"timelockedChange".as_ref(), lending_protocol.timelocked_change_count.to_le_bytes().as_ref()], 
+        bump)]
+    pub timelocked_change: Account<'info, Structs::TimelockedChange>,
+
+    //Only needed for Token Reserve, Insurance Fund share rate, collateral haircut, and asset category changes
+    pub token_reserve: Option<Box<Account<'info, Structs::TokenReserve>>>,
+
+    //Only needed for SubMarket Oracle Override approvals
+    pub sub_market: Option<AccountLoader<'info, Structs::SubMarket>>,
+
+    //Only needed for LP Token haircut changes
+    pub lp_token_price_adapter: Option<Box<Account<'info, Structs::LpTokenPriceAdapter>>>,
+
+    //Only needed for E-Mode category changes
+    pub e_mode_category: Option<Box<Account<'info, Structs::EModeCategory>>>,
+
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>
-}*/
+}
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct DepositTokens<'info> 
+#[instruction(change_id: u32)]
+pub struct ExecuteTimelockedChange<'info> 
 {
-    ///CHECK: This is the wallet address of the user who owns the Sub Market
-    pub sub_market_owner: UncheckedAccount<'info>,
-
     #[account(
+        mut,
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
-        mut, 
-        seeds = [b"lendingStats".as_ref()],
+        mut,
+        close = proposer,
+        seeds = [b"timelockedChange".as_ref(), change_id.to_le_bytes().as_ref()], 
         bump)]
-    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+    pub timelocked_change: Account<'info, Structs::TimelockedChange>,
 
+    ///CHECK: This is the address that queued the change and gets the rent back
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump)]
-    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+        address = timelocked_change.proposer_address)]
+    pub proposer: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        seeds = [b"tokenReserveStats".as_ref()],
         bump)]
-    pub sub_market: Box<Account<'info, Structs::SubMarket>>,
-
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+    //Only needed for Token Reserve, Insurance Fund share rate, collateral haircut, and asset category changes
+    #[account(mut)]
+    pub token_reserve: Option<Box<Account<'info, Structs::TokenReserve>>>,
 
+    //Only needed for Oracle Price Validator changes
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        mut,
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Option<Account<'info, Structs::OraclePriceValidator>>,
 
-    #[account(
-        init_if_needed, //SOL has to be deposited as wSol and the user may or may not have a wSol account already.
-        payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+    //Only needed for SubMarket Oracle Override approvals
+    #[account(mut)]
+    pub sub_market: Option<AccountLoader<'info, Structs::SubMarket>>,
 
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = token_reserve,
-        associated_token::token_program = token_program
-    )]
-    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+    //Only needed for LP Token haircut changes
+    #[account(mut)]
+    pub lp_token_price_adapter: Option<Box<Account<'info, Structs::LpTokenPriceAdapter>>>,
 
-    pub token_mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    //Only needed for E-Mode category changes
+    #[account(mut)]
+    pub e_mode_category: Option<Box<Account<'info, Structs::EModeCategory>>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
     pub system_program: Program<'info, System>
 }
 
-//The Lending User Account gets created with a deposit and you can edit the account name on it afterwards
-//It can also be creating while liquidating or collecting fees
 #[derive(Accounts)]
-#[instruction(user_account_index: u8)]
-pub struct EditLendingUserAccountName<'info> 
+#[instruction(change_id: u32)]
+pub struct CancelTimelockedChange<'info> 
 {
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
     #[account(
         mut,
-        seeds = [b"lendingUserStats".as_ref()],
+        close = proposer,
+        seeds = [b"timelockedChange".as_ref(), change_id.to_le_bytes().as_ref()], 
         bump)]
-    pub lending_user_stats: Account<'info, Structs::LendingUserStats>,
+    pub timelocked_change: Account<'info, Structs::TimelockedChange>,
 
+    ///CHECK: This is the address that queued the change and gets the rent back
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        address = timelocked_change.proposer_address)]
+    pub proposer: UncheckedAccount<'info>,
+
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
         bump)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -495,275 +558,268 @@ pub struct EditLendingUserAccountName<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct WithdrawTokens<'info> 
+pub struct CreateAdminAction<'info> 
 {
-    ///CHECK: This is the wallet address of the user who owns the Sub Market
-    pub sub_market_owner: UncheckedAccount<'info>,
-
     #[account(
+        mut,
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
-        mut, 
-        seeds = [b"lendingStats".as_ref()],
+        init, 
+        payer = signer,
+        space = 8 + Structs::AdminAction::INIT_SPACE,
+        seeds = [b"adminAction".as_ref(), lending_protocol.admin_action_count.to_le_bytes().as_ref()], 
         bump)]
-    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+    pub admin_action: Account<'info, Structs::AdminAction>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
 
+#[derive(Accounts)]
+#[instruction(action_id: u32)]
+pub struct ExecuteAdminAction<'info> 
+{
     #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
+        mut,
+        seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
         mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        close = proposer,
+        seeds = [b"adminAction".as_ref(), action_id.to_le_bytes().as_ref()], 
         bump)]
-    pub sub_market: Box<Account<'info, Structs::SubMarket>>,
+    pub admin_action: Account<'info, Structs::AdminAction>,
 
+    ///CHECK: This is the address that staged the Admin Action and gets the rent back
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        address = admin_action.proposer_address)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(action_id: u32)]
+pub struct CancelAdminAction<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
         mut,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
+        close = proposer,
+        seeds = [b"adminAction".as_ref(), action_id.to_le_bytes().as_ref()], 
         bump)]
-    pub lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+    pub admin_action: Account<'info, Structs::AdminAction>,
 
+    ///CHECK: This is the address that staged the Admin Action and gets the rent back
     #[account(
-        init_if_needed, //Users that withdraw with no debt won't have to use the refresh_user_health_chunk instruction. Create monthly statement if it doesn't exist.
+        mut,
+        address = admin_action.proposer_address)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct ListTokenReserve<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"tokenReserveStats".as_ref()],
+        bump)]
+    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
+
+    #[account(
+        init, 
         payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        space = Structs::TokenReserve::INIT_SPACE + 8)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
 
     #[account(
-        init_if_needed, //SOL has to be withdrawn as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        init, 
         payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+        space = 8 + Structs::TokenReserveListing::INIT_SPACE,
+        seeds = [b"tokenReserveListing".as_ref(), token_mint.key().as_ref()], 
+        bump)]
+    pub token_reserve_listing: Account<'info, Structs::TokenReserveListing>,
 
     #[account(
-        mut,
+        init, 
+        payer = signer,
         associated_token::mint = token_mint,
         associated_token::authority = token_reserve,
         associated_token::token_program = token_program
     )]
     pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
 
+    //The mint can belong to either the Token Program or Token-2022, as long as it matches the token program passed in
+    #[account(
+        mint::token_program = token_program
+    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct BorrowTokens<'info> 
+pub struct VetoTokenReserveListing<'info> 
 {
-    ///CHECK: This is the wallet address of the user who owns the Sub Market
-    pub sub_market_owner: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"lendingProtocol".as_ref()],
-        bump)]
-    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+    ///CHECK: This is the token mint address of the listed Token Reserve
+    pub token_mint_address: UncheckedAccount<'info>,
 
     #[account(
-        mut, 
-        seeds = [b"lendingStats".as_ref()],
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
-        bump)]
-    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
 
+    //Closing to the signer forfeits the bond to the CEO
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
+        close = signer,
+        seeds = [b"tokenReserveListing".as_ref(), token_mint_address.key().as_ref()], 
         bump)]
-    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+    pub token_reserve_listing: Account<'info, Structs::TokenReserveListing>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct ReclaimTokenReserveListingBond<'info> 
+{
+    ///CHECK: This is the token mint address of the listed Token Reserve
+    pub token_mint_address: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        close = signer,
+        seeds = [b"tokenReserveListing".as_ref(), token_mint_address.key().as_ref()], 
         bump)]
-    pub sub_market: Box<Account<'info, Structs::SubMarket>>,
+    pub token_reserve_listing: Account<'info, Structs::TokenReserveListing>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
 
+#[derive(Accounts)]
+pub struct AddTokenReserve<'info> 
+{
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"tokenReserveStats".as_ref()],
         bump)]
-    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
 
     #[account(
-        init_if_needed, //User may be borrowing from a token reserve they have never interacted with before
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
-        init_if_needed, //User may be borrowing from a token reserve they have never interacted with before
+        init, 
         payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        space = Structs::TokenReserve::INIT_SPACE + 8)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
 
     #[account(
-        init_if_needed, //Init ATA account of token being borrowed if it doesn't exist for User
+        init, 
         payer = signer,
         associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub user_ata: InterfaceAccount<'info, TokenAccount>,
-
-    #[account(
-        mut,
-        associated_token::mint = token_mint,
         associated_token::authority = token_reserve,
         associated_token::token_program = token_program
     )]
     pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
 
+    //The mint can belong to either the Token Program or Token-2022, as long as it matches the token program passed in
+    #[account(
+        mint::token_program = token_program
+    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct RepayTokens<'info> 
+#[instruction(reserve_version: u8)]
+pub struct AddTokenReserveVersion<'info> 
 {
-    ///CHECK: This is the wallet address of the user who owns the Sub Market
-    pub sub_market_owner: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"lendingProtocol".as_ref()],
-        bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
-
-    #[account(
-        mut, 
-        seeds = [b"lendingStats".as_ref()],
-        bump)]
-    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
-
-    #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
-        bump)]
-    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
-
-    #[account(
-        mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump)]
-    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>, 
-
-    #[account(
-        mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
-        bump)]
-    pub sub_market: Box<Account<'info, Structs::SubMarket>>,
-
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"tokenReserveStats".as_ref()],
         bump)]
-    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
 
     #[account(
-        mut,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
+    //The latest existing version of the mint's Token Reserve
     #[account(
-        mut,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump)]
-    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&previous_token_reserve)], 
+        bump = previous_token_reserve.bump)]
+    pub previous_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
-        init_if_needed, //SOL has to be repaid as wSol and the user may or may not have a wSol account already.
+        init, 
         payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), reserve_version.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::TokenReserve::INIT_SPACE + 8)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
-        mut,
+        init, 
+        payer = signer,
         associated_token::mint = token_mint,
         associated_token::authority = token_reserve,
         associated_token::token_program = token_program
     )]
     pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mint::token_program = token_program
+    )]
     pub token_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -774,123 +830,4256 @@ pub struct RepayTokens<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(repayment_sub_market_index: u16,
-    liquidation_sub_market_index: u16,
-    liquidati_account_index: u8,
-    liquidator_account_index: u8)]
-pub struct LiquidateAccount<'info>
+pub struct UpdateTokenReserve<'info> 
 {
-    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
-    pub liquidati_account_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the wallet address of the user who owns the repayment Sub Market
-    pub repayment_sub_market_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the wallet address of the user who owns the liquidation Sub Market
-    pub liquidation_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the token mint address of the Token Reserve the CEO wants to update
+    pub token_mint_address: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), repayment_mint.key().as_ref()], 
+        seeds = [b"tokenReserveStats".as_ref()],
         bump)]
+    pub token_reserve_stats: Account<'info, Structs::TokenReserveStats>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
+
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16)]
+pub struct CreateSubMarket<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve the user wants to create a Sub Market under
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    ///CHECK: This is the fee collector address that the Sub Market owner wants to designate to be able to collect fees from this Sub Market
+    pub fee_collector_address: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::SubMarket::INIT_SPACE + 8)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"subMarketOwnerLookUpTable".as_ref(), signer.key().as_ref()], 
+        bump, 
+        space = Structs::SubMarketOwnerLookUpTable::INIT_SPACE + 8)]
+    pub sub_market_owner_look_up_table: Account<'info, Structs::SubMarketOwnerLookUpTable>,
+
+    //The Token Reserve must exist to create a SubMarket. Only the ceo can create a Token Reserve.
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct EditSubMarket<'info> 
+{
+    ///CHECK: This is the fee collector address that the Sub Market owner wants to designate to be able to collect fees from this Sub Market
+    pub fee_collector_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+    
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct RetireSubMarket<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct AcceptSubMarketFeeCollector<'info>
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct SetSubMarketReferralFeeShareRate<'info>
+{
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct SetSubMarketOracleOverride<'info>
+{
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct ApproveSubMarketOracleOverride<'info>
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarketStats".as_ref()],
+        bump)]
+    pub sub_market_stats: Account<'info, Structs::SubMarketStats>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+/*#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct UpdateLendingUserLookUpTableAddress<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Account<'info, LendingUserAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}*/
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct CreateLendingUserLookUpTable<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    ///CHECK: The Look Up Table address is derived from the Lending User Account and recent slot in the function
+    #[account(mut)]
+    pub look_up_table: UncheckedAccount<'info>,
+
+    ///CHECK: This is the Address Lookup Table program
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS)]
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct ExtendLendingUserLookUpTable<'info> 
+{
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    ///CHECK: Has to be the Look Up Table saved on the Lending User Account. The Address Lookup Table program checks the Lending User Account is its authority
+    #[account(
+        mut,
+        address = lending_user_account.look_up_table_address)]
+    pub look_up_table: UncheckedAccount<'info>,
+
+    ///CHECK: This is the Address Lookup Table program
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS)]
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct DepositTokens<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be deposited as wSol and the user may or may not have a wSol account already.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    //Optional token account the signer is a delegate of (ie a program-managed vault). When passed in, the deposit comes from it instead of the user's ATA
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program
+    )]
+    pub delegated_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed by users who opted in to keeping their recent activity history on chain
+    #[account(
+        mut,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+
+    ///CHECK: Only needed on the first deposit into the SubMarket by a referred user. This is the referrer's wallet address
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    //Only needed on the first deposit into the SubMarket by a referred user
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"lendingUserReferral".as_ref(), lending_user_tab_account.key().as_ref()],
+        bump, 
+        space = Structs::LendingUserReferral::INIT_SPACE + 8)]
+    pub lending_user_referral: Option<Box<Account<'info, Structs::LendingUserReferral>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+//The Lending User Account gets created with a deposit and you can edit the account name on it afterwards
+//It can also be creating while liquidating or collecting fees
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct DepositMany<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct EditLendingUserAccountName<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserStats".as_ref()],
+        bump)]
+    pub lending_user_stats: Account<'info, Structs::LendingUserStats>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct SetMonthlyStatementsDisabled<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct SetCoSigner<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    //Only needed when the Lending User Account already has a co-signer
+    pub current_co_signer: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct CreateLendingUserActivityAccount<'info> 
+{
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserActivityAccount::INIT_SPACE + 8)]
+    pub lending_user_activity_account: Box<Account<'info, Structs::LendingUserActivityAccount>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct CloseLendingUserActivityAccount<'info> 
+{
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Box<Account<'info, Structs::LendingUserActivityAccount>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct CloseLendingUserAccount<'info> 
+{
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct WithdrawTokens<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed, //Users that withdraw with no debt won't have to use the refresh_user_health_chunk instruction. Create monthly statement if it doesn't exist.
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be withdrawn as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed to withdraw SOL as native SOL. It's created and closed in the same instruction, so the user's wSOL ATA isn't touched
+    ///CHECK: Temporary wSOL token account that's created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"temporaryWsolAccount".as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub temporary_wsol_account: Option<UncheckedAccount<'info>>,
+
+    //Only needed to send the tokens to a different wallet (ie a cold wallet or payment destination). The position stays with the signer
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
+
+    //Only needed when the amount is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    //Only needed by users who opted in to keeping their recent activity history on chain
+    #[account(
+        mut,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClosePosition<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed, //Users with no other debt won't have to use the refresh_user_health_chunk instruction. Create monthly statement if it doesn't exist.
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL debt is repaid from the user's SOL and the collateral comes back as native SOL through the temporary wSOL account
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed to withdraw SOL as native SOL. It's created and closed in the same instruction, so the user's wSOL ATA isn't touched
+    ///CHECK: Temporary wSOL token account that's created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"temporaryWsolAccount".as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub temporary_wsol_account: Option<UncheckedAccount<'info>>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
+
+    //Only needed when the collateral is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    //Only needed by users who opted in to keeping their recent activity history on chain
+    #[account(
+        mut,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8, recipient_account_index: u8)]
+pub struct TransferDeposit<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user receiving the deposit
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), recipient.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed, //Users that transfer with no debt won't have to use the refresh_user_health_chunk instruction. Create monthly statement if it doesn't exist.
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), recipient.key().as_ref(), recipient_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub recipient_lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    //The recipient has to have already opened this tab, so transfers can't fill up someone else's Tab Account registry
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        recipient.key().as_ref(),
+        recipient_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub recipient_lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        recipient.key().as_ref(),
+        recipient_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub recipient_lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
+
+    //Only needed when the amount is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct BorrowTokens<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed, //User may be borrowing from a token reserve they have never interacted with before
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed, //User may be borrowing from a token reserve they have never interacted with before
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //Init ATA account of token being borrowed if it doesn't exist for User
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed to withdraw SOL as native SOL. It's created and closed in the same instruction, so the user's wSOL ATA isn't touched
+    ///CHECK: Temporary wSOL token account that's created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"temporaryWsolAccount".as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub temporary_wsol_account: Option<UncheckedAccount<'info>>,
+
+    //Only needed to send the tokens to a different wallet (ie a cold wallet or payment destination). The position stays with the signer
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
+
+    //Only needed when the amount is at or above the Token Reserve's Borrow Intent threshold. The Borrow Intent is closed once it's used
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"borrowIntent".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub borrow_intent: Option<Box<Account<'info, Structs::BorrowIntent>>>,
+
+    //Only needed when the amount is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    //Only needed by users who opted in to keeping their recent activity history on chain
+    #[account(
+        mut,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct ApproveCreditDelegation<'info> 
+{
+    ///CHECK: This is the wallet address the Lending User is letting borrow against their collateral
+    pub delegate: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"creditDelegation".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref(), token_mint.key().as_ref(), delegate.key().as_ref()], 
+        bump, 
+        space = Structs::CreditDelegation::INIT_SPACE + 8)]
+    pub credit_delegation: Box<Account<'info, Structs::CreditDelegation>>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct BorrowDelegated<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    ///CHECK: This is the wallet address of the Lending User whose collateral is borrowed against
+    pub lending_user_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    //The Lending User has to have a Tab Account for the token already, the delegate can't open one for them
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_owner.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_owner.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"creditDelegation".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref(), token_mint.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub credit_delegation: Box<Account<'info, Structs::CreditDelegation>>,
+
+    #[account(
+        init_if_needed, //Init ATA account of token being borrowed if it doesn't exist for the delegate
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed to withdraw SOL as native SOL. It's created and closed in the same instruction, so the delegate's wSOL ATA isn't touched
+    ///CHECK: Temporary wSOL token account that's created and closed by the handler
+    #[account(
+        mut,
+        seeds = [b"temporaryWsolAccount".as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub temporary_wsol_account: Option<UncheckedAccount<'info>>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
+
+    //Only needed when the amount is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct RepayTokens<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>, 
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be repaid as wSol and the user may or may not have a wSol account already.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    //Only needed by users who opted in to keeping their recent activity history on chain
+    #[account(
+        mut,
+        seeds = [b"lendingUserActivity".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_activity_account: Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct Deleverage<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(collateral_sub_market_index: u16, debt_sub_market_index: u16, user_account_index: u8)]
+pub struct DeleverageWithSwap<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the collateral Sub Market
+    pub collateral_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the debt Sub Market
+    pub debt_sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), collateral_mint.key().as_ref(), get_token_reserve_version_seed(&collateral_token_reserve)], 
+        bump = collateral_token_reserve.bump)]
+    pub collateral_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), debt_mint.key().as_ref(), get_token_reserve_version_seed(&debt_token_reserve)], 
+        bump = debt_token_reserve.bump)]
+    pub debt_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), collateral_token_reserve.token_id.to_le_bytes().as_ref(), collateral_sub_market_owner.key().as_ref(), collateral_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub collateral_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), debt_token_reserve.token_id.to_le_bytes().as_ref(), debt_sub_market_owner.key().as_ref(), debt_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub debt_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        collateral_token_reserve.token_id.to_le_bytes().as_ref(),
+        collateral_sub_market_owner.key().as_ref(),
+        collateral_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub collateral_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        debt_token_reserve.token_id.to_le_bytes().as_ref(),
+        debt_sub_market_owner.key().as_ref(),
+        debt_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub debt_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        collateral_token_reserve.token_id.to_le_bytes().as_ref(),
+        collateral_sub_market_owner.key().as_ref(),
+        collateral_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub collateral_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        debt_token_reserve.token_id.to_le_bytes().as_ref(),
+        debt_sub_market_owner.key().as_ref(),
+        debt_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub debt_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    //Only needed when the collateral is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed, //The withdrawn collateral lands here for the swap to spend
+        payer = signer,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = collateral_token_reserve,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        init_if_needed, //The swap pays out here and the repayment is taken from here
+        payer = signer,
+        associated_token::mint = debt_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = debt_token_program
+    )]
+    pub debt_user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = debt_mint,
+        associated_token::authority = debt_token_reserve,
+        associated_token::token_program = debt_token_program
+    )]
+    pub debt_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub debt_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub debt_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(source_sub_market_index: u16, destination_sub_market_index: u16, user_account_index: u8)]
+pub struct SwapCollateral<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the source Sub Market
+    pub source_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the destination Sub Market
+    pub destination_sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), source_mint.key().as_ref(), get_token_reserve_version_seed(&source_token_reserve)], 
+        bump = source_token_reserve.bump)]
+    pub source_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), destination_mint.key().as_ref(), get_token_reserve_version_seed(&destination_token_reserve)], 
+        bump = destination_token_reserve.bump)]
+    pub destination_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), source_token_reserve.token_id.to_le_bytes().as_ref(), source_sub_market_owner.key().as_ref(), source_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub source_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), destination_token_reserve.token_id.to_le_bytes().as_ref(), destination_sub_market_owner.key().as_ref(), destination_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub destination_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the destination SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), destination_sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        source_token_reserve.token_id.to_le_bytes().as_ref(),
+        source_sub_market_owner.key().as_ref(),
+        source_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub source_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        destination_token_reserve.token_id.to_le_bytes().as_ref(),
+        destination_sub_market_owner.key().as_ref(),
+        destination_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub destination_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        source_token_reserve.token_id.to_le_bytes().as_ref(),
+        source_sub_market_owner.key().as_ref(),
+        source_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub source_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        destination_token_reserve.token_id.to_le_bytes().as_ref(),
+        destination_sub_market_owner.key().as_ref(),
+        destination_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub destination_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    //Only needed when the swapped collateral is worth more than the Lending User Account's co-signer threshold
+    pub co_signer: Option<Signer<'info>>,
+
+    #[account(
+        init_if_needed, //The withdrawn collateral lands here for the swap to spend
+        payer = signer,
+        associated_token::mint = source_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = source_token_program
+    )]
+    pub source_user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = source_mint,
+        associated_token::authority = source_token_reserve,
+        associated_token::token_program = source_token_program
+    )]
+    pub source_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub source_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub source_token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        init_if_needed, //The swap pays out here and the new collateral is deposited from here
+        payer = signer,
+        associated_token::mint = destination_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = destination_token_program
+    )]
+    pub destination_user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = destination_mint,
+        associated_token::authority = destination_token_reserve,
+        associated_token::token_program = destination_token_program
+    )]
+    pub destination_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub destination_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub destination_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    //The loan is sent here for the receiver program to use
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::token_program = token_program
+    )]
+    pub receiver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    ///CHECK: Any executable program other than the Lending Protocol that implements the on_flash_loan receiver instruction
+    pub receiver_program: UncheckedAccount<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct RepayAll<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(repayment_sub_market_index: u16,
+    liquidation_sub_market_index: u16,
+    liquidati_account_index: u8,
+    liquidator_account_index: u8)]
+pub struct LiquidateAccount<'info>
+{
+    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
+    pub liquidati_account_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the repayment Sub Market
+    pub repayment_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the liquidation Sub Market
+    pub liquidation_sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), repayment_mint.key().as_ref(), get_token_reserve_version_seed(&repayment_token_reserve)], 
+        bump = repayment_token_reserve.bump)]
     pub repayment_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
+    //Only needed while the Token Reserve restricts liquidations to its allowlist
+    #[account(
+        seeds = [b"liquidatorAllowlist".as_ref(), repayment_token_reserve.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub liquidator_allowlist_entry: Option<Account<'info, Structs::LiquidatorAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), liquidation_mint.key().as_ref(), get_token_reserve_version_seed(&liquidation_token_reserve)], 
+        bump = liquidation_token_reserve.bump)]
+    pub liquidation_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        repayment_token_reserve.token_id.to_le_bytes().as_ref(),
+        repayment_sub_market_owner.key().as_ref(),
+        repayment_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub liquidator_repayment_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        liquidation_token_reserve.token_id.to_le_bytes().as_ref(),
+        liquidation_sub_market_owner.key().as_ref(),
+        liquidation_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub liquidator_liquidation_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        repayment_token_reserve.token_id.to_le_bytes().as_ref(),
+        repayment_sub_market_owner.key().as_ref(),
+        repayment_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub liquidator_repayment_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        liquidation_token_reserve.token_id.to_le_bytes().as_ref(),
+        liquidation_sub_market_owner.key().as_ref(),
+        liquidation_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub liquidator_liquidation_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = repayment_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = repayment_token_program
+    )]
+    pub liquidator_repayment_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = liquidation_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = liquidation_token_program
+    )]
+    pub liquidator_liquidation_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub repayment_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub liquidation_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub repayment_token_program: Interface<'info, TokenInterface>,
+    pub liquidation_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(repayment_sub_market_index: u16,
+    liquidation_sub_market_index: u16,
+    liquidati_account_index: u8,
+    liquidator_account_index: u8)]
+pub struct LiquidateAccountSameToken<'info>
+{
+    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
+    pub liquidati_account_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the repayment Sub Market
+    pub repayment_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the liquidation Sub Market
+    pub liquidation_sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    //Only needed while the Token Reserve restricts liquidations to its allowlist
+    #[account(
+        seeds = [b"liquidatorAllowlist".as_ref(), token_reserve.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub liquidator_allowlist_entry: Option<Account<'info, Structs::LiquidatorAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        repayment_sub_market_owner.key().as_ref(),
+        repayment_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub liquidator_repayment_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        liquidation_sub_market_owner.key().as_ref(),
+        liquidation_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub liquidator_liquidation_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        repayment_sub_market_owner.key().as_ref(),
+        repayment_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub liquidator_repayment_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        liquidation_sub_market_owner.key().as_ref(),
+        liquidation_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub liquidator_liquidation_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub liquidator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16,
+    liquidati_account_index: u8,
+    liquidator_account_index: u8)]
+pub struct LiquidateAccountSameSubMarket<'info>
+{
+    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
+    pub liquidati_account_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    //Only needed while the Token Reserve restricts liquidations to its allowlist
+    #[account(
+        seeds = [b"liquidatorAllowlist".as_ref(), token_reserve.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub liquidator_allowlist_entry: Option<Account<'info, Structs::LiquidatorAllowlistEntry>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub liquidator_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        liquidator_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub liquidator_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub liquidator_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+//The monthly statement accounts have to exists before calling the refresh_user_health_chunk instruction.
+//Use the create_new_monthly_statement function if it's a new month and it doesn't exist yet.
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct RefreshUserHealth<'info> 
+{
+    ///CHECK: This is the wallet address of the Lending User having their health cached
+    pub lending_user_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userHealth".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::UserHealth::INIT_SPACE + 8)]
+    pub user_health: Account<'info, Structs::UserHealth>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+//This refreshes the Lending User Account and associated Token Reserves
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct RefreshUserHealthChunkAndTokenReserves<'info> 
+{
+    ///CHECK: This is the wallet address of the Lending User having their health refreshed
+    pub lending_user_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    //Optional. When passed in, the Protocol TVL entries of the Token Reserves being refreshed are repriced too
+    #[account(
+        mut,
+        seeds = [b"protocolTvl".as_ref()],
+        bump)]
+    pub protocol_tvl: Option<Box<Account<'info, Structs::ProtocolTvl>>>,
+
+    //Only needed when the Lending User opted into E-Mode
+    #[account(
+        seeds = [b"eModeCategory".as_ref(), lending_user_account.e_mode_category.to_le_bytes().as_ref()],
+        bump = e_mode_category.bump)]
+    pub e_mode_category: Option<Account<'info, Structs::EModeCategory>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct CreateNewMonthlyStatement<'info> 
+{
+    ///CHECK: This is the Sub Market Owner address for the monthly statement that will be created
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the Lending User wallet address for the monthly statement that will be created
+    pub lending_user_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_owner.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user_owner.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Account<'info, Structs::LendingUserMonthlyStatementAccount>,
+
+    //Only needed by keepers collecting the keeper bounty
+    #[account(
+        mut,
+        seeds = [b"keeperBountyVault".as_ref()],
+        bump = keeper_bounty_vault.bump)]
+    pub keeper_bounty_vault: Option<Box<Account<'info, Structs::KeeperBountyVault>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimSubMarketFees<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve for the Sub Market where the fees are being claimed
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the fees being claimed
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Account<'info, Structs::LendingStats>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(initial_sub_market_index: u16, destination_sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimSubMarketFeesAndDepositInDifferentSubMarket<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve for the Sub Market where the fees are being claimed
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Initial Sub Market Owner address for the fees being claimed
+    pub initial_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the Destination Sub Market Owner address for the fees being claimed
+    pub destination_sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Account<'info, Structs::LendingStats>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)],
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), initial_sub_market_owner.key().as_ref(), initial_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub initial_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), destination_sub_market_owner.key().as_ref(), destination_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub destination_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), destination_sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        initial_sub_market_owner.key().as_ref(),
+        initial_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub initial_lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        destination_sub_market_owner.key().as_ref(),
+        destination_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub destination_lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        initial_sub_market_owner.key().as_ref(),
+        initial_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub initial_lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        destination_sub_market_owner.key().as_ref(),
+        destination_sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub destination_lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimSolvencyInsuranceFees<'info> 
+{
+    ///CHECK: This is the Sub Market Owner address for the solvency fees being claimed
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    //The SubMarket doesn't matter that much here since all of the fees are collected from the Token Reserve, but a SubMarket is still neccessary for using the tab account
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    //The SubMarket doesn't matter that much here since all of the fees are collected from the Token Reserve, but a SubMarket is still neccessary for using the monthly statements
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed, //SOL has to be claimed as wSOL then converted to SOL for Treasurer. This function also closes wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub treasurer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimLiquidationFees<'info> 
+{
+    ///CHECK: This is the Token Mint address for the liquidation fees being claimed
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the liquidation fees being claimed
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        mut, 
+        seeds = [b"lendingStats".as_ref()],
+        bump)]
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
+
+    #[account(
+        seeds = [b"liquidationTreasurer".as_ref()],
+        bump)]
+    pub liquidation_treasurer: Account<'info, Structs::LiquidationTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8)]
+    pub lending_user_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimReferralFees<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the referred Lending User
+    pub lending_user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserReferral".as_ref(), lending_user_tab_account.key().as_ref()],
+        bump = lending_user_referral.bump)]
+    pub lending_user_referral: Box<Account<'info, Structs::LendingUserReferral>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub referrer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(emissions_per_second: u64, borrow_rewards: bool)]
+pub struct CreateRewardEmissions<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve whose depositors or borrowers earn the rewards
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"rewardEmissions".as_ref(), token_reserve.key().as_ref(), get_reward_emissions_side_seed(borrow_rewards)], 
+        bump, 
+        space = Structs::RewardEmissions::INIT_SPACE + 8)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_emissions,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardEmissions<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve whose depositors or borrowers earn the rewards
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"rewardEmissions".as_ref(), token_reserve.key().as_ref(), get_reward_emissions_side_seed(reward_emissions.borrow_rewards)], 
+        bump = reward_emissions.bump)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+pub struct SetRewardVestingSchedule<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve whose depositors or borrowers earn the rewards
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"rewardEmissions".as_ref(), token_reserve.key().as_ref(), get_reward_emissions_side_seed(reward_emissions.borrow_rewards)], 
+        bump = reward_emissions.bump)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(
+        init_if_needed, 
+        payer = signer,
+        seeds = [b"rewardVestingSchedule".as_ref(), reward_emissions.key().as_ref()], 
+        bump, 
+        space = Structs::RewardVestingSchedule::INIT_SPACE + 8)]
+    pub reward_vesting_schedule: Account<'info, Structs::RewardVestingSchedule>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimRewards<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve whose depositors or borrowers earn the rewards
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address of the Tab Account earning the rewards
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"rewardEmissions".as_ref(), token_reserve.key().as_ref(), get_reward_emissions_side_seed(reward_emissions.borrow_rewards)], 
+        bump = reward_emissions.bump)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    //Only needed once the CEO has set a Reward Vesting Schedule for the Reward Emissions
+    #[account(
+        seeds = [b"rewardVestingSchedule".as_ref(), reward_emissions.key().as_ref()], 
+        bump = reward_vesting_schedule.bump)]
+    pub reward_vesting_schedule: Option<Box<Account<'info, Structs::RewardVestingSchedule>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_emissions,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = reward_emissions.reward_mint_address,
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserReward".as_ref(), reward_emissions.key().as_ref(), lending_user_tab_account.key().as_ref()],
+        bump, 
+        space = Structs::LendingUserRewardAccount::INIT_SPACE + 8)]
+    pub lending_user_reward_account: Box<Account<'info, Structs::LendingUserRewardAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct CreateSubMarketRewardBoost<'info> 
+{
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"subMarketRewardEmissions".as_ref(), sub_market.key().as_ref(), reward_mint.key().as_ref()], 
+        bump, 
+        space = Structs::RewardEmissions::INIT_SPACE + 8)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_emissions,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16)]
+pub struct UpdateSubMarketRewardBoost<'info> 
+{
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), signer.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarketRewardEmissions".as_ref(), sub_market.key().as_ref(), reward_mint.key().as_ref()], 
+        bump = reward_emissions.bump)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_emissions,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub sub_market_owner_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct ClaimSubMarketRewards<'info> 
+{
+    ///CHECK: This is the Sub Market Owner address of the Tab Account earning the rewards
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()],
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarketRewardEmissions".as_ref(), sub_market.key().as_ref(), reward_mint.key().as_ref()], 
+        bump = reward_emissions.bump)]
+    pub reward_emissions: Box<Account<'info, Structs::RewardEmissions>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = reward_emissions,
+        associated_token::token_program = token_program
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserReward".as_ref(), reward_emissions.key().as_ref(), lending_user_tab_account.key().as_ref()],
+        bump, 
+        space = Structs::LendingUserRewardAccount::INIT_SPACE + 8)]
+    pub lending_user_reward_account: Box<Account<'info, Structs::LendingUserRewardAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_index: u32)]
+pub struct CreateMerkleDistribution<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"merkleDistribution".as_ref(), distribution_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::MerkleDistribution::INIT_SPACE + 8)]
+    pub merkle_distribution: Box<Account<'info, Structs::MerkleDistribution>>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = merkle_distribution,
+        associated_token::token_program = token_program
+    )]
+    pub distribution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_index: u32)]
+pub struct ClaimMerkleDistribution<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"merkleDistribution".as_ref(), distribution_index.to_le_bytes().as_ref()], 
+        bump = merkle_distribution.bump)]
+    pub merkle_distribution: Box<Account<'info, Structs::MerkleDistribution>>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"merkleClaimReceipt".as_ref(), merkle_distribution.key().as_ref(), signer.key().as_ref()], 
+        bump, 
+        space = Structs::MerkleClaimReceipt::INIT_SPACE + 8)]
+    pub merkle_claim_receipt: Box<Account<'info, Structs::MerkleClaimReceipt>>,
+
+    #[account(
+        mut,
+        associated_token::mint = reward_mint,
+        associated_token::authority = merkle_distribution,
+        associated_token::token_program = token_program
+    )]
+    pub distribution_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = merkle_distribution.reward_mint_address,
+        mint::token_program = token_program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct CreateStakingPool<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"stakingPool".as_ref()], 
+        bump, 
+        space = Structs::StakingPool::INIT_SPACE + 8)]
+    pub staking_pool: Box<Account<'info, Structs::StakingPool>>,
+
+    #[account(
+        init,
+        payer = signer,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staking_pool,
+        associated_token::token_program = token_program
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakingPool<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"stakingPool".as_ref()], 
+        bump = staking_pool.bump)]
+    pub staking_pool: Box<Account<'info, Structs::StakingPool>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct StakeTokens<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"stakingPool".as_ref()], 
+        bump = staking_pool.bump)]
+    pub staking_pool: Box<Account<'info, Structs::StakingPool>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staking_pool,
+        associated_token::token_program = token_program
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = staking_pool.stake_mint_address,
+        mint::token_program = token_program
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"lendingUserStake".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump, 
+        space = Structs::LendingUserStake::INIT_SPACE + 8)]
+    pub lending_user_stake: Box<Account<'info, Structs::LendingUserStake>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_stake_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct UnstakeTokens<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"stakingPool".as_ref()], 
+        bump = staking_pool.bump)]
+    pub staking_pool: Box<Account<'info, Structs::StakingPool>>,
+
+    #[account(
+        mut,
+        associated_token::mint = stake_mint,
+        associated_token::authority = staking_pool,
+        associated_token::token_program = token_program
+    )]
+    pub stake_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = staking_pool.stake_mint_address,
+        mint::token_program = token_program
+    )]
+    pub stake_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserStake".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump = lending_user_stake.bump)]
+    pub lending_user_stake: Box<Account<'info, Structs::LendingUserStake>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = stake_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_stake_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(asset_category: u8)]
+pub struct CreateEModeCategory<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"eModeCategory".as_ref(), asset_category.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::EModeCategory::INIT_SPACE + 8)]
+    pub e_mode_category: Account<'info, Structs::EModeCategory>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(asset_category: u8)]
+pub struct UpdateEModeCategory<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"eModeCategory".as_ref(), asset_category.to_le_bytes().as_ref()], 
+        bump = e_mode_category.bump)]
+    pub e_mode_category: Account<'info, Structs::EModeCategory>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8, asset_category: u8)]
+pub struct SetEModeCategory<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    //Only needed when opting into an E-Mode category, not when turning E-Mode off
+    #[account(
+        seeds = [b"eModeCategory".as_ref(), asset_category.to_le_bytes().as_ref()], 
+        bump = e_mode_category.bump)]
+    pub e_mode_category: Option<Account<'info, Structs::EModeCategory>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve the Insurance Fund is for
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
+
+    #[account(
+        init, 
+        payer = signer,
+        seeds = [b"insuranceFund".as_ref(), token_mint_address.key().as_ref()], 
+        bump, 
+        space = Structs::InsuranceFund::INIT_SPACE + 8)]
+    pub insurance_fund: Account<'info, Structs::InsuranceFund>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct UpdateInsuranceFundShareRate<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve the Insurance Fund is for
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Account<'info, Structs::TokenReserve>,
+
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), token_mint_address.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Account<'info, Structs::InsuranceFund>,
+
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
+    #[account(
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, liquidati_account_index: u8)]
+pub struct WriteOffBadDebt<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve with the bad debt
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the debt being written off
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the insolvent Lending User whose debt is being written off
+    pub liquidati_account_owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    //The bad debt circuit breaker compares bad debt against the Insurance Fund balance. Token Reserves without an Insurance Fund can leave it out
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), token_mint_address.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Option<Box<Account<'info, Structs::InsuranceFund>>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        liquidati_account_owner.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        liquidati_account_owner.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16)]
+pub struct DeployInsuranceFund<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve with the bad debt
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the bad debt being covered
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"insuranceFund".as_ref(), token_mint_address.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16)]
+pub struct SocializeLoss<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve with the bad debt
+    pub token_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the bad debt being socialized
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), token_mint_address.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, liquidati_account_index: u8)]
+pub struct SeizeCollateralForAuction<'info> 
+{
+    ///CHECK: This is the Token Mint address for the Token Reserve of the collateral being seized
+    pub collateral_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Token Mint address for the Token Reserve whose Insurance Fund is recapitalized by the auction
+    pub proceeds_mint_address: UncheckedAccount<'info>,
+    ///CHECK: This is the Sub Market Owner address for the collateral being seized
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the insolvent Lending User whose collateral is being seized
+    pub liquidati_account_owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), collateral_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    //The proceeds Insurance Fund must exist before collateral can be auctioned off for it
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), proceeds_mint_address.key().as_ref()], 
+        bump)]
+    pub proceeds_insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()],
+        bump)]
+    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        liquidati_account_owner.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        liquidati_account_owner.key().as_ref(),
+        liquidati_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub liquidati_monthly_statement_account: Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"collateralAuction".as_ref(), collateral_mint_address.key().as_ref(), proceeds_mint_address.key().as_ref()], 
+        bump, 
+        space = Structs::CollateralAuction::INIT_SPACE + 8)]
+    pub collateral_auction: Box<Account<'info, Structs::CollateralAuction>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct BidCollateralAuction<'info> 
+{
+    #[account(
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), collateral_mint.key().as_ref(), get_token_reserve_version_seed(&collateral_token_reserve)], 
+        bump = collateral_token_reserve.bump)]
+    pub collateral_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), proceeds_mint.key().as_ref(), get_token_reserve_version_seed(&proceeds_token_reserve)], 
+        bump = proceeds_token_reserve.bump)]
+    pub proceeds_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"insuranceFund".as_ref(), proceeds_mint.key().as_ref()], 
+        bump)]
+    pub proceeds_insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        mut,
+        seeds = [b"collateralAuction".as_ref(), collateral_mint.key().as_ref(), proceeds_mint.key().as_ref()], 
+        bump)]
+    pub collateral_auction: Box<Account<'info, Structs::CollateralAuction>>,
+
+    #[account(
+        init_if_needed, //SOL has to be withdrawn as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub bidder_collateral_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed, //SOL has to be paid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        payer = signer,
+        associated_token::mint = proceeds_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = proceeds_token_program
+    )]
+    pub bidder_proceeds_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = collateral_token_reserve,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = proceeds_mint,
+        associated_token::authority = proceeds_token_reserve,
+        associated_token::token_program = proceeds_token_program
+    )]
+    pub proceeds_token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub proceeds_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+    pub proceeds_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[cfg(feature = "dev")]
+#[derive(Accounts)]
+pub struct RunAccrualTestVectors<'info> 
+{
+    pub signer: Signer<'info>
+}
+
+#[cfg(feature = "compute-metrics")]
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct MeasureComputeUnits<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"computeMetrics".as_ref()],
+        bump,
+        space = Structs::ComputeMetrics::INIT_SPACE + 8)]
+    pub compute_metrics: Box<Account<'info, Structs::ComputeMetrics>>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
+        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
+        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct GetTokenReserveRateCurve<'info> 
+{
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>
+}
+
+#[derive(Accounts)]
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct GetLendingUserTabPosition<'info> 
+{
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the Lending User whose position is being viewed
+    pub lending_user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    #[account(
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct GetUserHealth<'info> 
+{
+    ///CHECK: This is the wallet address of the Lending User whose health is being viewed
+    pub lending_user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingUserAccount".as_ref(), lending_user.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+
+    //The temp price account is seeded with the signer's address
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+pub struct SyncReserve<'info> 
+{
+    #[account(
+        seeds = [b"solvencyTreasurer".as_ref()],
+        bump)]
+    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    //The Insurance Fund's tokens are held in the Token Reserve ATA, so its balance is needed to reconcile the ATA
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), token_mint.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct VerifyReserveInvariants<'info> 
+{
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"insuranceFund".as_ref(), token_mint.key().as_ref()], 
+        bump)]
+    pub insurance_fund: Box<Account<'info, Structs::InsuranceFund>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenReserveAuditSnapshot<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"tokenReserveAuditSnapshot".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve), token_reserve.audit_snapshot_count.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::TokenReserveAuditSnapshot::INIT_SPACE + 8)]
+    pub token_reserve_audit_snapshot: Box<Account<'info, Structs::TokenReserveAuditSnapshot>>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolTvl<'info> 
+{
     #[account(
-        mut,
-        seeds = [b"tokenReserve".as_ref(), liquidation_mint.key().as_ref()], 
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub liquidation_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
-        init_if_needed,
+        init, 
         payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
+        seeds = [b"protocolTvl".as_ref()],
         bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+        space = Structs::ProtocolTvl::INIT_SPACE + 8)]
+    pub protocol_tvl: Box<Account<'info, Structs::ProtocolTvl>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
 
+#[derive(Accounts)]
+pub struct RefreshProtocolTvl<'info> 
+{
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        repayment_token_reserve.token_id.to_le_bytes().as_ref(),
-        repayment_sub_market_owner.key().as_ref(),
-        repayment_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidati_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub liquidator_repayment_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        mut,
+        seeds = [b"protocolTvl".as_ref()],
+        bump)]
+    pub protocol_tvl: Box<Account<'info, Structs::ProtocolTvl>>,
 
     #[account(
-        init_if_needed,
+        seeds = [b"oraclePriceValidator".as_ref()],
+        bump)]
+    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(day: u32)]
+pub struct SnapshotReserveDay<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        init,
         payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        liquidation_token_reserve.token_id.to_le_bytes().as_ref(),
-        liquidation_sub_market_owner.key().as_ref(),
-        liquidation_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()],
+        seeds = [b"tokenReserveDaySnapshot".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve), day.to_le_bytes().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub liquidator_liquidation_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        space = Structs::TokenReserveDaySnapshot::INIT_SPACE + 8)]
+    pub token_reserve_day_snapshot: Box<Account<'info, Structs::TokenReserveDaySnapshot>>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
+    //Only needed by keepers collecting the keeper bounty
     #[account(
-        init_if_needed,
+        mut,
+        seeds = [b"keeperBountyVault".as_ref()],
+        bump = keeper_bounty_vault.bump)]
+    pub keeper_bounty_vault: Option<Box<Account<'info, Structs::KeeperBountyVault>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct RefreshTokenReserveInterest<'info> 
+{
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    //Only needed by keepers collecting the keeper bounty
+    #[account(
+        mut,
+        seeds = [b"keeperBountyVault".as_ref()],
+        bump = keeper_bounty_vault.bump)]
+    pub keeper_bounty_vault: Option<Box<Account<'info, Structs::KeeperBountyVault>>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>
+}
+
+#[derive(Accounts)]
+pub struct SetKeeperBounty<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        init_if_needed, 
         payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        repayment_token_reserve.token_id.to_le_bytes().as_ref(),
-        repayment_sub_market_owner.key().as_ref(),
-        repayment_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"keeperBountyVault".as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub liquidator_repayment_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        space = Structs::KeeperBountyVault::INIT_SPACE + 8)]
+    pub keeper_bounty_vault: Account<'info, Structs::KeeperBountyVault>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct CollectDeadTabAccount<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the Lending User who owns the dead Tab Account. The rest of the rent goes back to them
+    #[account(mut)]
+    pub lending_user: UncheckedAccount<'info>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), lending_user.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(statement_month: u8, statement_year: u16, token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct CollectArchivedMonthlyStatementAccount<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the Lending User who owns the archived Monthly Statement. The rest of the rent goes back to them
+    #[account(mut)]
+    pub lending_user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
+
+    #[account(
+        mut,
         seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        liquidation_token_reserve.token_id.to_le_bytes().as_ref(),
-        liquidation_sub_market_owner.key().as_ref(),
-        liquidation_sub_market_index.to_le_bytes().as_ref(),
+        statement_month.to_le_bytes().as_ref(),
+        statement_year.to_le_bytes().as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        lending_user.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    ///CHECK: This is any Lending Protocol account that needs to be migrated to the current layout. migrate_account checks its discriminator before touching it
+    #[account(
+        mut,
+        owner = crate::ID)]
+    pub account_to_migrate: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct ReallocLendingProtocol<'info> 
+{
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    ///CHECK: The Lending Protocol account is unchecked since it may be too small to deserialize into the current layout until it's grown
+    #[account(
+        mut,
+        seeds = [b"lendingProtocol".as_ref()],
+        bump)]
+    pub lending_protocol: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct ReallocTokenReserve<'info> 
+{
+    ///CHECK: This is the token mint address of the Token Reserve the CEO wants to grow
+    pub token_mint_address: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    ///CHECK: The Token Reserve account is unchecked since it may be too small to deserialize into the current layout until it's grown
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump)]
+    pub token_reserve: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(user_account_index: u8)]
+pub struct ReallocLendingUserAccount<'info> 
+{
+    ///CHECK: The Lending User Account is unchecked since it may be too small to deserialize into the current layout until it's grown. Only its owner can grow it
+    #[account(
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct ReallocLendingUserTabAccount<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    ///CHECK: The Lending User Tab Account is unchecked since it may be too small to deserialize into the current layout until it's grown. Only its owner can grow it
+    #[account(
+        mut,
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub liquidator_liquidation_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_tab_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
 
+#[derive(Accounts)]
+pub struct AddLpTokenPriceAdapter<'info> 
+{
     #[account(
-        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
-        payer = signer,
-        associated_token::mint = repayment_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = repayment_token_program
-    )]
-    pub liquidator_repayment_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        seeds = [b"lendingProtocolCEO".as_ref()],
+        bump)]
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
+
+    #[account(
+        seeds = [b"tokenReserve".as_ref(), lp_token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
-        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
+        init,
         payer = signer,
-        associated_token::mint = liquidation_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = liquidation_token_program
-    )]
-    pub liquidator_liquidation_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        seeds = [b"lpTokenPriceAdapter".as_ref(), lp_token_mint.key().as_ref()], 
+        bump, 
+        space = Structs::LpTokenPriceAdapter::INIT_SPACE + 8)]
+    pub lp_token_price_adapter: Box<Account<'info, Structs::LpTokenPriceAdapter>>,
 
-    pub repayment_mint: Box<InterfaceAccount<'info, Mint>>,
-    pub liquidation_mint: Box<InterfaceAccount<'info, Mint>>,
-    pub repayment_token_program: Interface<'info, TokenInterface>,
-    pub liquidation_token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+    pub token_a_mint: InterfaceAccount<'info, Mint>,
+    //Left out for receipt tokens backed by a single vault
+    pub token_b_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_a_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_b_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -898,295 +5087,198 @@ pub struct LiquidateAccount<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(repayment_sub_market_index: u16,
-    liquidation_sub_market_index: u16,
-    liquidati_account_index: u8,
-    liquidator_account_index: u8)]
-pub struct LiquidateAccountSameToken<'info>
+pub struct UpdateLpTokenPriceAdapter<'info> 
 {
-    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
-    pub liquidati_account_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the wallet address of the user who owns the repayment Sub Market
-    pub repayment_sub_market_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the wallet address of the user who owns the liquidation Sub Market
-    pub liquidation_sub_market_owner: UncheckedAccount<'info>,
-
     #[account(
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
     pub lending_protocol: Account<'info, Structs::LendingProtocol>,
 
     #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
-    #[account(
-        mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump)]
-    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+    ///CHECK: This is the LP token mint address of the adapter the CEO wants to update
+    pub lp_token_mint_address: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()], 
+        seeds = [b"lpTokenPriceAdapter".as_ref(), lp_token_mint_address.key().as_ref()], 
         bump)]
-    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
-
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
-
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        repayment_sub_market_owner.key().as_ref(),
-        repayment_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidati_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub liquidator_repayment_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+    pub lp_token_price_adapter: Box<Account<'info, Structs::LpTokenPriceAdapter>>,
 
+    //Only needed when the signer is calling as the Risk Admin instead of the CEO
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        liquidation_sub_market_owner.key().as_ref(),
-        liquidation_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub liquidator_liquidation_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        seeds = [b"lendingProtocolRoles".as_ref()],
+        bump)]
+    pub roles: Option<Account<'info, Structs::LendingProtocolRoles>>,
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        repayment_sub_market_owner.key().as_ref(),
-        repayment_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub liquidator_repayment_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        liquidation_sub_market_owner.key().as_ref(),
-        liquidation_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub liquidator_liquidation_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+#[derive(Accounts)]
+pub struct RefreshLpTokenPrice<'info> 
+{
+    ///CHECK: This is the address of the lending user the price data was created for
+    pub lending_user_address: UncheckedAccount<'info>,
 
     #[account(
-        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
-        payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub liquidator_ata: InterfaceAccount<'info, TokenAccount>,
+        seeds = [b"lpTokenPriceAdapter".as_ref(), lp_token_mint.key().as_ref()], 
+        bump)]
+    pub lp_token_price_adapter: Box<Account<'info, Structs::LpTokenPriceAdapter>>,
 
+    //The derived LP token price is appended to the price data, so the account grows by one price entry
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = token_reserve,
-        associated_token::token_program = token_program
-    )]
-    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        seeds = [b"oraclePriceData".as_ref(), lending_user_address.key().as_ref()], 
+        bump,
+        realloc = temp_price_account.to_account_info().data_len() + 17,
+        realloc::payer = signer,
+        realloc::zero = false)]
+    pub temp_price_account: Account<'info, Structs::TempOraclePriceAccount>,
 
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub lp_token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_a_vault: InterfaceAccount<'info, TokenAccount>,
+    //Left out for receipt tokens backed by a single vault
+    pub token_b_vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16,
-    liquidati_account_index: u8,
-    liquidator_account_index: u8)]
-pub struct LiquidateAccountSameSubMarket<'info>
+pub struct AddLstTokenPriceAdapter<'info> 
 {
-    ///CHECK: This is the wallet address of the liquidati (borrower) being liquidated
-    pub liquidati_account_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the wallet address of the user who owns the Sub Market
-    pub sub_market_owner: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"lendingProtocol".as_ref()],
-        bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
-
     #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
+        seeds = [b"lendingProtocolCEO".as_ref()],
         bump)]
-    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+    pub ceo: Account<'info, Structs::LendingProtocolCEO>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump)]
+        seeds = [b"tokenReserve".as_ref(), lst_token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
-        mut,
-        seeds = [b"lendingUserAccount".as_ref(), liquidati_account_owner.key().as_ref(), liquidati_account_index.to_le_bytes().as_ref()], 
-        bump)]
-    pub liquidati_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
-
-    #[account(
-        init_if_needed,
+        init,
         payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), liquidator_account_index.to_le_bytes().as_ref()],
+        seeds = [b"lstTokenPriceAdapter".as_ref(), lst_token_mint.key().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub liquidator_lending_account: Box<Account<'info, Structs::LendingUserAccount>>,
+        space = Structs::LstTokenPriceAdapter::INIT_SPACE + 8)]
+    pub lst_token_price_adapter: Box<Account<'info, Structs::LstTokenPriceAdapter>>,
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidati_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub liquidator_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+    pub lst_token_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        liquidator_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub liquidator_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+    ///CHECK: SPL Stake Pool account. The owner and pool mint are checked in the handler
+    pub stake_pool: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+#[derive(Accounts)]
+pub struct RefreshLstTokenPrice<'info> 
+{
+    ///CHECK: This is the address of the lending user the price data was created for
+    pub lending_user_address: UncheckedAccount<'info>,
 
     #[account(
-        init_if_needed, //SOL has to be repaid as wSOL then converted to SOL for User. This function also closes user wSOL ata if it is empty.
-        payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub liquidator_ata: InterfaceAccount<'info, TokenAccount>,
+        seeds = [b"lstTokenPriceAdapter".as_ref(), lst_token_mint.key().as_ref()], 
+        bump)]
+    pub lst_token_price_adapter: Box<Account<'info, Structs::LstTokenPriceAdapter>>,
 
+    //The derived LST price is appended to the price data, so the account grows by one price entry
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = token_reserve,
-        associated_token::token_program = token_program
-    )]
-    pub token_reserve_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        seeds = [b"oraclePriceData".as_ref(), lending_user_address.key().as_ref()], 
+        bump,
+        realloc = temp_price_account.to_account_info().data_len() + 17,
+        realloc::payer = signer,
+        realloc::zero = false)]
+    pub temp_price_account: Account<'info, Structs::TempOraclePriceAccount>,
 
-    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub lst_token_mint: InterfaceAccount<'info, Mint>,
+
+    ///CHECK: SPL Stake Pool account. It's checked against the LST Token Price Adapter in the handler
+    pub stake_pool: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>
 }
 
-//The monthly statement accounts have to exists before calling the refresh_user_health_chunk instruction.
-//Use the create_new_monthly_statement function if it's a new month and it doesn't exist yet.
-//This refreshes the Lending User Account and associated Token Reserves
 #[derive(Accounts)]
-#[instruction(user_account_index: u8)]
-pub struct RefreshUserHealthChunkAndTokenReserves<'info> 
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct PostBorrowIntent<'info> 
 {
-    ///CHECK: This is the wallet address of the Lending User having their health refreshed
-    pub lending_user_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
-        seeds = [b"lendingProtocol".as_ref()],
+        seeds = [b"oraclePriceValidator".as_ref()],
         bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
 
     #[account(
-        seeds = [b"oraclePriceValidator".as_ref()],
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
+    pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub price_validator: Account<'info, Structs::OraclePriceValidator>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
     #[account(
-        mut,
-        seeds = [b"lendingUserAccount".as_ref(), lending_user_owner.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
         bump)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"borrowIntent".as_ref(),
+        token_reserve.token_id.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
+        signer.key().as_ref(),
+        user_account_index.to_le_bytes().as_ref()], 
+        bump, 
+        space = Structs::BorrowIntent::INIT_SPACE + 8)]
+    pub borrow_intent: Box<Account<'info, Structs::BorrowIntent>>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub system_program: Program<'info, System>
 }
 
-#[derive(Accounts)]
-#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
-pub struct CreateNewMonthlyStatement<'info> 
-{
-    ///CHECK: This is the Sub Market Owner address for the monthly statement that will be created
-    pub sub_market_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the Lending User wallet address for the monthly statement that will be created
-    pub lending_user_owner: UncheckedAccount<'info>,
-
-    #[account(
-        seeds = [b"lendingProtocol".as_ref()],
-        bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
-
-    #[account(
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        lending_user_owner.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump)]
-    pub lending_user_tab_account: Account<'info, Structs::LendingUserTabAccount>,
+#[derive(Accounts)]
+#[instruction(token_id: u8, sub_market_index: u16, user_account_index: u8)]
+pub struct CancelBorrowIntent<'info> 
+{
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
-        init,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        mut,
+        close = signer,
+        seeds = [b"borrowIntent".as_ref(),
         token_id.to_le_bytes().as_ref(),
         sub_market_owner.key().as_ref(),
         sub_market_index.to_le_bytes().as_ref(),
-        lending_user_owner.key().as_ref(),
+        signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Account<'info, Structs::LendingUserMonthlyStatementAccount>,
+        bump)]
+    pub borrow_intent: Box<Account<'info, Structs::BorrowIntent>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -1195,71 +5287,84 @@ pub struct CreateNewMonthlyStatement<'info>
 
 #[derive(Accounts)]
 #[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct ClaimSubMarketFees<'info> 
+pub struct ExportPosition<'info> 
 {
-    ///CHECK: This is the Token Mint address for the Token Reserve for the Sub Market where the fees are being claimed
-    pub token_mint_address: UncheckedAccount<'info>,
-    ///CHECK: This is the Sub Market Owner address for the fees being claimed
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
     pub sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
 
     #[account(
         mut, 
         seeds = [b"lendingStats".as_ref()],
         bump)]
-    pub lending_stats: Account<'info, Structs::LendingStats>,
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
 
     #[account(
-        mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()], 
+        seeds = [b"oraclePriceValidator".as_ref()],
         bump)]
+    pub price_validator: Box<Account<'info, Structs::OraclePriceValidator>>,
+
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
         mut,
         seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub sub_market: Account<'info, Structs::SubMarket>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
+        mut,
         seeds = [b"lendingUserTabAccount".as_ref(),
         token_reserve.token_id.to_le_bytes().as_ref(),
         sub_market_owner.key().as_ref(),
         sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub lending_user_tab_account: Account<'info, Structs::LendingUserTabAccount>,
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Account<'info, Structs::LendingUserMonthlyStatementAccount>,
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    //The successor program's Token Reserve. It's checked against the registered successor program in the handler
+    pub successor_token_reserve: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = successor_token_reserve,
+        token::token_program = token_program
+    )]
+    pub successor_token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    //Only needed when the health refresh isn't in the same transaction. The cached User Health is used instead if it's still fresh
+    #[account(
+        mut,
+        seeds = [b"userHealth".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub user_health: Option<Box<Account<'info, Structs::UserHealth>>>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -1267,108 +5372,72 @@ pub struct ClaimSubMarketFees<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(initial_sub_market_index: u16, destination_sub_market_index: u16, user_account_index: u8)]
-pub struct ClaimSubMarketFeesAndDepositInDifferentSubMarket<'info> 
+#[instruction(sub_market_index: u16, user_account_index: u8)]
+pub struct ImportPosition<'info> 
 {
-    ///CHECK: This is the Token Mint address for the Token Reserve for the Sub Market where the fees are being claimed
-    pub token_mint_address: UncheckedAccount<'info>,
-    ///CHECK: This is the Initial Sub Market Owner address for the fees being claimed
-    pub initial_sub_market_owner: UncheckedAccount<'info>,
-    ///CHECK: This is the Destination Sub Market Owner address for the fees being claimed
-    pub destination_sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
 
     #[account(
         mut, 
         seeds = [b"lendingStats".as_ref()],
         bump)]
-    pub lending_stats: Account<'info, Structs::LendingStats>,
+    pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()],
-        bump)]
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
         mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), initial_sub_market_owner.key().as_ref(), initial_sub_market_index.to_le_bytes().as_ref()], 
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub initial_sub_market: Box<Account<'info, Structs::SubMarket>>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
     #[account(
-        mut,
-        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), destination_sub_market_owner.key().as_ref(), destination_sub_market_index.to_le_bytes().as_ref()], 
+        seeds = [b"subMarketAllowlist".as_ref(), sub_market.key().as_ref(), signer.key().as_ref()], 
         bump)]
-    pub destination_sub_market: Box<Account<'info, Structs::SubMarket>>,
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
 
     #[account(
         init_if_needed,
         payer = signer,
         seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
         bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
-
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserTabAccount".as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        initial_sub_market_owner.key().as_ref(),
-        initial_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub initial_lending_user_tab_account: Account<'info, Structs::LendingUserTabAccount>,
+        space = Structs::LendingUserAccount::INIT_SPACE + 8)]
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
 
     #[account(
         init_if_needed,
         payer = signer,
         seeds = [b"lendingUserTabAccount".as_ref(),
         token_reserve.token_id.to_le_bytes().as_ref(),
-        destination_sub_market_owner.key().as_ref(),
-        destination_sub_market_index.to_le_bytes().as_ref(),
+        sub_market_owner.key().as_ref(),
+        sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub destination_lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        initial_sub_market_owner.key().as_ref(),
-        initial_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub initial_lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
-        destination_sub_market_owner.key().as_ref(),
-        destination_sub_market_index.to_le_bytes().as_ref(),
-        signer.key().as_ref(),
-        user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub destination_lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -1377,9 +5446,9 @@ pub struct ClaimSubMarketFeesAndDepositInDifferentSubMarket<'info>
 
 #[derive(Accounts)]
 #[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct ClaimSolvencyInsuranceFees<'info> 
+pub struct MigratePositionToTokenReserveVersion<'info> 
 {
-    ///CHECK: This is the Sub Market Owner address for the solvency fees being claimed
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
     pub sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
@@ -1394,74 +5463,84 @@ pub struct ClaimSolvencyInsuranceFees<'info>
     pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
 
     #[account(
-        seeds = [b"solvencyTreasurer".as_ref()],
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
         bump)]
-    pub solvency_treasurer: Account<'info, Structs::SolvencyTreasurer>,
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref()], 
-        bump)]
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
-    //The SubMarket doesn't matter that much here since all of the fees are collected from the Token Reserve, but a SubMarket is still neccessary for using the tab account
     #[account(
-        init_if_needed,
-        payer = signer,
+        mut,
         seeds = [b"lendingUserTabAccount".as_ref(),
         token_reserve.token_id.to_le_bytes().as_ref(),
         sub_market_owner.key().as_ref(),
         sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub lending_user_tab_account: Box<Account<'info, Structs::LendingUserTabAccount>>,
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = token_reserve,
+        associated_token::token_program = token_program
+    )]
+    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+
+    //The newer version of the mint's Token Reserve the position moves to. The SubMarket owner has to have created the same SubMarket on it
+    #[account(
+        mut,
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&new_token_reserve)], 
+        bump = new_token_reserve.bump)]
+    pub new_token_reserve: Box<Account<'info, Structs::TokenReserve>>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), new_token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub new_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), new_sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
 
-    //The SubMarket doesn't matter that much here since all of the fees are collected from the Token Reserve, but a SubMarket is still neccessary for using the monthly statements
     #[account(
         init_if_needed,
         payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
-        token_reserve.token_id.to_le_bytes().as_ref(),
+        seeds = [b"lendingUserTabAccount".as_ref(),
+        new_token_reserve.token_id.to_le_bytes().as_ref(),
         sub_market_owner.key().as_ref(),
         sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>,
-
-    #[account(
-        init_if_needed, //SOL has to be claimed as wSOL then converted to SOL for Treasurer. This function also closes wSOL ata if it is empty.
-        payer = signer,
-        associated_token::mint = token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program
-    )]
-    pub treasurer_ata: InterfaceAccount<'info, TokenAccount>,
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub new_lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
 
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = token_reserve,
+        associated_token::authority = new_token_reserve,
         associated_token::token_program = token_program
     )]
-    pub token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
+    pub new_token_reserve_ata: InterfaceAccount<'info, TokenAccount>,
 
     pub token_mint: InterfaceAccount<'info, Mint>,
     pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -1469,18 +5548,18 @@ pub struct ClaimSolvencyInsuranceFees<'info>
 }
 
 #[derive(Accounts)]
-#[instruction(sub_market_index: u16, user_account_index: u8)]
-pub struct ClaimLiquidationFees<'info> 
+#[instruction(sub_market_index: u16, new_sub_market_index: u16, user_account_index: u8)]
+pub struct MigrateSubMarket<'info> 
 {
-    ///CHECK: This is the Token Mint address for the liquidation fees being claimed
-    pub token_mint_address: UncheckedAccount<'info>,
-    ///CHECK: This is the Sub Market Owner address for the liquidation fees being claimed
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
     pub sub_market_owner: UncheckedAccount<'info>,
+    ///CHECK: This is the wallet address of the user who owns the Sub Market the position moves to
+    pub new_sub_market_owner: UncheckedAccount<'info>,
 
     #[account(
         seeds = [b"lendingProtocol".as_ref()],
         bump)]
-    pub lending_protocol: Account<'info, Structs::LendingProtocol>,
+    pub lending_protocol: Box<Account<'info, Structs::LendingProtocol>>,
 
     #[account(
         mut, 
@@ -1489,57 +5568,60 @@ pub struct ClaimLiquidationFees<'info>
     pub lending_stats: Box<Account<'info, Structs::LendingStats>>,
 
     #[account(
-        seeds = [b"liquidationTreasurer".as_ref()],
+        mut,
+        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()], 
         bump)]
-    pub liquidation_treasurer: Account<'info, Structs::LiquidationTreasurer>,
+    pub lending_user_account: Box<Account<'info, Structs::LendingUserAccount>>,
 
     #[account(
         mut,
-        seeds = [b"tokenReserve".as_ref(), token_mint_address.key().as_ref()], 
-        bump)]
+        seeds = [b"tokenReserve".as_ref(), token_mint.key().as_ref(), get_token_reserve_version_seed(&token_reserve)], 
+        bump = token_reserve.bump)]
     pub token_reserve: Box<Account<'info, Structs::TokenReserve>>,
 
     #[account(
         mut,
         seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), sub_market_owner.key().as_ref(), sub_market_index.to_le_bytes().as_ref()], 
         bump)]
-    pub sub_market: Account<'info, Structs::SubMarket>,
-
-    #[account(
-        init_if_needed,
-        payer = signer,
-        seeds = [b"lendingUserAccount".as_ref(), signer.key().as_ref(), user_account_index.to_le_bytes().as_ref()],
-        bump, 
-        space = size_of::<Structs::LendingUserAccount>() + LENDING_USER_ACCOUNT_EXTRA_SIZE + 8)]
-    pub lending_user_account: Account<'info, Structs::LendingUserAccount>,
+    pub sub_market: AccountLoader<'info, Structs::SubMarket>,
 
     #[account(
-        init_if_needed,
-        payer = signer,
+        mut,
         seeds = [b"lendingUserTabAccount".as_ref(),
         token_reserve.token_id.to_le_bytes().as_ref(),
         sub_market_owner.key().as_ref(),
         sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
-        bump, 
-        space = size_of::<Structs::LendingUserTabAccount>() + 8)]
-    pub lending_user_tab_account: Account<'info, Structs::LendingUserTabAccount>,
+        bump)]
+    pub lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"subMarket".as_ref(), token_reserve.token_id.to_le_bytes().as_ref(), new_sub_market_owner.key().as_ref(), new_sub_market_index.to_le_bytes().as_ref()], 
+        bump)]
+    pub new_sub_market: AccountLoader<'info, Structs::SubMarket>,
+
+    //Only needed when the SubMarket owner has turned on the depositor allowlist
+    #[account(
+        seeds = [b"subMarketAllowlist".as_ref(), new_sub_market.key().as_ref(), signer.key().as_ref()], 
+        bump)]
+    pub sub_market_allowlist_entry: Option<Account<'info, Structs::SubMarketAllowlistEntry>>,
 
     #[account(
         init_if_needed,
         payer = signer,
-        seeds = [b"userMonthlyStatementAccount".as_ref(),//lendingUserMonthlyStatementAccount was too long, can only be 32 characters, lol
-        lending_protocol.current_statement_month.to_le_bytes().as_ref(),
-        lending_protocol.current_statement_year.to_le_bytes().as_ref(),
+        seeds = [b"lendingUserTabAccount".as_ref(),
         token_reserve.token_id.to_le_bytes().as_ref(),
-        sub_market_owner.key().as_ref(),
-        sub_market_index.to_le_bytes().as_ref(),
+        new_sub_market_owner.key().as_ref(),
+        new_sub_market_index.to_le_bytes().as_ref(),
         signer.key().as_ref(),
         user_account_index.to_le_bytes().as_ref()], 
         bump, 
-        space = size_of::<Structs::LendingUserMonthlyStatementAccount>() + 8)]
-    pub lending_user_monthly_statement_account: Account<'info, Structs::LendingUserMonthlyStatementAccount>,
+        space = Structs::LendingUserTabAccount::INIT_SPACE + 8)]
+    pub new_lending_user_tab_account: AccountLoader<'info, Structs::LendingUserTabAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut)]
     pub signer: Signer<'info>,
@@ -161,5 +161,245 @@ pub enum LendingError
     #[msg("Duplicate SubMarket Detected")]
     DuplicateSubMarket,
     #[msg("Each Lending User Account can have no more than 5 Tab Accounts. Please make new account")]
-    TooManyTabAccounts
+    TooManyTabAccounts,
+    #[msg("Oracle price ID override did not match the SubMarket's requested override")]
+    OracleOverrideMisMatched,
+    #[msg("The insurance fund share of interest earned can't be greater than 10%")]
+    InvalidInsuranceFundShareRate,
+    #[msg("Only debt left on an insolvent account with no remaining collateral can be written off as bad debt")]
+    NotBadDebt,
+    #[msg("Not enough tokens in the insurance fund to cover this bad debt")]
+    InsufficientInsuranceFund,
+    #[msg("Auction duration must be greater than 0 and the max discount can't be greater than 50%")]
+    InvalidAuctionSettings,
+    #[msg("Not enough seized collateral left in the auction for this bid")]
+    InsufficientAuctionCollateral,
+    #[msg("Losses can only be socialized after the insurance fund is exhausted")]
+    InsuranceFundNotExhausted,
+    #[msg("The rate curve must be sampled at 2 to 101 points")]
+    InvalidRateCurveSampleCount,
+    #[msg("The Monthly Statement account is required unless the Lending User Account opted out of Monthly Statements")]
+    MissingMonthlyStatementAccount,
+    #[msg("This Lending User Account opted out of Monthly Statements")]
+    MonthlyStatementsDisabled,
+    #[msg("Only the pending fee collector can accept the SubMarket fee collector role")]
+    NotPendingFeeCollector,
+    #[msg("This action needs the Lending User Account's co-signer to sign")]
+    MissingCoSigner,
+    #[msg("The co-signer doesn't match the Lending User Account's co-signer")]
+    CoSignerMisMatched,
+    #[msg("The Token Reserve ATA doesn't hold more tokens than the accounting expects")]
+    NoReserveSurplus,
+    #[msg("A Token Reserve can't use its own price feed as its USD quote price feed")]
+    InvalidUsdQuotePriceId,
+    #[msg("A Lending User Account can't be closed while it has Tab Accounts")]
+    LendingUserAccountHasTabAccounts,
+    #[msg("The bad debt circuit breaker rate can't be greater than 100%")]
+    InvalidBadDebtCircuitBreakerRate,
+    #[msg("New borrows are paused")]
+    BorrowsPaused,
+    #[msg("Only Tab Accounts with no balance or debt that have been inactive for a year can be collected")]
+    TabAccountNotDead,
+    #[msg("Only a Lending User Account's last Tab Account can be collected")]
+    TabAccountNotLast,
+    #[msg("Only Monthly Statements older than the archival window can be collected")]
+    MonthlyStatementNotArchived,
+    #[msg("This account is already on the current account version")]
+    AccountAlreadyMigrated,
+    #[msg("This account isn't a Lending Protocol account that can be migrated")]
+    UnknownAccountType,
+    #[msg("The LP token pool vault doesn't hold the expected token")]
+    LpTokenVaultMintMisMatched,
+    #[msg("This isn't the LP token pool vault set on the LP Token Price Adapter")]
+    LpTokenVaultMisMatched,
+    #[msg("LP token constituent price IDs can't be missing or be the LP token's own Token ID")]
+    InvalidLpTokenPriceId,
+    #[msg("An LP token with no supply can't be priced")]
+    LpTokenSupplyZero,
+    #[msg("The LP token price was already added to this price data")]
+    LpTokenPriceAlreadyRefreshed,
+    #[msg("Borrow Intent price deviation rate can't be greater than 100%")]
+    InvalidBorrowIntentPriceDeviationRate,
+    #[msg("This borrow is below the Token Reserve's Borrow Intent threshold and can be made directly")]
+    BorrowIntentNotRequired,
+    #[msg("Borrows at or above the Token Reserve's Borrow Intent threshold must be posted as a Borrow Intent first")]
+    MissingBorrowIntent,
+    #[msg("Can't borrow more than the amount posted in the Borrow Intent")]
+    BorrowIntentAmountExceeded,
+    #[msg("The Borrow Intent can't be executed until its delay has passed")]
+    BorrowIntentDelayNotElapsed,
+    #[msg("The price moved too far from the Borrow Intent's posted price")]
+    BorrowIntentPriceOutOfBounds,
+    #[msg("Max tabs per lending account can't be more than the Tab Account registry has room for")]
+    InvalidMaxTabAmount,
+    #[msg("This Tab Account isn't in the Lending User Account's Tab Account registry")]
+    UnregisteredTabAccount,
+    #[msg("This Tab Account was already refreshed in the current refresh")]
+    TabAccountAlreadyRefreshed,
+    #[msg("The Lending Protocol can't migrate positions to or from itself, or through an unregistered program")]
+    InvalidMigrationProgramId,
+    #[msg("Position migration to or from this program hasn't been turned on")]
+    PositionMigrationNotEnabled,
+    #[msg("The successor Token Reserve doesn't match the registered successor program")]
+    InvalidSuccessorTokenReserve,
+    #[msg("A position's debt can't exceed 70% of its own deposits to be exported or migrated")]
+    ExportedPositionUndercollateralized,
+    #[msg("The predecessor program didn't return a Position Attestation")]
+    MissingPositionAttestation,
+    #[msg("The Position Attestation doesn't match this Tab Account or the tokens received")]
+    PositionAttestationMismatch,
+    #[msg("Token-2022 mints with a permanent delegate, non-transferable, default-frozen, or confidential transfer extension need the CEO's override to be listed")]
+    RestrictedMintExtension,
+    #[msg("The stake pool account isn't an SPL Stake Pool")]
+    InvalidLstStakePool,
+    #[msg("The stake pool doesn't match the liquid staking token")]
+    LstStakePoolMisMatched,
+    #[msg("The SOL price ID can't be missing or be the liquid staking token's own Token ID")]
+    InvalidLstTokenPriceId,
+    #[msg("The stake pool has no pool tokens to price")]
+    LstTokenSupplyZero,
+    #[msg("The liquid staking token price was already added to this price data")]
+    LstTokenPriceAlreadyRefreshed,
+    #[msg("The stake pool hasn't been updated for the current epoch")]
+    StakePoolNotUpdated,
+    #[msg("LP token price haircut rate can't be greater than 100%")]
+    InvalidLpTokenHaircutRate,
+    #[msg("Token Reserve versions have to be added in order and positions can only move to a newer version")]
+    InvalidTokenReserveVersion,
+    #[msg("The signer isn't the delegate of the token account being deposited from")]
+    InvalidDelegatedTokenAccount,
+    #[msg("The token account hasn't delegated enough tokens to the signer to cover the deposit")]
+    InsufficientDelegatedAmount,
+    #[msg("A Lending User can't delegate credit to themselves")]
+    InvalidCreditDelegate,
+    #[msg("This borrow is more than the delegate's remaining borrowing allowance")]
+    CreditDelegationAllowanceExceeded,
+    #[msg("Swap program doesn't match the Lending Protocol's swap program or swaps are turned off")]
+    InvalidSwapProgram,
+    #[msg("Deleverage swaps need different collateral and debt tokens. Use deleverage for the same token")]
+    SameTokenDeleverageSwap,
+    #[msg("Swap returned less than the minimum repayment amount")]
+    SwapSlippageExceeded,
+    #[msg("Deleverage can't raise the Lending User Account's loan to value")]
+    DeleverageIncreasedLoanToValue,
+    #[msg("A batch deposit needs atleast one deposit")]
+    EmptyBatchDeposit,
+    #[msg("There's no repayment budget for this token")]
+    MissingRepaymentBudget,
+    #[msg("A deposit can't be transferred to the same Tab Account it's coming from")]
+    InvalidDepositTransferRecipient,
+    #[msg("A position can't be migrated to the SubMarket it's already in")]
+    InvalidSubMarketMigration,
+    #[msg("The repayment didn't cover all of the tab's debt, ie a transfer fee was taken. Repay the rest with repay_tokens first")]
+    PositionDebtNotFullyRepaid,
+    #[msg("Day snapshots can only be written for the current day")]
+    InvalidSnapshotDay,
+    #[msg("Every one of the Lending User's Tab Accounts has to be fed in to get their User Health")]
+    MissingUserHealthTabAccounts,
+    #[msg("The Protocol TVL account doesn't have room for another Token Reserve")]
+    ProtocolTvlFull,
+    #[msg("Only the Guardian or the CEO can call this function")]
+    NotGuardian,
+    #[msg("Only the Risk Admin or the CEO can call this function")]
+    NotRiskAdmin,
+    #[msg("This change has to be queued through the timelock while the timelock delay is turned on")]
+    TimelockRequired,
+    #[msg("The timelock delay for this change hasn't elapsed yet")]
+    TimelockNotElapsed,
+    #[msg("Invalid timelocked change type")]
+    InvalidTimelockedChange,
+    #[msg("An account needed to execute this timelocked change wasn't passed in")]
+    MissingTimelockedChangeAccount,
+    #[msg("The account passed in doesn't match the timelocked change target")]
+    TimelockedChangeTargetMisMatched,
+    #[msg("Invalid admin action type")]
+    InvalidAdminAction,
+    #[msg("This Token Reserve only allows allowlisted liquidators during its guarded launch period")]
+    LiquidatorNotAllowlisted,
+    #[msg("This SubMarket only allows wallets on its owner's allowlist to deposit or borrow")]
+    NotOnSubMarketAllowlist,
+    #[msg("The veto window for this Token Reserve listing has closed")]
+    ListingVetoWindowClosed,
+    #[msg("The listing bond can't be reclaimed until the veto window closes")]
+    ListingVetoWindowOpen,
+    #[msg("Only the lister of this Token Reserve can reclaim its bond")]
+    NotTokenReserveLister,
+    #[msg("A SubMarket can only be retired once it has no deposits, debt, or uncollected fees")]
+    SubMarketNotEmpty,
+    #[msg("This SubMarket is supply only and doesn't allow borrows")]
+    SubMarketBorrowsDisabled,
+    #[msg("Oracle price data slot is too far ahead of the current slot")]
+    OracleDataFutureDated,
+    #[msg("Interest index is out of range")]
+    InterestIndexOverflow,
+    #[msg("Token decimal amount doesn't match the token mint's decimals")]
+    TokenDecimalAmountMisMatched,
+    #[msg("The same Token Reserve Account can't be passed in more than once")]
+    DuplicateTokenReserveAccount,
+    #[msg("The same Tab Account can't be passed in more than once")]
+    DuplicateTabAccount,
+    #[msg("The same SubMarket Account can't be passed in more than once")]
+    DuplicateSubMarketAccount,
+    #[msg("Look Up Table account doesn't match the expected address")]
+    UnexpectedLookUpTableAccount,
+    #[msg("Look Up Table can't hold any more addresses")]
+    LookUpTableFull,
+    #[msg("Can't refresh more Tab Accounts or Token Reserves than the Tab Account registry holds")]
+    TooManyHealthRefreshAccounts,
+    #[msg("Too many verified prices to attest in the User Health")]
+    TooManyAttestedPrices,
+    #[msg("The User Health attestation has expired or has no attested prices. Refresh User Health again or pass in the price accounts")]
+    UserHealthAttestationExpired,
+    #[msg("Liquidation swaps spend the liquidation reward from the liquidator's wallet, so send_reward_to_wallet has to be true")]
+    LiquidationSwapNeedsRewardToWallet,
+    #[msg("Collateral swaps need different source and destination tokens. Use migrate_sub_market to move collateral between SubMarkets of the same token")]
+    SameTokenCollateralSwap,
+    #[msg("Flash loan receiver has to be an executable program other than the Lending Protocol")]
+    InvalidFlashLoanReceiver,
+    #[msg("Flash loan receiver didn't pay the loan plus the flash loan fee back to the Token Reserve")]
+    FlashLoanNotRepaid,
+    #[msg("This Reward Emissions has a Reward Vesting Schedule that has to be passed in")]
+    MissingRewardVestingSchedule,
+    #[msg("A referrer can only be attached on the first deposit into a SubMarket")]
+    ReferrerAfterFirstDeposit,
+    #[msg("The referrer has to be passed in with the Lending User Referral account")]
+    MissingReferrer,
+    #[msg("You can't refer yourself")]
+    SelfReferral,
+    #[msg("The referral fee share rate can't be more than 100%")]
+    InvalidReferralFeeShareRate,
+    #[msg("Only the referrer can claim referral fees")]
+    NotReferrer,
+    #[msg("Too many Fee Discount Tiers")]
+    TooManyFeeDiscountTiers,
+    #[msg("Fee Discount Tiers must climb in minimum deposited value and discount, with discounts of 100% or less")]
+    InvalidFeeDiscountTiers,
+    #[msg("Borrow rate discount can't be greater than 100%")]
+    InvalidBorrowRateDiscountRate,
+    #[msg("Stake amount must be greater than 0 and no more than the staked amount")]
+    InvalidStakeAmount,
+    #[msg("Only reward tokens that haven't been emitted yet can be withdrawn from a SubMarket reward boost")]
+    RewardBoostWithdrawTooLarge,
+    #[msg("Merkle proof is too long")]
+    MerkleProofTooLong,
+    #[msg("Merkle proof doesn't match the distribution's Merkle root")]
+    InvalidMerkleProof,
+    #[msg("Claim would pay out more than the Merkle Distribution's total claimable amount")]
+    MerkleDistributionOverClaimed,
+    #[msg("Lending User Accounts in E-Mode can only deposit or borrow tokens in their E-Mode asset category")]
+    EModeAssetCategoryMismatch,
+    #[msg("E-Mode limits can't be lower than the regular limits, the Loan To Value has to be below the liquidation threshold, and the liquidation threshold can't be above 97%")]
+    InvalidEModeCategoryRates,
+    #[msg("Asset category 0 means no category")]
+    InvalidAssetCategory,
+    #[msg("The Lending User Account's E-Mode Category account is missing")]
+    MissingEModeCategoryAccount,
+    #[msg("Siloed tokens can't be borrowed alongside other tokens under the same Lending User Account")]
+    SiloedBorrowing,
+    #[msg("The proceeds the Insurance Fund received don't cover the auction price")]
+    AuctionPaymentShortfall,
+    #[msg("Collateral haircut rate can't be greater than 100%")]
+    InvalidCollateralHaircutRate,
+    #[msg("This Token Reserve's listing was vetoed, so it can't be borrowed from or count as collateral")]
+    TokenReserveListingVetoed
 }
\ No newline at end of file
@@ -0,0 +1,265 @@
+use anchor_lang::prelude::*;
+use crate::structs as Structs;
+
+//Administrative Events
+//Emitted whenever protocol level settings change, so governance monitoring tools can follow along without diffing account state
+#[event]
+pub struct LendingProtocolCEOChanged
+{
+    pub previous_ceo_address: Pubkey,
+    pub new_ceo_address: Pubkey
+}
+
+#[event]
+pub struct LendingProtocolRolesUpdated
+{
+    pub guardian_address: Pubkey,
+    pub risk_admin_address: Pubkey
+}
+
+#[event]
+pub struct BorrowsPaused
+{
+    pub paused_by_address: Pubkey
+}
+
+#[event]
+pub struct TimelockedChangeQueued
+{
+    pub change_id: u32,
+    pub change_type: u8,
+    pub target_address: Pubkey,
+    pub proposer_address: Pubkey,
+    pub parameters: Structs::TimelockedChangeParameters,
+    pub executable_time_stamp: u64
+}
+
+#[event]
+pub struct TimelockedChangeExecuted
+{
+    pub change_id: u32,
+    pub change_type: u8,
+    pub target_address: Pubkey
+}
+
+#[event]
+pub struct TimelockedChangeCancelled
+{
+    pub change_id: u32,
+    pub change_type: u8,
+    pub cancelled_by_address: Pubkey
+}
+
+#[event]
+pub struct AdminActionCreated
+{
+    pub action_id: u32,
+    pub action_type: u8,
+    pub proposer_address: Pubkey,
+    pub parameters: Structs::AdminActionParameters
+}
+
+#[event]
+pub struct AdminActionExecuted
+{
+    pub action_id: u32,
+    pub action_type: u8
+}
+
+#[event]
+pub struct AdminActionCancelled
+{
+    pub action_id: u32,
+    pub action_type: u8,
+    pub cancelled_by_address: Pubkey
+}
+
+#[event]
+pub struct TimelockDelayUpdated
+{
+    pub timelock_delay_seconds: u64
+}
+
+#[event]
+pub struct StatementMonthAndYearUpdated
+{
+    pub statement_month: u8,
+    pub statement_year: u16
+}
+
+#[event]
+pub struct TokenReserveAdded
+{
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub reserve_version: u8,
+    pub token_decimal_amount: u8,
+    pub base_borrow_apy: u16,
+    pub use_fixed_borrow_apy: bool,
+    pub global_limit: u128,
+    pub solvency_insurance_fee_rate: u16,
+    pub usd_quote_price_id: u8,
+    pub restricted_mint_extensions_allowed: bool
+}
+
+#[event]
+pub struct TokenReserveListed
+{
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub lister_address: Pubkey,
+    pub bond_amount: u64,
+    pub veto_deadline_time_stamp: u64
+}
+
+#[event]
+pub struct TokenReserveListingVetoed
+{
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub lister_address: Pubkey
+}
+
+#[event]
+pub struct TokenReserveUpdated
+{
+    pub token_id: u8,
+    pub base_borrow_apy: u16,
+    pub use_fixed_borrow_apy: bool,
+    pub global_limit: u128,
+    pub solvency_insurance_fee_rate: u16
+}
+
+#[event]
+pub struct TokenReserveBorrowIntentSettingsUpdated
+{
+    pub token_id: u8,
+    pub borrow_intent_threshold_amount: u64,
+    pub borrow_intent_delay_slots: u64,
+    pub borrow_intent_max_price_deviation_rate: u16
+}
+
+//Emitted by verify_reserve_invariants when the Token Reserve's books don't reconcile, so monitoring can alarm before the drift grows
+#[event]
+pub struct ReserveInvariantDrift
+{
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub token_reserve_ata_balance_amount: u128,
+    pub expected_balance_amount: u128,
+    pub deposited_amount: u128,
+    pub sub_markets_deposited_amount: u128,
+    pub borrowed_amount: u128,
+    pub sub_markets_borrowed_amount: u128,
+    pub sub_market_count: u16,
+    pub clock_slot: u64
+}
+
+#[event]
+pub struct SubMarketCreated
+{
+    pub id: u32,
+    pub token_id: u8,
+    pub owner: Pubkey,
+    pub sub_market_index: u16,
+    pub fee_collector_address: Pubkey,
+    pub fee_on_interest_earned_rate: u16
+}
+
+#[event]
+pub struct SubMarketEdited
+{
+    pub token_id: u8,
+    pub owner: Pubkey,
+    pub sub_market_index: u16,
+    pub fee_collector_address: Pubkey,
+    pub pending_fee_collector_address: Pubkey,
+    pub fee_on_interest_earned_rate: u16,
+    pub pending_fee_on_interest_earned_rate: u16,
+    pub fee_ramp_end_time_stamp: u64
+}
+
+#[event]
+pub struct SubMarketRetired
+{
+    pub token_id: u8,
+    pub owner: Pubkey,
+    pub sub_market_index: u16
+}
+
+#[event]
+pub struct SubMarketFeeCollectorChanged
+{
+    pub token_id: u8,
+    pub owner: Pubkey,
+    pub sub_market_index: u16,
+    pub fee_collector_address: Pubkey
+}
+
+//Lending User Events
+//Emitted for every Tab Account in a health refresh, so monitoring bots can track health drift between full health checks
+#[event]
+pub struct TabAccountHealthSnapShot
+{
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub deposited_amount: u64,
+    pub borrowed_amount: u64,
+    pub supply_interest_change_index: u128,
+    pub borrow_interest_change_index: u128,
+    pub deposited_usd_value: u128,
+    pub borrowed_usd_value: u128,
+    pub clock_slot: u64
+}
+
+//Flash Loan Events
+#[event]
+pub struct FlashLoanRepaid
+{
+    pub token_mint_address: Pubkey,
+    pub receiver_program_id: Pubkey,
+    pub signer: Pubkey,
+    pub amount: u64,
+    pub fee_amount: u64
+}
+
+//Reward Events
+#[event]
+pub struct RewardsClaimed
+{
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub reward_mint_address: Pubkey,
+    pub earned_amount: u64,
+    pub claimed_amount: u64,
+    pub locked_amount: u64
+}
+
+//Referral Events
+#[event]
+pub struct ReferralFeesClaimed
+{
+    pub referrer: Pubkey,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub claimed_amount: u64
+}
+
+//Merkle Distribution Events
+#[event]
+pub struct MerkleDistributionClaimed
+{
+    pub distribution_index: u32,
+    pub claimant: Pubkey,
+    pub reward_mint_address: Pubkey,
+    pub amount: u64
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::errors::LendingError;
 use crate::structs as Structs;
-use crate::shared_constants::MAX_ACCOUNT_NAME_LENGTH;
+use crate::shared_constants::{MAX_ACCOUNT_NAME_LENGTH, CURRENT_ACCOUNT_VERSION};
 
 //Helper function to initialize Lending User Account
 pub fn initialize_lending_user_account<'info>(lending_user_account: &mut Structs::LendingUserAccount,
@@ -16,6 +16,8 @@ pub fn initialize_lending_user_account<'info>(lending_user_account: &mut Structs
     require!(account_name.len() <= MAX_ACCOUNT_NAME_LENGTH, LendingError::LendingUserAccountNameTooLong);
 
     lending_user_account.bump = bump;
+
+    lending_user_account.version = CURRENT_ACCOUNT_VERSION;
     lending_user_account.owner = user_account_owner;
     lending_user_account.user_account_index = user_account_index;
     lending_user_account.account_name = account_name.clone();
@@ -23,7 +25,7 @@ pub fn initialize_lending_user_account<'info>(lending_user_account: &mut Structs
     lending_user_account.lending_user_account_added = true;
 
     msg!("Created Lending User Account Named: {}", account_name);
-    msg!("Set Lending User Look Up Table Address: {}", lending_user_account.look_up_table_address);
+    verbose_msg!("Set Lending User Look Up Table Address: {}", lending_user_account.look_up_table_address);
 
     Ok(())
 }
@@ -31,6 +33,7 @@ pub fn initialize_lending_user_account<'info>(lending_user_account: &mut Structs
 //Helper function to initialize Lending User Tab Account
 pub fn initialize_lending_user_tab_account<'info>(lending_user_account: &mut Structs::LendingUserAccount,
     lending_user_tab_account: &mut Structs::LendingUserTabAccount,
+    lending_user_tab_account_address: Pubkey,
     lending_protocol: &Structs::LendingProtocol,
     bump: u8,
     token_id: u8,
@@ -41,16 +44,20 @@ pub fn initialize_lending_user_tab_account<'info>(lending_user_account: &mut Str
 ) -> Result<()>
 {
     lending_user_tab_account.bump = bump;
+    lending_user_tab_account.version = CURRENT_ACCOUNT_VERSION;
     lending_user_tab_account.token_id = token_id;
     lending_user_tab_account.sub_market_owner_address = sub_market_owner_address;
     lending_user_tab_account.sub_market_index = sub_market_index;
     lending_user_tab_account.user_tab_account_index = lending_user_account.tab_account_count;
     lending_user_tab_account.owner = user_account_owner;
     lending_user_tab_account.user_account_index = user_account_index;
-    lending_user_tab_account.user_tab_account_added = true;
+    lending_user_tab_account.user_tab_account_added = 1;
 
     lending_user_account.tab_account_count += 1;
 
+    //Register the Tab Account so the health refresh can check fed in Tab Accounts against the registry
+    lending_user_account.tab_account_addresses.push(lending_user_tab_account_address);
+
     //Limit the number of tab accounts to prevent accounts from becoming broken with too many tab accounts.
     //Unable to withdraw, borrow, repay, or be liquidated because too many transactions would be required to land in the same slot.
     //Jito bundles only allow for up to 5 transacations.
@@ -77,6 +84,7 @@ pub fn initialize_lending_user_monthly_statement_account<'info>(lending_user_mon
 ) -> Result<()>
 {
     lending_user_monthly_statement_account.bump = bump;
+    lending_user_monthly_statement_account.version = CURRENT_ACCOUNT_VERSION;
     lending_user_monthly_statement_account.token_id = token_id;
     lending_user_monthly_statement_account.sub_market_owner_address = sub_market_owner_address;
     lending_user_monthly_statement_account.sub_market_index = sub_market_index;
@@ -90,5 +98,48 @@ pub fn initialize_lending_user_monthly_statement_account<'info>(lending_user_mon
 
     msg!("Created Statement Account for month: {}, year: {}", lending_user_monthly_statement_account.statement_month, lending_user_monthly_statement_account.statement_year);
 
+    Ok(())
+}
+
+//Helper function to initialize a Token Reserve, shared by the original Token Reserve of a mint and its later versions
+pub fn initialize_token_reserve<'info>(token_reserve: &mut Structs::TokenReserve,
+    token_reserve_stats: &mut Structs::TokenReserveStats,
+    bump: u8,
+    token_mint_address: Pubkey,
+    reserve_version: u8,
+    token_decimal_amount: u8,
+    base_borrow_apy: u16,
+    use_fixed_borrow_apy: bool,
+    global_limit: u128,
+    solvency_insurance_fee_rate: u16,
+    usd_quote_price_id: u8
+) -> Result<()>
+{
+    //Base Borrow APY can't be greater than 5%, 0.05 in decimal form, 500 in fixed point notation
+    require!(base_borrow_apy <= 500, LendingError::InvalidBaseBorrowAPY);
+
+    //Solvency Insurance Fee on interest earned rate can't be greater than 4%, 0.04 in decimal form, 400 in fixed point notation
+    require!(solvency_insurance_fee_rate <= 400, LendingError::InvalidSolvencyInsuranceFeeRate);
+
+    //A Token Reserve's price can't be converted to USD with its own price feed
+    require!(usd_quote_price_id != token_reserve_stats.token_reserve_count + 1, LendingError::InvalidUsdQuotePriceId);
+
+    token_reserve.bump = bump;
+    token_reserve.version = CURRENT_ACCOUNT_VERSION;
+    token_reserve.reserve_version = reserve_version;
+    token_reserve.token_mint_address = token_mint_address;
+    token_reserve.token_decimal_amount = token_decimal_amount;
+    token_reserve.borrow_apy = base_borrow_apy;
+    token_reserve.base_borrow_apy = base_borrow_apy;
+    token_reserve.use_fixed_borrow_apy = use_fixed_borrow_apy;
+    token_reserve.global_limit = global_limit;
+    token_reserve.solvency_insurance_fee_rate = solvency_insurance_fee_rate;
+    token_reserve.usd_quote_price_id = usd_quote_price_id;
+    token_reserve.supply_interest_change_index = 1_000_000_000_000_000_000;
+    token_reserve.borrow_interest_change_index = 1_000_000_000_000_000_000;
+
+    token_reserve_stats.token_reserve_count += 1;
+    token_reserve.token_id = token_reserve_stats.token_reserve_count;
+
     Ok(())
 }
\ No newline at end of file
@@ -1,11 +1,46 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{self};
-use anchor_spl::token_interface::{self, Mint, TokenInterface, TransferChecked, SyncNative, CloseAccount};
+use anchor_lang::InstructionData;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed, get_return_data};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions, transfer_fee::TransferFeeConfig};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as SplMint;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked, SyncNative, CloseAccount, InitializeAccount3};
 use ra_solana_math::FixedPoint;
 use crate::errors::LendingError;
 use crate::structs as Structs;
+use crate::validation::load_zero_copy_account_mut;
+use std::cell::RefMut;
+use crate::shared_constants::{CURRENT_ACCOUNT_VERSION, MAX_LENDING_USER_ACTIVITY_RECORDS, MAX_PROTOCOL_TVL_TOKEN_RESERVES, FLASH_LOAN_RECEIVER_DISCRIMINATOR};
 
 const SOL_TOKEN_MINT_ADDRESS: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+const TOKEN_ACCOUNT_SPACE: u64 = 165; //Size of a Token Program token account with no extensions
+pub const SPL_STAKE_POOL_PROGRAM_ADDRESS: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+const LOOK_UP_TABLE_META_SIZE: usize = 56; //Addresses start after the Look Up Table's metadata
+const MAX_LOOK_UP_TABLE_ADDRESSES: usize = 256;
+const CREATE_LOOK_UP_TABLE_INSTRUCTION_INDEX: u32 = 0;
+const EXTEND_LOOK_UP_TABLE_INSTRUCTION_INDEX: u32 = 2;
+//Byte offsets into an SPL Stake Pool account. Everything before total_lamports is fixed size
+const STAKE_POOL_POOL_MINT_OFFSET: usize = 162;
+const STAKE_POOL_TOTAL_LAMPORTS_OFFSET: usize = 258;
+const STAKE_POOL_POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+const STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+const SOL_DECIMAL_AMOUNT: u32 = 9;
+const MAX_PRICE_DATA_FUTURE_SLOTS: i64 = 2; //Leaders can run a slot or two ahead of each other, so a price slot can be slightly ahead of the slot it executes in
+pub const DECIMAL_SCALING: u128 = 10_000; //10_000 = 100.00%
+pub const OPTIMAL_UTILIZATION_RATE: u128 = 7_000; //7_000 = 70.00%
+pub const BORROW_APY_SLOPE2: u128 = 3_400; //3,400 = 34.00%
+pub const INTEREST_INDEX_RESCALE_THRESHOLD: u128 = 9_000_000_000_000_000_000; //Tab interest math reads indexes as u64, so indexes are rescaled well before they reach u64::MAX
+pub const INTEREST_INDEX_RESCALE_FACTOR: u128 = 16;
+pub const SECONDS_IN_A_YEAR: u64 = 31_556_952; //365.2425 days
+pub const REWARD_INDEX_SCALING: u128 = 1_000_000_000_000_000_000; //Reward indexes are 18 decimal fixed point so small emission rates over large deposits don't round down to 0
+pub const LOAN_TO_VALUE_RATE: u128 = 7_000; //7_000 = 70.00%. Max debt against collateral for withdraws and borrows outside of E-Mode
+pub const LIQUIDATION_THRESHOLD_RATE: u128 = 8_000; //8_000 = 80.00%. Debt against collateral where accounts outside of E-Mode become liquidatable
+pub const MAX_E_MODE_LIQUIDATION_THRESHOLD_RATE: u16 = 9_700; //9_700 = 97.00%. Leaves E-Mode accounts room to be liquidated before their debt is worth more than their collateral
+pub const SLOTS_IN_A_YEAR: u64 = 78_892_380; //Seconds in a year at the 400ms target slot time, used to convert APYs to a per slot rate for slot based accrual
 
 //Helper function to update Token Reserve Accrued Interest Index before a lending transaction (deposit, withdraw, borrow, repay, liquidate)
 //This function helps determine how much compounding interest a Token Reserve has earned for its token over the Token Reserve's entire existence
@@ -25,7 +60,7 @@ const SOL_TOKEN_MINT_ADDRESS: Pubkey = pubkey!("So111111111111111111111111111111
         let borrow_apy_fp = FixedPoint::from_bps(token_reserve.borrow_apy as u64)?;
         let change_in_time = new_time_stamp - token_reserve.last_lending_activity_time_stamp;
         let change_in_time_fp =  FixedPoint::from_int(change_in_time);
-        let seconds_in_a_year_fp = FixedPoint::from_int(31_556_952); //1 year = (365.2425 days) × (24 hours/day) × (3600 seconds/hour) = 31,556,952 seconds
+        let seconds_in_a_year_fp = FixedPoint::from_int(SECONDS_IN_A_YEAR); //1 year = (365.2425 days) × (24 hours/day) × (3600 seconds/hour) = 31,556,952 seconds
         
         //Set Token Reserve Supply Interest Index = Old Supply Interest Index * (1 + Supply APY * Δt/Seconds in a Year)
         //Multiply before dividing to help keep precision
@@ -45,9 +80,9 @@ const SOL_TOKEN_MINT_ADDRESS: Pubkey = pubkey!("So111111111111111111111111111111
         let new_borrow_interest_index = new_borrow_interest_index_fp.to_u128()?;
         token_reserve.borrow_interest_change_index = new_borrow_interest_index;
 
-        msg!("Updated Token Reserve Interest Change Indexes");
-        msg!("Supply Change Index: {}", token_reserve.supply_interest_change_index);
-        msg!("Borrow Change Index: {}", token_reserve.borrow_interest_change_index);
+        verbose_msg!("Updated Token Reserve Interest Change Indexes");
+        verbose_msg!("Supply Change Index: {}", token_reserve.supply_interest_change_index);
+        verbose_msg!("Borrow Change Index: {}", token_reserve.borrow_interest_change_index);
     }
 
     token_reserve.last_lending_activity_time_stamp = new_time_stamp;
@@ -66,6 +101,8 @@ pub fn update_token_reserve_supply_and_borrow_interest_change_index<'info>(
     new_time_stamp: u64, 
     new_clock_slot: Option<u64>
 ) -> Result<()> {
+    //Only slot based accrual reads the clock slot, so the time stamp math can still be run off chain
+    let current_clock_slot = if token_reserve.use_slot_based_accrual { Clock::get()?.slot } else { 0 };
     
     //Skip if there is no borrowing in the Token Reserve. There is no interest change if there is no borrowing.
     if token_reserve.borrowed_amount != 0
@@ -87,9 +124,18 @@ pub fn update_token_reserve_supply_and_borrow_interest_change_index<'info>(
         let borrow_apy_fp = FixedPoint::from_bps(token_reserve.borrow_apy as u64)
             .map_err(|_| anchor_lang::prelude::ProgramError::InvalidArgument)?;
         
-        let change_in_time = new_time_stamp - token_reserve.last_lending_activity_time_stamp;
+        //Slot based accrual measures Δt in clock slots instead of seconds. Slots only ever go up, so the interest math stays monotonic even when the cluster's time stamps stall or repeat.
+        //The cluster clock can land a few seconds behind the last recorded time stamp. Signed math keeps that skew from underflowing into a huge Δt, and no interest accrues for negative time
+        let (change_in_time, periods_in_a_year) = if token_reserve.use_slot_based_accrual
+        {
+            (current_clock_slot.saturating_sub(token_reserve.last_accrual_clock_slot), SLOTS_IN_A_YEAR)
+        }
+        else
+        {
+            ((new_time_stamp as i64 - token_reserve.last_lending_activity_time_stamp as i64).max(0) as u64, SECONDS_IN_A_YEAR)
+        };
         let change_in_time_fp = FixedPoint::from_int(change_in_time);
-        let seconds_in_a_year_fp = FixedPoint::from_int(31_556_952); 
+        let seconds_in_a_year_fp = FixedPoint::from_int(periods_in_a_year); 
 
         //Taylor Series 4th Order Interest Calculation: e^x = 1 + x + (x^2 / 2!) + (x^3 / 3!) + (x^4 / 4!) 
         //1. Calculate common time factor x: APY * Δt / seconds_in_a_year
@@ -137,12 +183,26 @@ pub fn update_token_reserve_supply_and_borrow_interest_change_index<'info>(
         token_reserve.borrow_interest_change_index = old_borrow_interest_index_fp.mul(&borrow_compounding_factor_fp)
             .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?.value.as_u128();
 
-        msg!("Updated Token Reserve Interest Change Indexes");
-        msg!("Supply: {}", token_reserve.supply_interest_change_index);
-        msg!("Borrow: {}", token_reserve.borrow_interest_change_index);
+        verbose_msg!("Updated Token Reserve Interest Change Indexes");
+        verbose_msg!("Supply: {}", token_reserve.supply_interest_change_index);
+        verbose_msg!("Borrow: {}", token_reserve.borrow_interest_change_index);
     }
 
-    token_reserve.last_lending_activity_time_stamp = new_time_stamp;
+    //Ancient Token Reserves eventually grow their indexes past what the tab math can hold. Scale both indexes down and start a new epoch.
+    //Tabs divide their own indexes by the same factor for every epoch they missed the next time their interest is updated, so the ratios stay the same
+    while token_reserve.supply_interest_change_index > INTEREST_INDEX_RESCALE_THRESHOLD || token_reserve.borrow_interest_change_index > INTEREST_INDEX_RESCALE_THRESHOLD
+    {
+        token_reserve.supply_interest_change_index /= INTEREST_INDEX_RESCALE_FACTOR;
+        token_reserve.borrow_interest_change_index /= INTEREST_INDEX_RESCALE_FACTOR;
+        token_reserve.interest_index_epoch = token_reserve.interest_index_epoch.checked_add(1).ok_or(LendingError::InterestIndexOverflow)?;
+
+        verbose_msg!("Rescaled Token Reserve Interest Change Indexes To Epoch: {}", token_reserve.interest_index_epoch);
+    }
+
+    //Never move the time stamp backwards, otherwise the skewed seconds would be charged twice on the next update.
+    //The time stamp is kept current in both accrual modes, so switching back to per second accrual never charges for time already accrued
+    token_reserve.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp.max(new_time_stamp);
+    token_reserve.last_accrual_clock_slot = token_reserve.last_accrual_clock_slot.max(current_clock_slot);
 
     //This setting keeps us from running update_token_reserve_supply_and_borrow_interest_change_index more than we need to when calling refresh_user_health_chunk_and_token_reserves
     //It also detects when a user borrows from a token reserve they have never interacted with before
@@ -155,6 +215,17 @@ pub fn update_token_reserve_supply_and_borrow_interest_change_index<'info>(
     Ok(())
 }
 
+//Brings a tab's interest indexes up to the Token Reserve's current interest index epoch
+pub fn sync_tab_interest_index_epoch(token_reserve: &Structs::TokenReserve, lending_user_tab_account: &mut Structs::LendingUserTabAccount)
+{
+    while lending_user_tab_account.interest_index_epoch < token_reserve.interest_index_epoch
+    {
+        lending_user_tab_account.supply_interest_change_index /= INTEREST_INDEX_RESCALE_FACTOR;
+        lending_user_tab_account.borrow_interest_change_index /= INTEREST_INDEX_RESCALE_FACTOR;
+        lending_user_tab_account.interest_index_epoch += 1;
+    }
+}
+
 //Helper function to update Token Reserve Utilization Rate, Borrow APY, and Supply APY after a lending transaction (deposit, withdraw, borrow, repay, liquidate)
 pub fn update_token_reserve_rates<'info>(token_reserve: &mut Structs::TokenReserve) -> Result<()>
 {
@@ -167,70 +238,99 @@ pub fn update_token_reserve_rates<'info>(token_reserve: &mut Structs::TokenReser
     else
     {
         //Borrow, Supply, and Utililzation rate stored as normal basis points, IE 101 basis points = 1.01%
-        let decimal_scaling = 10_000; //10_000 = 100.00%
+        let decimal_scaling = DECIMAL_SCALING;
 
         //Set Token Reserve Utilization Rate = Borrowed Amount / Deposited Amount
-        let borrowed_amount_scaled = token_reserve.borrowed_amount * decimal_scaling;
-        let utilization_rate = borrowed_amount_scaled / token_reserve.deposited_amount;
-        token_reserve.utilization_rate = utilization_rate as u16;
-
-        //Set Borrow APY
-        if token_reserve.use_fixed_borrow_apy
+        //Accrued interest lands on the borrowed amount before the matching interest earned lands on deposits, so borrows can run ahead of deposits.
+        //Clamp to 100% so the u16 cast can't truncate, and treat borrows with no deposits left as fully utilized
+        let utilization_rate = if token_reserve.deposited_amount == 0
         {
-            token_reserve.borrow_apy = token_reserve.base_borrow_apy;
+            decimal_scaling
         }
         else
         {
-            let optimal_utilization_rate = 7_000; //7_000 = 70.00%
-            let utilization_rate = token_reserve.utilization_rate as u128;
-            
-            //Borrow APY = Borrow APY Base(Borrow APY Slope1 in this case) + ((Utilization Rate/Optimal Utialization Rate) * Borrow APY Slope1)
-            //Setting Borrow APY Base to Borrow APY Slope1 in this case
-            if utilization_rate < optimal_utilization_rate
-            {
-                //Max Borrow Rate = token_reserve.base_borrow_apy + token_reserve.base_borrow_apy @Less Than 70% Utilization Rate
-                let borrow_apy_slope1 = token_reserve.base_borrow_apy as u128;
-                //Multiply before dividing to help keep precision
-                let u_rate_times_borrow_apy_slope1 = utilization_rate * borrow_apy_slope1;
-                let u_rate_times_borrow_apy_slope1_divide_optimal_u_rate = u_rate_times_borrow_apy_slope1 / optimal_utilization_rate;
-
-                //Max Borrow Rate = token_reserve.base_borrow_apy + token_reserve.base_borrow_apy @Less Than 70% Utilization Rate
-                token_reserve.borrow_apy = (borrow_apy_slope1 + u_rate_times_borrow_apy_slope1_divide_optimal_u_rate) as u16;
-            }
-            else
-            {
-                //Max Borrow Rate = 10% + 34% = 44% @100% Utilization Rate. Max base borrow apy is 5%. I think having a rate more than 44% would appear too pay day loany...just seems like a bad look lol.
-                let borrow_apy_slope2 = 3_400; //3,400 = 34.00%
-
-                /*
-                * Formula: New High Rate Base = (Current Utilization Rate - Optimal Utilization Rate) / (100% - Optimal Utilization Rate) * Borrow APY Slope 2
-                * * This linearly scales the interest rate upward from the 10% base rate at 70% utilization, 
-                * reaching the full 34% slope cap (44% total APY) only when utilization hits 100%.
-                * * Order of operations: Multiply before dividing to prevent integer truncation / precision loss.
-                */
-                let u_rate_minus_optimal_u_rate = utilization_rate - optimal_utilization_rate;
-                let one_hundred_percent_minus_optimal_u_rate = decimal_scaling - optimal_utilization_rate;
-                //Multiply before dividing to help keep precision
-                let u_rate_minus_optimal_u_rate_times_borrow_apy_slope2 = u_rate_minus_optimal_u_rate * borrow_apy_slope2;
-                let new_high_rate_base = u_rate_minus_optimal_u_rate_times_borrow_apy_slope2 / one_hundred_percent_minus_optimal_u_rate;
-
-                //Max Borrow Rate = 10% + 34% = 44% @100% Utilization Rate. Max base borrow apy is 5%.
-                token_reserve.borrow_apy = (token_reserve.base_borrow_apy * 2) + new_high_rate_base as u16;
-            }
-        }
+            let borrowed_amount_scaled = token_reserve.borrowed_amount.saturating_mul(decimal_scaling);
+            (borrowed_amount_scaled / token_reserve.deposited_amount).min(decimal_scaling)
+        };
+        token_reserve.utilization_rate = utilization_rate as u16;
+
+        //Set Borrow APY
+        token_reserve.borrow_apy = calculate_borrow_apy(token_reserve, token_reserve.utilization_rate as u128);
 
         //Set Supply APY = Borrowed APY * Utilization Rate
-        let unscaled_supply_apy = token_reserve.borrow_apy as u32 * token_reserve.utilization_rate as u32;
-        token_reserve.supply_apy = (unscaled_supply_apy / decimal_scaling as u32) as u16;
+        token_reserve.supply_apy = calculate_supply_apy(token_reserve.borrow_apy, token_reserve.utilization_rate);
     }
     
-    msg!("Updated Token Reserve Rates");
-    msg!("Utilization Rate: {}", token_reserve.utilization_rate as f64 / 100.0);
-    msg!("Supply Apy: {}", token_reserve.supply_apy as f64 / 100.0);
+    verbose_msg!("Updated Token Reserve Rates");
+    verbose_msg!("Utilization Rate: {}", token_reserve.utilization_rate as f64 / 100.0);
+    verbose_msg!("Supply Apy: {}", token_reserve.supply_apy as f64 / 100.0);
 
     Ok(())
 }
 
+//Helper function to calculate the Borrow APY of a Token Reserve at any Utilization Rate. Used by update_token_reserve_rates and the rate curve view
+pub fn calculate_borrow_apy(token_reserve: &Structs::TokenReserve, utilization_rate: u128) -> u16
+{
+    //Borrow, Supply, and Utililzation rate stored as normal basis points, IE 101 basis points = 1.01%
+    let decimal_scaling = DECIMAL_SCALING;
+    let utilization_rate = utilization_rate.min(decimal_scaling); //The curve ends at 100% Utilization Rate
+    let borrow_apy: u128;
+
+    if token_reserve.use_fixed_borrow_apy
+    {
+        borrow_apy = token_reserve.base_borrow_apy as u128;
+    }
+    else
+    {
+        let optimal_utilization_rate = OPTIMAL_UTILIZATION_RATE;
+        
+        //Borrow APY = Borrow APY Base(Borrow APY Slope1 in this case) + ((Utilization Rate/Optimal Utialization Rate) * Borrow APY Slope1)
+        //Setting Borrow APY Base to Borrow APY Slope1 in this case
+        if utilization_rate < optimal_utilization_rate
+        {
+            //Max Borrow Rate = token_reserve.base_borrow_apy + token_reserve.base_borrow_apy @Less Than 70% Utilization Rate
+            let borrow_apy_slope1 = token_reserve.base_borrow_apy as u128;
+            //Multiply before dividing to help keep precision
+            let u_rate_times_borrow_apy_slope1 = utilization_rate * borrow_apy_slope1;
+            let u_rate_times_borrow_apy_slope1_divide_optimal_u_rate = u_rate_times_borrow_apy_slope1 / optimal_utilization_rate;
+
+            //Max Borrow Rate = token_reserve.base_borrow_apy + token_reserve.base_borrow_apy @Less Than 70% Utilization Rate
+            borrow_apy = borrow_apy_slope1 + u_rate_times_borrow_apy_slope1_divide_optimal_u_rate;
+        }
+        else
+        {
+            //Max Borrow Rate = 10% + 34% = 44% @100% Utilization Rate. Max base borrow apy is 5%. I think having a rate more than 44% would appear too pay day loany...just seems like a bad look lol.
+            let borrow_apy_slope2 = BORROW_APY_SLOPE2;
+
+            /*
+            * Formula: New High Rate Base = (Current Utilization Rate - Optimal Utilization Rate) / (100% - Optimal Utilization Rate) * Borrow APY Slope 2
+            * * This linearly scales the interest rate upward from the 10% base rate at 70% utilization, 
+            * reaching the full 34% slope cap (44% total APY) only when utilization hits 100%.
+            * * Order of operations: Multiply before dividing to prevent integer truncation / precision loss.
+            */
+            let u_rate_minus_optimal_u_rate = utilization_rate - optimal_utilization_rate;
+            let one_hundred_percent_minus_optimal_u_rate = decimal_scaling - optimal_utilization_rate;
+            //Multiply before dividing to help keep precision
+            let u_rate_minus_optimal_u_rate_times_borrow_apy_slope2 = u_rate_minus_optimal_u_rate * borrow_apy_slope2;
+            let new_high_rate_base = u_rate_minus_optimal_u_rate_times_borrow_apy_slope2 / one_hundred_percent_minus_optimal_u_rate;
+
+            //Max Borrow Rate = 10% + 34% = 44% @100% Utilization Rate. Max base borrow apy is 5%.
+            borrow_apy = (token_reserve.base_borrow_apy as u128 * 2) + new_high_rate_base;
+        }
+    }
+
+    borrow_apy.min(u16::MAX as u128) as u16
+}
+
+//Helper function to calculate the Supply APY = Borrowed APY * Utilization Rate
+pub fn calculate_supply_apy(borrow_apy: u16, utilization_rate: u16) -> u16
+{
+    //Suppliers can never earn more than borrowers pay, so the Utilization Rate is capped at 100% here too
+    let utilization_rate = (utilization_rate as u32).min(DECIMAL_SCALING as u32);
+    let unscaled_supply_apy = borrow_apy as u32 * utilization_rate;
+    (unscaled_supply_apy / DECIMAL_SCALING as u32) as u16
+}
+
 //Helper function to update User Interest Earned amounts. Also updates deposit amounts on the Token Reserve, SubMarket, and user Monthly Statement
 pub fn update_user_previous_interest_earned<'info>(
     token_reserve: &mut Structs::TokenReserve,
@@ -239,12 +339,35 @@ pub fn update_user_previous_interest_earned<'info>(
     lending_user_monthly_statement_account: &mut Structs::LendingUserMonthlyStatementAccount
 ) -> Result<()>
 {
+    //Every activity on a tab updates its interest first, so this doubles as the tab's last activity time stamp for garbage collection
+    lending_user_tab_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+    sync_tab_interest_index_epoch(token_reserve, lending_user_tab_account);
+
     //Skip if the user has no deposited amount
     if lending_user_tab_account.deposited_amount == 0
     {
         return Ok(())
     }
 
+    //A socialized loss lowers the Token Reserve supply index below the user's index. Apply the loss to the user's balance with no fees.
+    //The Token Reserve deposited amount only tracks what has been applied to tabs, so the loss is taken off of it here as well.
+    if token_reserve.supply_interest_change_index < lending_user_tab_account.supply_interest_change_index
+    {
+        //Multiply before dividing to help keep precision
+        let new_user_deposited_amount = (lending_user_tab_account.deposited_amount as u128 * token_reserve.supply_interest_change_index) / lending_user_tab_account.supply_interest_change_index;
+        let user_loss_amount = lending_user_tab_account.deposited_amount as u128 - new_user_deposited_amount;
+
+        token_reserve.deposited_amount = token_reserve.deposited_amount.saturating_sub(user_loss_amount);
+        sub_market.deposited_amount = sub_market.deposited_amount.saturating_sub(user_loss_amount);
+        lending_user_tab_account.deposited_amount = new_user_deposited_amount as u64;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+
+        return Ok(())
+    }
+
+    //The indexes are read as u64 below. Rescaling keeps them in range, this makes sure they never silently truncate
+    require!(token_reserve.supply_interest_change_index <= u64::MAX as u128 && lending_user_tab_account.supply_interest_change_index <= u64::MAX as u128, LendingError::InterestIndexOverflow);
+
     //Use ra_solana_math library FixedPoint for fixed point math
     //User New Balance = Old Balance * Token Reserve Earned Interest Index / User Earned Interest Index
     let token_reserve_supply_index_fp = FixedPoint::from_int(token_reserve.supply_interest_change_index as u64);
@@ -257,18 +380,20 @@ pub fn update_user_previous_interest_earned<'info>(
     let new_user_deposited_amount_before_fees_fp = old_user_balance_mul_token_reserve_index_fp.div(&user_supply_index_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
     let new_user_interest_earned_amount_before_fees_fp = new_user_deposited_amount_before_fees_fp.sub(&old_user_deposited_amount_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
 
-    //Make Sure SubMarket Fee and Solvency Insurance Fee don't exceed 100%
+    //Make Sure SubMarket Fee, Solvency Insurance Fee, and Insurance Fund Share don't exceed 100%
     let sub_market_fee;
     let solvency_insurance_fee;
-    if sub_market.fee_on_interest_earned_rate + token_reserve.solvency_insurance_fee_rate <= 10_000
+    let insurance_fund_share = token_reserve.insurance_fund_share_rate;
+    let sub_market_fee_rate = get_tab_sub_market_fee_rate(sub_market, lending_user_tab_account, Clock::get()?.unix_timestamp as u64);
+    if sub_market_fee_rate + token_reserve.solvency_insurance_fee_rate + insurance_fund_share <= 10_000
     {
-        sub_market_fee = sub_market.fee_on_interest_earned_rate;
+        sub_market_fee = sub_market_fee_rate;
         solvency_insurance_fee = token_reserve.solvency_insurance_fee_rate;
     }
     else
     {
         solvency_insurance_fee = token_reserve.solvency_insurance_fee_rate;
-        sub_market_fee = 10_000 - token_reserve.solvency_insurance_fee_rate;
+        sub_market_fee = 10_000 - token_reserve.solvency_insurance_fee_rate - insurance_fund_share;
     }
    
     //Calculate Total Fee
@@ -305,17 +430,26 @@ pub fn update_user_previous_interest_earned<'info>(
     let new_solvency_insurance_fees_generated_amount_fp_floor = (new_solvency_insurance_fees_generated_amount_before_round/*.add(&round_up_at_point_5)?*/).floor();
     let mut new_solvency_insurance_fees_generated_amount = new_solvency_insurance_fees_generated_amount_fp_floor.to_u128().map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
 
+    //Calculate Insurance Fund Share. These tokens stay in the Token Reserve ATA and are swept into the Insurance Fund when it is deployed against bad debt
+    let insurance_fund_share_rate_fp = FixedPoint::from_bps(insurance_fund_share as u64).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
+    let new_insurance_fund_amount_before_round = new_user_interest_earned_amount_before_fees_fp.mul(&insurance_fund_share_rate_fp)
+        .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
+    let new_insurance_fund_amount_fp_floor = new_insurance_fund_amount_before_round.floor();
+    let new_insurance_fund_amount = new_insurance_fund_amount_fp_floor.to_u128().map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
+
     //Apply Fees to Interest Earned
     let new_user_interest_earned_amount_after_sb_fee_fp = new_user_interest_earned_amount_before_fees_fp.sub(&new_sub_market_fees_generated_amount_fp_floor)
         .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
-    let new_user_interest_earned_amount_after_fees_fp = new_user_interest_earned_amount_after_sb_fee_fp.sub(&new_solvency_insurance_fees_generated_amount_fp_floor)
+    let new_user_interest_earned_amount_after_solvency_fee_fp = new_user_interest_earned_amount_after_sb_fee_fp.sub(&new_solvency_insurance_fees_generated_amount_fp_floor)
+        .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
+    let new_user_interest_earned_amount_after_fees_fp = new_user_interest_earned_amount_after_solvency_fee_fp.sub(&new_insurance_fund_amount_fp_floor)
         .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
     let mut new_user_interest_earned_amount_after_fees = new_user_interest_earned_amount_after_fees_fp.to_u128()
         .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
 
     //User should earn 0% interest when combine fee rates are 100%
     //Due to the separate fee operations above, 'new_user_interest_earned_amount_after_fees' might still hold 1 dust.
-    if sub_market_fee + solvency_insurance_fee == 10_000 && new_user_interest_earned_amount_after_fees > 0
+    if sub_market_fee + solvency_insurance_fee + insurance_fund_share == 10_000 && new_user_interest_earned_amount_after_fees > 0
     {
         //Sweep the remaining dust into Solvency
         new_solvency_insurance_fees_generated_amount += new_user_interest_earned_amount_after_fees;
@@ -325,19 +459,23 @@ pub fn update_user_previous_interest_earned<'info>(
     token_reserve.deposited_amount += new_user_interest_earned_amount_after_fees;
     token_reserve.interest_earned_amount += new_user_interest_earned_amount_after_fees;
     token_reserve.uncollected_solvency_insurance_fees_amount += new_solvency_insurance_fees_generated_amount;
+    token_reserve.unswept_insurance_fund_amount += new_insurance_fund_amount;
     sub_market.deposited_amount += new_user_interest_earned_amount_after_fees;
     sub_market.interest_earned_amount += new_user_interest_earned_amount_after_fees;
     sub_market.sub_market_fees_generated_amount += new_sub_market_fees_generated_amount;
     sub_market.uncollected_sub_market_fees_amount += new_sub_market_fees_generated_amount;
+    token_reserve.uncollected_sub_market_fees_amount += new_sub_market_fees_generated_amount;
     sub_market.solvency_insurance_fees_generated_amount += new_solvency_insurance_fees_generated_amount;
     lending_user_tab_account.deposited_amount += new_user_interest_earned_amount_after_fees as u64;
     lending_user_tab_account.interest_earned_amount += new_user_interest_earned_amount_after_fees as u64;
     lending_user_tab_account.fees_generated_amount += new_sub_market_fees_generated_amount as u64;
     lending_user_tab_account.fees_generated_amount += new_solvency_insurance_fees_generated_amount as u64;
+    lending_user_tab_account.fees_generated_amount += new_insurance_fund_amount as u64;
     lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
     lending_user_monthly_statement_account.monthly_interest_earned_amount += new_user_interest_earned_amount_after_fees as u64;
     lending_user_monthly_statement_account.monthly_fees_generated_amount += new_sub_market_fees_generated_amount as u64;
     lending_user_monthly_statement_account.monthly_fees_generated_amount += new_solvency_insurance_fees_generated_amount as u64;
+    lending_user_monthly_statement_account.monthly_fees_generated_amount += new_insurance_fund_amount as u64;
 
     Ok(())
 }
@@ -350,12 +488,19 @@ pub fn update_user_previous_interest_accrued<'info>(
     lending_user_monthly_statement_account: &mut Structs::LendingUserMonthlyStatementAccount
 ) -> Result<()>
 {
+    //Every activity on a tab updates its interest first, so this doubles as the tab's last activity time stamp for garbage collection
+    lending_user_tab_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+    sync_tab_interest_index_epoch(token_reserve, lending_user_tab_account);
+
     //Skip if the user has no borrowed amount
     if lending_user_tab_account.borrowed_amount == 0
     {
         return Ok(())
     }
 
+    //The indexes are read as u64 below. Rescaling keeps them in range, this makes sure they never silently truncate
+    require!(token_reserve.borrow_interest_change_index <= u64::MAX as u128 && lending_user_tab_account.borrow_interest_change_index <= u64::MAX as u128, LendingError::InterestIndexOverflow);
+
     //Use ra_solana_math library FixedPoint for fixed point math
     //User New Debt = Old Debt * Token Reserve Accrued Interest Index / User Accrued Interest Index
     let token_reserve_borrow_index_fp = FixedPoint::from_int(token_reserve.borrow_interest_change_index as u64);
@@ -370,6 +515,12 @@ pub fn update_user_previous_interest_accrued<'info>(
     .map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
     let new_user_interest_accrued_amount = new_user_interest_accrued_amount_fp.to_u128().map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?;
 
+    //Staking discounts are paid for out of the Token Reserve's uncollected solvency insurance fees so the reserve stays balanced, and stop once they run out
+    let borrow_rate_discount_rate = u16::from_le_bytes(lending_user_tab_account.borrow_rate_discount_rate).min(10_000) as u128;
+    let borrow_rate_discount_amount = (new_user_interest_accrued_amount * borrow_rate_discount_rate / DECIMAL_SCALING).min(token_reserve.uncollected_solvency_insurance_fees_amount);
+    token_reserve.uncollected_solvency_insurance_fees_amount -= borrow_rate_discount_amount;
+    let new_user_interest_accrued_amount = new_user_interest_accrued_amount - borrow_rate_discount_amount;
+
     token_reserve.borrowed_amount += new_user_interest_accrued_amount;
     token_reserve.interest_accrued_amount += new_user_interest_accrued_amount;
     sub_market.borrowed_amount += new_user_interest_accrued_amount;
@@ -384,8 +535,18 @@ pub fn update_user_previous_interest_accrued<'info>(
 
 pub fn check_token_price_staleness(price_data_clock_slot: u64, current_clock_slot: u64) -> Result<()>
 {
+    //Signed so a price written slightly ahead of the slot the bundle lands in reads as a small negative age instead of wrapping around.
+    //Anything further in the future than MAX_PRICE_DATA_FUTURE_SLOTS can't have come from the api and is rejected
+    let price_data_age_slots = current_clock_slot as i64 - price_data_clock_slot as i64;
+    if price_data_age_slots < -MAX_PRICE_DATA_FUTURE_SLOTS
+    {
+        msg!("Current Slot: {}", current_clock_slot);
+        msg!("Data Slot: {}", price_data_clock_slot);
+        return Err(error!(LendingError::OracleDataFutureDated));
+    }
+
     //Allow a max age of 75 slots (approx 30 seconds)
-    if current_clock_slot.saturating_sub(price_data_clock_slot) > 75 //The price data clock slot is set by the m4a api right before it sends off the bundles. There can be a slight delay by the time the bundle executes everything in the same slot, so it's not the slot that the api wrote.
+    if price_data_age_slots > 75 //The price data clock slot is set by the m4a api right before it sends off the bundles. There can be a slight delay by the time the bundle executes everything in the same slot, so it's not the slot that the api wrote.
     {                                                                //But the price can only come from the api and it will always fire off immediately if input is correct. This is more of a safety check, incase like the api price server got stuck and was holding on to an old price for some reason.
         msg!("Current Slot: {}", current_clock_slot);                //StaleTokenReserveOrLendingUser error checks will ensure the necessary transactions atleast execute in the same slot. 75 slots, 400ms per slot, about 30 seconds
         msg!("Data Slot: {}", price_data_clock_slot);                //Think of this as the amount of time the Jito Bundle has to find a slot to execute on
@@ -429,132 +590,1587 @@ pub fn get_verified_token_price(verified_token_prices: &[Structs::VerifiedPriceD
     }
 }
 
-pub fn deposit_tokens_into_token_reserve_from_user<'info>(token_mint_address: Pubkey,
-    token_reserve_ata_info: &AccountInfo<'info>,
-    user_ata_info: &AccountInfo<'info>,
-    token_mint: &InterfaceAccount<'info, Mint>,
-    token_program: &Interface<'info, TokenInterface>,
-    signer: &Signer<'info>,
-    system_program_account: &Program<'info, System>,
-    transfer_amount: u64,
-    should_close_ata: bool
-) -> Result<()>
+//Returns a token's verified price in USD. Token Reserves quoted in another currency chain their price with the USD quote price feed: USD Price = Token Price * Quote Currency USD Price
+pub fn get_verified_token_usd_price(verified_token_prices: &[Structs::VerifiedPriceData], price_id: u8, usd_quote_price_id: u8) -> Result<u128>
 {
-    //Handle native SOL transactions
-    if token_mint_address.key() == SOL_TOKEN_MINT_ADDRESS.key()
+    let normalized_price_18_decimals = get_verified_token_price(verified_token_prices, price_id)?;
+
+    if usd_quote_price_id == 0
     {
-        //CPI to the System Program to transfer SOL from the user to the program's wSOL ATA.
-        let cpi_accounts = system_program::Transfer
-        {
-            from: signer.to_account_info(),
-            to: token_reserve_ata_info.clone()
-        };
-        let cpi_program = system_program_account.key();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        system_program::transfer(cpi_ctx, transfer_amount)?;
+        return Ok(normalized_price_18_decimals)
+    }
 
-        //CPI to the SPL Token Program to "sync" the wSOL ATA's balance.
-        let cpi_accounts = SyncNative
-        {
-            account: token_reserve_ata_info.clone(),
-        };
-        let cpi_program = token_program.key();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token_interface::sync_native(cpi_ctx)?;
+    let quote_normalized_price_18_decimals = get_verified_token_price(verified_token_prices, usd_quote_price_id)?;
 
-        //Close temporary wSOL ATA if its balance is zero
-        if should_close_ata
-        {
-            //Since the User has no other wrapped SOL, close the temporary wrapped SOL account
-            let cpi_accounts = CloseAccount
-            {
-                account: user_ata_info.clone(),
-                destination: signer.to_account_info(),
-                authority: signer.to_account_info(),
-            };
-            let cpi_program = token_program.key();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token_interface::close_account(cpi_ctx)?; 
-        }
+    convert_quoted_price_to_usd(normalized_price_18_decimals, quote_normalized_price_18_decimals)
+}
+
+//USD Price = Token Price * Quote Currency USD Price
+pub fn convert_quoted_price_to_usd(normalized_price_18_decimals: u128, quote_normalized_price_18_decimals: u128) -> Result<u128>
+{
+    //Use ra_solana_math library FixedPoint for fixed point math since both prices have 18 decimals
+    let normalized_price_fp = FixedPoint::from_scaled_u128(normalized_price_18_decimals);
+    let quote_normalized_price_fp = FixedPoint::from_scaled_u128(quote_normalized_price_18_decimals);
+    let usd_normalized_price_18_decimals = normalized_price_fp.mul(&quote_normalized_price_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        .value.as_u128();
+
+    Ok(usd_normalized_price_18_decimals)
+}
+
+//Returns the reserve_version seed of a Token Reserve's PDA. The original Token Reserve for a mint is version 0 and has no version seed, so its address is the same as before Token Reserves were versioned
+pub fn get_token_reserve_version_seed(token_reserve: &Structs::TokenReserve) -> &[u8]
+{
+    if token_reserve.reserve_version == 0
+    {
+        &[]
     }
-    //Handle all other tokens
     else
     {
-        //Cross Program Invocation for Token Transfer
-        let cpi_accounts = TransferChecked
-        {
-            from: user_ata_info.clone(),
-            to: token_reserve_ata_info.clone(),
-            mint: token_mint.to_account_info(),
-            authority: signer.to_account_info()
-        };
-        let cpi_program = token_program.key();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        std::slice::from_ref(&token_reserve.reserve_version)
+    }
+}
 
-        //Transfer Tokens Into The Reserve
-        token_interface::transfer_checked(cpi_ctx, transfer_amount, token_mint.decimals)?;  
+//Returns the side seed of a Token Reserve's Reward Emissions PDA. Deposit Reward Emissions don't have a side seed, so their address is the same as before borrow rewards were added
+pub fn get_reward_emissions_side_seed(borrow_rewards: bool) -> &'static [u8]
+{
+    if borrow_rewards
+    {
+        &[1]
+    }
+    else
+    {
+        &[]
     }
+}
 
-    Ok(())
+//Returns the amount a Tab Account earns Reward Emissions on, its debt for borrow rewards and its deposit otherwise
+pub fn get_reward_earning_amount(reward_emissions: &Structs::RewardEmissions, lending_user_tab_account: &Structs::LendingUserTabAccount) -> u64
+{
+    if reward_emissions.borrow_rewards
+    {
+        lending_user_tab_account.borrowed_amount
+    }
+    else
+    {
+        lending_user_tab_account.deposited_amount
+    }
 }
 
-pub fn withdraw_tokens_from_token_reserve_to_user<'info>(token_mint_address: Pubkey,
-    token_reserve: &Account<'info, Structs::TokenReserve>,
-    token_reserve_ata_info: &AccountInfo<'info>,
-    user_ata_info: &AccountInfo<'info>,
-    token_mint: &InterfaceAccount<'info, Mint>,
-    token_program: &Interface<'info, TokenInterface>,
-    signer: &Signer<'info>,
-    system_program_account: &Program<'info, System>,
-    transfer_amount: u64,
-    should_close: bool
-) -> Result<()>
+//Returns the total a Token Reserve's Reward Emissions are spread across, its borrows for borrow rewards and its deposits otherwise
+pub fn get_reward_total_earning_amount(reward_emissions: &Structs::RewardEmissions, token_reserve: &Structs::TokenReserve) -> u128
 {
-    let seeds = &[b"tokenReserve", token_mint_address.as_ref(), &[token_reserve.bump]];
-    let signer_seeds = &[&seeds[..]];
+    if reward_emissions.borrow_rewards
+    {
+        token_reserve.borrowed_amount
+    }
+    else
+    {
+        token_reserve.deposited_amount
+    }
+}
 
-    let cpi_accounts = TransferChecked
+//Returns the price ID a SubMarket's tabs should be valued with. This is the Token Reserve's token_id unless the SubMarket has a CEO approved oracle override
+pub fn get_sub_market_oracle_price_id(sub_market: &Structs::SubMarket) -> u8
+{
+    if sub_market.oracle_override_approved == 1 && sub_market.oracle_price_id_override != 0
     {
-        from: token_reserve_ata_info.clone(),
-        to: user_ata_info.clone(),
-        mint: token_mint.to_account_info(),
-        authority: token_reserve.to_account_info()
-    };
-    let cpi_program = token_program.key();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        sub_market.oracle_price_id_override
+    }
+    else
+    {
+        sub_market.token_id
+    }
+}
 
-    //Transfer Tokens Back to the User
-    token_interface::transfer_checked(cpi_ctx, transfer_amount, token_mint.decimals)?;
+//Returns the SubMarket fee on interest earned rate at the given time, linearly ramping fee increases between the ramp start and end time stamps
+pub fn get_sub_market_fee_rate(sub_market: &Structs::SubMarket, time_stamp: u64) -> u16
+{
+    if sub_market.fee_ramp_end_time_stamp == 0
+    {
+        sub_market.fee_on_interest_earned_rate
+    }
+    else if time_stamp >= sub_market.fee_ramp_end_time_stamp
+    {
+        sub_market.pending_fee_on_interest_earned_rate
+    }
+    else
+    {
+        let elapsed_seconds = time_stamp.saturating_sub(sub_market.fee_ramp_start_time_stamp) as u128;
+        let ramp_seconds = (sub_market.fee_ramp_end_time_stamp - sub_market.fee_ramp_start_time_stamp) as u128;
+        let fee_increase = (sub_market.pending_fee_on_interest_earned_rate - sub_market.fee_on_interest_earned_rate) as u128;
 
-    //Handle wSOL Token unwrap
-    if token_mint_address.key() == SOL_TOKEN_MINT_ADDRESS.key()
+        //Multiply before dividing to help keep precision
+        sub_market.fee_on_interest_earned_rate + ((fee_increase * elapsed_seconds) / ramp_seconds) as u16
+    }
+}
+
+//The referrer's slice of the SubMarket fees in fees newly generated by a referred Tab Account. Tab Accounts only keep one total for the fees they generate,
+//so the SubMarket's part is split back out at the tab's current fee rates the same way update_user_previous_interest_earned splits them
+pub fn get_referral_fees_amount(token_reserve: &Structs::TokenReserve, sub_market: &Structs::SubMarket, tab_sub_market_fee_rate: u16, fees_generated_amount: u64) -> u64
+{
+    let solvency_insurance_fee_rate = token_reserve.solvency_insurance_fee_rate as u128;
+    let insurance_fund_share_rate = token_reserve.insurance_fund_share_rate as u128;
+    let sub_market_fee_rate = (tab_sub_market_fee_rate as u128).min(DECIMAL_SCALING.saturating_sub(solvency_insurance_fee_rate + insurance_fund_share_rate));
+    let total_fee_rate = sub_market_fee_rate + solvency_insurance_fee_rate + insurance_fund_share_rate;
+
+    if total_fee_rate == 0
     {
-        if !should_close
-        {
-            //Since User already had wrapped SOL, only unwrapped the amount withdrawn
-            let cpi_accounts = system_program::Transfer
-            {
-                from: user_ata_info.clone(),
-                to: signer.to_account_info()
-            };
-            let cpi_program = system_program_account.key();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            system_program::transfer(cpi_ctx, transfer_amount)?;
-        }
-        else
-        {
-            //Since the User has no other wrapped SOL, unwrap it all, send it to the User, and close the temporary wrapped SOL account
-            let cpi_accounts = CloseAccount
-            {
-                account: user_ata_info.clone(),
-                destination: signer.to_account_info(),
-                authority: signer.to_account_info(),
-            };
-            let cpi_program = token_program.key();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-            token_interface::close_account(cpi_ctx)?; 
-        }
+        return 0;
+    }
+
+    //Multiply before dividing to help keep precision
+    let sub_market_fees_amount = fees_generated_amount as u128 * sub_market_fee_rate / total_fee_rate;
+    (sub_market_fees_amount * sub_market.referral_fee_share_rate as u128 / DECIMAL_SCALING) as u64
+}
+
+//Returns the SubMarket fee rate a Tab Account pays, after the Fee Discount Tier discount stamped on it at its last health snap shot
+pub fn get_tab_sub_market_fee_rate(sub_market: &Structs::SubMarket, lending_user_tab_account: &Structs::LendingUserTabAccount, time_stamp: u64) -> u16
+{
+    let fee_discount_rate = u16::from_le_bytes(lending_user_tab_account.fee_discount_rate).min(10_000) as u32;
+
+    //Multiply before dividing to help keep precision
+    (get_sub_market_fee_rate(sub_market, time_stamp) as u32 * (10_000 - fee_discount_rate) / 10_000) as u16
+}
+
+//Returns the discount of the highest Fee Discount Tier the Lending User's total deposited value reaches
+pub fn get_fee_discount_rate(fee_discount_tiers: &[Structs::FeeDiscountTier], total_deposited_usd_value: u128) -> u16
+{
+    fee_discount_tiers.iter()
+        .rev()
+        .find(|fee_discount_tier| total_deposited_usd_value >= fee_discount_tier.minimum_deposited_usd_value)
+        .map_or(0, |fee_discount_tier| fee_discount_tier.fee_discount_rate)
+}
+
+//Lowers the Fee Discount Tier discount stamped on a Tab Account to the tier the Lending User's current total deposited value reaches.
+//Called before interest is earned so a withdrawal since the tab's last health snap shot doesn't keep paying out at the higher tier
+pub fn lower_tab_fee_discount_rate(fee_discount_tiers: &[Structs::FeeDiscountTier], total_deposited_usd_value: u128, lending_user_tab_account: &mut Structs::LendingUserTabAccount)
+{
+    let fee_discount_rate = u16::from_le_bytes(lending_user_tab_account.fee_discount_rate).min(get_fee_discount_rate(fee_discount_tiers, total_deposited_usd_value));
+    lending_user_tab_account.fee_discount_rate = fee_discount_rate.to_le_bytes();
+}
+
+//Returns the staking discount off interest accrued for a staked amount, climbing linearly up to the Staking Pool's max discount
+pub fn get_borrow_rate_discount_rate(staking_pool: &Structs::StakingPool, staked_amount: u64) -> u16
+{
+    if staking_pool.full_discount_staked_amount == 0
+    {
+        return 0;
+    }
+
+    let discounted_staked_amount = staked_amount.min(staking_pool.full_discount_staked_amount) as u128;
+
+    //Multiply before dividing to help keep precision
+    (staking_pool.max_borrow_rate_discount_rate as u128 * discounted_staked_amount / staking_pool.full_discount_staked_amount as u128) as u16
+}
+
+//Lending User Accounts use their E-Mode category's limits while their last finished refresh found all of their collateral and debt in the category
+pub fn get_loan_to_value_rate(lending_user_account: &Structs::LendingUserAccount) -> u128
+{
+    if lending_user_account.e_mode_active
+    {
+        lending_user_account.e_mode_loan_to_value_rate as u128
+    }
+    else
+    {
+        LOAN_TO_VALUE_RATE
+    }
+}
+
+pub fn get_liquidation_threshold_rate(lending_user_account: &Structs::LendingUserAccount) -> u128
+{
+    if lending_user_account.e_mode_active
+    {
+        lending_user_account.e_mode_liquidation_threshold_rate as u128
+    }
+    else
+    {
+        LIQUIDATION_THRESHOLD_RATE
+    }
+}
+
+//Positions moving to a successor program or a newer Token Reserve version take their debt with them, so the tab's own deposits have to back it.
+//The max Loan To Value is under 100%, so the tab's deposits always cover its debt and tokens only ever flow out to where the position is going
+pub fn check_moved_position_collateral(lending_user_account: &Structs::LendingUserAccount, token_reserve: &Structs::TokenReserve, deposited_amount: u64, borrowed_amount: u64) -> Result<()>
+{
+    //Only the part of the deposits left after the Token Reserve's collateral haircut can back the debt. Multiply before dividing to help keep precision
+    let collateral_amount = deposited_amount as u128 * (10_000 - token_reserve.collateral_haircut_rate.min(10_000) as u128) / 10_000;
+    let max_borrowed_amount = (collateral_amount * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+    require!(max_borrowed_amount >= borrowed_amount as u128, LendingError::ExportedPositionUndercollateralized);
+
+    Ok(())
+}
+
+//Returns the part of a deposit's USD value that counts toward user health, after the Token Reserve's collateral haircut
+pub fn get_collateral_usd_value(token_reserve: &Structs::TokenReserve, deposited_usd_value: u128) -> u128
+{
+    //Multiply before dividing to help keep precision
+    deposited_usd_value * (10_000 - token_reserve.collateral_haircut_rate.min(10_000) as u128) / 10_000
+}
+
+//Lending Users who opted into E-Mode can only add collateral or debt in their E-Mode asset category
+pub fn check_e_mode_asset_category(lending_user_account: &Structs::LendingUserAccount, token_reserve: &Structs::TokenReserve) -> Result<()>
+{
+    if lending_user_account.e_mode_category != 0
+    {
+        require!(token_reserve.asset_category == lending_user_account.e_mode_category, LendingError::EModeAssetCategoryMismatch);
     }
 
     Ok(())
+}
+
+//Adds a Token Reserve the Lending User Account has debt in to its borrowed tokens
+pub fn record_borrowed_token(borrowed_tokens: &mut Structs::BorrowedTokens, token_reserve: &Structs::TokenReserve)
+{
+    if borrowed_tokens.token_count == 0
+    {
+        borrowed_tokens.token_id = token_reserve.token_id;
+        borrowed_tokens.token_count = 1;
+    }
+    else if borrowed_tokens.token_id != token_reserve.token_id
+    {
+        borrowed_tokens.token_count = 2;
+    }
+
+    borrowed_tokens.has_siloed_token |= token_reserve.siloed_borrowing;
+}
+
+//Siloed tokens can only be borrowed by Lending User Accounts with no debt in other tokens, and accounts with siloed debt can't borrow other tokens
+pub fn check_siloed_borrowing(lending_user_account: &Structs::LendingUserAccount, token_reserve: &Structs::TokenReserve) -> Result<()>
+{
+    let borrowed_tokens = &lending_user_account.borrowed_tokens;
+    let has_debt_in_other_tokens = borrowed_tokens.token_count > 1 || (borrowed_tokens.token_count == 1 && borrowed_tokens.token_id != token_reserve.token_id);
+
+    if has_debt_in_other_tokens
+    {
+        require!(!token_reserve.siloed_borrowing && !borrowed_tokens.has_siloed_token, LendingError::SiloedBorrowing);
+    }
+
+    Ok(())
+}
+
+//E-Mode limits can't be lower than the regular limits, and the Loan To Value has to stay below the liquidation threshold
+pub fn check_e_mode_category_rates(loan_to_value_rate: u16, liquidation_threshold_rate: u16) -> Result<()>
+{
+    require!(loan_to_value_rate as u128 >= LOAN_TO_VALUE_RATE
+        && liquidation_threshold_rate as u128 >= LIQUIDATION_THRESHOLD_RATE
+        && loan_to_value_rate < liquidation_threshold_rate
+        && liquidation_threshold_rate <= MAX_E_MODE_LIQUIDATION_THRESHOLD_RATE, LendingError::InvalidEModeCategoryRates);
+
+    Ok(())
+}
+
+//Returns the user's Monthly Statement account, or an unsaved placeholder statement when the user opted out of Monthly Statements and left it out of the instruction
+pub fn get_lending_user_monthly_statement_account<'a, 'info>(
+    lending_user_monthly_statement_account: &'a mut Option<Box<Account<'info, Structs::LendingUserMonthlyStatementAccount>>>,
+    placeholder_monthly_statement_account: &'a mut Structs::LendingUserMonthlyStatementAccount,
+    monthly_statements_disabled: bool
+) -> Result<&'a mut Structs::LendingUserMonthlyStatementAccount>
+{
+    match lending_user_monthly_statement_account
+    {
+        Some(monthly_statement_account) => Ok(&mut ***monthly_statement_account),
+        None =>
+        {
+            require!(monthly_statements_disabled, LendingError::MissingMonthlyStatementAccount);
+
+            //Mark the placeholder as added so it never gets initialized like a real statement
+            placeholder_monthly_statement_account.monthly_statement_account_added = true;
+            Ok(placeholder_monthly_statement_account)
+        }
+    }
+}
+
+//Withdrawals and borrows worth more than the user's co-signer threshold need the user's registered co-signer to sign the transaction too
+pub fn check_co_signer<'info>(lending_user_account: &Structs::LendingUserAccount, co_signer: &Option<Signer<'info>>, usd_value: u128) -> Result<()>
+{
+    if lending_user_account.co_signer_address != Pubkey::default() && usd_value > lending_user_account.co_signer_threshold_usd_value
+    {
+        let co_signer = co_signer.as_ref().ok_or(LendingError::MissingCoSigner)?;
+        require_keys_eq!(co_signer.key(), lending_user_account.co_signer_address, LendingError::CoSignerMisMatched);
+    }
+
+    Ok(())
+}
+
+//Withdraws and borrows can use the cached User Health in place of a health refresh in the same transaction, as long as it's within the Lending Protocol's max age
+pub fn check_user_health_freshness<'info>(lending_user_account: &mut Structs::LendingUserAccount,
+    user_health: &Option<Box<Account<'info, Structs::UserHealth>>>,
+    user_health_max_age_slots: u64,
+    clock_slot: u64
+) -> Result<()>
+{
+    if lending_user_account.last_health_update_clock_slot == clock_slot
+    {
+        return Ok(());
+    }
+
+    let user_health = user_health.as_ref().ok_or(LendingError::StaleTokenReserveOrLendingUser)?;
+    require!(user_health_max_age_slots > 0 && clock_slot - user_health.refresh_clock_slot <= user_health_max_age_slots, LendingError::StaleTokenReserveOrLendingUser);
+    require!(!lending_user_account.user_health_invalidated, LendingError::StaleTokenReserveOrLendingUser);
+
+    lending_user_account.total_deposited_usd_value = user_health.total_deposited_usd_value;
+    lending_user_account.total_borrowed_usd_value = user_health.total_borrowed_usd_value;
+
+    Ok(())
+}
+
+//Second phase of a two-phase withdraw or borrow. The User Health attestation carries the verified prices it was refreshed with, so the price accounts can be left out until it expires
+pub fn get_attested_user_health_usd_price<'info>(user_health: &Option<Box<Account<'info, Structs::UserHealth>>>,
+    clock_slot: u64,
+    price_id: u8,
+    usd_quote_price_id: u8
+) -> Result<u128>
+{
+    let user_health = user_health.as_ref().ok_or(LendingError::MissingRemainingAccount)?;
+    require!(!user_health.attested_prices.is_empty() && clock_slot <= user_health.expiry_clock_slot, LendingError::UserHealthAttestationExpired);
+
+    get_verified_token_usd_price(&user_health.attested_prices, price_id, usd_quote_price_id)
+}
+
+//Keeps the cached User Health in step with withdraws and borrows, so the same headroom can't be used twice before the next refresh
+pub fn update_cached_user_health<'info>(lending_user_account: &mut Structs::LendingUserAccount, user_health: &mut Option<Box<Account<'info, Structs::UserHealth>>>)
+{
+    if let Some(user_health) = user_health
+    {
+        user_health.total_deposited_usd_value = lending_user_account.total_deposited_usd_value;
+        user_health.total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value;
+    }
+    else
+    {
+        invalidate_cached_user_health(lending_user_account);
+    }
+}
+
+//Liquidations, deleverages, and anything else that takes collateral or adds debt without the User Health account passed in
+//leave the cached totals too high, so they can't be used again until the next finished refresh
+pub fn invalidate_cached_user_health(lending_user_account: &mut Structs::LendingUserAccount)
+{
+    lending_user_account.user_health_invalidated = true;
+}
+
+//Keeps the Token Reserve's active depositor and borrower counts in step with a tab's balances. The tab remembers whether it's been counted,
+//so this can be called after any balance change, and tabs from before the counts existed are picked up the first time they're touched.
+//Since it's called after every balance change, it also stamps the tab's balance change time for rewards
+pub fn sync_token_reserve_active_user_counts(token_reserve: &mut Structs::TokenReserve, lending_user_tab_account: &mut Structs::LendingUserTabAccount)
+{
+    lending_user_tab_account.balance_changed_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+    let is_depositor = (lending_user_tab_account.deposited_amount > 0) as u8;
+    if is_depositor != lending_user_tab_account.counted_as_depositor
+    {
+        if is_depositor == 1
+        {
+            token_reserve.active_depositor_count += 1;
+        }
+        else
+        {
+            token_reserve.active_depositor_count = token_reserve.active_depositor_count.saturating_sub(1);
+        }
+        lending_user_tab_account.counted_as_depositor = is_depositor;
+    }
+
+    let is_borrower = (lending_user_tab_account.borrowed_amount > 0) as u8;
+    if is_borrower != lending_user_tab_account.counted_as_borrower
+    {
+        if is_borrower == 1
+        {
+            token_reserve.active_borrower_count += 1;
+        }
+        else
+        {
+            token_reserve.active_borrower_count = token_reserve.active_borrower_count.saturating_sub(1);
+        }
+        lending_user_tab_account.counted_as_borrower = is_borrower;
+    }
+}
+
+//Tokens the Token Reserve ATA should be holding = Deposits - Loans (including written off bad debt) + Everything set aside in the ATA that isn't a deposit yet
+pub fn get_expected_token_reserve_ata_balance(token_reserve: &Structs::TokenReserve, insurance_fund_balance_amount: u128) -> u128
+{
+    let lent_out_amount = token_reserve.borrowed_amount + token_reserve.bad_debt_amount;
+    let set_aside_amount = token_reserve.uncollected_solvency_insurance_fees_amount +
+        token_reserve.uncollected_liquidation_fees_amount +
+        token_reserve.uncollected_sub_market_fees_amount +
+        token_reserve.unswept_insurance_fund_amount +
+        insurance_fund_balance_amount +
+        token_reserve.auction_collateral_amount;
+
+    (token_reserve.deposited_amount + set_aside_amount).saturating_sub(lent_out_amount)
+}
+
+//Reprices a Token Reserve's entry in the Protocol TVL account and adds the entries back up, so the totals never drift from the entries
+pub fn update_protocol_tvl(protocol_tvl: &mut Structs::ProtocolTvl,
+    token_reserve: &Structs::TokenReserve,
+    normalized_price_18_decimals: u128,
+    time_stamp: u64
+) -> Result<()>
+{
+    let token_conversion_number = 10u128.pow(token_reserve.token_decimal_amount as u32);
+    let deposited_usd_value = token_reserve.deposited_amount.checked_mul(normalized_price_18_decimals).ok_or(ProgramError::ArithmeticOverflow)? / token_conversion_number;
+    let borrowed_usd_value = token_reserve.borrowed_amount.checked_mul(normalized_price_18_decimals).ok_or(ProgramError::ArithmeticOverflow)? / token_conversion_number;
+
+    match protocol_tvl.token_reserve_tvls.iter_mut().find(|token_reserve_tvl| token_reserve_tvl.token_id == token_reserve.token_id)
+    {
+        Some(token_reserve_tvl) =>
+        {
+            token_reserve_tvl.deposited_usd_value = deposited_usd_value;
+            token_reserve_tvl.borrowed_usd_value = borrowed_usd_value;
+            token_reserve_tvl.time_stamp = time_stamp;
+        },
+        None =>
+        {
+            require!(protocol_tvl.token_reserve_tvls.len() < MAX_PROTOCOL_TVL_TOKEN_RESERVES, LendingError::ProtocolTvlFull);
+            protocol_tvl.token_reserve_tvls.push(Structs::TokenReserveTvl
+            {
+                token_id: token_reserve.token_id,
+                deposited_usd_value,
+                borrowed_usd_value,
+                time_stamp
+            });
+        }
+    }
+
+    protocol_tvl.total_deposited_usd_value = protocol_tvl.token_reserve_tvls.iter().map(|token_reserve_tvl| token_reserve_tvl.deposited_usd_value).sum();
+    protocol_tvl.total_borrowed_usd_value = protocol_tvl.token_reserve_tvls.iter().map(|token_reserve_tvl| token_reserve_tvl.borrowed_usd_value).sum();
+    protocol_tvl.last_update_time_stamp = time_stamp;
+
+    Ok(())
+}
+
+//Writes over the oldest activity once the ring buffer is full. Does nothing for users who haven't opted in
+pub fn record_lending_user_activity<'info>(lending_user_activity_account: &mut Option<Box<Account<'info, Structs::LendingUserActivityAccount>>>,
+    activity_type: u8,
+    token_mint_address: Pubkey,
+    amount: u64,
+    time_stamp: u64
+)
+{
+    if let Some(lending_user_activity_account) = lending_user_activity_account
+    {
+        let next_activity_index = lending_user_activity_account.next_activity_index as usize;
+        lending_user_activity_account.activities[next_activity_index] = Structs::LendingUserActivityRecord
+        {
+            activity_type,
+            token_mint_address,
+            amount,
+            time_stamp
+        };
+        lending_user_activity_account.next_activity_index = ((next_activity_index + 1) % MAX_LENDING_USER_ACTIVITY_RECORDS) as u8;
+        lending_user_activity_account.activity_count += 1;
+    }
+}
+
+//Shared by update_token_reserve and execute_timelocked_change so both paths validate and apply the same way
+pub fn set_token_reserve_parameters(token_reserve: &mut Structs::TokenReserve,
+    base_borrow_apy: u16,
+    use_fixed_borrow_apy: bool,
+    global_limit: u128,
+    solvency_insurance_fee_rate: u16) -> Result<()>
+{
+    //Base Borrow APY can't be greater than 5%, 0.05 in decimal form, 500 in fixed point notation
+    require!(base_borrow_apy <= 500, LendingError::InvalidBaseBorrowAPY);
+
+    //Solvency Insurance Fee on interest earned rate can't be greater than 4%, 0.04 in decimal form, 400 in fixed point notation
+    require!(solvency_insurance_fee_rate <= 400, LendingError::InvalidSolvencyInsuranceFeeRate);
+
+    //If the value of the Token Reserve Borrow APY will change, calculate previous interest changes before updating it
+    if token_reserve.base_borrow_apy != base_borrow_apy || token_reserve.use_fixed_borrow_apy != use_fixed_borrow_apy
+    {
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+    }
+
+    token_reserve.base_borrow_apy = base_borrow_apy;
+    token_reserve.use_fixed_borrow_apy = use_fixed_borrow_apy;
+    token_reserve.global_limit = global_limit;
+    token_reserve.solvency_insurance_fee_rate = solvency_insurance_fee_rate;
+
+    //Update Token Reserve Global Utilization Rate, Borrow APY, and, Supply APY
+    update_token_reserve_rates(token_reserve)
+}
+
+//Shared by update_token_reserve_collateral_haircut_rate and execute_timelocked_change so both paths validate and apply the same way
+pub fn set_token_reserve_collateral_haircut_rate(token_reserve: &mut Structs::TokenReserve, collateral_haircut_rate: u16) -> Result<()>
+{
+    //Collateral haircut rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+    require!(collateral_haircut_rate <= 10_000, LendingError::InvalidCollateralHaircutRate);
+
+    //A listing only starts counting toward user health once its veto window closes without a veto
+    if collateral_haircut_rate < token_reserve.collateral_haircut_rate
+    {
+        require!(!token_reserve.listing_vetoed, LendingError::TokenReserveListingVetoed);
+
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        require!(time_stamp >= token_reserve.listing_veto_deadline_time_stamp, LendingError::ListingVetoWindowOpen);
+    }
+
+    token_reserve.collateral_haircut_rate = collateral_haircut_rate;
+
+    Ok(())
+}
+
+//Borrows at or above the Token Reserve's Borrow Intent threshold have to be executed through a Borrow Intent that has waited out its delay with the price still close to the posted price
+pub fn check_borrow_intent(token_reserve: &Structs::TokenReserve,
+    borrow_intent: &Option<Box<Account<Structs::BorrowIntent>>>,
+    borrow_amount: u64,
+    normalized_price_18_decimals: u128,
+    clock_slot: u64
+) -> Result<()>
+{
+    if token_reserve.borrow_intent_threshold_amount == 0 || borrow_amount < token_reserve.borrow_intent_threshold_amount
+    {
+        return Ok(());
+    }
+
+    let borrow_intent = borrow_intent.as_ref().ok_or(LendingError::MissingBorrowIntent)?;
+    require!(borrow_amount <= borrow_intent.amount, LendingError::BorrowIntentAmountExceeded);
+    require!(clock_slot >= borrow_intent.posted_clock_slot + token_reserve.borrow_intent_delay_slots, LendingError::BorrowIntentDelayNotElapsed);
+
+    let price_deviation = normalized_price_18_decimals.abs_diff(borrow_intent.posted_price_18_decimals);
+    let max_price_deviation = (borrow_intent.posted_price_18_decimals * token_reserve.borrow_intent_max_price_deviation_rate as u128) / DECIMAL_SCALING;
+    require!(price_deviation <= max_price_deviation, LendingError::BorrowIntentPriceOutOfBounds);
+
+    Ok(())
+}
+
+//Returns the amount the Token Reserve actually received. Token-2022 mints with the transfer fee extension deliver less than the amount sent, so callers credit this instead.
+//Withdrawals don't need the same treatment: the full amount leaves the Token Reserve and the transfer fee comes out of what the user receives
+pub fn deposit_tokens_into_token_reserve_from_user<'info>(token_mint_address: Pubkey,
+    token_reserve_ata_info: &AccountInfo<'info>,
+    user_ata_info: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &Signer<'info>,
+    system_program_account: &Program<'info, System>,
+    transfer_amount: u64,
+    should_close_ata: bool,
+    transfer_hook_accounts: &[AccountInfo<'info>] //Extra accounts for Token-2022 transfer hooks. Empty for mints without one
+) -> Result<u64>
+{
+    let token_reserve_ata_amount_before_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    //Handle native SOL transactions
+    if token_mint_address.key() == SOL_TOKEN_MINT_ADDRESS.key()
+    {
+        //CPI to the System Program to transfer SOL from the user to the program's wSOL ATA.
+        let cpi_accounts = system_program::Transfer
+        {
+            from: signer.to_account_info(),
+            to: token_reserve_ata_info.clone()
+        };
+        let cpi_program = system_program_account.key();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        system_program::transfer(cpi_ctx, transfer_amount)?;
+
+        //CPI to the SPL Token Program to "sync" the wSOL ATA's balance.
+        let cpi_accounts = SyncNative
+        {
+            account: token_reserve_ata_info.clone(),
+        };
+        let cpi_program = token_program.key();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::sync_native(cpi_ctx)?;
+
+        //Close temporary wSOL ATA if its balance is zero
+        if should_close_ata
+        {
+            //Since the User has no other wrapped SOL, close the temporary wrapped SOL account
+            let cpi_accounts = CloseAccount
+            {
+                account: user_ata_info.clone(),
+                destination: signer.to_account_info(),
+                authority: signer.to_account_info(),
+            };
+            let cpi_program = token_program.key();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+            token_interface::close_account(cpi_ctx)?; 
+        }
+    }
+    //Handle all other tokens
+    else
+    {
+        //Transfer Tokens Into The Reserve
+        transfer_tokens_checked(user_ata_info,
+            token_reserve_ata_info,
+            &signer.to_account_info(),
+            token_mint,
+            token_program,
+            transfer_hook_accounts,
+            transfer_amount,
+            &[]
+        )?;
+    }
+
+    let token_reserve_ata_amount_after_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    Ok(token_reserve_ata_amount_after_transfer - token_reserve_ata_amount_before_transfer)
+}
+
+//Repays with tokens the signer just received from a swap. wSOL is moved as a token transfer here since a swap pays out wSOL, not native SOL.
+//Returns the amount the Token Reserve actually received, the same as deposit_tokens_into_token_reserve_from_user
+pub fn deposit_swapped_tokens_into_token_reserve<'info>(token_reserve_ata_info: &AccountInfo<'info>,
+    user_ata_info: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &Signer<'info>,
+    transfer_amount: u64
+) -> Result<u64>
+{
+    let token_reserve_ata_amount_before_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    transfer_tokens_checked(user_ata_info,
+        token_reserve_ata_info,
+        &signer.to_account_info(),
+        token_mint,
+        token_program,
+        &[],
+        transfer_amount,
+        &[]
+    )?;
+
+    let token_reserve_ata_amount_after_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    Ok(token_reserve_ata_amount_after_transfer - token_reserve_ata_amount_before_transfer)
+}
+
+//Deposits from a token account the signer is a delegate of (ie a program-managed vault) instead of the signer's own ATA. The signer signs the transfer as the delegate.
+//wSOL is moved as a token transfer here since the SOL isn't the signer's. Returns the amount the Token Reserve actually received, the same as deposit_tokens_into_token_reserve_from_user
+pub fn deposit_tokens_into_token_reserve_from_delegated_token_account<'info>(token_reserve_ata_info: &AccountInfo<'info>,
+    delegated_token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &Signer<'info>,
+    transfer_amount: u64,
+    transfer_hook_accounts: &[AccountInfo<'info>] //Extra accounts for Token-2022 transfer hooks. Empty for mints without one
+) -> Result<u64>
+{
+    //The signer has to be approved as the token account's delegate for at least the deposit amount
+    require!(delegated_token_account.delegate == COption::Some(signer.key()), LendingError::InvalidDelegatedTokenAccount);
+    require!(delegated_token_account.delegated_amount >= transfer_amount, LendingError::InsufficientDelegatedAmount);
+
+    let token_reserve_ata_amount_before_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    transfer_tokens_checked(&delegated_token_account.to_account_info(),
+        token_reserve_ata_info,
+        &signer.to_account_info(),
+        token_mint,
+        token_program,
+        transfer_hook_accounts,
+        transfer_amount,
+        &[]
+    )?;
+
+    let token_reserve_ata_amount_after_transfer = TokenAccount::try_deserialize(&mut &token_reserve_ata_info.data.borrow()[..])?.amount;
+
+    Ok(token_reserve_ata_amount_after_transfer - token_reserve_ata_amount_before_transfer)
+}
+
+//Grosses an amount up by the Token-2022 transfer fee, so the Token Reserve receives the full amount from mints with the transfer fee extension
+pub fn get_amount_with_transfer_fee<'info>(token_mint: &InterfaceAccount<'info, Mint>, amount: u64) -> Result<u64>
+{
+    let token_mint_info = token_mint.to_account_info();
+
+    //Mints owned by the original Token Program don't have extensions
+    if *token_mint_info.owner != anchor_spl::token_2022::ID
+    {
+        return Ok(amount);
+    }
+
+    let data = token_mint_info.try_borrow_data()?;
+    let token_mint_state = StateWithExtensions::<SplMint>::unpack(&data)?;
+
+    if let Ok(transfer_fee_config) = token_mint_state.get_extension::<TransferFeeConfig>()
+    {
+        let transfer_fee = transfer_fee_config.calculate_inverse_epoch_fee(Clock::get()?.epoch, amount).ok_or(LendingError::AuctionPaymentShortfall)?;
+        return Ok(amount + transfer_fee);
+    }
+
+    Ok(amount)
+}
+
+pub fn withdraw_tokens_from_token_reserve_to_user<'info>(token_mint_address: Pubkey,
+    token_reserve: &Account<'info, Structs::TokenReserve>,
+    token_reserve_ata_info: &AccountInfo<'info>,
+    user_ata_info: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &Signer<'info>,
+    system_program_account: &Program<'info, System>,
+    transfer_amount: u64,
+    temporary_wsol_account: Option<(&AccountInfo<'info>, u8)>, //Temporary wSOL account and its bump. Only used to pay SOL out as native SOL
+    transfer_hook_accounts: &[AccountInfo<'info>] //Extra accounts for Token-2022 transfer hooks. Empty for mints without one
+) -> Result<()>
+{
+    let seeds = &[b"tokenReserve", token_mint_address.as_ref(), get_token_reserve_version_seed(token_reserve), &[token_reserve.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    //Handle native SOL withdrawals. The wSOL goes through a temporary token account that's created and closed right here, so the user's wSOL ATA and any wSOL already in it aren't touched
+    if let (true, Some((temporary_wsol_account_info, temporary_wsol_account_bump))) = (token_mint_address.key() == SOL_TOKEN_MINT_ADDRESS.key(), temporary_wsol_account)
+    {
+        let signer_address = signer.key();
+        let temporary_wsol_account_seeds = &[b"temporaryWsolAccount", signer_address.as_ref(), &[temporary_wsol_account_bump]];
+        let temporary_wsol_account_signer_seeds = &[&temporary_wsol_account_seeds[..]];
+
+        create_temporary_wsol_account(temporary_wsol_account_info, signer, system_program_account, token_program, temporary_wsol_account_signer_seeds)?;
+
+        //The Token Reserve owns the temporary account, so only this instruction can move what's in it
+        let cpi_accounts = InitializeAccount3
+        {
+            account: temporary_wsol_account_info.clone(),
+            mint: token_mint.to_account_info(),
+            authority: token_reserve.to_account_info()
+        };
+        let cpi_program = token_program.key();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::initialize_account3(cpi_ctx)?;
+
+        let cpi_accounts = TransferChecked
+        {
+            from: token_reserve_ata_info.clone(),
+            to: temporary_wsol_account_info.clone(),
+            mint: token_mint.to_account_info(),
+            authority: token_reserve.to_account_info()
+        };
+        let cpi_program = token_program.key();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, transfer_amount, token_mint.decimals)?;
+
+        //Closing the temporary account unwraps the SOL and sends it to the user along with the rent they paid to create it
+        let cpi_accounts = CloseAccount
+        {
+            account: temporary_wsol_account_info.clone(),
+            destination: signer.to_account_info(),
+            authority: token_reserve.to_account_info()
+        };
+        let cpi_program = token_program.key();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::close_account(cpi_ctx)?;
+
+        return Ok(());
+    }
+
+    //Everything else, including SOL withdrawn without a temporary wSOL account, is sent to the user's ATA
+    transfer_tokens_checked(token_reserve_ata_info,
+        user_ata_info,
+        &token_reserve.to_account_info(),
+        token_mint,
+        token_program,
+        transfer_hook_accounts,
+        transfer_amount,
+        signer_seeds
+    )?;
+
+    Ok(())
+}
+
+//Token-2022 mints with a transfer hook need the hook program's extra accounts on every transfer.
+//The spl-transfer-hook interface helper resolves them from the mint's extra account metas and the accounts passed in
+fn transfer_tokens_checked<'info>(from_info: &AccountInfo<'info>,
+    to_info: &AccountInfo<'info>,
+    authority_info: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_hook_accounts: &[AccountInfo<'info>],
+    transfer_amount: u64,
+    signer_seeds: &[&[&[u8]]]
+) -> Result<()>
+{
+    if transfer_hook_accounts.is_empty()
+    {
+        let cpi_accounts = TransferChecked
+        {
+            from: from_info.clone(),
+            to: to_info.clone(),
+            mint: token_mint.to_account_info(),
+            authority: authority_info.clone()
+        };
+        let cpi_program = token_program.key();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, transfer_amount, token_mint.decimals)?;
+
+        return Ok(());
+    }
+
+    invoke_transfer_checked(&token_program.key(),
+        from_info.clone(),
+        token_mint.to_account_info(),
+        to_info.clone(),
+        authority_info.clone(),
+        transfer_hook_accounts,
+        transfer_amount,
+        token_mint.decimals,
+        signer_seeds
+    )?;
+
+    Ok(())
+}
+
+//Creates the temporary wSOL account at its PDA. Lamports sent to the PDA ahead of time can't block it, the same way Anchor's init handles a prefunded account
+fn create_temporary_wsol_account<'info>(temporary_wsol_account_info: &AccountInfo<'info>,
+    signer: &Signer<'info>,
+    system_program_account: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+    temporary_wsol_account_signer_seeds: &[&[&[u8]]]
+) -> Result<()>
+{
+    let rent_amount = Rent::get()?.minimum_balance(TOKEN_ACCOUNT_SPACE as usize);
+    let cpi_program = system_program_account.key();
+
+    if temporary_wsol_account_info.lamports() == 0
+    {
+        let cpi_accounts = system_program::CreateAccount
+        {
+            from: signer.to_account_info(),
+            to: temporary_wsol_account_info.clone()
+        };
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, temporary_wsol_account_signer_seeds);
+        system_program::create_account(cpi_ctx, rent_amount, TOKEN_ACCOUNT_SPACE, &token_program.key())?;
+
+        return Ok(());
+    }
+
+    let missing_rent_amount = rent_amount.saturating_sub(temporary_wsol_account_info.lamports());
+    if missing_rent_amount > 0
+    {
+        let cpi_accounts = system_program::Transfer
+        {
+            from: signer.to_account_info(),
+            to: temporary_wsol_account_info.clone()
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        system_program::transfer(cpi_ctx, missing_rent_amount)?;
+    }
+
+    let cpi_accounts = system_program::Allocate
+    {
+        account_to_allocate: temporary_wsol_account_info.clone()
+    };
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, temporary_wsol_account_signer_seeds);
+    system_program::allocate(cpi_ctx, TOKEN_ACCOUNT_SPACE)?;
+
+    let cpi_accounts = system_program::Assign
+    {
+        account_to_assign: temporary_wsol_account_info.clone()
+    };
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, temporary_wsol_account_signer_seeds);
+    system_program::assign(cpi_ctx, &token_program.key())?;
+
+    Ok(())
+}
+
+//Sends an exported or migrated position's net balance (deposits minus debt) to the successor program's Token Reserve or a newer version of the Token Reserve. The debt moves with it, so no tokens come back
+pub fn transfer_tokens_from_token_reserve_to_successor_token_reserve<'info>(token_mint_address: Pubkey,
+    token_reserve: &Account<'info, Structs::TokenReserve>,
+    token_reserve_ata_info: &AccountInfo<'info>,
+    successor_token_reserve_ata_info: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_amount: u64
+) -> Result<()>
+{
+    let seeds = &[b"tokenReserve", token_mint_address.as_ref(), get_token_reserve_version_seed(token_reserve), &[token_reserve.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked
+    {
+        from: token_reserve_ata_info.clone(),
+        to: successor_token_reserve_ata_info.clone(),
+        mint: token_mint.to_account_info(),
+        authority: token_reserve.to_account_info()
+    };
+    let cpi_program = token_program.key();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, transfer_amount, token_mint.decimals)?;
+
+    Ok(())
+}
+
+//Creates an Address Lookup Table with the Lending User Account as its authority, so only this program can extend it
+pub fn create_lending_user_look_up_table<'info>(look_up_table: &AccountInfo<'info>,
+    lending_user_account: &Account<'info, Structs::LendingUserAccount>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    recent_slot: u64,
+    look_up_table_bump: u8
+) -> Result<()>
+{
+    //Instruction data is the bincode encoded CreateLookupTable { recent_slot, bump_seed }
+    let mut data = CREATE_LOOK_UP_TABLE_INSTRUCTION_INDEX.to_le_bytes().to_vec();
+    data.extend_from_slice(&recent_slot.to_le_bytes());
+    data.push(look_up_table_bump);
+
+    let create_look_up_table_instruction = Instruction
+    {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS,
+        accounts: vec![
+            AccountMeta::new(look_up_table.key(), false),
+            AccountMeta::new_readonly(lending_user_account.key(), true),
+            AccountMeta::new(signer.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false)
+        ],
+        data
+    };
+
+    let user_account_index_to_le_bytes = lending_user_account.user_account_index.to_le_bytes();
+    let seeds = &[b"lendingUserAccount", lending_user_account.owner.as_ref(), user_account_index_to_le_bytes.as_ref(), &[lending_user_account.bump]];
+    let signer_seeds = &[&seeds[..]];
+    invoke_signed(&create_look_up_table_instruction,
+        &[look_up_table.clone(), lending_user_account.to_account_info(), signer.clone(), system_program.clone()],
+        signer_seeds)?;
+
+    Ok(())
+}
+
+//Appends the addresses the Look Up Table doesn't hold yet and returns how many were added. Extended addresses can only be used starting the next slot
+pub fn extend_lending_user_look_up_table<'info>(look_up_table: &AccountInfo<'info>,
+    lending_user_account: &Account<'info, Structs::LendingUserAccount>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    addresses: Vec<Pubkey>
+) -> Result<usize>
+{
+    require_keys_eq!(*look_up_table.owner, ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS, LendingError::UnexpectedLookUpTableAccount);
+
+    let existing_addresses: Vec<Pubkey> =
+    {
+        let look_up_table_data = look_up_table.try_borrow_data()?;
+        require!(look_up_table_data.len() >= LOOK_UP_TABLE_META_SIZE, LendingError::UnexpectedLookUpTableAccount);
+        look_up_table_data[LOOK_UP_TABLE_META_SIZE..]
+            .chunks_exact(32)
+            .map(|address_bytes| Pubkey::try_from(address_bytes).unwrap())
+            .collect()
+    };
+
+    let mut new_addresses: Vec<Pubkey> = Vec::with_capacity(addresses.len());
+    for address in addresses
+    {
+        if !existing_addresses.contains(&address) && !new_addresses.contains(&address)
+        {
+            new_addresses.push(address);
+        }
+    }
+
+    if new_addresses.is_empty()
+    {
+        return Ok(0)
+    }
+
+    require!(existing_addresses.len() + new_addresses.len() <= MAX_LOOK_UP_TABLE_ADDRESSES, LendingError::LookUpTableFull);
+
+    //Instruction data is the bincode encoded ExtendLookupTable { new_addresses }
+    let mut data = EXTEND_LOOK_UP_TABLE_INSTRUCTION_INDEX.to_le_bytes().to_vec();
+    data.extend_from_slice(&(new_addresses.len() as u64).to_le_bytes());
+    for address in new_addresses.iter()
+    {
+        data.extend_from_slice(address.as_ref());
+    }
+
+    let extend_look_up_table_instruction = Instruction
+    {
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS,
+        accounts: vec![
+            AccountMeta::new(look_up_table.key(), false),
+            AccountMeta::new_readonly(lending_user_account.key(), true),
+            AccountMeta::new(signer.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false)
+        ],
+        data
+    };
+
+    let user_account_index_to_le_bytes = lending_user_account.user_account_index.to_le_bytes();
+    let seeds = &[b"lendingUserAccount", lending_user_account.owner.as_ref(), user_account_index_to_le_bytes.as_ref(), &[lending_user_account.bump]];
+    let signer_seeds = &[&seeds[..]];
+    invoke_signed(&extend_look_up_table_instruction,
+        &[look_up_table.clone(), lending_user_account.to_account_info(), signer.clone(), system_program.clone()],
+        signer_seeds)?;
+
+    Ok(new_addresses.len())
+}
+
+//CPIs into the predecessor program's export_position with this program's Token Reserve signing, then reads back the Position Attestation it returns.
+//The remaining accounts are the predecessor's export_position accounts in order, followed by the predecessor program itself
+pub fn export_position_from_predecessor_program<'info>(predecessor_program_id: Pubkey,
+    token_mint_address: Pubkey,
+    token_reserve: &Account<'info, Structs::TokenReserve>,
+    remaining_accounts: &[AccountInfo<'info>],
+    sub_market_index: u16,
+    user_account_index: u8
+) -> Result<Structs::PositionAttestation>
+{
+    let token_reserve_address = token_reserve.key();
+
+    //The predecessor program is the last remaining account. Optional accounts left out of export_position are passed in as the predecessor program too, so only the last one is dropped
+    let (_predecessor_program, export_position_accounts) = remaining_accounts.split_last().ok_or(LendingError::MissingRemainingAccount)?;
+    let account_metas: Vec<AccountMeta> = export_position_accounts
+        .iter()
+        .map(|account| AccountMeta
+        {
+            pubkey: account.key(),
+            is_signer: account.is_signer || account.key() == token_reserve_address,
+            is_writable: account.is_writable
+        })
+        .collect();
+
+    let export_position_instruction = Instruction
+    {
+        program_id: predecessor_program_id,
+        accounts: account_metas,
+        data: crate::instruction::ExportPosition { sub_market_index, user_account_index }.data()
+    };
+
+    let mut account_infos = remaining_accounts.to_vec();
+    account_infos.push(token_reserve.to_account_info());
+
+    let seeds = &[b"tokenReserve", token_mint_address.as_ref(), get_token_reserve_version_seed(token_reserve), &[token_reserve.bump]];
+    let signer_seeds = &[&seeds[..]];
+    invoke_signed(&export_position_instruction, &account_infos, signer_seeds)?;
+
+    //The attestation only counts if the predecessor program itself set the return data
+    let (return_data_program_id, return_data) = get_return_data().ok_or(LendingError::MissingPositionAttestation)?;
+    require_keys_eq!(return_data_program_id, predecessor_program_id, LendingError::MissingPositionAttestation);
+
+    let position_attestation = Structs::PositionAttestation::try_from_slice(&return_data)?;
+
+    Ok(position_attestation)
+}
+
+//CPIs into the Lending Protocol's swap program with the signer's own signature. None of this program's PDAs sign, so the swap can only move the signer's tokens.
+//The remaining accounts are the swap program's accounts in order, along with the swap program itself
+pub fn swap_through_swap_program<'info>(swap_program_id: Pubkey,
+    swap_accounts: &[AccountInfo<'info>],
+    swap_instruction_data: Vec<u8>
+) -> Result<()>
+{
+    let swap_program_serialized = swap_accounts.iter().find(|account| account.key() == swap_program_id).ok_or(LendingError::InvalidSwapProgram)?;
+    require!(swap_program_serialized.executable, LendingError::InvalidSwapProgram);
+
+    let account_metas: Vec<AccountMeta> = swap_accounts
+        .iter()
+        .filter(|account| account.key() != swap_program_id)
+        .map(|account| AccountMeta
+        {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable
+        })
+        .collect();
+
+    let swap_instruction = Instruction
+    {
+        program_id: swap_program_id,
+        accounts: account_metas,
+        data: swap_instruction_data
+    };
+
+    invoke(&swap_instruction, swap_accounts)?;
+
+    Ok(())
+}
+
+//Calls the flash loan receiver's on_flash_loan instruction. The receiver gets the token account the loan was sent to, the token mint, the Token Reserve ATA to pay back,
+//and the signer, followed by the flash loan's remaining accounts in order. None of this program's PDAs sign, so the receiver can only move what it was lent and what the signer owns
+pub fn invoke_flash_loan_receiver<'info>(receiver_program_info: &AccountInfo<'info>,
+    receiver_token_account_info: &AccountInfo<'info>,
+    token_mint_info: &AccountInfo<'info>,
+    token_reserve_ata_info: &AccountInfo<'info>,
+    signer_info: &AccountInfo<'info>,
+    receiver_remaining_accounts: &[AccountInfo<'info>],
+    flash_loan_receiver_payload: Structs::FlashLoanReceiverPayload
+) -> Result<()>
+{
+    let mut account_metas = vec![
+        AccountMeta::new(receiver_token_account_info.key(), false),
+        AccountMeta::new_readonly(token_mint_info.key(), false),
+        AccountMeta::new(token_reserve_ata_info.key(), false),
+        AccountMeta::new_readonly(signer_info.key(), true)
+    ];
+    account_metas.extend(receiver_remaining_accounts
+        .iter()
+        .map(|account| AccountMeta
+        {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable
+        }));
+
+    let mut receiver_instruction_data = FLASH_LOAN_RECEIVER_DISCRIMINATOR.to_vec();
+    flash_loan_receiver_payload.serialize(&mut receiver_instruction_data)?;
+
+    let mut account_infos = vec![receiver_token_account_info.clone(), token_mint_info.clone(), token_reserve_ata_info.clone(), signer_info.clone()];
+    account_infos.extend_from_slice(receiver_remaining_accounts);
+    account_infos.push(receiver_program_info.clone());
+
+    let receiver_instruction = Instruction
+    {
+        program_id: receiver_program_info.key(),
+        accounts: account_metas,
+        data: receiver_instruction_data
+    };
+
+    invoke(&receiver_instruction, &account_infos)?;
+
+    Ok(())
+}
+
+//Helper function to hash a Token Reserve Audit Snapshot's key fields in little endian byte order, so auditors can recompute it off chain
+//Grows the Reward Emissions index by what's been emitted since it was last updated, spread across the Token Reserve's deposits.
+//Nothing is emitted while the Token Reserve has no deposits
+pub fn update_reward_emissions_index(reward_emissions: &mut Structs::RewardEmissions, total_earning_amount: u128, time_stamp: u64) -> Result<()>
+{
+    let elapsed_seconds = time_stamp.saturating_sub(reward_emissions.reward_index_last_updated_time_stamp);
+    reward_emissions.reward_index_last_updated_time_stamp = time_stamp;
+
+    if elapsed_seconds == 0 || total_earning_amount == 0 || reward_emissions.emissions_per_second == 0
+    {
+        return Ok(());
+    }
+
+    let emitted_amount = reward_emissions.emissions_per_second as u128 * elapsed_seconds as u128;
+    let reward_index_increase = emitted_amount.checked_mul(REWARD_INDEX_SCALING).ok_or(ProgramError::ArithmeticOverflow)? / total_earning_amount;
+
+    reward_emissions.reward_index = reward_emissions.reward_index.checked_add(reward_index_increase).ok_or(ProgramError::ArithmeticOverflow)?;
+    reward_emissions.emitted_amount += emitted_amount;
+
+    Ok(())
+}
+
+//Unlocks the vested part of a Lending User Reward Account's locked rewards, then earns its rewards up to the Reward Emissions index.
+//Rewards earned while vesting is on are added to what's still locked, and the whole locked amount unlocks linearly over a fresh vesting duration.
+//Rewards are only earned for stretches where the Tab Account's balance didn't change, so they have to be claimed right before any deposit, withdraw, borrow, or repay to keep them
+pub fn update_lending_user_rewards(lending_user_reward_account: &mut Structs::LendingUserRewardAccount,
+    reward_index: u128,
+    earning_amount: u64,
+    balance_changed_time_stamp: u64,
+    vesting_duration_seconds: u64,
+    time_stamp: u64
+) -> Result<u64>
+{
+    if lending_user_reward_account.locked_reward_amount > 0
+    {
+        let unlocked_reward_amount = if time_stamp >= lending_user_reward_account.vesting_end_time_stamp
+        {
+            lending_user_reward_account.locked_reward_amount
+        }
+        else
+        {
+            let elapsed_seconds = time_stamp.saturating_sub(lending_user_reward_account.vesting_last_updated_time_stamp) as u128;
+            let remaining_seconds = (lending_user_reward_account.vesting_end_time_stamp - lending_user_reward_account.vesting_last_updated_time_stamp) as u128;
+            (lending_user_reward_account.locked_reward_amount as u128 * elapsed_seconds / remaining_seconds) as u64
+        };
+
+        lending_user_reward_account.locked_reward_amount -= unlocked_reward_amount;
+        lending_user_reward_account.claimable_reward_amount += unlocked_reward_amount;
+    }
+    lending_user_reward_account.vesting_last_updated_time_stamp = time_stamp;
+
+    //A balance change since the snap shot means the balance in between isn't known, so a withdraw and redeposit can't earn for the time the tokens were gone
+    let eligible_earning_amount = if balance_changed_time_stamp > lending_user_reward_account.snap_shot_time_stamp
+    {
+        0
+    }
+    else
+    {
+        lending_user_reward_account.snap_shot_earning_amount.min(earning_amount)
+    };
+    let earned_reward_amount = (eligible_earning_amount as u128)
+        .checked_mul(reward_index - lending_user_reward_account.reward_index)
+        .ok_or(ProgramError::ArithmeticOverflow)? / REWARD_INDEX_SCALING;
+    let earned_reward_amount = u64::try_from(earned_reward_amount).map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+    lending_user_reward_account.reward_index = reward_index;
+    lending_user_reward_account.snap_shot_earning_amount = earning_amount;
+    lending_user_reward_account.snap_shot_time_stamp = time_stamp;
+
+    if vesting_duration_seconds > 0 && earned_reward_amount > 0
+    {
+        lending_user_reward_account.locked_reward_amount += earned_reward_amount;
+        lending_user_reward_account.vesting_end_time_stamp = time_stamp + vesting_duration_seconds;
+    }
+    else
+    {
+        lending_user_reward_account.claimable_reward_amount += earned_reward_amount;
+    }
+
+    Ok(earned_reward_amount)
+}
+
+//Pays claimed rewards out of the reward vault. The Reward Emissions PDA owns the vault and signs the transfer
+pub fn withdraw_reward_tokens_to_user<'info>(reward_emissions: &Account<'info, Structs::RewardEmissions>,
+    reward_vault_info: &AccountInfo<'info>,
+    user_reward_ata_info: &AccountInfo<'info>,
+    reward_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_amount: u64
+) -> Result<()>
+{
+    let token_reserve_address = reward_emissions.token_reserve_address;
+    let sub_market_address = reward_emissions.sub_market_address;
+    let reward_mint_address = reward_emissions.reward_mint_address;
+    let bump = [reward_emissions.bump];
+
+    //SubMarket reward boosts are seeded by their SubMarket and reward mint instead of the Token Reserve
+    let token_reserve_seeds: &[&[u8]] = &[b"rewardEmissions", token_reserve_address.as_ref(), get_reward_emissions_side_seed(reward_emissions.borrow_rewards), &bump];
+    let sub_market_seeds: &[&[u8]] = &[b"subMarketRewardEmissions", sub_market_address.as_ref(), reward_mint_address.as_ref(), &bump];
+    let seeds = if sub_market_address == Pubkey::default()
+    {
+        token_reserve_seeds
+    }
+    else
+    {
+        sub_market_seeds
+    };
+    let signer_seeds = &[seeds];
+
+    transfer_tokens_checked(reward_vault_info,
+        user_reward_ata_info,
+        &reward_emissions.to_account_info(),
+        reward_mint,
+        token_program,
+        &[],
+        transfer_amount,
+        signer_seeds)
+}
+
+//Locks staked tokens in the stake vault. Returns the amount the vault actually received, so Token-2022 transfer fees aren't counted as staked
+pub fn deposit_tokens_into_stake_vault<'info>(stake_vault_info: &AccountInfo<'info>,
+    user_stake_ata_info: &AccountInfo<'info>,
+    stake_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer: &Signer<'info>,
+    transfer_amount: u64
+) -> Result<u64>
+{
+    let stake_vault_amount_before_transfer = TokenAccount::try_deserialize(&mut &stake_vault_info.data.borrow()[..])?.amount;
+
+    transfer_tokens_checked(user_stake_ata_info,
+        stake_vault_info,
+        &signer.to_account_info(),
+        stake_mint,
+        token_program,
+        &[],
+        transfer_amount,
+        &[]
+    )?;
+
+    let stake_vault_amount_after_transfer = TokenAccount::try_deserialize(&mut &stake_vault_info.data.borrow()[..])?.amount;
+
+    Ok(stake_vault_amount_after_transfer - stake_vault_amount_before_transfer)
+}
+
+//Unlocks staked tokens out of the stake vault. The Staking Pool PDA owns the vault and signs the transfer
+pub fn withdraw_tokens_from_stake_vault_to_user<'info>(staking_pool: &Account<'info, Structs::StakingPool>,
+    stake_vault_info: &AccountInfo<'info>,
+    user_stake_ata_info: &AccountInfo<'info>,
+    stake_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_amount: u64
+) -> Result<()>
+{
+    let seeds = &[b"stakingPool".as_ref(), &[staking_pool.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    transfer_tokens_checked(stake_vault_info,
+        user_stake_ata_info,
+        &staking_pool.to_account_info(),
+        stake_mint,
+        token_program,
+        &[],
+        transfer_amount,
+        signer_seeds)
+}
+
+pub fn get_token_reserve_audit_snapshot_hash(token_reserve_audit_snapshot: &Structs::TokenReserveAuditSnapshot) -> [u8; 32]
+{
+    anchor_lang::solana_program::hash::hashv(&[
+        &[token_reserve_audit_snapshot.token_id],
+        token_reserve_audit_snapshot.snapshot_index.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.clock_slot.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.supply_interest_change_index.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.borrow_interest_change_index.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.deposited_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.borrowed_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.bad_debt_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.uncollected_solvency_insurance_fees_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.uncollected_liquidation_fees_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.uncollected_sub_market_fees_amount.to_le_bytes().as_ref(),
+        token_reserve_audit_snapshot.token_reserve_ata_balance_amount.to_le_bytes().as_ref()
+    ]).to_bytes()
+}
+
+//Merkle leaves hash the claimant's wallet and allocation behind a 0 prefix, so a leaf can never be passed off as an inner node
+pub fn get_merkle_leaf_hash(claimant_address: &Pubkey, amount: u64) -> [u8; 32]
+{
+    anchor_lang::solana_program::hash::hashv(&[&[0], claimant_address.as_ref(), amount.to_le_bytes().as_ref()]).to_bytes()
+}
+
+//Inner nodes hash their two children in sorted order behind a 1 prefix, so proofs don't have to say which side each sibling is on
+pub fn get_merkle_node_hash(first_child_hash: &[u8; 32], second_child_hash: &[u8; 32]) -> [u8; 32]
+{
+    let (left_child_hash, right_child_hash) = if first_child_hash <= second_child_hash
+    {
+        (first_child_hash, second_child_hash)
+    }
+    else
+    {
+        (second_child_hash, first_child_hash)
+    };
+
+    anchor_lang::solana_program::hash::hashv(&[&[1], left_child_hash.as_ref(), right_child_hash.as_ref()]).to_bytes()
+}
+
+pub fn verify_merkle_proof(merkle_root: &[u8; 32], merkle_proof: &[[u8; 32]], claimant_address: &Pubkey, amount: u64) -> bool
+{
+    let merkle_node_hash = merkle_proof.iter().fold(get_merkle_leaf_hash(claimant_address, amount), |merkle_node_hash, sibling_hash| get_merkle_node_hash(&merkle_node_hash, sibling_hash));
+    merkle_node_hash == *merkle_root
+}
+
+//Pays a claimed Merkle allocation out of the distribution's vault. The Merkle Distribution PDA owns the vault and signs the transfer
+pub fn withdraw_merkle_distribution_tokens_to_user<'info>(merkle_distribution: &Account<'info, Structs::MerkleDistribution>,
+    distribution_vault_info: &AccountInfo<'info>,
+    user_reward_ata_info: &AccountInfo<'info>,
+    reward_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    transfer_amount: u64
+) -> Result<()>
+{
+    let distribution_index_bytes = merkle_distribution.distribution_index.to_le_bytes();
+    let seeds = &[b"merkleDistribution".as_ref(), distribution_index_bytes.as_ref(), &[merkle_distribution.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    transfer_tokens_checked(distribution_vault_info,
+        user_reward_ata_info,
+        &merkle_distribution.to_account_info(),
+        reward_mint,
+        token_program,
+        &[],
+        transfer_amount,
+        signer_seeds)
+}
+
+//Helper function to pay the caller of a garbage collection instruction their bounty out of the dead account's rent. The rest of the rent goes back to the account owner when the account is closed
+pub fn pay_garbage_collection_bounty<'info>(dead_account_info: &AccountInfo<'info>, caller_info: &AccountInfo<'info>, bounty_rate: u64) -> Result<u64>
+{
+    let bounty_amount = (dead_account_info.lamports() * bounty_rate) / DECIMAL_SCALING as u64;
+
+    **dead_account_info.try_borrow_mut_lamports()? -= bounty_amount;
+    **caller_info.try_borrow_mut_lamports()? += bounty_amount;
+
+    Ok(bounty_amount)
+}
+
+//Pays the caller of a permissionless maintenance crank the keeper bounty out of the Keeper Bounty Vault. The vault never pays out below its rent exempt minimum,
+//so an empty vault or a crank called without the vault just pays nothing
+pub fn pay_keeper_bounty<'info>(keeper_bounty_vault: &mut Option<Box<Account<'info, Structs::KeeperBountyVault>>>, caller_info: &AccountInfo<'info>) -> Result<u64>
+{
+    let keeper_bounty_vault = match keeper_bounty_vault
+    {
+        Some(keeper_bounty_vault) => keeper_bounty_vault,
+        None => return Ok(0)
+    };
+
+    let keeper_bounty_vault_info = keeper_bounty_vault.to_account_info();
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(keeper_bounty_vault_info.data_len());
+    let bounty_amount = keeper_bounty_vault.bounty_lamports.min(keeper_bounty_vault_info.lamports().saturating_sub(rent_exempt_lamports));
+
+    if bounty_amount > 0
+    {
+        **keeper_bounty_vault_info.try_borrow_mut_lamports()? -= bounty_amount;
+        **caller_info.try_borrow_mut_lamports()? += bounty_amount;
+
+        keeper_bounty_vault.paid_bounty_count += 1;
+        keeper_bounty_vault.paid_bounty_lamports += bounty_amount;
+    }
+
+    Ok(bounty_amount)
+}
+
+//Helper function to grow an account that was created before new fields were appended to its layout. The payer covers the extra rent.
+//New fields are appended to the end of the layout, so the grown space reads as zeroed new fields
+pub fn grow_account<'info, T: Discriminator>(account_info: &AccountInfo<'info>,
+    payer_info: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    new_space: usize
+) -> Result<()>
+{
+    //Make sure this is the account type being grown before touching it
+    require!(account_info.data_len() >= 8 && &account_info.data.borrow()[..8] == T::DISCRIMINATOR, LendingError::UnknownAccountType);
+
+    if account_info.data_len() < new_space
+    {
+        let extra_rent_amount = Rent::get()?.minimum_balance(new_space).saturating_sub(account_info.lamports());
+        if extra_rent_amount > 0
+        {
+            let cpi_accounts = system_program::Transfer
+            {
+                from: payer_info.clone(),
+                to: account_info.clone()
+            };
+            let cpi_ctx = CpiContext::new(system_program_info.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, extra_rent_amount)?;
+        }
+
+        account_info.resize(new_space)?;
+    }
+
+    Ok(())
+}
+
+//Helper function to migrate an account to its current layout. Grows the account to the new space, then lets the caller backfill the new fields
+pub fn migrate_account_layout<'info, T: AccountSerialize + AccountDeserialize + Discriminator>(account_info: &AccountInfo<'info>,
+    payer_info: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    new_space: usize,
+    backfill: impl FnOnce(&mut T) -> Result<()>
+) -> Result<()>
+{
+    grow_account::<T>(account_info, payer_info, system_program_info, new_space)?;
+
+    let mut account = T::try_deserialize(&mut &account_info.data.borrow()[..])?;
+    backfill(&mut account)?;
+    account.try_serialize(&mut &mut account_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+//Zero copy version of migrate_account_layout. The account is read in place, so the backfill runs straight on the grown account data
+pub fn migrate_zero_copy_account_layout<'info, T: ZeroCopy + Owner>(account_info: &AccountInfo<'info>,
+    payer_info: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    new_space: usize,
+    backfill: impl FnOnce(&mut T) -> Result<()>
+) -> Result<()>
+{
+    grow_account::<T>(account_info, payer_info, system_program_info, new_space)?;
+
+    let mut account = load_zero_copy_account_mut::<T>(account_info)?;
+    backfill(&mut account)?;
+
+    Ok(())
+}
+
+//Zero copy accounts created with init_if_needed have to be loaded with load_init the first time, then load_mut every time after that
+pub fn load_mut_or_init<'a, 'info, T: ZeroCopy + Owner>(account_loader: &'a AccountLoader<'info, T>) -> Result<RefMut<'a, T>>
+{
+    match account_loader.load_mut()
+    {
+        Ok(account) => Ok(account),
+        Err(_) => account_loader.load_init()
+    }
+}
+
+//Helper function to move an account's version up to the current version. Already migrated accounts can't be migrated again
+pub fn set_current_account_version(version: &mut u8) -> Result<()>
+{
+    require!(*version < CURRENT_ACCOUNT_VERSION, LendingError::AccountAlreadyMigrated);
+    *version = CURRENT_ACCOUNT_VERSION;
+
+    Ok(())
+}
+
+//Helper function for the integer square root of a u128 using Newton's method
+pub fn integer_sqrt(value: u128) -> u128
+{
+    if value < 2
+    {
+        return value
+    }
+
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x
+    {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+
+    x
+}
+
+//Helper function to calculate an AMM LP token's fair price with 18 decimals.
+//Pool reserves can be pushed around with a large swap, but the product of the reserves can't, so the pool is valued as 2 * sqrt(Token A USD Value * Token B USD Value) instead of adding up the reserves.
+//The sqrt is taken on each side separately to stay within u128: sqrt(a * 10^18) * sqrt(b * 10^18) = sqrt(a * b) * 10^18
+pub fn calculate_fair_lp_token_price(token_a_reserve_amount: u64,
+    token_a_decimal_amount: u8,
+    token_a_price_18_decimals: u128,
+    token_b_reserve_amount: u64,
+    token_b_decimal_amount: u8,
+    token_b_price_18_decimals: u128,
+    lp_token_supply_amount: u64,
+    lp_token_decimal_amount: u8
+) -> Result<u128>
+{
+    require!(lp_token_supply_amount > 0, LendingError::LpTokenSupplyZero);
+
+    //Use ra_solana_math library FixedPoint for fixed point math. Multiply before dividing to help keep precision
+    let token_a_usd_value_18_decimals = FixedPoint::from_int(token_a_reserve_amount).mul(&FixedPoint::from_scaled_u128(token_a_price_18_decimals)).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        .div(&FixedPoint::from_int(10_u64.pow(token_a_decimal_amount as u32))).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        .value.as_u128();
+    let token_b_usd_value_18_decimals = FixedPoint::from_int(token_b_reserve_amount).mul(&FixedPoint::from_scaled_u128(token_b_price_18_decimals)).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        .div(&FixedPoint::from_int(10_u64.pow(token_b_decimal_amount as u32))).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        .value.as_u128();
+
+    let fair_pool_usd_value_18_decimals = 2 * integer_sqrt(token_a_usd_value_18_decimals) * integer_sqrt(token_b_usd_value_18_decimals);
+
+    //LP Token Price = Fair Pool Value / LP Token Supply (in whole tokens)
+    let lp_token_price_18_decimals = fair_pool_usd_value_18_decimals.checked_mul(10_u128.pow(lp_token_decimal_amount as u32))
+        .ok_or(anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        / lp_token_supply_amount as u128;
+
+    Ok(lp_token_price_18_decimals)
+}
+
+//Helper function to calculate an external receipt token's price with 18 decimals from the single vault backing it.
+//Receipt Token Price = Vault USD Value / Receipt Token Supply (in whole tokens)
+pub fn calculate_receipt_token_price(vault_amount: u64,
+    vault_decimal_amount: u8,
+    vault_token_price_18_decimals: u128,
+    receipt_token_supply_amount: u64,
+    receipt_token_decimal_amount: u8
+) -> Result<u128>
+{
+    require!(receipt_token_supply_amount > 0, LendingError::LpTokenSupplyZero);
+
+    //Multiply before dividing to help keep precision
+    let receipt_token_price_18_decimals = vault_token_price_18_decimals.checked_mul(vault_amount as u128)
+        .and_then(|value| value.checked_mul(10_u128.pow(receipt_token_decimal_amount as u32)))
+        .ok_or(anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        / (receipt_token_supply_amount as u128 * 10_u128.pow(vault_decimal_amount as u32));
+
+    Ok(receipt_token_price_18_decimals)
+}
+
+//Helper function to read an SPL Stake Pool's pool mint, total lamports, pool token supply, and last update epoch straight from the account data
+pub fn get_stake_pool_balances(stake_pool_info: &AccountInfo) -> Result<(Pubkey, u64, u64, u64)>
+{
+    require_keys_eq!(*stake_pool_info.owner, SPL_STAKE_POOL_PROGRAM_ADDRESS, LendingError::InvalidLstStakePool);
+
+    let data = stake_pool_info.try_borrow_data()?;
+    require!(data.len() >= STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 8, LendingError::InvalidLstStakePool);
+
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let pool_mint_address = Pubkey::try_from(&data[STAKE_POOL_POOL_MINT_OFFSET..STAKE_POOL_POOL_MINT_OFFSET + 32]).unwrap();
+
+    Ok((pool_mint_address, read_u64(STAKE_POOL_TOTAL_LAMPORTS_OFFSET), read_u64(STAKE_POOL_POOL_TOKEN_SUPPLY_OFFSET), read_u64(STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET)))
+}
+
+//Helper function to calculate a liquid staking token's price with 18 decimals.
+//LST Price = SOL Price * Total Lamports / Pool Token Supply, adjusted for the LST's decimals
+pub fn calculate_lst_token_price(sol_price_18_decimals: u128,
+    total_lamports_amount: u64,
+    pool_token_supply_amount: u64,
+    lst_token_decimal_amount: u8
+) -> Result<u128>
+{
+    require!(pool_token_supply_amount > 0, LendingError::LstTokenSupplyZero);
+
+    //Multiply before dividing to help keep precision
+    let lst_token_price_18_decimals = sol_price_18_decimals.checked_mul(total_lamports_amount as u128)
+        .and_then(|value| value.checked_mul(10_u128.pow(lst_token_decimal_amount as u32)))
+        .ok_or(anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+        / (pool_token_supply_amount as u128 * 10_u128.pow(SOL_DECIMAL_AMOUNT));
+
+    Ok(lst_token_price_18_decimals)
 }
\ No newline at end of file
@@ -1,7 +1,22 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount};
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface};
 use solana_security_txt::security_txt;
 use std::ops::Deref;
+use ra_solana_math::FixedPoint;
+
+//Detail logs cost compute units on every instruction, so they're only compiled in with the verbose-logs feature (on for dev and local builds).
+//Production builds keep each instruction's summary msg! along with events. Defined before the modules so they can use it too
+macro_rules! verbose_msg
+{
+    ($($arg:tt)*) =>
+    {
+        if cfg!(feature = "verbose-logs")
+        {
+            msg!($($arg)*);
+        }
+    };
+}
+
 pub mod validation;
 pub mod errors;
 pub mod initialization;
@@ -9,16 +24,36 @@ pub mod contexts;
 pub mod structs;
 pub mod lending_helpers;
 pub mod shared_constants;
+pub mod events;
 use crate::contexts::*;
 use crate::errors::LendingError;
 use crate::initialization::*;
 use crate::lending_helpers::*;
 use crate::structs as Structs;
 use crate::validation::*;
-use crate::shared_constants::MAX_ACCOUNT_NAME_LENGTH;
+use crate::events::*;
+use crate::shared_constants::{MAX_ACCOUNT_NAME_LENGTH, MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, MAX_FEE_DISCOUNT_TIERS, MAX_MERKLE_PROOF_LENGTH, CURRENT_ACCOUNT_VERSION, TOKEN_RESERVE_LISTING_BOND_LAMPORTS, TOKEN_RESERVE_LISTING_VETO_WINDOW_SECONDS, TOKEN_RESERVE_LISTING_GLOBAL_LIMIT_TOKENS, FLASH_LOAN_FEE_RATE, FLASH_LOAN_RECEIVER_DISCRIMINATOR};
 
 declare_id!("LendVMybdnkGL9yX9VFJamrtCSzL3izpUoB9JDhSU6M");
 
+//Everything another Anchor program needs to integrate the lending protocol as a money market. Depend on this crate with the cpi feature,
+//then call cpi::deposit_tokens, cpi::borrow_tokens, and the rest with the matching cpi_accounts struct
+//
+//Positions can be held by a PDA of the integrating program. The PDA signs as the signer through CpiContext::new_with_signer, and every user account is seeded by it the same as a wallet.
+//The signer also pays rent for the Lending User, Tab, and Monthly Statement accounts it opens, so the signing PDA has to be a system owned account holding lamports, not a data account.
+//Vault programs that don't want to fund statements can leave the Monthly Statement account out of their first deposit to opt out of them.
+//programs/money_market_vault is a working example that funds its PDA, then deposits and withdraws from the PDA's position
+#[cfg(feature = "cpi")]
+pub mod money_market
+{
+    pub use crate::cpi::{self, accounts as cpi_accounts};
+    pub use crate::program::LendingProtocol;
+    pub use crate::structs::{LendingProtocol as LendingProtocolAccount, TokenReserve, SubMarket, LendingUserAccount, LendingUserTabAccount, LendingUserMonthlyStatementAccount, UserHealth};
+    pub use crate::errors::LendingError;
+    pub use crate::events;
+    pub use crate::ID;
+}
+
 #[cfg(not(feature = "no-entrypoint"))] //Ensure it's not included when compiled as a library
 security_txt!
 {
@@ -49,7 +84,44 @@ const INITIAL_LIQUIDATION_TREASURER_ADDRESS: Pubkey = pubkey!("4FVD4AkuAKCUozYpQ
 const INITIAL_PRICE_ORACLE_VALIDATOR_ADDRESS: Pubkey = pubkey!("3jYmEG7Y8fU2696Gqukt95TSNzpkgkYHQsJpypdGW3WE");
 
 const INITIAL_MAX_TABS_PER_LENDING_ACCOUNT: u8 = 10;
+const INITIAL_SUB_MARKET_FEE_RAMP_SECONDS: u64 = 604_800; //7 days
 const BASE_10_INT :u128 = 10;
+const GARBAGE_COLLECTION_BOUNTY_RATE: u64 = 1_000; //10% of the reclaimed rent goes to whoever closes a dead account
+const DEAD_TAB_INACTIVE_SECONDS: u64 = 31_536_000; //365 days
+const MONTHLY_STATEMENT_ARCHIVAL_MONTHS: u32 = 24;
+const SECONDS_PER_DAY: u64 = 86_400;
+const KEEPER_INTEREST_REFRESH_INTERVAL_SECONDS: u64 = 3_600; //Interest refreshes only pay the keeper bounty once a Token Reserve has gone an hour without accruing
+
+//Canonical interest accrual scenarios checked by run_accrual_test_vectors on dev deployments.
+//(borrow_apy, supply_apy, duration_seconds, borrowed_amount, starting_index, expected_supply_index, expected_borrow_index)
+//Expected indexes are the 4th order Taylor Series e^x = 1 + x + x^2/2! + x^3/3! + x^4/4! worked out to 18 decimals off chain
+#[cfg(feature = "dev")]
+const ACCRUAL_TEST_VECTORS: [(u16, u16, u64, u128, u128, u128, u128); 7] =
+[
+    (400, 200, 31_556_952, 1_000, 1_000_000_000_000_000_000, 1_020_201_340_000_000_000, 1_040_810_773_333_333_333), //4% Borrow, 2% Supply, 1 year
+    (1_000, 700, 2_592_000, 1_000, 1_000_000_000_000_000_000, 1_005_766_165_415_759_614, 1_008_247_546_173_858_486), //10% Borrow, 7% Supply, 30 days
+    (0, 0, 31_556_952, 1_000, 1_000_000_000_000_000_000, 1_000_000_000_000_000_000, 1_000_000_000_000_000_000), //0% APYs, 1 year
+    (3_400, 2_380, 86_400, 1_000, 1_000_000_000_000_000_000, 1_000_651_834_219_314_294, 1_000_931_321_793_441_650), //34% Borrow, 23.8% Supply, 1 day
+    (400, 200, 1, 1_000, 1_000_000_000_000_000_000, 1_000_000_000_633_774_770, 1_000_000_001_267_549_541), //4% Borrow, 2% Supply, 1 second
+    (400, 200, 31_556_952, 0, 1_000_000_000_000_000_000, 1_000_000_000_000_000_000, 1_000_000_000_000_000_000), //No borrowing, no interest
+    (400, 200, 31_556_952, 1_000, 1_500_000_000_000_000_000, 1_530_302_010_000_000_000, 1_561_216_160_000_000_000) //4% Borrow, 2% Supply, 1 year on top of an existing index
+];
+#[cfg(feature = "dev")]
+const ACCRUAL_TEST_VECTOR_TOLERANCE: u128 = 1_000; //Fixed point truncation leaves a few units of error per term
+
+//Regression scenarios checked by run_rate_test_vectors on dev deployments, including interest inflated borrows that outgrow deposits.
+//(base_borrow_apy, use_fixed_borrow_apy, deposited_amount, borrowed_amount, expected_utilization_rate, expected_borrow_apy, expected_supply_apy)
+#[cfg(feature = "dev")]
+const RATE_TEST_VECTORS: [(u16, bool, u128, u128, u16, u16, u16); 7] =
+[
+    (500, false, 1_000, 500, 5_000, 857, 428), //50% Utilization Rate
+    (500, false, 10_000, 7_000, 7_000, 1_000, 700), //Optimal Utilization Rate
+    (500, false, 1_000, 1_000, 10_000, 4_400, 4_400), //100% Utilization Rate
+    (500, false, 1_000, 1_500, 10_000, 4_400, 4_400), //Interest inflated borrows past deposits are clamped to 100%
+    (500, false, 0, 100, 10_000, 4_400, 4_400), //Borrows with no deposits left
+    (500, false, 1, u128::MAX / 2, 10_000, 4_400, 4_400), //Scaling the borrowed amount would overflow
+    (500, true, 1_000, 1_500, 10_000, 500, 500) //Fixed Borrow APY past 100% Utilization Rate
+];
 
 enum Activity
 {
@@ -60,7 +132,46 @@ enum Activity
     Liquidate = 4,
     CollectSubMarketFees = 5,
     CollectSolvencyFees = 6,
-    CollectLiquidationFees = 7
+    CollectLiquidationFees = 7,
+    DeployInsuranceFund = 8,
+    WriteOffBadDebt = 9,
+    SocializeLoss = 10,
+    SyncReserve = 11,
+    ExportPosition = 12,
+    ImportPosition = 13,
+    Deleverage = 14,
+    TransferDeposit = 15,
+    ClosePosition = 16,
+    CollateralSwap = 17
+}
+
+//Risk parameter changes that have to wait out the timelock delay while it is turned on
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TimelockedChangeType
+{
+    TokenReserveParameters = 0,
+    InsuranceFundShareRate = 1,
+    OraclePriceValidator = 2,
+    TimelockDelay = 3,
+    SubMarketOracleOverride = 4,
+    LpTokenPriceHaircutRate = 5,
+    TokenReserveCollateralHaircutRate = 6,
+    EModeCategory = 7,
+    TokenReserveAssetCategory = 8
+}
+
+//CEO instructions that can be staged with create_admin_action and run with execute_admin_action
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AdminActionType
+{
+    UpdateCurrentStatementMonthAndYear = 0,
+    UpdateMaxTabAmount = 1,
+    UpdateSubMarketFeeRampSeconds = 2,
+    UpdateBadDebtCircuitBreaker = 3,
+    UpdatePositionMigrationProgramIds = 4,
+    UpdateSwapProgramId = 5,
+    UpdateUserHealthMaxAgeSlots = 6,
+    PassOnLendingProtocolCEO = 7
 }
 
 #[program]
@@ -74,31 +185,50 @@ pub mod lending_protocol
         require_keys_eq!(ctx.accounts.signer.key(), INITIAL_CEO_ADDRESS, LendingError::NotCEO);
 
         let ceo = &mut ctx.accounts.ceo;
+        ceo.bump = ctx.bumps.ceo;
+        ceo.version = CURRENT_ACCOUNT_VERSION;
         ceo.address = INITIAL_CEO_ADDRESS;
 
         let solvency_treasurer = &mut ctx.accounts.solvency_treasurer;
+        solvency_treasurer.bump = ctx.bumps.solvency_treasurer;
+        solvency_treasurer.version = CURRENT_ACCOUNT_VERSION;
         solvency_treasurer.address = INITIAL_SOLVENCY_TREASURER_ADDRESS;
 
         let liquidation_treasurer = &mut ctx.accounts.liquidation_treasurer;
+        liquidation_treasurer.bump = ctx.bumps.liquidation_treasurer;
+        liquidation_treasurer.version = CURRENT_ACCOUNT_VERSION;
         liquidation_treasurer.address = INITIAL_LIQUIDATION_TREASURER_ADDRESS;
 
         let price_validator = &mut ctx.accounts.price_validator;
         price_validator.bump = ctx.bumps.price_validator;
+        price_validator.version = CURRENT_ACCOUNT_VERSION;
         price_validator.address = INITIAL_PRICE_ORACLE_VALIDATOR_ADDRESS;
 
         let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.bump = ctx.bumps.lending_protocol;
+        lending_protocol.version = CURRENT_ACCOUNT_VERSION;
         lending_protocol.current_statement_month = statement_month;
         lending_protocol.current_statement_year = statement_year;
         lending_protocol.max_tabs_per_lending_account = INITIAL_MAX_TABS_PER_LENDING_ACCOUNT;
+        lending_protocol.sub_market_fee_ramp_seconds = INITIAL_SUB_MARKET_FEE_RAMP_SECONDS;
         lending_protocol.look_up_table_address = ctx.accounts.look_up_table_address.key();
 
         let lending_stats = &mut ctx.accounts.lending_stats;
         lending_stats.bump = ctx.bumps.lending_stats;
+        lending_stats.version = CURRENT_ACCOUNT_VERSION;
+
+        ctx.accounts.lending_user_stats.bump = ctx.bumps.lending_user_stats;
+
+        ctx.accounts.lending_user_stats.version = CURRENT_ACCOUNT_VERSION;
+        ctx.accounts.token_reserve_stats.bump = ctx.bumps.token_reserve_stats;
+        ctx.accounts.token_reserve_stats.version = CURRENT_ACCOUNT_VERSION;
+        ctx.accounts.sub_market_stats.bump = ctx.bumps.sub_market_stats;
+        ctx.accounts.sub_market_stats.version = CURRENT_ACCOUNT_VERSION;
 
         msg!("Lending Protocol Initialized");
-        msg!("New CEO Address: {}", ceo.address.key());
-        msg!("Current Statement Month: {}, Year: {}", lending_protocol.current_statement_month, lending_protocol.current_statement_year);
-        msg!("Lending Protocol Look Up Table: {}", lending_protocol.look_up_table_address);
+        verbose_msg!("New CEO Address: {}", ceo.address.key());
+        verbose_msg!("Current Statement Month: {}, Year: {}", lending_protocol.current_statement_month, lending_protocol.current_statement_year);
+        verbose_msg!("Lending Protocol Look Up Table: {}", lending_protocol.look_up_table_address);
 
         Ok(())
     }
@@ -110,13 +240,45 @@ pub mod lending_protocol
         require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
         msg!("The Lending Protocol CEO has passed on the title to a new CEO");
-        msg!("New CEO: {}", ctx.accounts.new_ceo_address.key());
+        verbose_msg!("New CEO: {}", ctx.accounts.new_ceo_address.key());
+
+        emit!(LendingProtocolCEOChanged
+        {
+            previous_ceo_address: ceo.address,
+            new_ceo_address: ctx.accounts.new_ceo_address.key()
+        });
 
         ceo.address = ctx.accounts.new_ceo_address.key();
 
         Ok(())
     }
 
+    //Hands the CEO title to an SPL Governance (Realms) Native Treasury so the protocol can be run by proposals.
+    //Admin instructions only check signer keys, so they work the same when the governance program signs for the Native Treasury through CPI
+    pub fn pass_on_lending_protocol_ceo_to_governance(ctx: Context<PassOnLendingProtocolCEOToGovernance>) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let native_treasury_address = ctx.accounts.native_treasury.key();
+
+        msg!("The Lending Protocol CEO has passed on the title to a Governance Native Treasury");
+        verbose_msg!("Governance Program: {}", ctx.accounts.governance_program.key());
+        verbose_msg!("Governance: {}", ctx.accounts.governance.key());
+        verbose_msg!("New CEO: {}", native_treasury_address);
+
+        emit!(LendingProtocolCEOChanged
+        {
+            previous_ceo_address: ceo.address,
+            new_ceo_address: native_treasury_address
+        });
+
+        ceo.address = native_treasury_address;
+
+        Ok(())
+    }
+
     pub fn pass_on_solvency_treasurer(ctx: Context<PassOnSolvencyTreasurer>) -> Result<()> 
     {
         let solvency_treasurer = &mut ctx.accounts.solvency_treasurer;
@@ -124,7 +286,7 @@ pub mod lending_protocol
         require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
 
         msg!("The Solvency Treasurer has passed on the title to a new Treasurer");
-        msg!("New Treasurer: {}", ctx.accounts.new_treasurer_address.key());
+        verbose_msg!("New Treasurer: {}", ctx.accounts.new_treasurer_address.key());
 
         solvency_treasurer.address = ctx.accounts.new_treasurer_address.key();
 
@@ -138,23 +300,76 @@ pub mod lending_protocol
         require_keys_eq!(ctx.accounts.signer.key(), liquidation_treasurer.address.key(), LendingError::NotLiquidationTreasurer);
 
         msg!("The Liquidation Treasurer has passed on the title to a new Treasurer");
-        msg!("New Treasurer: {}", ctx.accounts.new_treasurer_address.key());
+        verbose_msg!("New Treasurer: {}", ctx.accounts.new_treasurer_address.key());
 
         liquidation_treasurer.address = ctx.accounts.new_treasurer_address.key();
 
         Ok(())
     }
 
+    //Assigns the Guardian and Risk Admin roles. Pass in the default Pubkey to leave a role unassigned
+    pub fn set_lending_protocol_roles(ctx: Context<SetLendingProtocolRoles>, guardian_address: Pubkey, risk_admin_address: Pubkey) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let roles = &mut ctx.accounts.roles;
+        if roles.version == 0
+        {
+            roles.bump = ctx.bumps.roles;
+            roles.version = CURRENT_ACCOUNT_VERSION;
+        }
+
+        roles.guardian_address = guardian_address;
+        roles.risk_admin_address = risk_admin_address;
+
+        msg!("Updated Lending Protocol Roles");
+        verbose_msg!("Guardian: {}", guardian_address);
+        verbose_msg!("Risk Admin: {}", risk_admin_address);
+
+        emit!(LendingProtocolRolesUpdated
+        {
+            guardian_address,
+            risk_admin_address
+        });
+
+        Ok(())
+    }
+
+    //The Guardian can only pause new borrows. Unpausing is left to the CEO with update_bad_debt_circuit_breaker
+    pub fn pause_borrows(ctx: Context<PauseBorrows>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Guardian or the CEO can call this function
+        check_ceo_or_guardian(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
+
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.borrows_paused = true;
+
+        msg!("{} paused new borrows", ctx.accounts.signer.key());
+
+        emit!(BorrowsPaused
+        {
+            paused_by_address: ctx.accounts.signer.key()
+        });
+
+        Ok(())
+    }
+
     pub fn set_oracle_price_validator(ctx: Context<SetOraclePriceValidator>) -> Result<()> 
     {
         let ceo = &ctx.accounts.ceo;
         //Only the CEO can call this function
         require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
+        //While the timelock is on, the Oracle Price Validator has to be changed through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
         let price_validator = &mut ctx.accounts.price_validator;
 
         msg!("A new Oracle Price Validator has been set");
-        msg!("New Price Validator: {}", ctx.accounts.new_price_validator_address.key());
+        verbose_msg!("New Price Validator: {}", ctx.accounts.new_price_validator_address.key());
 
         price_validator.address = ctx.accounts.new_price_validator_address.key();
 
@@ -170,6 +385,8 @@ pub mod lending_protocol
         let temp_price_account = &mut ctx.accounts.temp_price_account;
 
         temp_price_account.bump = ctx.bumps.temp_price_account;
+        temp_price_account.version = CURRENT_ACCOUNT_VERSION;
+
         temp_price_account.data = payload.data;
         temp_price_account.slot = payload.slot;
 
@@ -215,6 +432,12 @@ pub mod lending_protocol
 
         msg!("Updated Lending Protocol To Statement Month: {}, Year: {}", lending_protocol.current_statement_month, lending_protocol.current_statement_year);
 
+        emit!(StatementMonthAndYearUpdated
+        {
+            statement_month,
+            statement_year
+        });
+
         Ok(())
     }
 
@@ -224,6 +447,9 @@ pub mod lending_protocol
         //Only the CEO can call this function
         require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
+        //Max tabs can't be more than the Tab Account registry has room for
+        require!(new_max_tab_amount as usize <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, LendingError::InvalidMaxTabAmount);
+
         let lending_protocol = &mut ctx.accounts.lending_protocol;
         lending_protocol.max_tabs_per_lending_account = new_max_tab_amount;
 
@@ -232,2413 +458,8532 @@ pub mod lending_protocol
         Ok(())
     }
 
-    pub fn add_token_reserve(ctx: Context<AddTokenReserve>,
-        token_decimal_amount: u8,
-        base_borrow_apy: u16,
-        use_fixed_borrow_apy: bool,
-        global_limit: u128,
-        solvency_insurance_fee_rate: u16) -> Result<()> 
+    pub fn update_sub_market_fee_ramp_seconds(ctx: Context<UpdateLendingProtocol>, sub_market_fee_ramp_seconds: u64) -> Result<()> 
     {
         let ceo = &mut ctx.accounts.ceo;
-        //Only the CEO can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        //Base Borrow APY can't be greater than 5%, 0.05 in decimal form, 500 in fixed point notation
-        require!(base_borrow_apy <= 500, LendingError::InvalidBaseBorrowAPY);
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.sub_market_fee_ramp_seconds = sub_market_fee_ramp_seconds;
 
-        //Solvency Insurance Fee on interest earned rate can't be greater than 4%, 0.04 in decimal form, 400 in fixed point notation
-        require!(solvency_insurance_fee_rate <= 400, LendingError::InvalidSolvencyInsuranceFeeRate);
+        msg!("Updated Lending Protocol SubMarket Fee Ramp Seconds To: {}", sub_market_fee_ramp_seconds);
 
-        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        token_reserve.bump = ctx.bumps.token_reserve;
-        token_reserve.token_mint_address = ctx.accounts.token_mint.key();
-        token_reserve.token_decimal_amount = token_decimal_amount;
-        token_reserve.borrow_apy = base_borrow_apy;
-        token_reserve.base_borrow_apy = base_borrow_apy;
-        token_reserve.use_fixed_borrow_apy = use_fixed_borrow_apy;
-        token_reserve.global_limit = global_limit;
-        token_reserve.solvency_insurance_fee_rate = solvency_insurance_fee_rate;
-        token_reserve.supply_interest_change_index = 1_000_000_000_000_000_000;
-        token_reserve.borrow_interest_change_index = 1_000_000_000_000_000_000;
-
-        token_reserve_stats.token_reserve_count += 1;
-        token_reserve.token_id = token_reserve_stats.token_reserve_count;
-        
-        msg!("Added Token Reserve #{}", token_reserve_stats.token_reserve_count);
-        msg!("Token Mint Address: {}", ctx.accounts.token_mint.key());
-        msg!("Token Decimal Amount: {}", token_decimal_amount);
-        msg!("Base Borrow APY: {}", base_borrow_apy);
-        msg!("Use fixed Borrow APY: {}", use_fixed_borrow_apy);
-        msg!("Global Limit: {}", global_limit);
-            
         Ok(())
     }
 
-    pub fn update_token_reserve(ctx: Context<UpdateTokenReserve>,
-        base_borrow_apy: u16,
-        use_fixed_borrow_apy: bool,
-        global_limit: u128,
-        solvency_insurance_fee_rate: u16) -> Result<()> 
+    //Configures the bad debt circuit breaker and lets the CEO pause or unpause new borrows protocol wide
+    pub fn update_bad_debt_circuit_breaker(ctx: Context<UpdateLendingProtocol>,
+        bad_debt_circuit_breaker_rate: u16,
+        bad_debt_window_seconds: u64,
+        borrows_paused: bool
+    ) -> Result<()> 
     {
         let ceo = &mut ctx.accounts.ceo;
-        //Only the CEO can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        //Base Borrow APY can't be greater than 5%, 0.05 in decimal form, 500 in fixed point notation
-        require!(base_borrow_apy <= 500, LendingError::InvalidBaseBorrowAPY);
+        //Bad Debt Circuit Breaker rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(bad_debt_circuit_breaker_rate <= 10_000, LendingError::InvalidBadDebtCircuitBreakerRate);
 
-        //Solvency Insurance Fee on interest earned rate can't be greater than 4%, 0.04 in decimal form, 400 in fixed point notation
-        require!(solvency_insurance_fee_rate <= 400, LendingError::InvalidSolvencyInsuranceFeeRate);
-
-        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
-        let token_reserve = &mut ctx.accounts.token_reserve;
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
 
-        //If the value of the Token Reserve Borrow APY will change, calculate previous interest changes before updating it
-        if token_reserve.base_borrow_apy != base_borrow_apy || token_reserve.use_fixed_borrow_apy != use_fixed_borrow_apy
+        //Only the CEO can unpause borrows
+        if lending_protocol.borrows_paused && !borrows_paused
         {
-            let time_stamp = Clock::get()?.unix_timestamp as u64;
-
-            //Calculate Token Reserve Previously Earned And Accrued Interest
-            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+            require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
         }
 
-        token_reserve.base_borrow_apy = base_borrow_apy;
-        token_reserve.use_fixed_borrow_apy = use_fixed_borrow_apy;
-        token_reserve.global_limit = global_limit;
-        token_reserve.solvency_insurance_fee_rate = solvency_insurance_fee_rate;
-        token_reserve_stats.token_reserves_updated_count += 1;
+        lending_protocol.bad_debt_circuit_breaker_rate = bad_debt_circuit_breaker_rate;
+        lending_protocol.bad_debt_window_seconds = bad_debt_window_seconds;
+        lending_protocol.borrows_paused = borrows_paused;
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, and, Supply APY
-        update_token_reserve_rates(token_reserve)?;
+        msg!("Updated Bad Debt Circuit Breaker Rate To: {:.2}%", bad_debt_circuit_breaker_rate as f64 / 100.0);
+        verbose_msg!("Bad Debt Window Seconds: {}", bad_debt_window_seconds);
+        verbose_msg!("Borrows Paused: {}", borrows_paused);
 
-        msg!("Token Reserve Updated");
-        msg!("Token ID: {}", token_reserve.token_id);
-        msg!("New Base Borrow APY: {}", base_borrow_apy);
-        msg!("New Global Limit: {}",  global_limit);
-            
         Ok(())
     }
 
-    pub fn create_sub_market(ctx: Context<CreateSubMarket>,
-        sub_market_index: u16,
-        fee_on_interest_earned_rate: u16,
-        look_up_table_address: Option<Pubkey> //Needed when a user creates their first Sub Market
+    //Registers the programs that positions can be exported to and imported from. Default Pubkeys turn either direction off
+    pub fn update_position_migration_program_ids(ctx: Context<UpdateLendingProtocol>,
+        successor_program_id: Pubkey,
+        predecessor_program_id: Pubkey
     ) -> Result<()> 
     {
-        //SubMarket Fee on interest earned rate can't be greater than 100%, 1 in decimal form, 10,000 in fixed point notation
-        require!(fee_on_interest_earned_rate <= 10_000, LendingError::InvalidSubMarketFeeRate);
-
-        let token_reserve = &ctx.accounts.token_reserve;
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let sub_market = &mut ctx.accounts.sub_market;
-        sub_market.bump = ctx.bumps.sub_market;
-        sub_market.owner = ctx.accounts.signer.key();
-        sub_market.fee_collector_address = ctx.accounts.fee_collector_address.key();
-        sub_market.fee_on_interest_earned_rate = fee_on_interest_earned_rate; //This should fed in fixed point notation from 0 to 10,000 (0 to 100%)
-        sub_market.token_id = token_reserve.token_id; //This can't be edited after. Allowing this to be edited would be like allowing some one to say this currency is a different kind of currency later when ever they wanted
-        sub_market.sub_market_index = sub_market_index;
-        
-        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
-        sub_market_stats.sub_market_creation_count += 1;
-        sub_market.id = sub_market_stats.sub_market_creation_count;
+        //The Lending Protocol can't migrate positions to or from itself
+        require_keys_neq!(successor_program_id, *ctx.program_id, LendingError::InvalidMigrationProgramId);
+        require_keys_neq!(predecessor_program_id, *ctx.program_id, LendingError::InvalidMigrationProgramId);
 
-        msg!("Created SubMarket #{}", sub_market.id);
-        msg!("Token ID: {}", sub_market.token_id);
-        msg!("SubMarket Index: {}", sub_market.sub_market_index);
-        msg!("Owner: {}", ctx.accounts.signer.key());
-        msg!("Fee Collector Address: {}", ctx.accounts.fee_collector_address.key());
-        msg!("Fee On Interest Earned Rate: {:.2}%", fee_on_interest_earned_rate as f64 / 100.0); //convert from fixed point notation with 4 decimal places back to decimal for logging
-        
-        //Add Look Up Table Address to account if being newly initialized.
-        let sub_market_owner_look_up_table = &mut ctx.accounts.sub_market_owner_look_up_table;
-        if sub_market_owner_look_up_table.look_up_table_added == false
-        {
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingSubMarketLookUpTable)?;
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.successor_program_id = successor_program_id;
+        lending_protocol.predecessor_program_id = predecessor_program_id;
 
-            sub_market_owner_look_up_table.owner = ctx.accounts.signer.key();
-            sub_market_owner_look_up_table.look_up_table_address = lut_address;
-            sub_market_owner_look_up_table.look_up_table_added = true;
-            msg!("Created Sub Market Owner Look Up Table: {}", sub_market_owner_look_up_table.look_up_table_address.key());
-        }
+        msg!("Updated Successor Program ID To: {}", successor_program_id);
+        verbose_msg!("Updated Predecessor Program ID To: {}", predecessor_program_id);
 
         Ok(())
     }
 
-    pub fn edit_sub_market(ctx: Context<EditSubMarket>,
-        token_id: u8,
-        sub_market_index: u16,
-        fee_on_interest_earned_rate: u16
-    ) -> Result<()> 
+    //Registers the swap program (ie Jupiter) that deleverage_with_swap routes collateral through. Default Pubkey turns swaps off
+    pub fn update_swap_program_id(ctx: Context<UpdateLendingProtocol>, swap_program_id: Pubkey) -> Result<()> 
     {
-        //SubMarket Fee on interest earned rate can't be greater than 100%, 1 in decimal form, 10,000 in fixed point notation
-        require!(fee_on_interest_earned_rate <= 10_000, LendingError::InvalidSubMarketFeeRate);
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let sub_market = &mut ctx.accounts.sub_market;
-        sub_market.fee_collector_address = ctx.accounts.fee_collector_address.key();
-        sub_market.fee_on_interest_earned_rate = fee_on_interest_earned_rate;
+        //The Lending Protocol can't be its own swap program
+        require_keys_neq!(swap_program_id, *ctx.program_id, LendingError::InvalidSwapProgram);
+
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.swap_program_id = swap_program_id;
+
+        msg!("Updated Swap Program ID To: {}", swap_program_id);
 
-        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
-        sub_market_stats.sub_market_edit_count += 1;
-        
-        msg!("Edited Submarket");
-        msg!("Token ID: {}", token_id);
-        msg!("SubMarket Index: {}", sub_market_index);
-        msg!("Owner: {}", ctx.accounts.signer.key());
-        msg!("Fee Collector Address: {}", ctx.accounts.fee_collector_address.key());
-        msg!("Fee On Interest Earned Rate: {:.2}%", fee_on_interest_earned_rate as f64 / 100.0); //convert from fixed point notation with 4 decimal places back to decimal for logging
-            
         Ok(())
     }
 
-    //Looking to see if this isn't necessary
-    /*pub fn update_lending_user_look_up_table_address(ctx: Context<UpdateLendingUserLookUpTableAddress>, look_up_table_address: Pubkey) -> Result<()> 
+    pub fn update_user_health_max_age_slots(ctx: Context<UpdateLendingProtocol>, user_health_max_age_slots: u64) -> Result<()> 
     {
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        lending_user_account.look_up_table_address = look_up_table_address;
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        msg!("Updated Lending User Look Up Table Address: {}", lending_user_account.look_up_table_address);
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.user_health_max_age_slots = user_health_max_age_slots;
+
+        msg!("Updated Lending Protocol User Health Max Age Slots To: {}", user_health_max_age_slots);
 
         Ok(())
-    }*/
+    }
 
-    pub fn deposit_tokens(ctx: Context<DepositTokens>,
-        sub_market_index: u16,
-        user_account_index: u8,
-        amount: u64,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()> 
+    //Replaces the Fee Discount Tiers. Tabs pick up the new discounts at their next health snap shot
+    pub fn update_fee_discount_tiers(ctx: Context<UpdateLendingProtocol>, fee_discount_tiers: Vec<Structs::FeeDiscountTier>) -> Result<()> 
     {
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let sub_market = &mut ctx.accounts.sub_market;
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let time_stamp = Clock::get()?.unix_timestamp as u64;
-
-        let new_token_reserve_deposited_amount = amount as u128 + token_reserve.deposited_amount;
-        //You can't deposit more than the global limit
-        require!(new_token_reserve_deposited_amount <= token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        require!(fee_discount_tiers.len() <= MAX_FEE_DISCOUNT_TIERS, LendingError::TooManyFeeDiscountTiers);
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if lending_user_account.lending_user_account_added == false
+        //Fee discounts can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation.
+        //Tiers have to climb in both minimum deposited value and discount so bigger depositors never get a smaller discount
+        for (tier_index, fee_discount_tier) in fee_discount_tiers.iter().enumerate()
         {
-            let mut new_account_name_to_use: String = String::from("Generic Depositer");
-            if let Some(new_account_name) = account_name
+            require!(fee_discount_tier.fee_discount_rate <= 10_000, LendingError::InvalidFeeDiscountTiers);
+
+            if tier_index > 0
             {
-                if !new_account_name.is_empty()//Check for empty string ""
-                {
-                    new_account_name_to_use = new_account_name;
-                }
+                let previous_fee_discount_tier = &fee_discount_tiers[tier_index - 1];
+                require!(fee_discount_tier.minimum_deposited_usd_value > previous_fee_discount_tier.minimum_deposited_usd_value &&
+                    fee_discount_tier.fee_discount_rate > previous_fee_discount_tier.fee_discount_rate, LendingError::InvalidFeeDiscountTiers);
             }
-
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
-
-            initialize_lending_user_account(
-                lending_user_account,
-                ctx.bumps.lending_user_account,
-                ctx.accounts.signer.key(),
-                user_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
-        }
-        
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if lending_user_tab_account.user_tab_account_added == false
-        {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_tab_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
         }
 
-        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
-        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        msg!("Updated Lending Protocol Fee Discount Tiers");
+        for fee_discount_tier in fee_discount_tiers.iter()
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                lending_user_monthly_statement_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
+            verbose_msg!("Minimum Deposited USD Value: {}, Fee Discount Rate: {:.2}%", fee_discount_tier.minimum_deposited_usd_value, fee_discount_tier.fee_discount_rate as f64 / 100.0);
         }
 
-        //Calculate Token Reserve Previously Earned And Accrued Interest
-        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        lending_protocol.fee_discount_tiers = fee_discount_tiers;
 
-        update_user_previous_interest_earned(
-            token_reserve,
-            sub_market,
-            lending_user_tab_account,
-            lending_user_monthly_statement_account
-        )?;
+        Ok(())
+    }
 
-        update_user_previous_interest_accrued(
-            token_reserve,
-            sub_market,
-            lending_user_tab_account,
-            lending_user_monthly_statement_account
-        )?;
+    //Turns on or lengthens the timelock right away. Shortening or turning off a running timelock has to be queued like any other timelocked change
+    pub fn update_timelock_delay_seconds(ctx: Context<UpdateLendingProtocol>, timelock_delay_seconds: u64) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
-        let should_close = user_ata_data.amount == 0;
-        deposit_tokens_into_token_reserve_from_user(
-            ctx.accounts.token_mint.key(),
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.user_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            amount,
-            should_close
-        )?;
-
-        //Update Values and Stat Listener
-        lending_stats.deposits += 1;
-        sub_market.deposited_amount += amount as u128;
-        token_reserve.deposited_amount += amount as u128;
-        lending_user_tab_account.deposited_amount += amount;
-        lending_user_monthly_statement_account.monthly_deposited_amount += amount;
-        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        require!(timelock_delay_seconds >= lending_protocol.timelock_delay_seconds, LendingError::TimelockRequired);
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_protocol.timelock_delay_seconds = timelock_delay_seconds;
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = amount;
-        token_reserve.last_lending_activity_type = Activity::Deposit as u8;
-        sub_market.last_lending_activity_amount = amount;
-        sub_market.last_lending_activity_type = Activity::Deposit as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        lending_user_monthly_statement_account.last_lending_activity_amount = amount;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Deposit as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        msg!("Updated Lending Protocol Timelock Delay Seconds To: {}", timelock_delay_seconds);
 
-        msg!("{} deposited at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+        emit!(TimelockDelayUpdated
+        {
+            timelock_delay_seconds
+        });
 
         Ok(())
     }
 
-    pub fn edit_lending_user_account_name(ctx: Context<EditLendingUserAccountName>,
-        _user_account_index: u8,
-        account_name: String
-    ) -> Result<()> 
+    pub fn queue_timelocked_change(ctx: Context<QueueTimelockedChange>, change_type: u8, parameters: Structs::TimelockedChangeParameters) -> Result<()> 
     {
-        //Account Name string must not be longer than 25 characters
-        require!(account_name.len() <= MAX_ACCOUNT_NAME_LENGTH, LendingError::LendingUserAccountNameTooLong);
-
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        lending_user_account.account_name = account_name.clone();
-
-        let lending_user_stats = &mut ctx.accounts.lending_user_stats;
-        lending_user_stats.name_change_count += 1;
-
-        msg!("Lending User Account name updated to: {}", account_name);
+        let ceo = &ctx.accounts.ceo;
+        let target_address;
 
-        Ok(()) 
-    }
+        if change_type == TimelockedChangeType::TokenReserveParameters as u8 ||
+            change_type == TimelockedChangeType::InsuranceFundShareRate as u8 ||
+            change_type == TimelockedChangeType::TokenReserveCollateralHaircutRate as u8 ||
+            change_type == TimelockedChangeType::TokenReserveAssetCategory as u8
+        {
+            //Only the Risk Admin or the CEO can queue Token Reserve changes
+            check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-    pub fn withdraw_tokens(ctx: Context<WithdrawTokens>,
-        sub_market_index: u16,
-        user_account_index: u8,
-        amount: u64,
-        withdraw_max: bool
-    ) -> Result<()> 
-    {
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let price_validator = &ctx.accounts.price_validator;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let sub_market = &mut ctx.accounts.sub_market;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let clock_slot = Clock::get()?.slot;
+            let token_reserve = ctx.accounts.token_reserve.as_ref().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            target_address = token_reserve.key();
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+            if change_type == TimelockedChangeType::TokenReserveParameters as u8
+            {
+                //Base Borrow APY can't be greater than 5%, 0.05 in decimal form, 500 in fixed point notation
+                require!(parameters.base_borrow_apy <= 500, LendingError::InvalidBaseBorrowAPY);
 
-        //This keeps users who have no debt at all from needing to check prices on withdrawals
-        if lending_user_account.total_borrowed_usd_value > 0
-        {
-            //This withdraw_tokens function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt
-            require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+                //Solvency Insurance Fee on interest earned rate can't be greater than 4%, 0.04 in decimal form, 400 in fixed point notation
+                require!(parameters.solvency_insurance_fee_rate <= 400, LendingError::InvalidSolvencyInsuranceFeeRate);
+            }
+            else if change_type == TimelockedChangeType::InsuranceFundShareRate as u8
+            {
+                //Insurance Fund share of interest earned can't be greater than 10%, 0.1 in decimal form, 1,000 in fixed point notation
+                require!(parameters.insurance_fund_share_rate <= 1_000, LendingError::InvalidInsuranceFundShareRate);
+            }
+            else if change_type == TimelockedChangeType::TokenReserveCollateralHaircutRate as u8
+            {
+                //Collateral haircut rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+                require!(parameters.collateral_haircut_rate <= 10_000, LendingError::InvalidCollateralHaircutRate);
+            }
         }
-        else
+        else if change_type == TimelockedChangeType::OraclePriceValidator as u8 || change_type == TimelockedChangeType::TimelockDelay as u8
         {
-            //Initialize monthly statement account if the statement month/year has changed.
-            if lending_user_monthly_statement_account.monthly_statement_account_added == false
+            //Only the CEO can queue Oracle and timelock changes
+            require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+            if change_type == TimelockedChangeType::OraclePriceValidator as u8
             {
-                let lending_protocol = &ctx.accounts.lending_protocol;
-                initialize_lending_user_monthly_statement_account(
-                    lending_user_monthly_statement_account,
-                    lending_user_tab_account,
-                    lending_protocol,
-                    ctx.bumps.lending_user_monthly_statement_account,
-                    token_reserve.token_id,
-                    sub_market_owner_address.key(),
-                    sub_market_index,
-                    ctx.accounts.signer.key(),
-                    user_account_index,
-                )?;
+                require_keys_neq!(parameters.new_address, Pubkey::default(), LendingError::InvalidTimelockedChange);
             }
 
-            let time_stamp = Clock::get()?.unix_timestamp as u64;
+            target_address = Pubkey::default();
+        }
+        else if change_type == TimelockedChangeType::SubMarketOracleOverride as u8
+        {
+            //Only the CEO can queue SubMarket Oracle Override approvals
+            require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-            //Calculate Token Reserve Previously Earned And Accrued Interest
-            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+            let sub_market_account_loader = ctx.accounts.sub_market.as_ref().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            let sub_market = sub_market_account_loader.load()?;
+            require!(sub_market.oracle_price_id_override == parameters.oracle_price_id_override, LendingError::OracleOverrideMisMatched);
 
-            update_user_previous_interest_earned(
-                token_reserve,
-                sub_market,
-                lending_user_tab_account,
-                lending_user_monthly_statement_account
-            )?;
+            target_address = sub_market_account_loader.key();
         }
+        else if change_type == TimelockedChangeType::LpTokenPriceHaircutRate as u8
+        {
+            //Only the Risk Admin or the CEO can queue LP Token haircut changes
+            check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        //After updating interest earned and accrued, set withdraw amount
-        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
-        let token_reserve_available_amount = token_reserve_ata_data.amount;
-        let mut withdraw_amount;
+            //Haircut rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+            require!(parameters.haircut_rate <= 10_000, LendingError::InvalidLpTokenHaircutRate);
 
-        if withdraw_max
+            let lp_token_price_adapter = ctx.accounts.lp_token_price_adapter.as_ref().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            target_address = lp_token_price_adapter.key();
+        }
+        else if change_type == TimelockedChangeType::EModeCategory as u8
         {
-            withdraw_amount = std::cmp::min(token_reserve_available_amount, lending_user_tab_account.deposited_amount);
+            //Only the CEO can queue E-Mode category changes
+            require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+            check_e_mode_category_rates(parameters.loan_to_value_rate, parameters.liquidation_threshold_rate)?;
+
+            let e_mode_category = ctx.accounts.e_mode_category.as_ref().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            target_address = e_mode_category.key();
         }
         else
         {
-            withdraw_amount = amount
+            return err!(LendingError::InvalidTimelockedChange);
         }
 
-        //Skip if user has no debt
-        if lending_user_account.total_borrowed_usd_value > 0
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        let timelocked_change = &mut ctx.accounts.timelocked_change;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        timelocked_change.bump = ctx.bumps.timelocked_change;
+        timelocked_change.version = CURRENT_ACCOUNT_VERSION;
+        timelocked_change.change_id = lending_protocol.timelocked_change_count;
+        timelocked_change.change_type = change_type;
+        timelocked_change.target_address = target_address;
+        timelocked_change.proposer_address = ctx.accounts.signer.key();
+        timelocked_change.parameters = parameters.clone();
+        timelocked_change.queued_time_stamp = time_stamp;
+        timelocked_change.executable_time_stamp = time_stamp + lending_protocol.timelock_delay_seconds;
+
+        lending_protocol.timelocked_change_count += 1;
+
+        msg!("Queued Timelocked Change: {}", timelocked_change.change_id);
+        verbose_msg!("Change Type: {}", change_type);
+        verbose_msg!("Executable Time Stamp: {}", timelocked_change.executable_time_stamp);
+
+        emit!(TimelockedChangeQueued
         {
-            ////////////////////////////
-            //Validate Oracle Price Data
-            let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
-            let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-                temp_price_account_serialized,
-                ctx.accounts.signer.key())?;
+            change_id: timelocked_change.change_id,
+            change_type,
+            target_address,
+            proposer_address: timelocked_change.proposer_address,
+            parameters,
+            executable_time_stamp: timelocked_change.executable_time_stamp
+        });
 
-            check_token_price_staleness(temp_price_account.slot, clock_slot)?;
-            
-            let normalized_price_18_decimals = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
-            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+        Ok(())
+    }
 
-            if !withdraw_max
-            {
-                let new_user_deposited_usd_value = lending_user_account.total_deposited_usd_value - ((withdraw_amount as u128 * normalized_price_18_decimals) / token_conversion_number);
-                
-                //Multiply before dividing to help keep precision
-                let seventy_percent_of_new_deposited_usd_value = (new_user_deposited_usd_value * 70) / 100;
+    //Anyone can execute a Timelocked Change once its delay has elapsed. The rent goes back to the proposer
+    pub fn execute_timelocked_change(ctx: Context<ExecuteTimelockedChange>, change_id: u32) -> Result<()> 
+    {
+        let timelocked_change = &ctx.accounts.timelocked_change;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        require!(time_stamp >= timelocked_change.executable_time_stamp, LendingError::TimelockNotElapsed);
 
-                //You can't withdraw an amount that would cause your borrow liabilities to exceed 70% of deposited collateral.
-                require!(seventy_percent_of_new_deposited_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
-            }
-            else
+        let change_type = timelocked_change.change_type;
+        let parameters = &timelocked_change.parameters;
+
+        if change_type == TimelockedChangeType::TokenReserveParameters as u8
+        {
+            let token_reserve = ctx.accounts.token_reserve.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(token_reserve.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
+
+            set_token_reserve_parameters(token_reserve,
+                parameters.base_borrow_apy,
+                parameters.use_fixed_borrow_apy,
+                parameters.global_limit,
+                parameters.solvency_insurance_fee_rate
+            )?;
+            ctx.accounts.token_reserve_stats.token_reserves_updated_count += 1;
+
+            emit!(TokenReserveUpdated
             {
-                //1. Calculate the exact floor amount of USD collateral that MUST remain behind to maintain a 70% LTV
-                let min_required_deposited_usd_value = (lending_user_account.total_borrowed_usd_value * 100) / 70;
+                token_id: token_reserve.token_id,
+                base_borrow_apy: parameters.base_borrow_apy,
+                use_fixed_borrow_apy: parameters.use_fixed_borrow_apy,
+                global_limit: parameters.global_limit,
+                solvency_insurance_fee_rate: parameters.solvency_insurance_fee_rate
+            });
+        }
+        else if change_type == TimelockedChangeType::InsuranceFundShareRate as u8
+        {
+            let token_reserve = ctx.accounts.token_reserve.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(token_reserve.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-                if lending_user_account.total_deposited_usd_value > min_required_deposited_usd_value 
-                {
-                    //2. Find out how much total USD value the user can safely strip out
-                    let max_withdraw_usd_value = lending_user_account.total_deposited_usd_value - min_required_deposited_usd_value;
+            //Calculate Token Reserve Previously Earned And Accrued Interest before the share rate changes
+            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+            token_reserve.insurance_fund_share_rate = parameters.insurance_fund_share_rate;
+        }
+        else if change_type == TimelockedChangeType::TokenReserveCollateralHaircutRate as u8
+        {
+            let token_reserve = ctx.accounts.token_reserve.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(token_reserve.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-                    //3. Convert that safe USD allowance back into native token units using the oracle price
-                    let max_allowed_token_withdraw = (max_withdraw_usd_value * token_conversion_number) / normalized_price_18_decimals;
+            //The veto window and veto are checked again here since a listing can be vetoed while the change waits
+            set_token_reserve_collateral_haircut_rate(token_reserve, parameters.collateral_haircut_rate)?;
+        }
+        else if change_type == TimelockedChangeType::OraclePriceValidator as u8
+        {
+            let price_validator = ctx.accounts.price_validator.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            price_validator.address = parameters.new_address;
+        }
+        else if change_type == TimelockedChangeType::TimelockDelay as u8
+        {
+            ctx.accounts.lending_protocol.timelock_delay_seconds = parameters.timelock_delay_seconds;
 
-                    //4. Cap it by the user's absolute token balance in this tab and token reserve liquidity amount
-                    let safe_max_tokens = std::cmp::min(max_allowed_token_withdraw, lending_user_tab_account.deposited_amount as u128) as u64;
-                    withdraw_amount = std::cmp::min(safe_max_tokens, token_reserve_available_amount);
-                } 
-                else 
-                {
-                    //User is already at or exceeding 70% LTV, they cannot withdraw anything safely.
-                    return Err(LendingError::LiquidationExposure.into());
-                }
-            }
-            
-            //Refund Oracle price account fees back to Oracle
-            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+            emit!(TimelockDelayUpdated
+            {
+                timelock_delay_seconds: parameters.timelock_delay_seconds
+            });
         }
+        else if change_type == TimelockedChangeType::SubMarketOracleOverride as u8
+        {
+            let sub_market_account_loader = ctx.accounts.sub_market.as_ref().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(sub_market_account_loader.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-        //You can't withdraw more funds than you've deposited
-        require!(lending_user_tab_account.deposited_amount >= withdraw_amount, LendingError::InsufficientFunds);
+            //The owner can't swap the requested override out while the approval waits out the timelock
+            let sub_market = &mut sub_market_account_loader.load_mut()?;
+            require!(sub_market.oracle_price_id_override == parameters.oracle_price_id_override, LendingError::OracleOverrideMisMatched);
+            sub_market.oracle_override_approved = parameters.approved as u8;
+        }
+        else if change_type == TimelockedChangeType::LpTokenPriceHaircutRate as u8
+        {
+            let lp_token_price_adapter = ctx.accounts.lp_token_price_adapter.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(lp_token_price_adapter.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
-        require!(token_reserve_available_amount >= withdraw_amount, LendingError::InsufficientLiquidity);
+            lp_token_price_adapter.haircut_rate = parameters.haircut_rate;
+        }
+        else if change_type == TimelockedChangeType::EModeCategory as u8
+        {
+            let e_mode_category = ctx.accounts.e_mode_category.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(e_mode_category.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-        let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
-        let balance_after_withdrawal = user_token_data.amount.saturating_sub(withdraw_amount);
-        let should_close = balance_after_withdrawal == 0;
-        withdraw_tokens_from_token_reserve_to_user(
-            ctx.accounts.token_mint.key(),
-            token_reserve,
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.user_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            withdraw_amount,
-            should_close
-        )?;
-        
-        //Update Values and Stat Listener
-        lending_stats.withdrawals += 1;
-        sub_market.deposited_amount -= withdraw_amount as u128;
-        token_reserve.deposited_amount -= withdraw_amount as u128;
-        lending_user_tab_account.deposited_amount -= withdraw_amount;
-        lending_user_monthly_statement_account.monthly_withdrawal_amount += withdraw_amount;
-        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
-        
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+            e_mode_category.loan_to_value_rate = parameters.loan_to_value_rate;
+            e_mode_category.liquidation_threshold_rate = parameters.liquidation_threshold_rate;
+        }
+        else if change_type == TimelockedChangeType::TokenReserveAssetCategory as u8
+        {
+            let token_reserve = ctx.accounts.token_reserve.as_mut().ok_or(LendingError::MissingTimelockedChangeAccount)?;
+            require_keys_eq!(token_reserve.key(), timelocked_change.target_address, LendingError::TimelockedChangeTargetMisMatched);
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = withdraw_amount;
-        token_reserve.last_lending_activity_type = Activity::Withdraw as u8;
-        sub_market.last_lending_activity_amount = withdraw_amount;
-        sub_market.last_lending_activity_type = Activity::Withdraw as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
-        lending_user_monthly_statement_account.last_lending_activity_amount = withdraw_amount;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Withdraw as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        
-        msg!("{} withdrew at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+            token_reserve.asset_category = parameters.asset_category;
+        }
+        else
+        {
+            return err!(LendingError::InvalidTimelockedChange);
+        }
+
+        msg!("Executed Timelocked Change: {}", change_id);
+        verbose_msg!("Change Type: {}", change_type);
+
+        emit!(TimelockedChangeExecuted
+        {
+            change_id,
+            change_type,
+            target_address: timelocked_change.target_address
+        });
 
         Ok(())
     }
 
-    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-    pub fn borrow_tokens(ctx: Context<BorrowTokens>,
-        sub_market_index: u16,
-        user_account_index: u8,
-        amount: u64,
-        borrow_max: bool
-    ) -> Result<()> 
+    pub fn cancel_timelocked_change(ctx: Context<CancelTimelockedChange>, change_id: u32) -> Result<()> 
     {
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let price_validator = &ctx.accounts.price_validator;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let sub_market = &mut ctx.accounts.sub_market;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let clock_slot = Clock::get()?.slot;
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        //The borrow_tokens function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-        if token_reserve.last_health_update_clock_slot != clock_slot
-        {
-            let time_stamp = Clock::get()?.unix_timestamp as u64;
-            
-            //When a user is borrowing from a token reserve they have never interacted with before, it won't get refreshed by refresh_user_health_chunk, so doing it here
-            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
-        }
-        
-        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        let change_type = ctx.accounts.timelocked_change.change_type;
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        msg!("Cancelled Timelocked Change: {}", change_id);
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        //This is for when a user is borrowing a token they have never interacted with before
-        if lending_user_tab_account.user_tab_account_added == false
+        emit!(TimelockedChangeCancelled
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_tab_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
-        }
-        //This is for when a user is borrowing a token they have never interacted with before
-        //You won't be able to use the create_new_monthly_statement until after the lending_user_tab_account exists
-        //Normally create_new_monthly_statement and refresh_user_health_chunk would have this covered
-        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+            change_id,
+            change_type,
+            cancelled_by_address: ctx.accounts.signer.key()
+        });
+
+        Ok(())
+    }
+
+    //Anyone can stage an Admin Action. Nothing changes until the CEO signs execute_admin_action, so a multisig or governance program only has to sign one small instruction
+    pub fn create_admin_action(ctx: Context<CreateAdminAction>, action_type: u8, parameters: Structs::AdminActionParameters) -> Result<()> 
+    {
+        require!(action_type <= AdminActionType::PassOnLendingProtocolCEO as u8, LendingError::InvalidAdminAction);
+
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        let admin_action = &mut ctx.accounts.admin_action;
+
+        admin_action.bump = ctx.bumps.admin_action;
+        admin_action.version = CURRENT_ACCOUNT_VERSION;
+        admin_action.action_id = lending_protocol.admin_action_count;
+        admin_action.action_type = action_type;
+        admin_action.proposer_address = ctx.accounts.signer.key();
+        admin_action.parameters = parameters.clone();
+        admin_action.created_time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        lending_protocol.admin_action_count += 1;
+
+        msg!("Created Admin Action: {}", admin_action.action_id);
+        verbose_msg!("Action Type: {}", action_type);
+
+        emit!(AdminActionCreated
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                lending_user_monthly_statement_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
-        }
+            action_id: admin_action.action_id,
+            action_type,
+            proposer_address: admin_action.proposer_address,
+            parameters
+        });
 
-        ////////////////////////////
-        //Validate Oracle Price Data
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+        Ok(())
+    }
 
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>, action_id: u32) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let normalized_price_18_decimals = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
-        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        let action_type = ctx.accounts.admin_action.action_type;
+        let parameters = &ctx.accounts.admin_action.parameters;
 
-        //Determine Borrow Amount
-        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
-        let token_reserve_available_amount = token_reserve_ata_data.amount;
-        let max_total_allowed_debt_usd_value = (lending_user_account.total_deposited_usd_value * 70) / 100;
-        let mut borrow_amount = amount;
+        if action_type == AdminActionType::UpdateCurrentStatementMonthAndYear as u8
+        {
+            lending_protocol.current_statement_month = parameters.statement_month;
+            lending_protocol.current_statement_year = parameters.statement_year;
 
-        if !borrow_max 
+            emit!(StatementMonthAndYearUpdated
+            {
+                statement_month: parameters.statement_month,
+                statement_year: parameters.statement_year
+            });
+        }
+        else if action_type == AdminActionType::UpdateMaxTabAmount as u8
         {
-            //You can't borrow an amount that would cause your borrow liabilities to exceed 70% of deposited collateral.
-            lending_user_account.total_borrowed_usd_value += (borrow_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
-            require!(max_total_allowed_debt_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+            //Max tabs can't be more than the Tab Account registry has room for
+            require!(parameters.max_tab_amount as usize <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, LendingError::InvalidMaxTabAmount);
+
+            lending_protocol.max_tabs_per_lending_account = parameters.max_tab_amount;
         }
-        else
+        else if action_type == AdminActionType::UpdateSubMarketFeeRampSeconds as u8
         {
-            if max_total_allowed_debt_usd_value > lending_user_account.total_borrowed_usd_value 
-            {
-                //1. Determine available headroom remaining in USD
-                let remaining_usd_borrow_headroom = max_total_allowed_debt_usd_value - lending_user_account.total_borrowed_usd_value;
+            lending_protocol.sub_market_fee_ramp_seconds = parameters.sub_market_fee_ramp_seconds;
+        }
+        else if action_type == AdminActionType::UpdateBadDebtCircuitBreaker as u8
+        {
+            //Bad Debt Circuit Breaker rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+            require!(parameters.bad_debt_circuit_breaker_rate <= 10_000, LendingError::InvalidBadDebtCircuitBreakerRate);
 
-                //2. Convert USD target capacity into native token fractions using the oracle price
-                let max_tokens_allowed = (remaining_usd_borrow_headroom * token_conversion_number) / normalized_price_18_decimals;
+            lending_protocol.bad_debt_circuit_breaker_rate = parameters.bad_debt_circuit_breaker_rate;
+            lending_protocol.bad_debt_window_seconds = parameters.bad_debt_window_seconds;
+            lending_protocol.borrows_paused = parameters.borrows_paused;
+        }
+        else if action_type == AdminActionType::UpdatePositionMigrationProgramIds as u8
+        {
+            //The Lending Protocol can't migrate positions to or from itself
+            require_keys_neq!(parameters.primary_address, *ctx.program_id, LendingError::InvalidMigrationProgramId);
+            require_keys_neq!(parameters.secondary_address, *ctx.program_id, LendingError::InvalidMigrationProgramId);
 
-                //3. Cap it by the user's max allowed amount and token reserve liquidity amount
-                borrow_amount = std::cmp::min(max_tokens_allowed, token_reserve_available_amount as u128) as u64;
-                
-                //4. Update global account trackers with finalized calculations
-                lending_user_account.total_borrowed_usd_value += (borrow_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
-                require!(max_total_allowed_debt_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
-            }
-            else
+            lending_protocol.successor_program_id = parameters.primary_address;
+            lending_protocol.predecessor_program_id = parameters.secondary_address;
+        }
+        else if action_type == AdminActionType::UpdateSwapProgramId as u8
+        {
+            //The Lending Protocol can't be its own swap program
+            require_keys_neq!(parameters.primary_address, *ctx.program_id, LendingError::InvalidSwapProgram);
+
+            lending_protocol.swap_program_id = parameters.primary_address;
+        }
+        else if action_type == AdminActionType::UpdateUserHealthMaxAgeSlots as u8
+        {
+            lending_protocol.user_health_max_age_slots = parameters.user_health_max_age_slots;
+        }
+        else if action_type == AdminActionType::PassOnLendingProtocolCEO as u8
+        {
+            emit!(LendingProtocolCEOChanged
             {
-                //User is already at or exceeding 70% LTV, they cannot borrow anything safely.
-                return Err(LendingError::LiquidationExposure.into());
-            }
+                previous_ceo_address: ceo.address,
+                new_ceo_address: parameters.primary_address
+            });
+
+            ceo.address = parameters.primary_address;
+        }
+        else
+        {
+            return err!(LendingError::InvalidAdminAction);
         }
 
-        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
-        require!(token_reserve_available_amount >= borrow_amount, LendingError::InsufficientLiquidity);
+        msg!("Executed Admin Action: {}", action_id);
+        verbose_msg!("Action Type: {}", action_type);
 
-        //Refund Oracle price account fees back to Oracle
-        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        emit!(AdminActionExecuted
+        {
+            action_id,
+            action_type
+        });
 
-        let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
-        let balance_after_withdrawal = user_token_data.amount.saturating_sub(borrow_amount);
-        let should_close = balance_after_withdrawal == 0;
-        withdraw_tokens_from_token_reserve_to_user(
-            ctx.accounts.token_mint.key(),
-            token_reserve,
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.user_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            borrow_amount,
-            should_close
-        )?;
+        Ok(())
+    }
 
-        //Update Values and Stat Listener
-        lending_stats.borrows += 1;
-        sub_market.borrowed_amount += borrow_amount as u128;
-        token_reserve.borrowed_amount += borrow_amount as u128;
-        lending_user_tab_account.borrowed_amount += borrow_amount;
-        lending_user_monthly_statement_account.monthly_borrowed_amount += borrow_amount;
-        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+    //The CEO or whoever staged the Admin Action can cancel it. The rent goes back to the proposer
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>, action_id: u32) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        let admin_action = &ctx.accounts.admin_action;
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        //Only the CEO or the proposer can call this function
+        if ctx.accounts.signer.key() != admin_action.proposer_address
+        {
+            require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+        }
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = borrow_amount;
-        token_reserve.last_lending_activity_type = Activity::Borrow as u8;
-        sub_market.last_lending_activity_amount = borrow_amount;
-        sub_market.last_lending_activity_type = Activity::Borrow as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
-        lending_user_monthly_statement_account.last_lending_activity_amount = borrow_amount;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Borrow as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        
-        msg!("{} borrowed at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+        msg!("Cancelled Admin Action: {}", action_id);
+
+        emit!(AdminActionCancelled
+        {
+            action_id,
+            action_type: admin_action.action_type,
+            cancelled_by_address: ctx.accounts.signer.key()
+        });
 
         Ok(())
     }
 
-    pub fn repay_tokens(ctx: Context<RepayTokens>,
-        sub_market_index: u16,
-        _user_account_index: u8,
-        amount: u64,
-        pay_off_loan: bool,
-        pay_10_percent: bool
-    ) -> Result<()> 
+    pub fn add_token_reserve(ctx: Context<AddTokenReserve>,
+        token_decimal_amount: u8,
+        base_borrow_apy: u16,
+        use_fixed_borrow_apy: bool,
+        global_limit: u128,
+        solvency_insurance_fee_rate: u16,
+        usd_quote_price_id: u8,
+        allow_restricted_mint_extensions: bool) -> Result<()> 
     {
-        let price_validator = &ctx.accounts.price_validator;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let sub_market = &mut ctx.accounts.sub_market;
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let clock_slot = Clock::get()?.slot;
-        
-        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        //Token-2022 mints with extensions that can break Token Reserve solvency need the CEO to explicitly allow them
+        check_token_mint_extensions(&ctx.accounts.token_mint.to_account_info(), allow_restricted_mint_extensions)?;
 
-        //After updating interest earned and accrued(with refresh_user_health_chunk), set payment amount
-        let repayment_amount;
+        //USD values divide raw amounts by 10^token_decimal_amount against 18 decimal normalized prices, so a wrong decimal amount would over or under weight this token in every health check
+        require!(token_decimal_amount == ctx.accounts.token_mint.decimals, LendingError::TokenDecimalAmountMisMatched);
 
-        if pay_off_loan
-        {
-            repayment_amount = lending_user_tab_account.borrowed_amount;
-        }
-        else if pay_10_percent
-        {
-            repayment_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
-        }
-        else
-        {
-            repayment_amount = amount
-        }
+        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
 
-        //Multiply before dividing to help keep precision
-        let eighty_percent_of_deposited_usd_value = (lending_user_account.total_deposited_usd_value * 80) / 100;
+        initialize_token_reserve(token_reserve,
+            token_reserve_stats,
+            ctx.bumps.token_reserve,
+            ctx.accounts.token_mint.key(),
+            0,
+            token_decimal_amount,
+            base_borrow_apy,
+            use_fixed_borrow_apy,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id
+        )?;
         
-        //Check if lending user account is in a liquidatable state
-        if lending_user_account.total_borrowed_usd_value >= eighty_percent_of_deposited_usd_value
+        msg!("Added Token Reserve #{}", token_reserve_stats.token_reserve_count);
+        verbose_msg!("Token Mint Address: {}", ctx.accounts.token_mint.key());
+        verbose_msg!("Token Decimal Amount: {}", token_decimal_amount);
+        verbose_msg!("Base Borrow APY: {}", base_borrow_apy);
+        verbose_msg!("Use fixed Borrow APY: {}", use_fixed_borrow_apy);
+        verbose_msg!("Global Limit: {}", global_limit);
+        verbose_msg!("USD Quote Price ID: {}", usd_quote_price_id);
+        verbose_msg!("Restricted Mint Extensions Allowed: {}", allow_restricted_mint_extensions);
+
+        emit!(TokenReserveAdded
         {
-            //Multiply before dividing to help keep precision
-            let ten_percent_of_borrowed_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
+            token_id: token_reserve.token_id,
+            token_mint_address: ctx.accounts.token_mint.key(),
+            reserve_version: 0,
+            token_decimal_amount,
+            base_borrow_apy,
+            use_fixed_borrow_apy,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id,
+            restricted_mint_extensions_allowed: allow_restricted_mint_extensions
+        });
+            
+        Ok(())
+    }
 
-            //You must repay atleast 10% of the borrow position if the account is in an unhealthy state. This prevents "griefing".
-            //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running the liquidator so their transaction fails and holding the protocol's solvency hostage!
-            require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
-        }
+    //Anyone can list a Token Reserve by posting a SOL bond. Listings start with conservative risk parameters and the CEO can veto them during the veto window.
+    //Their deposits don't count toward user health until the CEO or Risk Admin lowers the collateral haircut after the veto window closes
+    pub fn list_token_reserve(ctx: Context<ListTokenReserve>, usd_quote_price_id: u8) -> Result<()> 
+    {
+        //Permissionless listings never get restricted Token-2022 mint extensions
+        check_token_mint_extensions(&ctx.accounts.token_mint.to_account_info(), false)?;
 
-        //You can't repay an amount that is greater than your borrowed amount.
-        require!(lending_user_tab_account.borrowed_amount >= repayment_amount, LendingError::TooManyFunds);
+        let token_decimal_amount = ctx.accounts.token_mint.decimals;
+        let global_limit = TOKEN_RESERVE_LISTING_GLOBAL_LIMIT_TOKENS * 10u128.pow(token_decimal_amount as u32);
+        let base_borrow_apy = 500;
+        let solvency_insurance_fee_rate = 400;
 
-        //Repay debt
-        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
-        let should_close = user_ata_data.amount == 0;
-        deposit_tokens_into_token_reserve_from_user(
+        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+
+        initialize_token_reserve(token_reserve,
+            token_reserve_stats,
+            ctx.bumps.token_reserve,
             ctx.accounts.token_mint.key(),
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.user_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            repayment_amount,
-            should_close
+            0,
+            token_decimal_amount,
+            base_borrow_apy,
+            false,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id
         )?;
 
-        ////////////////////////////
-        //Validate Oracle Price Data
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let veto_deadline_time_stamp = time_stamp + TOKEN_RESERVE_LISTING_VETO_WINDOW_SECONDS;
+        token_reserve.collateral_haircut_rate = TOKEN_RESERVE_LISTING_COLLATERAL_HAIRCUT_RATE;
+        token_reserve.listing_veto_deadline_time_stamp = veto_deadline_time_stamp;
 
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+        //Post the bond on top of the listing account's rent
+        let cpi_accounts = anchor_lang::system_program::Transfer
+        {
+            from: ctx.accounts.signer.to_account_info(),
+            to: ctx.accounts.token_reserve_listing.to_account_info()
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, TOKEN_RESERVE_LISTING_BOND_LAMPORTS)?;
+
+        let token_reserve_listing = &mut ctx.accounts.token_reserve_listing;
+        token_reserve_listing.bump = ctx.bumps.token_reserve_listing;
+        token_reserve_listing.version = CURRENT_ACCOUNT_VERSION;
+        token_reserve_listing.token_reserve_address = token_reserve.key();
+        token_reserve_listing.lister_address = ctx.accounts.signer.key();
+        token_reserve_listing.bond_amount = TOKEN_RESERVE_LISTING_BOND_LAMPORTS;
+        token_reserve_listing.listed_time_stamp = time_stamp;
+        token_reserve_listing.veto_deadline_time_stamp = veto_deadline_time_stamp;
+
+        msg!("Listed Token Reserve #{}", token_reserve_stats.token_reserve_count);
+        verbose_msg!("Token Mint Address: {}", ctx.accounts.token_mint.key());
+        verbose_msg!("Lister: {}", ctx.accounts.signer.key());
+        verbose_msg!("Veto Deadline Time Stamp: {}", token_reserve_listing.veto_deadline_time_stamp);
+
+        emit!(TokenReserveAdded
+        {
+            token_id: token_reserve.token_id,
+            token_mint_address: ctx.accounts.token_mint.key(),
+            reserve_version: 0,
+            token_decimal_amount,
+            base_borrow_apy,
+            use_fixed_borrow_apy: false,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id,
+            restricted_mint_extensions_allowed: false
+        });
+
+        emit!(TokenReserveListed
+        {
+            token_id: token_reserve.token_id,
+            token_mint_address: ctx.accounts.token_mint.key(),
+            lister_address: ctx.accounts.signer.key(),
+            bond_amount: TOKEN_RESERVE_LISTING_BOND_LAMPORTS,
+            veto_deadline_time_stamp: token_reserve_listing.veto_deadline_time_stamp
+        });
 
-        let normalized_price_18_decimals = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
+        Ok(())
+    }
 
-        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+    //Vetoing stops new deposits into and borrows from the listed Token Reserve, keeps its deposits from counting toward user health, and forfeits the bond to the CEO.
+    //Existing depositors can still withdraw and existing borrowers can still repay
+    pub fn veto_token_reserve_listing(ctx: Context<VetoTokenReserveListing>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Calculate the USD value of the repayment first
-        let repayment_usd_value = (repayment_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        let token_reserve_listing = &ctx.accounts.token_reserve_listing;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        require!(time_stamp < token_reserve_listing.veto_deadline_time_stamp, LendingError::ListingVetoWindowClosed);
 
-        //Use saturating_sub to safely deduct the value
-        //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
-        lending_user_account.total_borrowed_usd_value = lending_user_account
-            .total_borrowed_usd_value
-            .saturating_sub(repayment_usd_value);
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        token_reserve.global_limit = 0;
+        token_reserve.collateral_haircut_rate = 10_000;
+        token_reserve.listing_vetoed = true;
 
-        //Refund Oracle price account fees back to Oracle
-        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        msg!("Vetoed Token Reserve Listing for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Lister: {}", token_reserve_listing.lister_address);
 
-        //Update Values and Stat Listener
-        lending_stats.repayments += 1;
-        sub_market.borrowed_amount -= repayment_amount as u128;
-        sub_market.repaid_debt_amount += repayment_amount as u128;
-        token_reserve.borrowed_amount -= repayment_amount as u128;
-        token_reserve.repaid_debt_amount += repayment_amount as u128;
-        lending_user_tab_account.borrowed_amount -= repayment_amount;
-        lending_user_tab_account.repaid_debt_amount += repayment_amount;
-        lending_user_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
-        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
-        
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        emit!(TokenReserveListingVetoed
+        {
+            token_id: token_reserve.token_id,
+            token_mint_address: ctx.accounts.token_mint_address.key(),
+            lister_address: token_reserve_listing.lister_address
+        });
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = repayment_amount;
-        token_reserve.last_lending_activity_type = Activity::Repay as u8;
-        sub_market.last_lending_activity_amount = repayment_amount;
-        sub_market.last_lending_activity_type = Activity::Repay as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        lending_user_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-  
-        msg!("{} repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
-        
         Ok(())
     }
 
-    pub fn liquidate_account<'info>(ctx: Context<'info, LiquidateAccount<'info>>,
-        repayment_sub_market_index: u16,
-        liquidation_sub_market_index: u16,
-        liquidati_account_index: u8,
-        liquidator_account_index: u8,
-        amount_to_repay: u64,
-        repay_max: bool,
-        paying_off_insolvent_account: bool,
-        send_reward_to_wallet: bool,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()>
+    //Once the veto window passes without a veto, the lister gets the bond and the listing account rent back
+    pub fn reclaim_token_reserve_listing_bond(ctx: Context<ReclaimTokenReserveListingBond>) -> Result<()> 
     {
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
-        let repayment_sub_market_owner_address = ctx.accounts.repayment_sub_market_owner.key();
-        let liquidation_sub_market_owner_address = ctx.accounts.liquidation_sub_market_owner.key();
-        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
-        let clock_slot = Clock::get()?.slot;
-
-        /////////////////////////////////
-        ////Validate Liquidati Lending User Account Account
-        let liquidati_lending_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_lending_account = validate_and_return_lending_user_account(*ctx.program_id,
-            liquidati_lending_account_serialized,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
-
-        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-        #[cfg(feature = "local")] 
-        require!(clock_slot.saturating_sub(liquidati_lending_account.last_health_update_clock_slot) <= 1, LendingError::StaleTokenReserveOrLendingUser);
-        #[cfg(feature = "dev")]
-        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
-        
-        let lending_protocol = &ctx.accounts.lending_protocol;
-        let repayment_token_reserve = &mut ctx.accounts.repayment_token_reserve;
-        let liquidation_token_reserve = &mut ctx.accounts.liquidation_token_reserve;
-        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
-        let liquidator_repayment_tab_account = &mut ctx.accounts.liquidator_repayment_tab_account;
-        let liquidator_liquidation_tab_account = &mut ctx.accounts.liquidator_liquidation_tab_account;
-        let liquidator_repayment_monthly_statement_account = &mut ctx.accounts.liquidator_repayment_monthly_statement_account;
-        let liquidator_liquidation_monthly_statement_account = &mut ctx.accounts.liquidator_liquidation_monthly_statement_account;
-
-        //Validate remaining accounts
-        let repayment_token_reserve_ata_info = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let liquidation_token_reserve_ata_info = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let token_reserve_listing = &ctx.accounts.token_reserve_listing;
+        //Only the lister can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), token_reserve_listing.lister_address, LendingError::NotTokenReserveLister);
 
-        //Repayment Token Reserve ATA
-        validate_token_reserve_ata(
-            repayment_token_reserve_ata_info,
-            ctx.accounts.repayment_mint.key(),
-            repayment_token_reserve.key()
-        )?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        require!(time_stamp >= token_reserve_listing.veto_deadline_time_stamp, LendingError::ListingVetoWindowOpen);
 
-        //Token Reserve Liquidation ATA
-        validate_token_reserve_ata(
-            liquidation_token_reserve_ata_info,
-            ctx.accounts.liquidation_mint.key(),
-            liquidation_token_reserve.key()
-        )?;
+        msg!("Reclaimed Token Reserve Listing Bond");
+        verbose_msg!("Bond Amount: {}", token_reserve_listing.bond_amount);
 
-        ////////////////////////
-        //Oracle Price Validator
-        let price_validator_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let price_validator = validate_and_return_price_validator_account(*ctx.program_id, price_validator_serialized)?;
+        Ok(())
+    }
 
-        ///////////////////
-        //Oracle Price Data
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+    //Spins up a fresh Token Reserve for a mint that already has one, so its risk parameters can be reset and users moved over with migrate_position_to_token_reserve_version.
+    //The new version gets its own Token ID, so its SubMarkets, Tab Accounts, and price data are separate from the previous version's
+    pub fn add_token_reserve_version(ctx: Context<AddTokenReserveVersion>,
+        reserve_version: u8,
+        token_decimal_amount: u8,
+        base_borrow_apy: u16,
+        use_fixed_borrow_apy: bool,
+        global_limit: u128,
+        solvency_insurance_fee_rate: u16,
+        usd_quote_price_id: u8,
+        allow_restricted_mint_extensions: bool) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        ///////////////
-        //Lending Stats
-        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+        //Versions have to be added in order, on top of the latest version
+        require!(reserve_version == ctx.accounts.previous_token_reserve.reserve_version + 1, LendingError::InvalidTokenReserveVersion);
 
-        /////////////////////////////
-        //Repayment SubMarket Account
-        let repayment_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut repayment_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-            repayment_sub_market_account_serialized,
-            repayment_token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index)?;
+        //Token-2022 mints with extensions that can break Token Reserve solvency need the CEO to explicitly allow them
+        check_token_mint_extensions(&ctx.accounts.token_mint.to_account_info(), allow_restricted_mint_extensions)?;
 
-        ///////////////////////////////
-        //Liquidation SubMarket Account
-        let liquidation_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidation_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-            liquidation_sub_market_account_serialized,
-            liquidation_token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index)?;
+        //USD values divide raw amounts by 10^token_decimal_amount against 18 decimal normalized prices, so a wrong decimal amount would over or under weight this token in every health check
+        require!(token_decimal_amount == ctx.accounts.token_mint.decimals, LendingError::TokenDecimalAmountMisMatched);
 
-        /////////////////////////////////
-        //Liquidati Repayment Tab Account
-        let liquidati_repayment_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_repayment_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-            liquidati_repayment_tab_account_serialized,
-            repayment_token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
 
-        ///////////////////////////////////
-        //Liquidati Liquidation Tab Account
-        let liquidati_liquidation_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_liquidation_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-            liquidati_liquidation_tab_account_serialized,
-            liquidation_token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        initialize_token_reserve(token_reserve,
+            token_reserve_stats,
+            ctx.bumps.token_reserve,
+            ctx.accounts.token_mint.key(),
+            reserve_version,
+            token_decimal_amount,
+            base_borrow_apy,
+            use_fixed_borrow_apy,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id
+        )?;
+        
+        msg!("Added Token Reserve #{}", token_reserve_stats.token_reserve_count);
+        verbose_msg!("Token Mint Address: {}", ctx.accounts.token_mint.key());
+        verbose_msg!("Reserve Version: {}, Previous Token ID: {}", reserve_version, ctx.accounts.previous_token_reserve.token_id);
+        verbose_msg!("Token Decimal Amount: {}", token_decimal_amount);
+        verbose_msg!("Base Borrow APY: {}", base_borrow_apy);
+        verbose_msg!("Use fixed Borrow APY: {}", use_fixed_borrow_apy);
+        verbose_msg!("Global Limit: {}", global_limit);
+        verbose_msg!("USD Quote Price ID: {}", usd_quote_price_id);
+        verbose_msg!("Restricted Mint Extensions Allowed: {}", allow_restricted_mint_extensions);
+
+        emit!(TokenReserveAdded
+        {
+            token_id: token_reserve.token_id,
+            token_mint_address: ctx.accounts.token_mint.key(),
+            reserve_version: reserve_version,
+            token_decimal_amount,
+            base_borrow_apy,
+            use_fixed_borrow_apy,
+            global_limit,
+            solvency_insurance_fee_rate,
+            usd_quote_price_id,
+            restricted_mint_extensions_allowed: allow_restricted_mint_extensions
+        });
+            
+        Ok(())
+    }
 
-        ///////////////////////////////////////////////
-        //Liquidati Repayment Monthly Statement Account
-        let liquidati_repayment_monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_repayment_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-            liquidati_repayment_monthly_statement_account_serialized,
-            lending_protocol.current_statement_month,
-            lending_protocol.current_statement_year,
-            repayment_token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+    pub fn update_token_reserve(ctx: Context<UpdateTokenReserve>,
+        base_borrow_apy: u16,
+        use_fixed_borrow_apy: bool,
+        global_limit: u128,
+        solvency_insurance_fee_rate: u16) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        ///////////////////////////////////////////////
-        //Liquidati Liquidation Monthly Statement Account
-        let liquidati_liquidation_monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_liquidation_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-            liquidati_liquidation_monthly_statement_account_serialized,
-            lending_protocol.current_statement_month,
-            lending_protocol.current_statement_year,
-            liquidation_token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        //While the timelock is on, Token Reserve changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
 
-        let repayment_amount;
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
 
-        //Get USD value of Repayment Amount
-        let repayment_token_conversion_number = BASE_10_INT.pow(repayment_token_reserve.token_decimal_amount as u32); 
-        let repayment_token_usd_value = get_verified_token_price(&temp_price_account.data, repayment_token_reserve.token_id)?;
-        let mut repayment_amount_usd_value = 0;
+        set_token_reserve_parameters(token_reserve, base_borrow_apy, use_fixed_borrow_apy, global_limit, solvency_insurance_fee_rate)?;
+        token_reserve_stats.token_reserves_updated_count += 1;
 
-        //Check if Account is liquidatable and set repayment_amount
-        if paying_off_insolvent_account
-        {
-            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+        msg!("Token Reserve Updated");
+        verbose_msg!("Token ID: {}", token_reserve.token_id);
+        verbose_msg!("New Base Borrow APY: {}", base_borrow_apy);
+        verbose_msg!("New Global Limit: {}",  global_limit);
 
-            if repay_max
-            {
-                repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
-                repayment_amount_usd_value = (repayment_amount as u128 * repayment_token_usd_value) / repayment_token_conversion_number;
-                
-                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
-                //Use saturating_sub to safely deduct the value
-                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
-                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
-                    .total_borrowed_usd_value
-                    .saturating_sub(repayment_amount_usd_value);
-            }
-            else
-            {
-                if amount_to_repay > liquidati_repayment_tab_account.borrowed_amount
-                {
-                    //Can't pay more debt than the user has accumulated
-                    repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
-                }
-                else
-                {
-                    repayment_amount = amount_to_repay;
-                }  
-            }
-        }
-        else
+        emit!(TokenReserveUpdated
         {
-            //Multiply before dividing to help keep precision
-            let eighty_percent_of_liquidati_deposited_usd_value = (liquidati_lending_account.total_deposited_usd_value * 80) / 100;
+            token_id: token_reserve.token_id,
+            base_borrow_apy,
+            use_fixed_borrow_apy,
+            global_limit,
+            solvency_insurance_fee_rate
+        });
+            
+        Ok(())
+    }
 
-            //You can't liquidate an account whose borrow liabilities aren't 80% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= eighty_percent_of_liquidati_deposited_usd_value, LendingError::NotLiquidatable);
+    //Borrows at or above the threshold have to be posted as a Borrow Intent and wait out the delay before they can be executed. A threshold of 0 turns borrow intents off
+    pub fn update_token_reserve_borrow_intent_settings(ctx: Context<UpdateTokenReserve>,
+        borrow_intent_threshold_amount: u64,
+        borrow_intent_delay_slots: u64,
+        borrow_intent_max_price_deviation_rate: u16
+    ) -> Result<()> 
+    {
+        let ceo = &mut ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-            //Multiply before dividing to help keep precision
-            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 50) / 100;
+        //Borrow Intent price deviation rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(borrow_intent_max_price_deviation_rate <= 10_000, LendingError::InvalidBorrowIntentPriceDeviationRate);
 
-            if repay_max
-            {
-                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
-            }
-            else
-            {
-                repayment_amount = amount_to_repay;
-            }
+        let token_reserve_stats = &mut ctx.accounts.token_reserve_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        token_reserve.borrow_intent_threshold_amount = borrow_intent_threshold_amount;
+        token_reserve.borrow_intent_delay_slots = borrow_intent_delay_slots;
+        token_reserve.borrow_intent_max_price_deviation_rate = borrow_intent_max_price_deviation_rate;
+        token_reserve_stats.token_reserves_updated_count += 1;
 
-            //You can't repay more than 50% of a liquidati's debt position
-            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
-        }
+        msg!("Token Reserve Borrow Intent Settings Updated");
+        verbose_msg!("Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Borrow Intent Threshold Amount: {}", borrow_intent_threshold_amount);
+        verbose_msg!("Borrow Intent Delay Slots: {}", borrow_intent_delay_slots);
+        verbose_msg!("Borrow Intent Max Price Deviation Rate: {:.2}%", borrow_intent_max_price_deviation_rate as f64 / 100.0);
 
-        if repayment_amount_usd_value == 0
+        emit!(TokenReserveBorrowIntentSettingsUpdated
         {
-            repayment_amount_usd_value = (repayment_amount as u128 * repayment_token_usd_value) / repayment_token_conversion_number;
-        }
+            token_id: token_reserve.token_id,
+            borrow_intent_threshold_amount,
+            borrow_intent_delay_slots,
+            borrow_intent_max_price_deviation_rate
+        });
 
-        //Multiply before dividing to help keep precision
-        let ten_percent_of_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 10) / 100;
+        Ok(())
+    }
 
-        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
-        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
-        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+    //Turns the guarded launch liquidator allowlist on or off for a Token Reserve. A deadline of 0 keeps it on until the CEO turns it off
+    pub fn update_liquidator_allowlist_settings(ctx: Context<UpdateTokenReserve>,
+        liquidator_allowlist_enabled: bool,
+        liquidator_allowlist_deadline_time_stamp: u64) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        token_reserve.liquidator_allowlist_enabled = liquidator_allowlist_enabled;
+        token_reserve.liquidator_allowlist_deadline_time_stamp = liquidator_allowlist_deadline_time_stamp;
+
+        msg!("Updated Liquidator Allowlist Settings for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Liquidator Allowlist Enabled: {}", liquidator_allowlist_enabled);
+        verbose_msg!("Liquidator Allowlist Deadline Time Stamp: {}", liquidator_allowlist_deadline_time_stamp);
+
+        Ok(())
+    }
+
+    //Switches the Token Reserve between per second and per clock slot interest accrual. Interest is accrued in the old mode first, so the switch only affects time from here on
+    pub fn update_token_reserve_accrual_mode(ctx: Context<UpdateTokenReserve>, use_slot_based_accrual: bool) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        //Slot based accrual starts counting from the current slot
+        if use_slot_based_accrual && !token_reserve.use_slot_based_accrual
+        {
+            token_reserve.last_accrual_clock_slot = Clock::get()?.slot;
+        }
+        token_reserve.use_slot_based_accrual = use_slot_based_accrual;
+
+        msg!("Updated Accrual Mode for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Use Slot Based Accrual: {}", use_slot_based_accrual);
+        verbose_msg!("Last Accrual Clock Slot: {}", token_reserve.last_accrual_clock_slot);
+
+        Ok(())
+    }
+
+    //Tags the Token Reserve with an E-Mode asset category. 0 takes it out of its category.
+    //Lending Users in E-Mode holding the Token Reserve's tokens fall back to the regular limits at their next refresh if it leaves their category
+    pub fn update_token_reserve_asset_category(ctx: Context<UpdateTokenReserve>, asset_category: u8) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
+
+        //While the timelock is on, asset category changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        token_reserve.asset_category = asset_category;
+
+        msg!("Updated Asset Category for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Asset Category: {}", asset_category);
+
+        Ok(())
+    }
+
+    //Lending User Accounts already borrowing a newly siloed token alongside other tokens keep their debt, but can't borrow any more of either until they're down to one token
+    pub fn update_token_reserve_siloed_borrowing(ctx: Context<UpdateTokenReserve>, siloed_borrowing: bool) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        token_reserve.siloed_borrowing = siloed_borrowing;
+
+        msg!("Updated Siloed Borrowing for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Siloed Borrowing: {}", siloed_borrowing);
+
+        Ok(())
+    }
+
+    //Lowering the haircut counts more of the Token Reserve's deposits toward user health, so a listing can only have it lowered once its veto window closes without a veto
+    pub fn update_token_reserve_collateral_haircut_rate(ctx: Context<UpdateTokenReserve>, collateral_haircut_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
+
+        //While the timelock is on, collateral haircut changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        set_token_reserve_collateral_haircut_rate(token_reserve, collateral_haircut_rate)?;
+
+        msg!("Updated Collateral Haircut Rate for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Collateral Haircut Rate: {:.2}%", collateral_haircut_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    pub fn add_liquidator_to_allowlist(ctx: Context<AddLiquidatorToAllowlist>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let liquidator_allowlist_entry = &mut ctx.accounts.liquidator_allowlist_entry;
+        liquidator_allowlist_entry.bump = ctx.bumps.liquidator_allowlist_entry;
+        liquidator_allowlist_entry.version = CURRENT_ACCOUNT_VERSION;
+        liquidator_allowlist_entry.token_reserve_address = ctx.accounts.token_reserve.key();
+        liquidator_allowlist_entry.liquidator_address = ctx.accounts.liquidator_address.key();
+
+        msg!("Added Liquidator to the Allowlist for Token ID: {}", ctx.accounts.token_reserve.token_id);
+        verbose_msg!("Liquidator: {}", ctx.accounts.liquidator_address.key());
+
+        Ok(())
+    }
+
+    pub fn remove_liquidator_from_allowlist(ctx: Context<RemoveLiquidatorFromAllowlist>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        msg!("Removed Liquidator from the Allowlist for Token ID: {}", ctx.accounts.token_reserve.token_id);
+        verbose_msg!("Liquidator: {}", ctx.accounts.liquidator_address.key());
+
+        Ok(())
+    }
+
+    pub fn create_sub_market(ctx: Context<CreateSubMarket>,
+        sub_market_index: u16,
+        fee_on_interest_earned_rate: u16,
+        look_up_table_address: Option<Pubkey> //Needed when a user creates their first Sub Market
+    ) -> Result<()> 
+    {
+        //SubMarket Fee on interest earned rate can't be greater than 100%, 1 in decimal form, 10,000 in fixed point notation
+        require!(fee_on_interest_earned_rate <= 10_000, LendingError::InvalidSubMarketFeeRate);
+
+        let token_reserve = &ctx.accounts.token_reserve;
+
+        let sub_market = &mut ctx.accounts.sub_market.load_init()?;
+        sub_market.bump = ctx.bumps.sub_market;
+        sub_market.version = CURRENT_ACCOUNT_VERSION;
+        sub_market.owner = ctx.accounts.signer.key();
+        sub_market.fee_collector_address = ctx.accounts.fee_collector_address.key();
+        sub_market.fee_on_interest_earned_rate = fee_on_interest_earned_rate; //This should fed in fixed point notation from 0 to 10,000 (0 to 100%)
+        sub_market.pending_fee_on_interest_earned_rate = fee_on_interest_earned_rate;
+        sub_market.token_id = token_reserve.token_id; //This can't be edited after. Allowing this to be edited would be like allowing some one to say this currency is a different kind of currency later when ever they wanted
+        sub_market.sub_market_index = sub_market_index;
+        
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_creation_count += 1;
+        sub_market.id = sub_market_stats.sub_market_creation_count;
+
+        msg!("Created SubMarket #{}", sub_market.id);
+        verbose_msg!("Token ID: {}", sub_market.token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market.sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Fee Collector Address: {}", ctx.accounts.fee_collector_address.key());
+        verbose_msg!("Fee On Interest Earned Rate: {:.2}%", fee_on_interest_earned_rate as f64 / 100.0); //convert from fixed point notation with 4 decimal places back to decimal for logging
+
+        emit!(SubMarketCreated
+        {
+            id: sub_market.id,
+            token_id: sub_market.token_id,
+            owner: sub_market.owner,
+            sub_market_index,
+            fee_collector_address: sub_market.fee_collector_address,
+            fee_on_interest_earned_rate
+        });
+        
+        //Add Look Up Table Address to account if being newly initialized.
+        let sub_market_owner_look_up_table = &mut ctx.accounts.sub_market_owner_look_up_table;
+        if sub_market_owner_look_up_table.look_up_table_added == false
+        {
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingSubMarketLookUpTable)?;
+
+            sub_market_owner_look_up_table.bump = ctx.bumps.sub_market_owner_look_up_table;
+
+            sub_market_owner_look_up_table.version = CURRENT_ACCOUNT_VERSION;
+            sub_market_owner_look_up_table.owner = ctx.accounts.signer.key();
+            sub_market_owner_look_up_table.look_up_table_address = lut_address;
+            sub_market_owner_look_up_table.look_up_table_added = true;
+            verbose_msg!("Created Sub Market Owner Look Up Table: {}", sub_market_owner_look_up_table.look_up_table_address.key());
+        }
+
+        Ok(())
+    }
+
+    pub fn edit_sub_market(ctx: Context<EditSubMarket>,
+        token_id: u8,
+        sub_market_index: u16,
+        fee_on_interest_earned_rate: u16
+    ) -> Result<()> 
+    {
+        //SubMarket Fee on interest earned rate can't be greater than 100%, 1 in decimal form, 10,000 in fixed point notation
+        require!(fee_on_interest_earned_rate <= 10_000, LendingError::InvalidSubMarketFeeRate);
+
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+
+        //A new fee collector has to accept with accept_sub_market_fee_collector before uncollected fees are redirected to it, preventing typo and hijack losses
+        if ctx.accounts.fee_collector_address.key() == sub_market.fee_collector_address
+        {
+            sub_market.pending_fee_collector_address = Pubkey::default();
+        }
+        else
+        {
+            sub_market.pending_fee_collector_address = ctx.accounts.fee_collector_address.key();
+        }
+
+        //Lock in where the fee currently is, even if it was part way through a previous ramp
+        let current_fee_rate = get_sub_market_fee_rate(sub_market, time_stamp);
+
+        //Fee decreases only help depositors so they take effect right away. Fee increases ramp up linearly so depositors' pending interest can't be rugged all at once.
+        if fee_on_interest_earned_rate <= current_fee_rate || lending_protocol.sub_market_fee_ramp_seconds == 0
+        {
+            sub_market.fee_on_interest_earned_rate = fee_on_interest_earned_rate;
+            sub_market.pending_fee_on_interest_earned_rate = fee_on_interest_earned_rate;
+            sub_market.fee_ramp_start_time_stamp = 0;
+            sub_market.fee_ramp_end_time_stamp = 0;
+        }
+        else
+        {
+            sub_market.fee_on_interest_earned_rate = current_fee_rate;
+            sub_market.pending_fee_on_interest_earned_rate = fee_on_interest_earned_rate;
+            sub_market.fee_ramp_start_time_stamp = time_stamp;
+            sub_market.fee_ramp_end_time_stamp = time_stamp + lending_protocol.sub_market_fee_ramp_seconds;
+        }
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+        
+        msg!("Edited Submarket");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Fee Collector Address: {}", sub_market.fee_collector_address.key());
+        verbose_msg!("Pending Fee Collector Address: {}", sub_market.pending_fee_collector_address.key());
+        verbose_msg!("Fee On Interest Earned Rate: {:.2}%", sub_market.fee_on_interest_earned_rate as f64 / 100.0); //convert from fixed point notation with 4 decimal places back to decimal for logging
+        verbose_msg!("Pending Fee On Interest Earned Rate: {:.2}%", fee_on_interest_earned_rate as f64 / 100.0);
+        verbose_msg!("Fee Ramp End Time Stamp: {}", sub_market.fee_ramp_end_time_stamp);
+
+        emit!(SubMarketEdited
+        {
+            token_id,
+            owner: ctx.accounts.signer.key(),
+            sub_market_index,
+            fee_collector_address: sub_market.fee_collector_address,
+            pending_fee_collector_address: sub_market.pending_fee_collector_address,
+            fee_on_interest_earned_rate: sub_market.fee_on_interest_earned_rate,
+            pending_fee_on_interest_earned_rate: fee_on_interest_earned_rate,
+            fee_ramp_end_time_stamp: sub_market.fee_ramp_end_time_stamp
+        });
+
+        Ok(())
+    }
+
+    //The pending fee collector proposed by the SubMarket owner in edit_sub_market has to sign to take over collecting the SubMarket's fees
+    pub fn accept_sub_market_fee_collector(ctx: Context<AcceptSubMarketFeeCollector>,
+        token_id: u8,
+        sub_market_index: u16
+    ) -> Result<()>
+    {
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        //Only the pending fee collector can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), sub_market.pending_fee_collector_address.key(), LendingError::NotPendingFeeCollector);
+
+        sub_market.fee_collector_address = sub_market.pending_fee_collector_address;
+        sub_market.pending_fee_collector_address = Pubkey::default();
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+
+        msg!("Accepted SubMarket Fee Collector");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Owner: {}", ctx.accounts.sub_market_owner.key());
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Fee Collector Address: {}", sub_market.fee_collector_address.key());
+
+        emit!(SubMarketFeeCollectorChanged
+        {
+            token_id,
+            owner: ctx.accounts.sub_market_owner.key(),
+            sub_market_index,
+            fee_collector_address: sub_market.fee_collector_address
+        });
+
+        Ok(())
+    }
+
+    //The SubMarket owner requests an alternative oracle price ID for their instance of the token, ie a wrapped/bridged variant priced off a different feed.
+    //Any new request resets the approval, so the override isn't used in health checks until the CEO approves it. Set to 0 to remove the override.
+    //Closes an empty SubMarket and gives the owner back its rent. Nothing can be deposited, borrowed, or left uncollected in it
+    pub fn retire_sub_market(ctx: Context<RetireSubMarket>, token_id: u8, sub_market_index: u16) -> Result<()> 
+    {
+        {
+            let sub_market = &ctx.accounts.sub_market.load()?;
+            require!(sub_market.deposited_amount == 0
+                && sub_market.borrowed_amount == 0
+                && sub_market.uncollected_sub_market_fees_amount == 0, LendingError::SubMarketNotEmpty);
+        }
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_retired_count += 1;
+
+        msg!("Retired SubMarket");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+
+        emit!(SubMarketRetired
+        {
+            token_id,
+            owner: ctx.accounts.signer.key(),
+            sub_market_index
+        });
+
+        Ok(())
+    }
+
+    //Lets a SubMarket owner run a supply only market that earns interest from other SubMarkets' borrowers without allowing new debt of its own
+    pub fn set_sub_market_borrows_enabled(ctx: Context<EditSubMarket>,
+        token_id: u8,
+        sub_market_index: u16,
+        borrows_enabled: bool
+    ) -> Result<()> 
+    {
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        sub_market.borrows_disabled = (!borrows_enabled) as u8;
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+
+        msg!("Updated SubMarket Borrows Enabled");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Borrows Enabled: {}", borrows_enabled);
+
+        Ok(())
+    }
+
+    //Turns a SubMarket into a private market where only wallets on the owner's allowlist can deposit or borrow
+    pub fn set_sub_market_depositor_allowlist_enabled(ctx: Context<EditSubMarket>,
+        token_id: u8,
+        sub_market_index: u16,
+        depositor_allowlist_enabled: bool
+    ) -> Result<()> 
+    {
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        sub_market.depositor_allowlist_enabled = depositor_allowlist_enabled as u8;
+
+        msg!("Updated SubMarket Depositor Allowlist");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Depositor Allowlist Enabled: {}", depositor_allowlist_enabled);
+
+        Ok(())
+    }
+
+    pub fn add_sub_market_allowlist_entry(ctx: Context<AddSubMarketAllowlistEntry>, token_id: u8, sub_market_index: u16) -> Result<()> 
+    {
+        let sub_market_allowlist_entry = &mut ctx.accounts.sub_market_allowlist_entry;
+        sub_market_allowlist_entry.bump = ctx.bumps.sub_market_allowlist_entry;
+        sub_market_allowlist_entry.version = CURRENT_ACCOUNT_VERSION;
+        sub_market_allowlist_entry.sub_market_address = ctx.accounts.sub_market.key();
+        sub_market_allowlist_entry.user_address = ctx.accounts.user_address.key();
+
+        msg!("Added User to SubMarket Allowlist");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("User: {}", ctx.accounts.user_address.key());
+
+        Ok(())
+    }
+
+    pub fn remove_sub_market_allowlist_entry(ctx: Context<RemoveSubMarketAllowlistEntry>, token_id: u8, sub_market_index: u16) -> Result<()> 
+    {
+        msg!("Removed User from SubMarket Allowlist");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("User: {}", ctx.accounts.user_address.key());
+
+        Ok(())
+    }
+
+    //The SubMarket owner sets how much of the SubMarket fees generated by referred Tab Accounts goes to their referrers.
+    //The rate applies to fees referrers haven't been credited for yet
+    pub fn set_sub_market_referral_fee_share_rate(ctx: Context<SetSubMarketReferralFeeShareRate>,
+        token_id: u8,
+        sub_market_index: u16,
+        referral_fee_share_rate: u16
+    ) -> Result<()>
+    {
+        //Referral fee share can't be greater than 100%, 1 in decimal form, 10,000 in fixed point notation
+        require!(referral_fee_share_rate <= 10_000, LendingError::InvalidReferralFeeShareRate);
+
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        sub_market.referral_fee_share_rate = referral_fee_share_rate;
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+
+        msg!("Set SubMarket Referral Fee Share Rate");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Referral Fee Share Rate: {:.2}%", referral_fee_share_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    pub fn set_sub_market_oracle_override(ctx: Context<SetSubMarketOracleOverride>,
+        token_id: u8,
+        sub_market_index: u16,
+        oracle_price_id_override: u8
+    ) -> Result<()>
+    {
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        sub_market.oracle_price_id_override = oracle_price_id_override;
+        sub_market.oracle_override_approved = 0;
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+
+        msg!("Requested SubMarket Oracle Override");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Oracle Price ID Override: {}", oracle_price_id_override);
+
+        Ok(())
+    }
+
+    pub fn approve_sub_market_oracle_override(ctx: Context<ApproveSubMarketOracleOverride>,
+        token_id: u8,
+        sub_market_index: u16,
+        oracle_price_id_override: u8, //Must match the requested override so the owner can't swap it out right before the CEO approves it
+        approved: bool
+    ) -> Result<()>
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        //While the timelock is on, Oracle Override approvals have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        require!(sub_market.oracle_price_id_override == oracle_price_id_override, LendingError::OracleOverrideMisMatched);
+        sub_market.oracle_override_approved = approved as u8;
+
+        let sub_market_stats = &mut ctx.accounts.sub_market_stats;
+        sub_market_stats.sub_market_edit_count += 1;
+
+        msg!("SubMarket Oracle Override Approved: {}", approved);
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Owner: {}", ctx.accounts.sub_market_owner.key());
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Oracle Price ID Override: {}", oracle_price_id_override);
+
+        Ok(())
+    }
+
+    //Looking to see if this isn't necessary
+    /*pub fn update_lending_user_look_up_table_address(ctx: Context<UpdateLendingUserLookUpTableAddress>, look_up_table_address: Pubkey) -> Result<()> 
+    {
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.look_up_table_address = look_up_table_address;
+
+        verbose_msg!("Updated Lending User Look Up Table Address: {}", lending_user_account.look_up_table_address);
+
+        Ok(())
+    }*/
+
+    //Creates an Address Lookup Table owned by the Lending User Account and points the Lending User Account at it.
+    //recent_slot has to be a recent slot from the cluster, it's part of the Look Up Table's address
+    pub fn create_lending_user_look_up_table(ctx: Context<CreateLendingUserLookUpTable>, _user_account_index: u8, recent_slot: u64) -> Result<()> 
+    {
+        let lending_user_account = &ctx.accounts.lending_user_account;
+        let (look_up_table_address, look_up_table_bump) = Pubkey::find_program_address(&[lending_user_account.key().as_ref(), recent_slot.to_le_bytes().as_ref()], &ADDRESS_LOOKUP_TABLE_PROGRAM_ADDRESS);
+        require_keys_eq!(ctx.accounts.look_up_table.key(), look_up_table_address, LendingError::UnexpectedLookUpTableAccount);
+
+        create_lending_user_look_up_table(&ctx.accounts.look_up_table.to_account_info(),
+            lending_user_account,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            recent_slot,
+            look_up_table_bump)?;
+
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.look_up_table_address = look_up_table_address;
+
+        msg!("Created Lending User Look Up Table: {}", look_up_table_address);
+        verbose_msg!("Lending User Account: {}, User Account Index: {}", lending_user_account.key(), lending_user_account.user_account_index);
+
+        Ok(())
+    }
+
+    //Adds the Lending User Account's registered Tab Accounts to its Look Up Table, along with any other addresses passed in (Monthly Statements, Token Reserves, SubMarkets, price accounts),
+    //so health checked instructions can fit every Tab Account in one v0 transaction. Addresses already in the Look Up Table are skipped
+    pub fn extend_lending_user_look_up_table(ctx: Context<ExtendLendingUserLookUpTable>, _user_account_index: u8, additional_addresses: Vec<Pubkey>) -> Result<()> 
+    {
+        let lending_user_account = &ctx.accounts.lending_user_account;
+
+        let mut addresses = lending_user_account.tab_account_addresses.clone();
+        addresses.extend(additional_addresses);
+
+        let added_address_count = extend_lending_user_look_up_table(&ctx.accounts.look_up_table.to_account_info(),
+            lending_user_account,
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            addresses)?;
+
+        msg!("Added {} addresses to Lending User Look Up Table: {}", added_address_count, ctx.accounts.look_up_table.key());
+
+        Ok(())
+    }
+
+    pub fn deposit_tokens(ctx: Context<DepositTokens>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()> 
+    {
+        //Leaving the Monthly Statement account out of the deposit that creates the Lending User Account opts it out of Monthly Statements from the start,
+        //so bots and cost sensitive users never have to pay rent for a statement or call set_monthly_statements_disabled first
+        let opting_out_of_monthly_statements = !ctx.accounts.lending_user_account.lending_user_account_added && ctx.accounts.lending_user_monthly_statement_account.is_none();
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled || opting_out_of_monthly_statements;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        check_sub_market_allowlist(sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        let new_token_reserve_deposited_amount = amount as u128 + token_reserve.deposited_amount;
+        //You can't deposit more than the global limit
+        require!(new_token_reserve_deposited_amount <= token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+
+        //Lending Users in E-Mode can only deposit tokens in their E-Mode asset category
+        check_e_mode_asset_category(lending_user_account, token_reserve)?;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if lending_user_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Generic Depositer");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()//Check for empty string ""
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                lending_user_account,
+                ctx.bumps.lending_user_account,
+                ctx.accounts.signer.key(),
+                user_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+
+            lending_user_account.monthly_statements_disabled = opting_out_of_monthly_statements;
+        }
+        
+        //A referrer can only be attached by the deposit that creates the Tab Account
+        require!(lending_user_tab_account.user_tab_account_added == 0 || ctx.accounts.lending_user_referral.is_none(), LendingError::ReferrerAfterFirstDeposit);
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.lending_user_tab_account,
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+
+            if let Some(lending_user_referral) = &mut ctx.accounts.lending_user_referral
+            {
+                let referrer = ctx.accounts.referrer.as_ref().ok_or(LendingError::MissingReferrer)?;
+                require_keys_neq!(referrer.key(), ctx.accounts.signer.key(), LendingError::SelfReferral);
+
+                lending_user_referral.bump = ctx.bumps.lending_user_referral.unwrap_or_default();
+                lending_user_referral.version = CURRENT_ACCOUNT_VERSION;
+                lending_user_referral.referrer = referrer.key();
+                lending_user_referral.owner = ctx.accounts.signer.key();
+                lending_user_referral.user_account_index = user_account_index;
+                lending_user_referral.token_id = token_reserve.token_id;
+                lending_user_referral.sub_market_owner_address = sub_market_owner_address;
+                lending_user_referral.sub_market_index = sub_market_index;
+
+                verbose_msg!("Referrer: {}", referrer.key());
+            }
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        lower_tab_fee_discount_rate(&ctx.accounts.lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let amount = if let Some(delegated_token_account) = &ctx.accounts.delegated_token_account
+        {
+            deposit_tokens_into_token_reserve_from_delegated_token_account(
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                delegated_token_account,
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                amount,
+                ctx.remaining_accounts
+            )?
+        }
+        else
+        {
+            let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
+            let should_close = user_ata_data.amount == 0;
+            deposit_tokens_into_token_reserve_from_user(
+                ctx.accounts.token_mint.key(),
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.user_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                amount,
+                should_close,
+                ctx.remaining_accounts
+            )?
+        };
+
+        //Update Values and Stat Listener
+        lending_stats.deposits += 1;
+        sub_market.deposited_amount += amount as u128;
+        token_reserve.deposited_amount += amount as u128;
+        lending_user_tab_account.deposited_amount += amount;
+        lending_user_monthly_statement_account.monthly_deposited_amount += amount;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = amount;
+        token_reserve.last_lending_activity_type = Activity::Deposit as u8;
+        sub_market.last_lending_activity_amount = amount;
+        sub_market.last_lending_activity_type = Activity::Deposit as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        lending_user_monthly_statement_account.last_lending_activity_amount = amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Deposit as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        record_lending_user_activity(&mut ctx.accounts.lending_user_activity_account, Activity::Deposit as u8, token_reserve.token_mint_address, amount, token_reserve.last_lending_activity_time_stamp);
+
+        msg!("{} deposited at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+
+        Ok(())
+    }
+
+    //Deposits into several SubMarkets in one transaction. Each deposit's accounts are fed in through the remaining accounts in order:
+    //Token Reserve, SubMarket, Tab Account, Monthly Statement (skipped when opted out), Token Reserve ATA, User ATA, Token Mint, and Token Program.
+    //The Tab Accounts have to already exist, so a user's first deposit into a SubMarket still goes through deposit_tokens. Transfer hook mints aren't supported here
+    pub fn deposit_many<'info>(ctx: Context<'info, DepositMany<'info>>,
+        user_account_index: u8,
+        batch_deposits: Vec<Structs::BatchDeposit>
+    ) -> Result<()> 
+    {
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &ctx.accounts.lending_user_account;
+        let signer_address = ctx.accounts.signer.key();
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        require!(!batch_deposits.is_empty(), LendingError::EmptyBatchDeposit);
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        for batch_deposit in batch_deposits.iter()
+        {
+            //Validate Remaining Accounts
+
+            ///////////////////////
+            //Token Reserve Account
+            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut token_reserve = validate_and_return_token_reserve_account(*ctx.program_id, token_reserve_account_serialized)?;
+            require_keys_eq!(token_reserve.token_mint_address, batch_deposit.token_mint_address, LendingError::UnexpectedTokenReserveAccount);
+
+            //You can't deposit more than the global limit
+            require!(batch_deposit.amount as u128 + token_reserve.deposited_amount <= token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+
+            //Lending Users in E-Mode can only deposit tokens in their E-Mode asset category
+            check_e_mode_asset_category(lending_user_account, &token_reserve)?;
+
+            ///////////////////
+            //SubMarket Account
+            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+                sub_market_account_serialized,
+                token_reserve.token_id,
+                batch_deposit.sub_market_owner_address,
+                batch_deposit.sub_market_index)?;
+
+            //Allowlisted SubMarkets have to be deposited into with deposit_tokens, where the allowlist entry can be passed in
+            require!(sub_market.depositor_allowlist_enabled == 0, LendingError::NotOnSubMarketAllowlist);
+
+            /////////////
+            //Tab Account
+            let tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut lending_user_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+                tab_account_serialized,
+                token_reserve.token_id,
+                batch_deposit.sub_market_owner_address,
+                batch_deposit.sub_market_index,
+                signer_address,
+                user_account_index)?;
+
+            ///////////////////////////
+            //Monthly Statement Account
+            //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+            let monthly_statement_account_serialized;
+            let mut monthly_statement_account;
+            if lending_user_account.monthly_statements_disabled
+            {
+                monthly_statement_account_serialized = None;
+                monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+            }
+            else
+            {
+                let statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                    statement_account_serialized,
+                    lending_protocol.current_statement_month,
+                    lending_protocol.current_statement_year,
+                    token_reserve.token_id,
+                    batch_deposit.sub_market_owner_address,
+                    batch_deposit.sub_market_index,
+                    signer_address,
+                    user_account_index)?;
+                monthly_statement_account_serialized = Some(statement_account_serialized);
+            }
+
+            ////////////////
+            //Token Accounts
+            let token_reserve_ata_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let user_ata_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let token_mint = InterfaceAccount::<Mint>::try_from(remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?)?;
+            let token_program = Interface::<TokenInterface>::try_from(remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?)?;
+            require_keys_eq!(token_mint.key(), batch_deposit.token_mint_address, LendingError::InvalidTokenAccountMint);
+
+            validate_token_reserve_ata(
+                token_reserve_ata_serialized,
+                token_mint.key(),
+                token_reserve_account_serialized.key()
+            )?;
+
+            //Calculate Token Reserve Previously Earned And Accrued Interest
+            update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, time_stamp, None)?;
+
+            lower_tab_fee_discount_rate(&lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, &mut lending_user_tab_account);
+            update_user_previous_interest_earned(
+                &mut token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                &mut token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account
+            )?;
+
+            //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+            let user_ata_data = TokenAccount::try_deserialize(&mut &user_ata_serialized.data.borrow()[..])?;
+            let should_close = user_ata_data.amount == 0;
+            let amount = deposit_tokens_into_token_reserve_from_user(
+                token_mint.key(),
+                token_reserve_ata_serialized,
+                user_ata_serialized,
+                &token_mint,
+                &token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                batch_deposit.amount,
+                should_close,
+                &[]
+            )?;
+
+            //Update Values and Stat Listener
+            lending_stats.deposits += 1;
+            sub_market.deposited_amount += amount as u128;
+            token_reserve.deposited_amount += amount as u128;
+            lending_user_tab_account.deposited_amount += amount;
+            monthly_statement_account.monthly_deposited_amount += amount;
+            monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+
+            sync_token_reserve_active_user_counts(&mut token_reserve, &mut lending_user_tab_account);
+
+            //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+            update_token_reserve_rates(&mut token_reserve)?;
+            sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+            lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+            //Update last activity on accounts
+            token_reserve.last_lending_activity_amount = amount;
+            token_reserve.last_lending_activity_type = Activity::Deposit as u8;
+            sub_market.last_lending_activity_amount = amount;
+            sub_market.last_lending_activity_type = Activity::Deposit as u8;
+            sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+            monthly_statement_account.last_lending_activity_amount = amount;
+            monthly_statement_account.last_lending_activity_type = Activity::Deposit as u8;
+            monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+            //1. Save Token Reserve (Skip 8 byte discriminator)
+            token_reserve.serialize(&mut &mut token_reserve_account_serialized.data.borrow_mut()[8..])?;
+
+            //SubMarket and User Tab Account are zero copy, their changes were written in place
+
+            //2. Save Monthly Statement (Skip 8 byte discriminator)
+            if let Some(monthly_statement_account_serialized) = monthly_statement_account_serialized
+            {
+                monthly_statement_account.serialize(&mut &mut monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+            }
+
+            msg!("{} deposited at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+            signer_address,
+            token_reserve.token_id,
+            batch_deposit.sub_market_owner_address,
+            batch_deposit.sub_market_index);
+        }
+
+        Ok(())
+    }
+
+    pub fn edit_lending_user_account_name(ctx: Context<EditLendingUserAccountName>,
+        _user_account_index: u8,
+        account_name: String
+    ) -> Result<()> 
+    {
+        //Account Name string must not be longer than 25 characters
+        require!(account_name.len() <= MAX_ACCOUNT_NAME_LENGTH, LendingError::LendingUserAccountNameTooLong);
+
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.account_name = account_name.clone();
+
+        let lending_user_stats = &mut ctx.accounts.lending_user_stats;
+        lending_user_stats.name_change_count += 1;
+
+        msg!("Lending User Account name updated to: {}", account_name);
+
+        Ok(()) 
+    }
+
+    //Monthly Statements cost rent every month for every token and SubMarket a user has a tab in, so users can opt out of them for each of their Lending User Accounts.
+    //Opted out users can leave their Monthly Statement accounts out of every instruction. Their totals are still tracked on their Tab Accounts.
+    //Leaving the Monthly Statement account out of the first deposit opts a new Lending User Account out without calling this.
+    pub fn set_monthly_statements_disabled(ctx: Context<SetMonthlyStatementsDisabled>,
+        user_account_index: u8,
+        monthly_statements_disabled: bool
+    ) -> Result<()> 
+    {
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.monthly_statements_disabled = monthly_statements_disabled;
+
+        msg!("Lending User Account Index: {}", user_account_index);
+        verbose_msg!("Monthly Statements Disabled: {}", monthly_statements_disabled);
+
+        Ok(()) 
+    }
+
+    //Registers a second required signer for withdrawals and borrows worth more than the threshold. Pass null to remove the co-signer.
+    //Once a co-signer is registered, it also has to sign any change to the co-signer or threshold so a compromised wallet can't just remove it.
+    pub fn set_co_signer(ctx: Context<SetCoSigner>,
+        user_account_index: u8,
+        co_signer_address: Option<Pubkey>,
+        co_signer_threshold_usd_value: u128
+    ) -> Result<()> 
+    {
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+
+        if lending_user_account.co_signer_address != Pubkey::default()
+        {
+            let current_co_signer = ctx.accounts.current_co_signer.as_ref().ok_or(LendingError::MissingCoSigner)?;
+            require_keys_eq!(current_co_signer.key(), lending_user_account.co_signer_address, LendingError::CoSignerMisMatched);
+        }
+
+        lending_user_account.co_signer_address = co_signer_address.unwrap_or_default();
+        lending_user_account.co_signer_threshold_usd_value = co_signer_threshold_usd_value;
+
+        msg!("Lending User Account Index: {}", user_account_index);
+        verbose_msg!("Co-Signer Address: {}", lending_user_account.co_signer_address);
+        verbose_msg!("Co-Signer Threshold USD Value: {}", co_signer_threshold_usd_value);
+
+        Ok(()) 
+    }
+
+    //Opts the Lending User Account in to keeping its most recent activities on chain. Deposits, withdrawals, borrows, and repayments are recorded when the account is passed in
+    pub fn create_lending_user_activity_account(ctx: Context<CreateLendingUserActivityAccount>, user_account_index: u8) -> Result<()> 
+    {
+        let lending_user_activity_account = &mut ctx.accounts.lending_user_activity_account;
+        lending_user_activity_account.bump = ctx.bumps.lending_user_activity_account;
+        lending_user_activity_account.version = CURRENT_ACCOUNT_VERSION;
+        lending_user_activity_account.owner = ctx.accounts.signer.key();
+        lending_user_activity_account.user_account_index = user_account_index;
+
+        msg!("Created Lending User Activity Account for Account Index: {}", user_account_index);
+
+        Ok(()) 
+    }
+
+    pub fn close_lending_user_activity_account(ctx: Context<CloseLendingUserActivityAccount>, user_account_index: u8) -> Result<()> 
+    {
+        msg!("Closed Lending User Activity Account for Account Index: {}", user_account_index);
+        verbose_msg!("Rent Returned To: {}", ctx.accounts.signer.key());
+
+        Ok(()) 
+    }
+
+    //Returns the Lending User Account's rent to its owner. Only accounts without any Tab Accounts can be closed since Tab Accounts are never closed
+    pub fn close_lending_user_account(ctx: Context<CloseLendingUserAccount>, user_account_index: u8) -> Result<()> 
+    {
+        let lending_user_account = &ctx.accounts.lending_user_account;
+
+        require!(lending_user_account.tab_account_count == 0, LendingError::LendingUserAccountHasTabAccounts);
+
+        msg!("Closed Lending User Account Index: {}", user_account_index);
+        verbose_msg!("Rent Returned To: {}", ctx.accounts.signer.key());
+
+        Ok(()) 
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    pub fn withdraw_tokens(ctx: Context<WithdrawTokens>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64,
+        withdraw_max: bool
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //This keeps users who have no debt at all from needing to check prices on withdrawals
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This withdraw_tokens function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt, unless their cached User Health is still fresh
+            check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, ctx.accounts.lending_protocol.user_health_max_age_slots, clock_slot)?;
+        }
+
+        //The health refresh already updated interest on every tab, so this is only needed without one
+        if lending_user_account.last_health_update_clock_slot != clock_slot
+        {
+            //Initialize monthly statement account if the statement month/year has changed.
+            if lending_user_monthly_statement_account.monthly_statement_account_added == false
+            {
+                let lending_protocol = &ctx.accounts.lending_protocol;
+                initialize_lending_user_monthly_statement_account(
+                    lending_user_monthly_statement_account,
+                    lending_user_tab_account,
+                    lending_protocol,
+                    ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                    token_reserve.token_id,
+                    sub_market_owner_address.key(),
+                    sub_market_index,
+                    ctx.accounts.signer.key(),
+                    user_account_index,
+                )?;
+            }
+
+            let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+            //Calculate Token Reserve Previously Earned And Accrued Interest
+            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+            lower_tab_fee_discount_rate(&ctx.accounts.lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+        }
+
+        //After updating interest earned and accrued, set withdraw amount
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        let token_reserve_available_amount = token_reserve_ata_data.amount;
+        let mut withdraw_amount;
+
+        if withdraw_max
+        {
+            withdraw_amount = std::cmp::min(token_reserve_available_amount, lending_user_tab_account.deposited_amount);
+        }
+        else
+        {
+            withdraw_amount = amount
+        }
+
+        //Skip if user has no debt and no co-signer. Users with a co-signer need prices to know if a withdrawal is over their co-signer threshold.
+        //The price accounts come first in the remaining accounts when they're passed in, followed by any transfer hook accounts.
+        //They can be left out when the User Health attestation from refresh_user_health still has the prices
+        let price_accounts_needed = lending_user_account.total_borrowed_usd_value > 0 || lending_user_account.co_signer_address != Pubkey::default();
+        let price_accounts_passed_in = price_accounts_needed && ctx.remaining_accounts.first().is_some_and(|account| is_temp_price_account(*ctx.program_id, account));
+        if price_accounts_needed
+        {
+            let normalized_price_18_decimals = if price_accounts_passed_in
+            {
+                ////////////////////////////
+                //Validate Oracle Price Data
+                let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+                let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+                    temp_price_account_serialized,
+                    ctx.accounts.signer.key())?;
+
+                check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+                let normalized_price_18_decimals = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+
+                //Refund Oracle price account fees back to Oracle
+                let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+                refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+                normalized_price_18_decimals
+            }
+            else
+            {
+                get_attested_user_health_usd_price(&ctx.accounts.user_health, clock_slot, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?
+            };
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+
+            //Only users with debt have a Loan To Value limit on withdrawals, and deposits with a 100% collateral haircut aren't backing any of it
+            if lending_user_account.total_borrowed_usd_value > 0 && token_reserve.collateral_haircut_rate < 10_000
+            {
+                if !withdraw_max
+                {
+                    let new_user_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(token_reserve, (withdraw_amount as u128 * normalized_price_18_decimals) / token_conversion_number));
+                
+                    //Multiply before dividing to help keep precision
+                    let max_borrowed_usd_value = (new_user_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+
+                    //You can't withdraw an amount that would cause your borrow liabilities to exceed the max Loan To Value of deposited collateral.
+                    require!(max_borrowed_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+                }
+                else
+                {
+                    //1. Calculate the exact floor amount of USD collateral that MUST remain behind to maintain the max LTV
+                    let min_required_deposited_usd_value = (lending_user_account.total_borrowed_usd_value * DECIMAL_SCALING) / get_loan_to_value_rate(lending_user_account);
+
+                    if lending_user_account.total_deposited_usd_value > min_required_deposited_usd_value 
+                    {
+                        //2. Find out how much total USD value the user can safely strip out
+                        let max_withdraw_usd_value = lending_user_account.total_deposited_usd_value - min_required_deposited_usd_value;
+
+                        //3. Convert that safe USD allowance back into native token units using the oracle price, grossed up for the part of each token the collateral haircut doesn't count
+                        let max_allowed_token_withdraw = (max_withdraw_usd_value * token_conversion_number * 10_000) / (normalized_price_18_decimals * (10_000 - token_reserve.collateral_haircut_rate as u128));
+
+                        //4. Cap it by the user's absolute token balance in this tab and token reserve liquidity amount
+                        let safe_max_tokens = std::cmp::min(max_allowed_token_withdraw, lending_user_tab_account.deposited_amount as u128) as u64;
+                        withdraw_amount = std::cmp::min(safe_max_tokens, token_reserve_available_amount);
+                    } 
+                    else 
+                    {
+                        //User is already at or exceeding the max LTV, they cannot withdraw anything safely.
+                        return Err(LendingError::LiquidationExposure.into());
+                    }
+                }
+            }
+
+            //High value withdrawals need the co-signer's approval too
+            let withdraw_usd_value = (withdraw_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            check_co_signer(lending_user_account, &ctx.accounts.co_signer, withdraw_usd_value)?;
+
+            //Take the withdrawal off of the health totals so the same collateral can't be withdrawn twice before the next refresh
+            lending_user_account.total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(token_reserve, withdraw_usd_value));
+            update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
+        }
+
+        //You can't withdraw more funds than you've deposited
+        require!(lending_user_tab_account.deposited_amount >= withdraw_amount, LendingError::InsufficientFunds);
+
+        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
+        require!(token_reserve_available_amount >= withdraw_amount, LendingError::InsufficientLiquidity);
+
+        //Tokens go to the recipient's token account when one is passed in. wSOL is sent to it as is, since only the signer can receive the native SOL from the temporary wSOL account
+        let (recipient_ata_info, temporary_wsol_account) = match &ctx.accounts.recipient_token_account
+        {
+            Some(recipient_token_account) => (recipient_token_account.to_account_info(), None),
+            None => (ctx.accounts.user_ata.to_account_info(),
+                ctx.accounts.temporary_wsol_account.as_ref().map(|temporary_wsol_account| (temporary_wsol_account.as_ref(), ctx.bumps.temporary_wsol_account.unwrap_or_default())))
+        };
+
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.token_mint.key(),
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &recipient_ata_info,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            withdraw_amount,
+            temporary_wsol_account,
+            ctx.remaining_accounts.get(if price_accounts_passed_in { 2 } else { 0 }..).unwrap_or(&[])
+        )?;
+        
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        sub_market.deposited_amount -= withdraw_amount as u128;
+        token_reserve.deposited_amount -= withdraw_amount as u128;
+        lending_user_tab_account.deposited_amount -= withdraw_amount;
+        lending_user_monthly_statement_account.monthly_withdrawal_amount += withdraw_amount;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = withdraw_amount;
+        token_reserve.last_lending_activity_type = Activity::Withdraw as u8;
+        sub_market.last_lending_activity_amount = withdraw_amount;
+        sub_market.last_lending_activity_type = Activity::Withdraw as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
+        lending_user_monthly_statement_account.last_lending_activity_amount = withdraw_amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Withdraw as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        record_lending_user_activity(&mut ctx.accounts.lending_user_activity_account, Activity::Withdraw as u8, token_reserve.token_mint_address, withdraw_amount, token_reserve.last_lending_activity_time_stamp);
+        
+        msg!("{} withdrew at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Recipient Token Account: {}", recipient_ata_info.key());
+
+        Ok(())
+    }
+
+    //Repays all of a tab's remaining debt from the user's wallet and withdraws all of its collateral in one instruction, leaving the tab empty so it can be garbage collected later.
+    //SOL debt is repaid from the user's native SOL and the collateral comes back as native SOL when the temporary wSOL account is passed in
+    pub fn close_position(ctx: Context<ClosePosition>,
+        sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //This keeps users who have no debt at all from needing to check prices
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This close_position function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt, unless their cached User Health is still fresh
+            check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, ctx.accounts.lending_protocol.user_health_max_age_slots, clock_slot)?;
+        }
+
+        //The health refresh already updated interest on every tab, so this is only needed without one
+        if lending_user_account.last_health_update_clock_slot != clock_slot
+        {
+            //Initialize monthly statement account if the statement month/year has changed.
+            if lending_user_monthly_statement_account.monthly_statement_account_added == false
+            {
+                let lending_protocol = &ctx.accounts.lending_protocol;
+                initialize_lending_user_monthly_statement_account(
+                    lending_user_monthly_statement_account,
+                    lending_user_tab_account,
+                    lending_protocol,
+                    ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                    token_reserve.token_id,
+                    sub_market_owner_address.key(),
+                    sub_market_index,
+                    ctx.accounts.signer.key(),
+                    user_account_index,
+                )?;
+            }
+
+            let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+            //Calculate Token Reserve Previously Earned And Accrued Interest
+            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+            lower_tab_fee_discount_rate(&ctx.accounts.lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+        }
+
+        //The price accounts come first in the remaining accounts when they're needed, followed by any transfer hook accounts
+        let price_accounts_needed = lending_user_account.total_borrowed_usd_value > 0 || lending_user_account.co_signer_address != Pubkey::default();
+        let transfer_hook_accounts = ctx.remaining_accounts.get(if price_accounts_needed { 2 } else { 0 }..).unwrap_or(&[]);
+
+        //After updating interest earned and accrued, repay all of the tab's debt
+        let repayment_amount = lending_user_tab_account.borrowed_amount;
+        if repayment_amount > 0
+        {
+            //The user's wSOL ATA is only closed when the collateral is coming back as native SOL, since it's needed to receive wSOL otherwise
+            let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
+            let should_close = user_ata_data.amount == 0 && ctx.accounts.temporary_wsol_account.is_some();
+            let repaid_amount = deposit_tokens_into_token_reserve_from_user(
+                ctx.accounts.token_mint.key(),
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.user_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                repayment_amount,
+                should_close,
+                transfer_hook_accounts
+            )?;
+
+            //Transfer fee mints deliver less than the amount sent, which would leave debt behind in a tab that's supposed to be closed
+            require!(repaid_amount == repayment_amount, LendingError::PositionDebtNotFullyRepaid);
+        }
+
+        let withdraw_amount = lending_user_tab_account.deposited_amount;
+
+        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(token_reserve_ata_data.amount >= withdraw_amount, LendingError::InsufficientLiquidity);
+
+        if price_accounts_needed
+        {
+            ////////////////////////////
+            //Validate Oracle Price Data
+            let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+            let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+                temp_price_account_serialized,
+                ctx.accounts.signer.key())?;
+
+            check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+            
+            let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+            let repayment_usd_value = (repayment_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            let withdraw_usd_value = (withdraw_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            let withdraw_collateral_usd_value = get_collateral_usd_value(token_reserve, withdraw_usd_value);
+
+            lending_user_account.total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value.saturating_sub(repayment_usd_value);
+
+            //Debt in the user's other tabs still has a Loan To Value limit on the withdrawal
+            if lending_user_account.total_borrowed_usd_value > 0
+            {
+                let new_user_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(withdraw_collateral_usd_value);
+                
+                //Multiply before dividing to help keep precision
+                let max_borrowed_usd_value = (new_user_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+
+                //You can't withdraw an amount that would cause your borrow liabilities to exceed the max Loan To Value of deposited collateral.
+                require!(max_borrowed_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+            }
+
+            //High value withdrawals need the co-signer's approval too
+            check_co_signer(lending_user_account, &ctx.accounts.co_signer, withdraw_usd_value)?;
+
+            //Take the withdrawal off of the health totals so the same collateral can't be withdrawn twice before the next refresh
+            lending_user_account.total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(withdraw_collateral_usd_value);
+            update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
+            
+            //Refund Oracle price account fees back to Oracle
+            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        }
+
+        if withdraw_amount > 0
+        {
+            withdraw_tokens_from_token_reserve_to_user(
+                ctx.accounts.token_mint.key(),
+                token_reserve,
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.user_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                withdraw_amount,
+                ctx.accounts.temporary_wsol_account.as_ref().map(|temporary_wsol_account| (temporary_wsol_account.as_ref(), ctx.bumps.temporary_wsol_account.unwrap_or_default())),
+                transfer_hook_accounts
+            )?;
+        }
+        
+        //Update Values and Stat Listener
+        lending_stats.repayments += 1;
+        lending_stats.withdrawals += 1;
+        sub_market.borrowed_amount -= repayment_amount as u128;
+        sub_market.repaid_debt_amount += repayment_amount as u128;
+        sub_market.deposited_amount -= withdraw_amount as u128;
+        token_reserve.borrowed_amount -= repayment_amount as u128;
+        token_reserve.repaid_debt_amount += repayment_amount as u128;
+        token_reserve.deposited_amount -= withdraw_amount as u128;
+        lending_user_tab_account.borrowed_amount = 0;
+        lending_user_tab_account.repaid_debt_amount += repayment_amount;
+        lending_user_tab_account.deposited_amount = 0;
+        lending_user_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        lending_user_monthly_statement_account.monthly_withdrawal_amount += withdraw_amount;
+        lending_user_monthly_statement_account.snap_shot_debt_amount = 0;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = 0;
+        
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = withdraw_amount;
+        token_reserve.last_lending_activity_type = Activity::ClosePosition as u8;
+        sub_market.last_lending_activity_amount = withdraw_amount;
+        sub_market.last_lending_activity_type = Activity::ClosePosition as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
+        lending_user_monthly_statement_account.last_lending_activity_amount = withdraw_amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::ClosePosition as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        record_lending_user_activity(&mut ctx.accounts.lending_user_activity_account, Activity::ClosePosition as u8, token_reserve.token_mint_address, withdraw_amount, token_reserve.last_lending_activity_time_stamp);
+        
+        msg!("{} closed a position at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Repaid Amount: {}, Withdrawn Amount: {}", repayment_amount, withdraw_amount);
+
+        Ok(())
+    }
+
+    //Moves deposited balance from the signer's tab to another user's tab in the same SubMarket without moving any tokens, so in-protocol payments keep earning interest.
+    //The balance leaves the signer's account, so this has the same health and co-signer checks as withdraw_tokens
+    pub fn transfer_deposit(ctx: Context<TransferDeposit>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        recipient_account_index: u8,
+        amount: u64
+    ) -> Result<()> 
+    {
+        //You can't transfer a deposit to the Tab Account it's coming from
+        require_keys_neq!(ctx.accounts.lending_user_tab_account.key(), ctx.accounts.recipient_lending_user_tab_account.key(), LendingError::InvalidDepositTransferRecipient);
+
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let recipient_monthly_statements_disabled = ctx.accounts.recipient_lending_user_account.monthly_statements_disabled;
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        check_sub_market_allowlist(sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let recipient_lending_user_tab_account = &mut ctx.accounts.recipient_lending_user_tab_account.load_mut()?;
+        let mut recipient_lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let recipient_lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.recipient_lending_user_monthly_statement_account, &mut recipient_lending_user_monthly_statement_account_placeholder, recipient_monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let recipient_address = ctx.accounts.recipient.key();
+
+        //This keeps users who have no debt at all from needing to check prices on transfers
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This transfer_deposit function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt, unless their cached User Health is still fresh
+            check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, lending_protocol.user_health_max_age_slots, clock_slot)?;
+        }
+
+        //Initialize monthly statement accounts if the statement month/year has changed.
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        if recipient_lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                recipient_lending_user_monthly_statement_account,
+                recipient_lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.recipient_lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                recipient_address,
+                recipient_account_index,
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        //The health refresh already updated interest on every one of the signer's tabs, so this is only needed without one
+        if lending_user_account.last_health_update_clock_slot != clock_slot
+        {
+            lower_tab_fee_discount_rate(&lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+        }
+
+        //The recipient's tab is never covered by the signer's health refresh
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            recipient_lending_user_tab_account,
+            recipient_lending_user_monthly_statement_account
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            recipient_lending_user_tab_account,
+            recipient_lending_user_monthly_statement_account
+        )?;
+
+        //Skip if user has no debt and no co-signer. Users with a co-signer need prices to know if a transfer is over their co-signer threshold.
+        if lending_user_account.total_borrowed_usd_value > 0 || lending_user_account.co_signer_address != Pubkey::default()
+        {
+            ////////////////////////////
+            //Validate Oracle Price Data
+            let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+            let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+                temp_price_account_serialized,
+                ctx.accounts.signer.key())?;
+
+            check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+            
+            let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+            let transfer_usd_value = (amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            let transfer_collateral_usd_value = get_collateral_usd_value(token_reserve, transfer_usd_value);
+
+            //Only users with debt have a Loan To Value limit on transfers
+            if lending_user_account.total_borrowed_usd_value > 0
+            {
+                let new_user_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(transfer_collateral_usd_value);
+                
+                //Multiply before dividing to help keep precision
+                let max_borrowed_usd_value = (new_user_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+
+                //You can't transfer an amount that would cause your borrow liabilities to exceed the max Loan To Value of deposited collateral.
+                require!(max_borrowed_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+            }
+
+            //High value transfers need the co-signer's approval too
+            check_co_signer(lending_user_account, &ctx.accounts.co_signer, transfer_usd_value)?;
+
+            //Take the transfer off of the health totals so the same collateral can't be moved twice before the next refresh
+            lending_user_account.total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(transfer_collateral_usd_value);
+            update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
+            
+            //Refund Oracle price account fees back to Oracle
+            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        }
+
+        //You can't transfer more funds than you've deposited
+        require!(lending_user_tab_account.deposited_amount >= amount, LendingError::InsufficientFunds);
+
+        //Update Values and Stat Listener. A transfer counts as a withdrawal for the signer and a deposit for the recipient
+        //The Token Reserve and SubMarket totals don't change since the balance never leaves the SubMarket
+        lending_stats.withdrawals += 1;
+        lending_stats.deposits += 1;
+        lending_user_tab_account.deposited_amount -= amount;
+        recipient_lending_user_tab_account.deposited_amount += amount;
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+        sync_token_reserve_active_user_counts(token_reserve, recipient_lending_user_tab_account);
+        lending_user_monthly_statement_account.monthly_withdrawal_amount += amount;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        recipient_lending_user_monthly_statement_account.monthly_deposited_amount += amount;
+        recipient_lending_user_monthly_statement_account.snap_shot_balance_amount = recipient_lending_user_tab_account.deposited_amount;
+
+        //Update the User time stamp based interest indexes
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        recipient_lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        recipient_lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = amount;
+        token_reserve.last_lending_activity_type = Activity::TransferDeposit as u8;
+        sub_market.last_lending_activity_amount = amount;
+        sub_market.last_lending_activity_type = Activity::TransferDeposit as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
+        lending_user_monthly_statement_account.last_lending_activity_amount = amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::TransferDeposit as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        recipient_lending_user_monthly_statement_account.last_lending_activity_amount = amount;
+        recipient_lending_user_monthly_statement_account.last_lending_activity_type = Activity::TransferDeposit as u8;
+        recipient_lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        
+        msg!("{} transferred a deposit at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Recipient: {}, Recipient Account Index: {}", recipient_address, recipient_account_index);
+
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Posts a borrow at or above the Token Reserve's Borrow Intent threshold along with the current price. borrow_tokens can execute it once the delay has passed if the price hasn't moved too far
+    pub fn post_borrow_intent(ctx: Context<PostBorrowIntent>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64
+    ) -> Result<()> 
+    {
+        let token_reserve = &ctx.accounts.token_reserve;
+        let sub_market = &ctx.accounts.sub_market.load()?;
+        let price_validator = &ctx.accounts.price_validator;
+        let clock_slot = Clock::get()?.slot;
+
+        //Smaller borrows don't need to wait, they can be made directly with borrow_tokens
+        require!(token_reserve.borrow_intent_threshold_amount > 0 && amount >= token_reserve.borrow_intent_threshold_amount, LendingError::BorrowIntentNotRequired);
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        let borrow_intent = &mut ctx.accounts.borrow_intent;
+        borrow_intent.bump = ctx.bumps.borrow_intent;
+        borrow_intent.version = CURRENT_ACCOUNT_VERSION;
+        borrow_intent.token_id = token_reserve.token_id;
+        borrow_intent.sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        borrow_intent.sub_market_index = sub_market_index;
+        borrow_intent.owner = ctx.accounts.signer.key();
+        borrow_intent.user_account_index = user_account_index;
+        borrow_intent.amount = amount;
+        borrow_intent.posted_price_18_decimals = normalized_price_18_decimals;
+        borrow_intent.posted_clock_slot = clock_slot;
+
+        msg!("{} posted a Borrow Intent at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index);
+        verbose_msg!("Amount: {}, Executable At Clock Slot: {}", amount, clock_slot + token_reserve.borrow_intent_delay_slots);
+
+        Ok(())
+    }
+
+    pub fn cancel_borrow_intent(ctx: Context<CancelBorrowIntent>,
+        token_id: u8,
+        sub_market_index: u16,
+        _user_account_index: u8
+    ) -> Result<()> 
+    {
+        msg!("{} cancelled their Borrow Intent at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index);
+
+        Ok(())
+    }
+
+    pub fn borrow_tokens(ctx: Context<BorrowTokens>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64,
+        borrow_max: bool
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        check_sub_market_allowlist(sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        //New borrows are paused protocol wide while the bad debt circuit breaker is tripped
+        require!(!ctx.accounts.lending_protocol.borrows_paused, LendingError::BorrowsPaused);
+
+        //Supply only SubMarkets don't allow new debt
+        require!(sub_market.borrows_disabled == 0, LendingError::SubMarketBorrowsDisabled);
+
+        //Vetoed listings can't be borrowed from
+        require!(!token_reserve.listing_vetoed, LendingError::TokenReserveListingVetoed);
+
+        //Lending Users in E-Mode can only borrow tokens in their E-Mode asset category
+        check_e_mode_asset_category(lending_user_account, token_reserve)?;
+
+        //Siloed tokens can't be borrowed alongside other tokens
+        check_siloed_borrowing(lending_user_account, token_reserve)?;
+
+        //The borrow_tokens function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        if token_reserve.last_health_update_clock_slot != clock_slot
+        {
+            let time_stamp = Clock::get()?.unix_timestamp as u64;
+            
+            //When a user is borrowing from a token reserve they have never interacted with before, it won't get refreshed by refresh_user_health_chunk, so doing it here
+            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        }
+        
+        //Unless the cached User Health is still fresh
+        check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, ctx.accounts.lending_protocol.user_health_max_age_slots, clock_slot)?;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        //This is for when a user is borrowing a token they have never interacted with before
+        if lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.lending_user_tab_account,
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+        //This is for when a user is borrowing a token they have never interacted with before
+        //You won't be able to use the create_new_monthly_statement until after the lending_user_tab_account exists
+        //Normally create_new_monthly_statement and refresh_user_health_chunk would have this covered
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        //The health refresh already updated interest on every tab, so this is only needed when the cached User Health is used
+        if lending_user_account.last_health_update_clock_slot != clock_slot
+        {
+            lower_tab_fee_discount_rate(&ctx.accounts.lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+        }
+
+        //The price accounts come first in the remaining accounts when they're passed in, followed by any transfer hook accounts.
+        //They can be left out when the User Health attestation from refresh_user_health still has the prices
+        let price_accounts_passed_in = ctx.remaining_accounts.first().is_some_and(|account| is_temp_price_account(*ctx.program_id, account));
+        let normalized_price_18_decimals = if price_accounts_passed_in
+        {
+            ////////////////////////////
+            //Validate Oracle Price Data
+            let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+            let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+                temp_price_account_serialized,
+                ctx.accounts.signer.key())?;
+
+            check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+            let normalized_price_18_decimals = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+
+            //Refund Oracle price account fees back to Oracle
+            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+            normalized_price_18_decimals
+        }
+        else
+        {
+            get_attested_user_health_usd_price(&ctx.accounts.user_health, clock_slot, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?
+        };
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+
+        //Determine Borrow Amount
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        let token_reserve_available_amount = token_reserve_ata_data.amount;
+        let max_total_allowed_debt_usd_value = (lending_user_account.total_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+        let mut borrow_amount = amount;
+
+        if !borrow_max 
+        {
+            //You can't borrow an amount that would cause your borrow liabilities to exceed the max Loan To Value of deposited collateral.
+            lending_user_account.total_borrowed_usd_value += (borrow_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            require!(max_total_allowed_debt_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+        }
+        else
+        {
+            if max_total_allowed_debt_usd_value > lending_user_account.total_borrowed_usd_value 
+            {
+                //1. Determine available headroom remaining in USD
+                let remaining_usd_borrow_headroom = max_total_allowed_debt_usd_value - lending_user_account.total_borrowed_usd_value;
+
+                //2. Convert USD target capacity into native token fractions using the oracle price
+                let max_tokens_allowed = (remaining_usd_borrow_headroom * token_conversion_number) / normalized_price_18_decimals;
+
+                //3. Cap it by the user's max allowed amount and token reserve liquidity amount
+                borrow_amount = std::cmp::min(max_tokens_allowed, token_reserve_available_amount as u128) as u64;
+                
+                //4. Update global account trackers with finalized calculations
+                lending_user_account.total_borrowed_usd_value += (borrow_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+                require!(max_total_allowed_debt_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+            }
+            else
+            {
+                //User is already at or exceeding the max LTV, they cannot borrow anything safely.
+                return Err(LendingError::LiquidationExposure.into());
+            }
+        }
+
+        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
+        require!(token_reserve_available_amount >= borrow_amount, LendingError::InsufficientLiquidity);
+
+        //Large borrows need a Borrow Intent that has waited out its delay with the price staying within bounds
+        check_borrow_intent(token_reserve, &ctx.accounts.borrow_intent, borrow_amount, normalized_price_18_decimals, clock_slot)?;
+
+        //High value borrows need the co-signer's approval too
+        let borrow_usd_value = (borrow_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        check_co_signer(lending_user_account, &ctx.accounts.co_signer, borrow_usd_value)?;
+        update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
+
+        //Tokens go to the recipient's token account when one is passed in. wSOL is sent to it as is, since only the signer can receive the native SOL from the temporary wSOL account
+        let (recipient_ata_info, temporary_wsol_account) = match &ctx.accounts.recipient_token_account
+        {
+            Some(recipient_token_account) => (recipient_token_account.to_account_info(), None),
+            None => (ctx.accounts.user_ata.to_account_info(),
+                ctx.accounts.temporary_wsol_account.as_ref().map(|temporary_wsol_account| (temporary_wsol_account.as_ref(), ctx.bumps.temporary_wsol_account.unwrap_or_default())))
+        };
+
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.token_mint.key(),
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &recipient_ata_info,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            borrow_amount,
+            temporary_wsol_account,
+            ctx.remaining_accounts.get(if price_accounts_passed_in { 2 } else { 0 }..).unwrap_or(&[])
+        )?;
+
+        //Update Values and Stat Listener
+        lending_stats.borrows += 1;
+        sub_market.borrowed_amount += borrow_amount as u128;
+        token_reserve.borrowed_amount += borrow_amount as u128;
+        lending_user_tab_account.borrowed_amount += borrow_amount;
+        lending_user_monthly_statement_account.monthly_borrowed_amount += borrow_amount;
+        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+        record_borrowed_token(&mut lending_user_account.borrowed_tokens, token_reserve);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = borrow_amount;
+        token_reserve.last_lending_activity_type = Activity::Borrow as u8;
+        sub_market.last_lending_activity_amount = borrow_amount;
+        sub_market.last_lending_activity_type = Activity::Borrow as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
+        lending_user_monthly_statement_account.last_lending_activity_amount = borrow_amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Borrow as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        record_lending_user_activity(&mut ctx.accounts.lending_user_activity_account, Activity::Borrow as u8, token_reserve.token_mint_address, borrow_amount, token_reserve.last_lending_activity_time_stamp);
+        
+        msg!("{} borrowed at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Recipient Token Account: {}", recipient_ata_info.key());
+
+        Ok(())
+    }
+
+    //Lets a delegate borrow one token against the Lending User's collateral, up to the allowance. Approving again replaces the allowance, 0 revokes it
+    pub fn approve_credit_delegation(ctx: Context<ApproveCreditDelegation>,
+        user_account_index: u8,
+        borrow_allowance_amount: u64
+    ) -> Result<()> 
+    {
+        //A Lending User can't delegate credit to themselves
+        require_keys_neq!(ctx.accounts.delegate.key(), ctx.accounts.signer.key(), LendingError::InvalidCreditDelegate);
+
+        let credit_delegation = &mut ctx.accounts.credit_delegation;
+        credit_delegation.bump = ctx.bumps.credit_delegation;
+        credit_delegation.version = CURRENT_ACCOUNT_VERSION;
+        credit_delegation.owner = ctx.accounts.signer.key();
+        credit_delegation.user_account_index = user_account_index;
+        credit_delegation.delegate = ctx.accounts.delegate.key();
+        credit_delegation.token_mint_address = ctx.accounts.token_mint.key();
+        credit_delegation.borrow_allowance_amount = borrow_allowance_amount;
+
+        msg!("{} approved {} to borrow against User Account Index: {}", ctx.accounts.signer.key(), ctx.accounts.delegate.key(), user_account_index);
+        verbose_msg!("Token Mint Address: {}, Borrow Allowance Amount: {}", ctx.accounts.token_mint.key(), borrow_allowance_amount);
+
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s), unless the Lending User's cached User Health is still fresh.
+    //The delegate borrows against the Lending User's collateral and receives the tokens, while the debt is recorded on the Lending User's Tab Account.
+    //Delegated borrows can't go through a Borrow Intent, so they have to stay under the Token Reserve's Borrow Intent threshold
+    pub fn borrow_delegated(ctx: Context<BorrowDelegated>,
+        sub_market_index: u16,
+        _user_account_index: u8,
+        amount: u64
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let credit_delegation = &mut ctx.accounts.credit_delegation;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        //New borrows are paused protocol wide while the bad debt circuit breaker is tripped
+        require!(!ctx.accounts.lending_protocol.borrows_paused, LendingError::BorrowsPaused);
+
+        //Supply only SubMarkets don't allow new debt
+        require!(sub_market.borrows_disabled == 0, LendingError::SubMarketBorrowsDisabled);
+
+        //Vetoed listings can't be borrowed from
+        require!(!token_reserve.listing_vetoed, LendingError::TokenReserveListingVetoed);
+
+        //Lending Users in E-Mode can only borrow tokens in their E-Mode asset category
+        check_e_mode_asset_category(lending_user_account, token_reserve)?;
+
+        //Siloed tokens can't be borrowed alongside other tokens
+        check_siloed_borrowing(lending_user_account, token_reserve)?;
+
+        //The delegate can't borrow more than the Lending User approved
+        require!(credit_delegation.borrow_allowance_amount >= amount, LendingError::CreditDelegationAllowanceExceeded);
+
+        if token_reserve.last_health_update_clock_slot != clock_slot
+        {
+            let time_stamp = Clock::get()?.unix_timestamp as u64;
+            update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        }
+        
+        //Unless the cached User Health is still fresh
+        check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, ctx.accounts.lending_protocol.user_health_max_age_slots, clock_slot)?;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //The health refresh already updated interest on every tab, so this is only needed when the cached User Health is used
+        if lending_user_account.last_health_update_clock_slot != clock_slot
+        {
+            lower_tab_fee_discount_rate(&ctx.accounts.lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                sub_market,
+                lending_user_tab_account,
+                lending_user_monthly_statement_account
+            )?;
+        }
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+
+        //You can't borrow an amount that would cause the Lending User's borrow liabilities to exceed the max Loan To Value of deposited collateral.
+        let max_total_allowed_debt_usd_value = (lending_user_account.total_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+        let borrow_usd_value = (amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        lending_user_account.total_borrowed_usd_value += borrow_usd_value;
+        require!(max_total_allowed_debt_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+
+        //You can't withdraw or borrow more funds than are currently available in the Token Reserve. This can happen if there is too much borrowing going on.
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(token_reserve_ata_data.amount >= amount, LendingError::InsufficientLiquidity);
+
+        //Delegated borrows can't use a Borrow Intent
+        check_borrow_intent(token_reserve, &None, amount, normalized_price_18_decimals, clock_slot)?;
+
+        //High value borrows need the Lending User's co-signer's approval too
+        check_co_signer(lending_user_account, &ctx.accounts.co_signer, borrow_usd_value)?;
+        update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.token_mint.key(),
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.user_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            amount,
+            ctx.accounts.temporary_wsol_account.as_ref().map(|temporary_wsol_account| (temporary_wsol_account.as_ref(), ctx.bumps.temporary_wsol_account.unwrap_or_default())),
+            ctx.remaining_accounts.get(2..).unwrap_or(&[])
+        )?;
+
+        //Update Values and Stat Listener
+        lending_stats.borrows += 1;
+        credit_delegation.borrow_allowance_amount -= amount;
+        credit_delegation.borrowed_amount += amount;
+        sub_market.borrowed_amount += amount as u128;
+        token_reserve.borrowed_amount += amount as u128;
+        lending_user_tab_account.borrowed_amount += amount;
+        lending_user_monthly_statement_account.monthly_borrowed_amount += amount;
+        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+        record_borrowed_token(&mut lending_user_account.borrowed_tokens, token_reserve);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = amount;
+        token_reserve.last_lending_activity_type = Activity::Borrow as u8;
+        sub_market.last_lending_activity_amount = amount;
+        sub_market.last_lending_activity_type = Activity::Borrow as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; 
+        lending_user_monthly_statement_account.last_lending_activity_amount = amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Borrow as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        
+        msg!("{} borrowed on behalf of {} at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        ctx.accounts.lending_user_owner.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Borrowed Amount: {}, Remaining Allowance: {}", amount, credit_delegation.borrow_allowance_amount);
+
+        Ok(())
+    }
+
+    pub fn repay_tokens(ctx: Context<RepayTokens>,
+        sub_market_index: u16,
+        _user_account_index: u8,
+        amount: u64,
+        pay_off_loan: bool,
+        pay_10_percent: bool
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+        
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //After updating interest earned and accrued(with refresh_user_health_chunk), set payment amount
+        let repayment_amount;
+
+        if pay_off_loan
+        {
+            repayment_amount = lending_user_tab_account.borrowed_amount;
+        }
+        else if pay_10_percent
+        {
+            repayment_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
+        }
+        else
+        {
+            repayment_amount = amount
+        }
+
+        //Multiply before dividing to help keep precision
+        let liquidation_threshold_usd_value = (lending_user_account.total_deposited_usd_value * get_liquidation_threshold_rate(lending_user_account)) / DECIMAL_SCALING;
+        
+        //Check if lending user account is in a liquidatable state
+        if lending_user_account.total_borrowed_usd_value >= liquidation_threshold_usd_value
+        {
+            //Multiply before dividing to help keep precision
+            let ten_percent_of_borrowed_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
+
+            //You must repay atleast 10% of the borrow position if the account is in an unhealthy state. This prevents "griefing".
+            //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running the liquidator so their transaction fails and holding the protocol's solvency hostage!
+            require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+        }
+
+        //You can't repay an amount that is greater than your borrowed amount.
+        require!(lending_user_tab_account.borrowed_amount >= repayment_amount, LendingError::TooManyFunds);
+
+        //Repay debt
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.user_ata.to_account_info().data.borrow()[..])?;
+        let should_close = user_ata_data.amount == 0;
+        let repayment_amount = deposit_tokens_into_token_reserve_from_user(
+            ctx.accounts.token_mint.key(),
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.user_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            repayment_amount,
+            should_close,
+            ctx.remaining_accounts.get(2..).unwrap_or(&[])
+        )?;
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+
+        //Calculate the USD value of the repayment first
+        let repayment_usd_value = (repayment_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+
+        //Use saturating_sub to safely deduct the value
+        //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
+        lending_user_account.total_borrowed_usd_value = lending_user_account
+            .total_borrowed_usd_value
+            .saturating_sub(repayment_usd_value);
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        //Update Values and Stat Listener
+        lending_stats.repayments += 1;
+        sub_market.borrowed_amount -= repayment_amount as u128;
+        sub_market.repaid_debt_amount += repayment_amount as u128;
+        token_reserve.borrowed_amount -= repayment_amount as u128;
+        token_reserve.repaid_debt_amount += repayment_amount as u128;
+        lending_user_tab_account.borrowed_amount -= repayment_amount;
+        lending_user_tab_account.repaid_debt_amount += repayment_amount;
+        lending_user_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+        
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = repayment_amount;
+        token_reserve.last_lending_activity_type = Activity::Repay as u8;
+        sub_market.last_lending_activity_amount = repayment_amount;
+        sub_market.last_lending_activity_type = Activity::Repay as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        lending_user_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        record_lending_user_activity(&mut ctx.accounts.lending_user_activity_account, Activity::Repay as u8, token_reserve.token_mint_address, repayment_amount, token_reserve.last_lending_activity_time_stamp);
+  
+        msg!("{} repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Repays every outstanding debt in the tabs fed in, up to each token's budget, to make winding down a Lending User Account one transaction.
+    //The remaining accounts are the temp price account and the Oracle, followed by a set per tab in order: Tab Account, Token Reserve, SubMarket,
+    //Monthly Statement (skipped when opted out), Token Reserve ATA, User ATA, Token Mint, and Token Program. Transfer hook mints aren't supported here
+    pub fn repay_all<'info>(ctx: Context<'info, RepayAll<'info>>,
+        user_account_index: u8,
+        set_count: u8, //The number of tab sets being fed in
+        repayment_budgets: Vec<Structs::RepaymentBudget>
+    ) -> Result<()> 
+    {
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let price_validator = &ctx.accounts.price_validator;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let signer_address = ctx.accounts.signer.key();
+        let clock_slot = Clock::get()?.slot;
+        let mut repayment_budgets = repayment_budgets;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        //Multiply before dividing to help keep precision
+        let liquidation_threshold_usd_value = (lending_user_account.total_deposited_usd_value * get_liquidation_threshold_rate(lending_user_account)) / DECIMAL_SCALING;
+        let account_in_unhealthy_state = lending_user_account.total_borrowed_usd_value >= liquidation_threshold_usd_value;
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            signer_address)?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+
+        let mut repaid_tab_account_flags: u32 = 0;
+        for _i in 0..set_count.into()
+        {
+            //Validate Remaining Accounts
+
+            /////////////
+            //Tab Account
+            //Only the Lending User Account's own registered Tab Accounts can be repaid here, and each one only once
+            let tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let tab_account_registry_index = lending_user_account.tab_account_addresses
+                .iter()
+                .position(|tab_account_address| *tab_account_address == tab_account_serialized.key())
+                .ok_or(LendingError::UnregisteredTabAccount)?;
+            let tab_account_repaid_flag = 1u32 << tab_account_registry_index;
+            require!(repaid_tab_account_flags & tab_account_repaid_flag == 0, LendingError::DuplicateTabAccount);
+            repaid_tab_account_flags |= tab_account_repaid_flag;
+            let mut lending_user_tab_account = load_zero_copy_account_mut::<Structs::LendingUserTabAccount>(tab_account_serialized)?;
+
+            ///////////////////////
+            //Token Reserve Account
+            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut token_reserve = validate_and_return_token_reserve_account(*ctx.program_id, token_reserve_account_serialized)?;
+            require!(token_reserve.token_id == lending_user_tab_account.token_id, LendingError::UnexpectedTokenReserveAccount);
+
+            ///////////////////
+            //SubMarket Account
+            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+                sub_market_account_serialized,
+                lending_user_tab_account.token_id,
+                lending_user_tab_account.sub_market_owner_address,
+                lending_user_tab_account.sub_market_index)?;
+
+            ///////////////////////////
+            //Monthly Statement Account
+            //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+            let monthly_statement_account_serialized;
+            let mut monthly_statement_account;
+            if lending_user_account.monthly_statements_disabled
+            {
+                monthly_statement_account_serialized = None;
+                monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+            }
+            else
+            {
+                let statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                    statement_account_serialized,
+                    lending_protocol.current_statement_month,
+                    lending_protocol.current_statement_year,
+                    lending_user_tab_account.token_id,
+                    lending_user_tab_account.sub_market_owner_address,
+                    lending_user_tab_account.sub_market_index,
+                    signer_address,
+                    user_account_index)?;
+                monthly_statement_account_serialized = Some(statement_account_serialized);
+            }
+
+            ////////////////
+            //Token Accounts
+            let token_reserve_ata_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let user_ata_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let token_mint = InterfaceAccount::<Mint>::try_from(remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?)?;
+            let token_program = Interface::<TokenInterface>::try_from(remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?)?;
+            require_keys_eq!(token_mint.key(), token_reserve.token_mint_address, LendingError::InvalidTokenAccountMint);
+
+            validate_token_reserve_ata(
+                token_reserve_ata_serialized,
+                token_mint.key(),
+                token_reserve_account_serialized.key()
+            )?;
+
+            //Tabs without debt are skipped
+            if lending_user_tab_account.borrowed_amount == 0
+            {
+                continue;
+            }
+
+            //Repay as much of the tab's debt as the token's budget has left
+            let repayment_budget = repayment_budgets.iter_mut()
+                .find(|repayment_budget| repayment_budget.token_mint_address == token_mint.key())
+                .ok_or(LendingError::MissingRepaymentBudget)?;
+            let repayment_amount = std::cmp::min(lending_user_tab_account.borrowed_amount, repayment_budget.budget_amount);
+
+            if repayment_amount == 0
+            {
+                continue;
+            }
+
+            //Same as repay_tokens, atleast 10% of each borrow position has to be repaid if the account is in an unhealthy state. This prevents "griefing".
+            if account_in_unhealthy_state
+            {
+                //Multiply before dividing to help keep precision
+                let ten_percent_of_borrowed_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
+                require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+            }
+
+            //Repay debt
+            //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+            let user_ata_data = TokenAccount::try_deserialize(&mut &user_ata_serialized.data.borrow()[..])?;
+            let should_close = user_ata_data.amount == 0;
+            repayment_budget.budget_amount -= repayment_amount;
+            let repayment_amount = deposit_tokens_into_token_reserve_from_user(
+                token_mint.key(),
+                token_reserve_ata_serialized,
+                user_ata_serialized,
+                &token_mint,
+                &token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                repayment_amount,
+                should_close,
+                &[]
+            )?;
+
+            //Use saturating_sub to safely deduct the value, the same as repay_tokens
+            let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(&sub_market), token_reserve.usd_quote_price_id)?;
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+            let repayment_usd_value = (repayment_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+            lending_user_account.total_borrowed_usd_value = lending_user_account
+                .total_borrowed_usd_value
+                .saturating_sub(repayment_usd_value);
+
+            //Update Values and Stat Listener
+            lending_stats.repayments += 1;
+            sub_market.borrowed_amount -= repayment_amount as u128;
+            sub_market.repaid_debt_amount += repayment_amount as u128;
+            token_reserve.borrowed_amount -= repayment_amount as u128;
+            token_reserve.repaid_debt_amount += repayment_amount as u128;
+            lending_user_tab_account.borrowed_amount -= repayment_amount;
+            lending_user_tab_account.repaid_debt_amount += repayment_amount;
+            monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+            monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+
+            sync_token_reserve_active_user_counts(&mut token_reserve, &mut lending_user_tab_account);
+
+            //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+            update_token_reserve_rates(&mut token_reserve)?;
+            sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+            lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+            //Update last activity on accounts
+            token_reserve.last_lending_activity_amount = repayment_amount;
+            token_reserve.last_lending_activity_type = Activity::Repay as u8;
+            sub_market.last_lending_activity_amount = repayment_amount;
+            sub_market.last_lending_activity_type = Activity::Repay as u8;
+            sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+            monthly_statement_account.last_lending_activity_amount = repayment_amount;
+            monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+            monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+            //1. Save Token Reserve (Skip 8 byte discriminator)
+            token_reserve.serialize(&mut &mut token_reserve_account_serialized.data.borrow_mut()[8..])?;
+
+            //SubMarket and User Tab Account are zero copy, their changes were written in place
+
+            //2. Save Monthly Statement (Skip 8 byte discriminator)
+            if let Some(monthly_statement_account_serialized) = monthly_statement_account_serialized
+            {
+                monthly_statement_account.serialize(&mut &mut monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+            }
+
+            msg!("{} repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+            signer_address,
+            token_reserve.token_id,
+            lending_user_tab_account.sub_market_owner_address,
+            lending_user_tab_account.sub_market_index);
+        }
+
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Pays down a tab's debt with the same tab's deposits so users can get away from liquidation without bringing in outside funds. No tokens move since the deposits are already in the Token Reserve
+    pub fn deleverage(ctx: Context<Deleverage>,
+        sub_market_index: u16,
+        _user_account_index: u8,
+        amount: u64,
+        deleverage_max: bool
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+        
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+
+        //After updating interest earned and accrued(with refresh_user_health_chunk), set deleverage amount
+        let deleverage_amount;
+
+        if deleverage_max
+        {
+            deleverage_amount = std::cmp::min(lending_user_tab_account.deposited_amount, lending_user_tab_account.borrowed_amount);
+        }
+        else
+        {
+            deleverage_amount = amount
+        }
+
+        //Multiply before dividing to help keep precision
+        let liquidation_threshold_usd_value = (lending_user_account.total_deposited_usd_value * get_liquidation_threshold_rate(lending_user_account)) / DECIMAL_SCALING;
+        
+        //Check if lending user account is in a liquidatable state
+        if lending_user_account.total_borrowed_usd_value >= liquidation_threshold_usd_value
+        {
+            //Multiply before dividing to help keep precision
+            let ten_percent_of_borrowed_amount = (lending_user_tab_account.borrowed_amount * 10) / 100;
+
+            //Same as repay_tokens, atleast 10% of the borrow position has to be paid down if the account is in an unhealthy state. This prevents "griefing".
+            require!(deleverage_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+        }
+
+        //You can't pay down more debt than you've borrowed, or with more funds than you've deposited
+        require!(lending_user_tab_account.borrowed_amount >= deleverage_amount, LendingError::TooManyFunds);
+        require!(lending_user_tab_account.deposited_amount >= deleverage_amount, LendingError::InsufficientFunds);
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
+
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+
+        //The same USD value comes off of both sides of the Lending User Account
+        let deleverage_usd_value = (deleverage_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        lending_user_account.total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(token_reserve, deleverage_usd_value));
+        lending_user_account.total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value.saturating_sub(deleverage_usd_value);
+        invalidate_cached_user_health(lending_user_account);
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        lending_stats.repayments += 1;
+        sub_market.deposited_amount -= deleverage_amount as u128;
+        sub_market.borrowed_amount -= deleverage_amount as u128;
+        sub_market.repaid_debt_amount += deleverage_amount as u128;
+        token_reserve.deposited_amount -= deleverage_amount as u128;
+        token_reserve.borrowed_amount -= deleverage_amount as u128;
+        token_reserve.repaid_debt_amount += deleverage_amount as u128;
+        lending_user_tab_account.deposited_amount -= deleverage_amount;
+        lending_user_tab_account.borrowed_amount -= deleverage_amount;
+        lending_user_tab_account.repaid_debt_amount += deleverage_amount;
+        lending_user_monthly_statement_account.monthly_withdrawal_amount += deleverage_amount;
+        lending_user_monthly_statement_account.monthly_repaid_debt_amount += deleverage_amount;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        lending_user_monthly_statement_account.snap_shot_debt_amount = lending_user_tab_account.borrowed_amount;
+        
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = deleverage_amount;
+        token_reserve.last_lending_activity_type = Activity::Deleverage as u8;
+        sub_market.last_lending_activity_amount = deleverage_amount;
+        sub_market.last_lending_activity_type = Activity::Deleverage as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        lending_user_monthly_statement_account.last_lending_activity_amount = deleverage_amount;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::Deleverage as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+  
+        msg!("{} deleveraged at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Withdraws collateral from one tab, swaps it through the Lending Protocol's swap program, and repays another tab's debt with what comes out of the swap.
+    //The remaining accounts are the temp price account and the Oracle, followed by the swap program's accounts. Only the signer signs the swap. Transfer hook mints aren't supported here
+    pub fn deleverage_with_swap<'info>(ctx: Context<'info, DeleverageWithSwap<'info>>,
+        collateral_sub_market_index: u16,
+        debt_sub_market_index: u16,
+        _user_account_index: u8,
+        collateral_amount: u64,
+        min_repayment_amount: u64,
+        swap_instruction_data: Vec<u8>
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let swap_program_id = ctx.accounts.lending_protocol.swap_program_id;
+        let price_validator = &ctx.accounts.price_validator;
+        let collateral_token_reserve = &mut ctx.accounts.collateral_token_reserve;
+        let debt_token_reserve = &mut ctx.accounts.debt_token_reserve;
+        let collateral_sub_market = &mut ctx.accounts.collateral_sub_market.load_mut()?;
+        let debt_sub_market = &mut ctx.accounts.debt_sub_market.load_mut()?;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let collateral_tab_account = &mut ctx.accounts.collateral_tab_account.load_mut()?;
+        let debt_tab_account = &mut ctx.accounts.debt_tab_account.load_mut()?;
+        let mut collateral_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let collateral_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.collateral_monthly_statement_account, &mut collateral_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let mut debt_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let debt_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.debt_monthly_statement_account, &mut debt_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        //Same token deleverages don't need a swap and go through deleverage instead
+        require_keys_neq!(ctx.accounts.collateral_mint.key(), ctx.accounts.debt_mint.key(), LendingError::SameTokenDeleverageSwap);
+        require_keys_neq!(swap_program_id, Pubkey::default(), LendingError::InvalidSwapProgram);
+
+        let collateral_sub_market_owner_address = ctx.accounts.collateral_sub_market_owner.key();
+        let debt_sub_market_owner_address = ctx.accounts.debt_sub_market_owner.key();
+
+        //You can't withdraw more funds than you've deposited
+        require!(collateral_tab_account.deposited_amount >= collateral_amount, LendingError::InsufficientFunds);
+
+        //You can't withdraw more funds than are currently available in the Token Reserve
+        let collateral_token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.collateral_token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(collateral_token_reserve_ata_data.amount >= collateral_amount, LendingError::InsufficientLiquidity);
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let collateral_normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(collateral_sub_market), collateral_token_reserve.usd_quote_price_id)?;
+        let debt_normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(debt_sub_market), debt_token_reserve.usd_quote_price_id)?;
+        let collateral_token_conversion_number = BASE_10_INT.pow(collateral_token_reserve.token_decimal_amount as u32); 
+        let debt_token_conversion_number = BASE_10_INT.pow(debt_token_reserve.token_decimal_amount as u32); 
+
+        //The swap could send the collateral anywhere, so high value deleverages need the co-signer's approval the same as a withdrawal
+        let collateral_usd_value = (collateral_amount as u128 * collateral_normalized_price_18_decimals) / collateral_token_conversion_number;
+        check_co_signer(lending_user_account, &ctx.accounts.co_signer, collateral_usd_value)?;
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        //Withdraw the collateral to the user for the swap to spend
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.collateral_mint.key(),
+            collateral_token_reserve,
+            &ctx.accounts.collateral_token_reserve_ata.to_account_info(),
+            &ctx.accounts.collateral_user_ata.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            collateral_amount,
+            None,
+            &[]
+        )?;
+
+        //Only what the swap actually paid out to the user counts toward the repayment
+        let debt_user_ata_amount_before_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.debt_user_ata.to_account_info().data.borrow()[..])?.amount;
+        swap_through_swap_program(swap_program_id, ctx.remaining_accounts.get(2..).unwrap_or(&[]), swap_instruction_data)?;
+        let debt_user_ata_amount_after_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.debt_user_ata.to_account_info().data.borrow()[..])?.amount;
+        let swapped_amount = debt_user_ata_amount_after_swap.saturating_sub(debt_user_ata_amount_before_swap);
+
+        require!(swapped_amount >= min_repayment_amount, LendingError::SwapSlippageExceeded);
+
+        //Anything the swap paid out past the tab's debt stays in the user's ATA
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let repayment_amount = std::cmp::min(swapped_amount, debt_tab_account.borrowed_amount);
+        let repayment_amount = deposit_swapped_tokens_into_token_reserve(
+            &ctx.accounts.debt_token_reserve_ata.to_account_info(),
+            &ctx.accounts.debt_user_ata.to_account_info(),
+            &ctx.accounts.debt_mint,
+            &ctx.accounts.debt_token_program,
+            &ctx.accounts.signer,
+            repayment_amount
+        )?;
+
+        //The deleverage can't leave the Lending User Account with a higher loan to value than it started with. The new loan to value rounds up so it can't slip past by rounding
+        let repayment_usd_value = (repayment_amount as u128 * debt_normalized_price_18_decimals) / debt_token_conversion_number;
+        let new_total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(collateral_token_reserve, collateral_usd_value));
+        let new_total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value.saturating_sub(repayment_usd_value);
+
+        if new_total_borrowed_usd_value > 0
+        {
+            require!(new_total_deposited_usd_value > 0, LendingError::DeleverageIncreasedLoanToValue);
+
+            let loan_to_value_rate = (lending_user_account.total_borrowed_usd_value * DECIMAL_SCALING) / lending_user_account.total_deposited_usd_value;
+            let new_loan_to_value_rate = (new_total_borrowed_usd_value * DECIMAL_SCALING).div_ceil(new_total_deposited_usd_value);
+            require!(new_loan_to_value_rate <= loan_to_value_rate, LendingError::DeleverageIncreasedLoanToValue);
+        }
+
+        lending_user_account.total_deposited_usd_value = new_total_deposited_usd_value;
+        lending_user_account.total_borrowed_usd_value = new_total_borrowed_usd_value;
+        invalidate_cached_user_health(lending_user_account);
+
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        lending_stats.repayments += 1;
+        collateral_sub_market.deposited_amount -= collateral_amount as u128;
+        collateral_token_reserve.deposited_amount -= collateral_amount as u128;
+        collateral_tab_account.deposited_amount -= collateral_amount;
+        collateral_monthly_statement_account.monthly_withdrawal_amount += collateral_amount;
+        collateral_monthly_statement_account.snap_shot_balance_amount = collateral_tab_account.deposited_amount;
+        debt_sub_market.borrowed_amount -= repayment_amount as u128;
+        debt_sub_market.repaid_debt_amount += repayment_amount as u128;
+        debt_token_reserve.borrowed_amount -= repayment_amount as u128;
+        debt_token_reserve.repaid_debt_amount += repayment_amount as u128;
+        debt_tab_account.borrowed_amount -= repayment_amount;
+        debt_tab_account.repaid_debt_amount += repayment_amount;
+        debt_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        debt_monthly_statement_account.snap_shot_debt_amount = debt_tab_account.borrowed_amount;
+
+        sync_token_reserve_active_user_counts(collateral_token_reserve, collateral_tab_account);
+        sync_token_reserve_active_user_counts(debt_token_reserve, debt_tab_account);
+
+        //Update both Token Reserves' Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(collateral_token_reserve)?;
+        collateral_sub_market.supply_interest_change_index = collateral_token_reserve.supply_interest_change_index;
+        collateral_sub_market.borrow_interest_change_index = collateral_token_reserve.borrow_interest_change_index;
+        collateral_tab_account.supply_interest_change_index = collateral_token_reserve.supply_interest_change_index;
+        collateral_tab_account.borrow_interest_change_index = collateral_token_reserve.borrow_interest_change_index;
+
+        update_token_reserve_rates(debt_token_reserve)?;
+        debt_sub_market.supply_interest_change_index = debt_token_reserve.supply_interest_change_index;
+        debt_sub_market.borrow_interest_change_index = debt_token_reserve.borrow_interest_change_index;
+        debt_tab_account.supply_interest_change_index = debt_token_reserve.supply_interest_change_index;
+        debt_tab_account.borrow_interest_change_index = debt_token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        collateral_token_reserve.last_lending_activity_amount = collateral_amount;
+        collateral_token_reserve.last_lending_activity_type = Activity::Deleverage as u8;
+        collateral_sub_market.last_lending_activity_amount = collateral_amount;
+        collateral_sub_market.last_lending_activity_type = Activity::Deleverage as u8;
+        collateral_sub_market.last_lending_activity_time_stamp = collateral_token_reserve.last_lending_activity_time_stamp;
+        collateral_monthly_statement_account.last_lending_activity_amount = collateral_amount;
+        collateral_monthly_statement_account.last_lending_activity_type = Activity::Deleverage as u8;
+        collateral_monthly_statement_account.last_lending_activity_time_stamp = collateral_token_reserve.last_lending_activity_time_stamp;
+        debt_token_reserve.last_lending_activity_amount = repayment_amount;
+        debt_token_reserve.last_lending_activity_type = Activity::Deleverage as u8;
+        debt_sub_market.last_lending_activity_amount = repayment_amount;
+        debt_sub_market.last_lending_activity_type = Activity::Deleverage as u8;
+        debt_sub_market.last_lending_activity_time_stamp = debt_token_reserve.last_lending_activity_time_stamp;
+        debt_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        debt_monthly_statement_account.last_lending_activity_type = Activity::Deleverage as u8;
+        debt_monthly_statement_account.last_lending_activity_time_stamp = debt_token_reserve.last_lending_activity_time_stamp;
+
+        msg!("{} deleveraged with a swap from Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        collateral_token_reserve.token_id,
+        collateral_sub_market_owner_address.key(),
+        collateral_sub_market_index);
+        verbose_msg!("To Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        debt_token_reserve.token_id,
+        debt_sub_market_owner_address.key(),
+        debt_sub_market_index);
+
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Withdraws collateral from one tab, swaps it through the Lending Protocol's swap program, and deposits what comes out of the swap into another tab, so the tokens really move between Token Reserves.
+    //The remaining accounts are the temp price account and the Oracle, followed by the swap program's accounts. Only the signer signs the swap. Transfer hook mints aren't supported here
+    pub fn swap_collateral<'info>(ctx: Context<'info, SwapCollateral<'info>>,
+        source_sub_market_index: u16,
+        destination_sub_market_index: u16,
+        _user_account_index: u8,
+        source_amount: u64,
+        min_destination_amount: u64,
+        swap_instruction_data: Vec<u8>
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let swap_program_id = ctx.accounts.lending_protocol.swap_program_id;
+        let price_validator = &ctx.accounts.price_validator;
+        let source_token_reserve = &mut ctx.accounts.source_token_reserve;
+        let destination_token_reserve = &mut ctx.accounts.destination_token_reserve;
+        let source_sub_market = &mut ctx.accounts.source_sub_market.load_mut()?;
+        let destination_sub_market = &mut ctx.accounts.destination_sub_market.load_mut()?;
+        check_sub_market_allowlist(destination_sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let source_tab_account = &mut ctx.accounts.source_tab_account.load_mut()?;
+        let destination_tab_account = &mut ctx.accounts.destination_tab_account.load_mut()?;
+        let mut source_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let source_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.source_monthly_statement_account, &mut source_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let mut destination_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let destination_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.destination_monthly_statement_account, &mut destination_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        //Same token moves don't need a swap and go through migrate_sub_market instead
+        require_keys_neq!(ctx.accounts.source_mint.key(), ctx.accounts.destination_mint.key(), LendingError::SameTokenCollateralSwap);
+
+        //Lending Users in E-Mode can only swap into collateral in their E-Mode asset category
+        check_e_mode_asset_category(lending_user_account, destination_token_reserve)?;
+        require_keys_neq!(swap_program_id, Pubkey::default(), LendingError::InvalidSwapProgram);
+
+        let source_sub_market_owner_address = ctx.accounts.source_sub_market_owner.key();
+        let destination_sub_market_owner_address = ctx.accounts.destination_sub_market_owner.key();
+
+        //You can't withdraw more funds than you've deposited
+        require!(source_tab_account.deposited_amount >= source_amount, LendingError::InsufficientFunds);
+
+        //You can't withdraw more funds than are currently available in the Token Reserve
+        let source_token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.source_token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(source_token_reserve_ata_data.amount >= source_amount, LendingError::InsufficientLiquidity);
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let source_normalized_price_18_decimals = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(source_sub_market), source_token_reserve.usd_quote_price_id)?;
+        let destination_normalized_price_18_decimals = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(destination_sub_market), destination_token_reserve.usd_quote_price_id)?;
+        let source_token_conversion_number = BASE_10_INT.pow(source_token_reserve.token_decimal_amount as u32); 
+        let destination_token_conversion_number = BASE_10_INT.pow(destination_token_reserve.token_decimal_amount as u32); 
+
+        //The swap could send the collateral anywhere, so high value collateral swaps need the co-signer's approval the same as a withdrawal
+        let source_usd_value = (source_amount as u128 * source_normalized_price_18_decimals) / source_token_conversion_number;
+        check_co_signer(lending_user_account, &ctx.accounts.co_signer, source_usd_value)?;
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        //Withdraw the collateral to the user for the swap to spend
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.source_mint.key(),
+            source_token_reserve,
+            &ctx.accounts.source_token_reserve_ata.to_account_info(),
+            &ctx.accounts.source_user_ata.to_account_info(),
+            &ctx.accounts.source_mint,
+            &ctx.accounts.source_token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            source_amount,
+            None,
+            &[]
+        )?;
+
+        //Only what the swap actually paid out to the user gets deposited
+        let destination_user_ata_amount_before_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.destination_user_ata.to_account_info().data.borrow()[..])?.amount;
+        swap_through_swap_program(swap_program_id, ctx.remaining_accounts.get(2..).unwrap_or(&[]), swap_instruction_data)?;
+        let destination_user_ata_amount_after_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.destination_user_ata.to_account_info().data.borrow()[..])?.amount;
+        let swapped_amount = destination_user_ata_amount_after_swap.saturating_sub(destination_user_ata_amount_before_swap);
+
+        require!(swapped_amount >= min_destination_amount, LendingError::SwapSlippageExceeded);
+
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let deposit_amount = deposit_swapped_tokens_into_token_reserve(
+            &ctx.accounts.destination_token_reserve_ata.to_account_info(),
+            &ctx.accounts.destination_user_ata.to_account_info(),
+            &ctx.accounts.destination_mint,
+            &ctx.accounts.destination_token_program,
+            &ctx.accounts.signer,
+            swapped_amount
+        )?;
+
+        //You can't deposit more than the global limit
+        require!(destination_token_reserve.deposited_amount + deposit_amount as u128 <= destination_token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+
+        //Users with debt can't swap into collateral that leaves their borrow liabilities above the max Loan To Value of deposited collateral
+        let deposit_usd_value = (deposit_amount as u128 * destination_normalized_price_18_decimals) / destination_token_conversion_number;
+        let new_total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(source_token_reserve, source_usd_value)) + get_collateral_usd_value(destination_token_reserve, deposit_usd_value);
+
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //Multiply before dividing to help keep precision
+            let max_borrowed_usd_value = (new_total_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
+            require!(max_borrowed_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
+        }
+
+        lending_user_account.total_deposited_usd_value = new_total_deposited_usd_value;
+        invalidate_cached_user_health(lending_user_account);
+
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        lending_stats.deposits += 1;
+        source_sub_market.deposited_amount -= source_amount as u128;
+        source_token_reserve.deposited_amount -= source_amount as u128;
+        source_tab_account.deposited_amount -= source_amount;
+        source_monthly_statement_account.monthly_withdrawal_amount += source_amount;
+        source_monthly_statement_account.snap_shot_balance_amount = source_tab_account.deposited_amount;
+        destination_sub_market.deposited_amount += deposit_amount as u128;
+        destination_token_reserve.deposited_amount += deposit_amount as u128;
+        destination_tab_account.deposited_amount += deposit_amount;
+        destination_monthly_statement_account.monthly_deposited_amount += deposit_amount;
+        destination_monthly_statement_account.snap_shot_balance_amount = destination_tab_account.deposited_amount;
+
+        sync_token_reserve_active_user_counts(source_token_reserve, source_tab_account);
+        sync_token_reserve_active_user_counts(destination_token_reserve, destination_tab_account);
+
+        //Update both Token Reserves' Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(source_token_reserve)?;
+        source_sub_market.supply_interest_change_index = source_token_reserve.supply_interest_change_index;
+        source_sub_market.borrow_interest_change_index = source_token_reserve.borrow_interest_change_index;
+        source_tab_account.supply_interest_change_index = source_token_reserve.supply_interest_change_index;
+        source_tab_account.borrow_interest_change_index = source_token_reserve.borrow_interest_change_index;
+
+        update_token_reserve_rates(destination_token_reserve)?;
+        destination_sub_market.supply_interest_change_index = destination_token_reserve.supply_interest_change_index;
+        destination_sub_market.borrow_interest_change_index = destination_token_reserve.borrow_interest_change_index;
+        destination_tab_account.supply_interest_change_index = destination_token_reserve.supply_interest_change_index;
+        destination_tab_account.borrow_interest_change_index = destination_token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        source_token_reserve.last_lending_activity_amount = source_amount;
+        source_token_reserve.last_lending_activity_type = Activity::CollateralSwap as u8;
+        source_sub_market.last_lending_activity_amount = source_amount;
+        source_sub_market.last_lending_activity_type = Activity::CollateralSwap as u8;
+        source_sub_market.last_lending_activity_time_stamp = source_token_reserve.last_lending_activity_time_stamp;
+        source_monthly_statement_account.last_lending_activity_amount = source_amount;
+        source_monthly_statement_account.last_lending_activity_type = Activity::CollateralSwap as u8;
+        source_monthly_statement_account.last_lending_activity_time_stamp = source_token_reserve.last_lending_activity_time_stamp;
+        destination_token_reserve.last_lending_activity_amount = deposit_amount;
+        destination_token_reserve.last_lending_activity_type = Activity::CollateralSwap as u8;
+        destination_sub_market.last_lending_activity_amount = deposit_amount;
+        destination_sub_market.last_lending_activity_type = Activity::CollateralSwap as u8;
+        destination_sub_market.last_lending_activity_time_stamp = destination_token_reserve.last_lending_activity_time_stamp;
+        destination_monthly_statement_account.last_lending_activity_amount = deposit_amount;
+        destination_monthly_statement_account.last_lending_activity_type = Activity::CollateralSwap as u8;
+        destination_monthly_statement_account.last_lending_activity_time_stamp = destination_token_reserve.last_lending_activity_time_stamp;
+
+        msg!("{} swapped collateral from Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        source_token_reserve.token_id,
+        source_sub_market_owner_address.key(),
+        source_sub_market_index);
+        verbose_msg!("To Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        destination_token_reserve.token_id,
+        destination_sub_market_owner_address.key(),
+        destination_sub_market_index);
+        verbose_msg!("Swapped Amount: {}, Deposited Amount: {}", source_amount, deposit_amount);
+
+        Ok(())
+    }
+
+    //Lends Token Reserve liquidity for the length of one instruction. The loan goes to the receiver token account, then the receiver program's on_flash_loan instruction is called
+    //and has to pay the loan plus the flash loan fee back to the Token Reserve ATA before it returns. Solana doesn't let the receiver call back into the Lending Protocol,
+    //so the loan can't be paid back with the Token Reserve's own deposits. The remaining accounts are passed on to the receiver after its fixed accounts. Transfer hook mints aren't supported here
+    pub fn flash_loan<'info>(ctx: Context<'info, FlashLoan<'info>>, amount: u64, receiver_data: Vec<u8>) -> Result<()> 
+    {
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let receiver_program_address = ctx.accounts.receiver_program.key();
+
+        //Flash loans are borrows, so they stop with the rest of borrowing
+        require!(!ctx.accounts.lending_protocol.borrows_paused, LendingError::BorrowsPaused);
+        require!(!token_reserve.listing_vetoed, LendingError::TokenReserveListingVetoed);
+        require!(ctx.accounts.receiver_program.executable && receiver_program_address != *ctx.program_id, LendingError::InvalidFlashLoanReceiver);
+
+        //You can't withdraw or borrow more funds than are currently available in the Token Reserve
+        let token_reserve_ata_amount_before_loan = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?.amount;
+        require!(token_reserve_ata_amount_before_loan >= amount, LendingError::InsufficientLiquidity);
+
+        //Round the fee up so small loans aren't free
+        let fee_amount = (amount as u128 * FLASH_LOAN_FEE_RATE as u128).div_ceil(10_000) as u64;
+
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.token_mint.key(),
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.receiver_token_account.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            amount,
+            None,
+            &[]
+        )?;
+
+        invoke_flash_loan_receiver(&ctx.accounts.receiver_program.to_account_info(),
+            &ctx.accounts.receiver_token_account.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.signer.to_account_info(),
+            ctx.remaining_accounts,
+            Structs::FlashLoanReceiverPayload
+            {
+                amount,
+                fee_amount,
+                receiver_data
+            }
+        )?;
+
+        //Only what the Token Reserve ATA actually got back counts
+        let token_reserve_ata_amount_after_loan = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?.amount;
+        require!(token_reserve_ata_amount_after_loan >= token_reserve_ata_amount_before_loan + fee_amount, LendingError::FlashLoanNotRepaid);
+
+        //Anything paid back past the loan is set aside as solvency insurance fees, so the Token Reserve ATA still reconciles
+        let collected_fee_amount = token_reserve_ata_amount_after_loan - token_reserve_ata_amount_before_loan;
+        token_reserve.uncollected_solvency_insurance_fees_amount += collected_fee_amount as u128;
+
+        emit!(FlashLoanRepaid
+        {
+            token_mint_address: token_reserve.token_mint_address,
+            receiver_program_id: receiver_program_address,
+            signer: ctx.accounts.signer.key(),
+            amount,
+            fee_amount: collected_fee_amount
+        });
+
+        msg!("{} flash loaned Token Mint: {} to Receiver Program: {}", ctx.accounts.signer.key(), token_reserve.token_mint_address, receiver_program_address);
+        verbose_msg!("Amount: {}, Fee Amount: {}", amount, collected_fee_amount);
+
+        Ok(())
+    }
+
+    pub fn liquidate_account<'info>(ctx: Context<'info, LiquidateAccount<'info>>,
+        repayment_sub_market_index: u16,
+        liquidation_sub_market_index: u16,
+        liquidati_account_index: u8,
+        liquidator_account_index: u8,
+        amount_to_repay: u64,
+        repay_max: bool,
+        paying_off_insolvent_account: bool,
+        send_reward_to_wallet: bool,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey>, //Needed when a user initializes their Lending User Account
+        liquidation_swap: Option<Structs::LiquidationSwap> //Optional variable. Swaps the reward into the repayment token so liquidators don't need an inventory of every collateral token
+    ) -> Result<()>
+    {
+        //The swap spends the reward out of the liquidator's wallet, so the reward has to be sent there
+        require!(liquidation_swap.is_none() || send_reward_to_wallet, LendingError::LiquidationSwapNeedsRewardToWallet);
+
+        let liquidator_monthly_statements_disabled = ctx.accounts.liquidator_lending_account.monthly_statements_disabled;
+
+        //While the Token Reserve is in its guarded launch period only allowlisted liquidators can liquidate
+        check_liquidator_allowlist(&ctx.accounts.repayment_token_reserve, &ctx.accounts.liquidator_allowlist_entry)?;
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let repayment_sub_market_owner_address = ctx.accounts.repayment_sub_market_owner.key();
+        let liquidation_sub_market_owner_address = ctx.accounts.liquidation_sub_market_owner.key();
+        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+        let clock_slot = Clock::get()?.slot;
+
+        /////////////////////////////////
+        ////Validate Liquidati Lending User Account Account
+        let liquidati_lending_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_lending_account = validate_and_return_lending_user_account(*ctx.program_id,
+            liquidati_lending_account_serialized,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        #[cfg(feature = "local")] 
+        require!(clock_slot.saturating_sub(liquidati_lending_account.last_health_update_clock_slot) <= 1, LendingError::StaleTokenReserveOrLendingUser);
+        #[cfg(feature = "dev")]
+        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let repayment_token_reserve = &mut ctx.accounts.repayment_token_reserve;
+        let liquidation_token_reserve = &mut ctx.accounts.liquidation_token_reserve;
+        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
+        let liquidator_repayment_tab_account = &mut load_mut_or_init(&ctx.accounts.liquidator_repayment_tab_account)?;
+        let liquidator_liquidation_tab_account = &mut load_mut_or_init(&ctx.accounts.liquidator_liquidation_tab_account)?;
+        let mut liquidator_repayment_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidator_repayment_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidator_repayment_monthly_statement_account, &mut liquidator_repayment_monthly_statement_account_placeholder, liquidator_monthly_statements_disabled)?;
+        let mut liquidator_liquidation_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidator_liquidation_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidator_liquidation_monthly_statement_account, &mut liquidator_liquidation_monthly_statement_account_placeholder, liquidator_monthly_statements_disabled)?;
+
+        //Validate remaining accounts
+        let repayment_token_reserve_ata_info = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let liquidation_token_reserve_ata_info = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+
+        //Repayment Token Reserve ATA
+        validate_token_reserve_ata(
+            repayment_token_reserve_ata_info,
+            ctx.accounts.repayment_mint.key(),
+            repayment_token_reserve.key()
+        )?;
+
+        //Token Reserve Liquidation ATA
+        validate_token_reserve_ata(
+            liquidation_token_reserve_ata_info,
+            ctx.accounts.liquidation_mint.key(),
+            liquidation_token_reserve.key()
+        )?;
+
+        ////////////////////////
+        //Oracle Price Validator
+        let price_validator_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let price_validator = validate_and_return_price_validator_account(*ctx.program_id, price_validator_serialized)?;
+
+        ///////////////////
+        //Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        ///////////////
+        //Lending Stats
+        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+
+        /////////////////////////////
+        //Repayment SubMarket Account
+        let repayment_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut repayment_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+            repayment_sub_market_account_serialized,
+            repayment_token_reserve.token_id,
+            repayment_sub_market_owner_address,
+            repayment_sub_market_index)?;
+
+        ///////////////////////////////
+        //Liquidation SubMarket Account
+        let liquidation_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidation_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+            liquidation_sub_market_account_serialized,
+            liquidation_token_reserve.token_id,
+            liquidation_sub_market_owner_address,
+            liquidation_sub_market_index)?;
+
+        /////////////////////////////////
+        //Liquidati Repayment Tab Account
+        let liquidati_repayment_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_repayment_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+            liquidati_repayment_tab_account_serialized,
+            repayment_token_reserve.token_id,
+            repayment_sub_market_owner_address,
+            repayment_sub_market_index,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        ///////////////////////////////////
+        //Liquidati Liquidation Tab Account
+        let liquidati_liquidation_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_liquidation_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+            liquidati_liquidation_tab_account_serialized,
+            liquidation_token_reserve.token_id,
+            liquidation_sub_market_owner_address,
+            liquidation_sub_market_index,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        ///////////////////////////////////////////////
+        //Liquidati Repayment Monthly Statement Account
+        //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+        let liquidati_repayment_monthly_statement_account_serialized;
+        let mut liquidati_repayment_monthly_statement_account;
+        if liquidati_lending_account.monthly_statements_disabled
+        {
+            liquidati_repayment_monthly_statement_account_serialized = None;
+            liquidati_repayment_monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+        }
+        else
+        {
+            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            liquidati_repayment_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                monthly_statement_account_serialized,
+                lending_protocol.current_statement_month,
+                lending_protocol.current_statement_year,
+                repayment_token_reserve.token_id,
+                repayment_sub_market_owner_address,
+                repayment_sub_market_index,
+                liquidati_account_owner_address,
+                liquidati_account_index)?;
+            liquidati_repayment_monthly_statement_account_serialized = Some(monthly_statement_account_serialized);
+        }
+
+        ///////////////////////////////////////////////
+        //Liquidati Liquidation Monthly Statement Account
+        //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+        let liquidati_liquidation_monthly_statement_account_serialized;
+        let mut liquidati_liquidation_monthly_statement_account;
+        if liquidati_lending_account.monthly_statements_disabled
+        {
+            liquidati_liquidation_monthly_statement_account_serialized = None;
+            liquidati_liquidation_monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+        }
+        else
+        {
+            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            liquidati_liquidation_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                monthly_statement_account_serialized,
+                lending_protocol.current_statement_month,
+                lending_protocol.current_statement_year,
+                liquidation_token_reserve.token_id,
+                liquidation_sub_market_owner_address,
+                liquidation_sub_market_index,
+                liquidati_account_owner_address,
+                liquidati_account_index)?;
+            liquidati_liquidation_monthly_statement_account_serialized = Some(monthly_statement_account_serialized);
+        }
+
+        let repayment_amount;
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //Get USD value of Repayment Amount
+        let repayment_token_conversion_number = BASE_10_INT.pow(repayment_token_reserve.token_decimal_amount as u32); 
+        let repayment_token_usd_value = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(&repayment_sub_market), repayment_token_reserve.usd_quote_price_id)?;
+        let mut repayment_amount_usd_value = 0;
+
+        //Check if Account is liquidatable and set repayment_amount
+        if paying_off_insolvent_account
+        {
+            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+
+            if repay_max
+            {
+                repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
+                repayment_amount_usd_value = (repayment_amount as u128 * repayment_token_usd_value) / repayment_token_conversion_number;
+                
+                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
+                //Use saturating_sub to safely deduct the value
+                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
+                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
+                    .total_borrowed_usd_value
+                    .saturating_sub(repayment_amount_usd_value);
+            }
+            else
+            {
+                if amount_to_repay > liquidati_repayment_tab_account.borrowed_amount
+                {
+                    //Can't pay more debt than the user has accumulated
+                    repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
+                }
+                else
+                {
+                    repayment_amount = amount_to_repay;
+                }  
+            }
+        }
+        else
+        {
+            //Multiply before dividing to help keep precision
+            let liquidati_liquidation_threshold_usd_value = (liquidati_lending_account.total_deposited_usd_value * get_liquidation_threshold_rate(&liquidati_lending_account)) / DECIMAL_SCALING;
+
+            //You can't liquidate an account whose borrow liabilities aren't at or above the liquidation threshold of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_liquidation_threshold_usd_value, LendingError::NotLiquidatable);
+
+            //Multiply before dividing to help keep precision
+            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 50) / 100;
+
+            if repay_max
+            {
+                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
+            }
+            else
+            {
+                repayment_amount = amount_to_repay;
+            }
+
+            //You can't repay more than 50% of a liquidati's debt position
+            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
+        }
+
+        if repayment_amount_usd_value == 0
+        {
+            repayment_amount_usd_value = (repayment_amount as u128 * repayment_token_usd_value) / repayment_token_conversion_number;
+        }
+
+        //Multiply before dividing to help keep precision
+        let ten_percent_of_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 10) / 100;
+
+        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
+        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
+        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if liquidator_lending_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                liquidator_lending_account,
+                ctx.bumps.liquidator_lending_account,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if liquidator_repayment_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                liquidator_lending_account,
+                liquidator_repayment_tab_account,
+                ctx.accounts.liquidator_repayment_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.liquidator_repayment_tab_account,
+                repayment_token_reserve.token_id,
+                repayment_sub_market_owner_address.key(),
+                repayment_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index
+            )?;
+        }
+        if liquidator_liquidation_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                liquidator_lending_account,
+                liquidator_liquidation_tab_account,
+                ctx.accounts.liquidator_liquidation_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.liquidator_liquidation_tab_account,
+                liquidation_token_reserve.token_id,
+                liquidation_sub_market_owner_address.key(),
+                liquidation_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
+        if liquidator_repayment_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                liquidator_repayment_monthly_statement_account,
+                liquidator_repayment_tab_account,
+                lending_protocol,
+                ctx.bumps.liquidator_repayment_monthly_statement_account.unwrap_or_default(),
+                repayment_token_reserve.token_id,
+                repayment_sub_market_owner_address,
+                repayment_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+            )?;
+        }
+        if liquidator_liquidation_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                liquidator_liquidation_monthly_statement_account,
+                liquidator_liquidation_tab_account,
+                lending_protocol,
+                ctx.bumps.liquidator_liquidation_monthly_statement_account.unwrap_or_default(),
+                liquidation_token_reserve.token_id,
+                liquidation_sub_market_owner_address,
+                liquidation_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+            )?;
+        }
+
+        //Update interest earned and accrued for the liquidator
+        update_user_previous_interest_earned(
+            repayment_token_reserve,
+            &mut repayment_sub_market,
+            liquidator_repayment_tab_account,
+            liquidator_repayment_monthly_statement_account
+        )?;
+        update_user_previous_interest_accrued(
+            repayment_token_reserve,
+            &mut repayment_sub_market,
+            liquidator_repayment_tab_account,
+            liquidator_repayment_monthly_statement_account
+        )?;
+        update_user_previous_interest_earned(
+            liquidation_token_reserve,
+            &mut liquidation_sub_market,
+            liquidator_liquidation_tab_account,
+            liquidator_liquidation_monthly_statement_account
+        )?;
+        update_user_previous_interest_accrued(
+            liquidation_token_reserve,
+            &mut liquidation_sub_market,
+            liquidator_liquidation_tab_account,
+            liquidator_liquidation_monthly_statement_account
+        )?;
+
+        //Repay Liquidati's Debt
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        //The swap pays out to the liquidator's repayment ATA, so it's kept open when swapping
+        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_repayment_ata.to_account_info().data.borrow()[..])?;
+        let should_close = user_ata_data.amount == 0 && liquidation_swap.is_none();
+        let repayment_amount = deposit_tokens_into_token_reserve_from_user(
+            ctx.accounts.repayment_mint.key(),
+            &repayment_token_reserve_ata_info,
+            &ctx.accounts.liquidator_repayment_ata.to_account_info(),
+            &ctx.accounts.repayment_mint,
+            &ctx.accounts.repayment_token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            repayment_amount,
+            should_close,
+            &[]
+        )?;
+
+        //The seized collateral is sized off of what the Token Reserve actually received
+        repayment_amount_usd_value = (repayment_amount as u128 * repayment_token_usd_value) / repayment_token_conversion_number;
+
+        //Get USD value of Liquidation Token
+        let liquidation_token_conversion_number = BASE_10_INT.pow(liquidation_token_reserve.token_decimal_amount as u32); 
+        let liquidation_token_usd_value = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(&liquidation_sub_market), liquidation_token_reserve.usd_quote_price_id)?;
+
+        let amount_to_be_liquidated = ((repayment_amount_usd_value * liquidation_token_conversion_number) / liquidation_token_usd_value) as u64;
+
+        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
+        //Multiply before dividing to help keep precision
+        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+
+        //Take a 1% liquidation fee
+        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+
+        //Check for underflow if liquidation isn't profitable
+        if liquidati_liquidation_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
+        {
+            //Take a 1% liquidation fee
+            liquidation_fee_amount = liquidati_liquidation_tab_account.deposited_amount / 100;
+            //Give remainder to liquidator
+            liquidation_amount_with_7_percent_bonus = liquidati_liquidation_tab_account.deposited_amount - liquidation_fee_amount;
+        }
+
+        //Update Repayment Values
+        repayment_sub_market.borrowed_amount -= repayment_amount as u128;
+        repayment_sub_market.repaid_debt_amount += repayment_amount as u128;
+        repayment_token_reserve.borrowed_amount -= repayment_amount as u128;
+        repayment_token_reserve.repaid_debt_amount += repayment_amount as u128;
+        liquidati_repayment_tab_account.borrowed_amount -= repayment_amount;
+        liquidator_repayment_tab_account.repaid_debt_amount += repayment_amount;
+        liquidati_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
+        liquidator_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        liquidati_repayment_monthly_statement_account.snap_shot_debt_amount = liquidati_repayment_tab_account.borrowed_amount;
+
+        //Update Liquidation and Fee Values
+        liquidation_sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        liquidation_sub_market.liquidated_amount += liquidation_fee_amount as u128;
+        liquidation_sub_market.deposited_amount -= liquidation_fee_amount as u128;
+        liquidation_sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
+        liquidation_token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        liquidation_token_reserve.liquidated_amount += liquidation_fee_amount as u128;
+        liquidation_token_reserve.deposited_amount -= liquidation_fee_amount as u128;
+        liquidation_token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
+        liquidati_liquidation_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_tab_account.deposited_amount -= liquidation_fee_amount;
+        liquidati_liquidation_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_tab_account.liquidated_amount += liquidation_fee_amount;
+        liquidator_liquidation_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_liquidation_tab_account.fees_generated_amount += liquidation_fee_amount;
+        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
+        liquidati_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidati_liquidation_tab_account.deposited_amount;
+        liquidator_liquidation_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_liquidation_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+
+        if send_reward_to_wallet
+        {
+            withdraw_tokens_from_token_reserve_to_user(
+                ctx.accounts.liquidation_mint.key(),
+                liquidation_token_reserve,
+                &liquidation_token_reserve_ata_info.clone(),
+                &ctx.accounts.liquidator_liquidation_ata.to_account_info(),
+                &ctx.accounts.liquidation_mint,
+                &ctx.accounts.liquidation_token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                liquidation_amount_with_7_percent_bonus,
+                None,
+                &[]
+            )?;
+
+            liquidation_sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
+            liquidation_token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
+        }
+        else
+        {
+            liquidator_liquidation_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
+            liquidator_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidator_liquidation_tab_account.deposited_amount;
+        }
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        //Swap the reward into the repayment token. The swap program's accounts come after the Oracle account in the remaining accounts and only the signer signs the swap
+        if let Some(liquidation_swap) = liquidation_swap
+        {
+            let swap_program_id = lending_protocol.swap_program_id;
+            require_keys_neq!(swap_program_id, Pubkey::default(), LendingError::InvalidSwapProgram);
+
+            //Only what the swap actually paid out to the liquidator counts toward the slippage guard
+            let repayment_ata_amount_before_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_repayment_ata.to_account_info().data.borrow()[..])?.amount;
+            swap_through_swap_program(swap_program_id, remaining_accounts_iter.as_slice(), liquidation_swap.swap_instruction_data)?;
+            let repayment_ata_amount_after_swap = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_repayment_ata.to_account_info().data.borrow()[..])?.amount;
+            let swapped_amount = repayment_ata_amount_after_swap.saturating_sub(repayment_ata_amount_before_swap);
+
+            require!(swapped_amount >= liquidation_swap.min_repayment_token_amount, LendingError::SwapSlippageExceeded);
+
+            verbose_msg!("Swapped Liquidation Reward: {} into Repayment Token Amount: {}", liquidation_amount_with_7_percent_bonus, swapped_amount);
+        }
+        
+        //Update Stat Listener
+        lending_stats.liquidations += 1;
+
+        invalidate_cached_user_health(&mut liquidati_lending_account);
+        
+        sync_token_reserve_active_user_counts(repayment_token_reserve, &mut liquidati_repayment_tab_account);
+        sync_token_reserve_active_user_counts(liquidation_token_reserve, &mut liquidati_liquidation_tab_account);
+        sync_token_reserve_active_user_counts(liquidation_token_reserve, liquidator_liquidation_tab_account);
+
+        //Update Repayment Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(repayment_token_reserve)?;
+        repayment_sub_market.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
+        repayment_sub_market.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
+        liquidati_repayment_tab_account.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
+        liquidati_repayment_tab_account.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
+        liquidator_repayment_tab_account.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
+        liquidator_repayment_tab_account.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
+
+        //Update Liquidation Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(liquidation_token_reserve)?;
+        liquidation_sub_market.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
+        liquidation_sub_market.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
+        liquidati_liquidation_tab_account.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
+        liquidati_liquidation_tab_account.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
+        liquidator_liquidation_tab_account.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
+        liquidator_liquidation_tab_account.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
+        repayment_token_reserve.last_lending_activity_amount = repayment_amount;
+        repayment_token_reserve.last_lending_activity_type = Activity::Repay as u8;
+        liquidation_token_reserve.last_lending_activity_amount = liquidation_amount;
+        liquidation_token_reserve.last_lending_activity_type = Activity::Liquidate as u8;
+        repayment_sub_market.last_lending_activity_amount = repayment_amount;
+        repayment_sub_market.last_lending_activity_type = Activity::Repay as u8;
+        repayment_sub_market.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
+        liquidation_sub_market.last_lending_activity_amount = liquidation_amount;
+        liquidation_sub_market.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidation_sub_market.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
+        
+        //Save changes to passed in remaining accounts
+        liquidati_lending_account.serialize(&mut &mut liquidati_lending_account_serialized.data.borrow_mut()[8..])?;
+        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
+        if let Some(liquidati_repayment_monthly_statement_account_serialized) = liquidati_repayment_monthly_statement_account_serialized
+        {
+            liquidati_repayment_monthly_statement_account.serialize(&mut &mut liquidati_repayment_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+        }
+        if let Some(liquidati_liquidation_monthly_statement_account_serialized) = liquidati_liquidation_monthly_statement_account_serialized
+        {
+            liquidati_liquidation_monthly_statement_account.serialize(&mut &mut liquidati_liquidation_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+        }
+        
+        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+
+        verbose_msg!("Repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        repayment_token_reserve.token_id,
+        repayment_sub_market_owner_address.key(),
+        repayment_sub_market_index);
+
+        verbose_msg!("Liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        liquidation_token_reserve.token_id,
+        liquidation_sub_market_owner_address.key(),
+        liquidation_sub_market_index);
+
+        Ok(())
+    }
+
+    //This liquidation is for when the repayment and liquidation tokens are the same
+    pub fn liquidate_account_same_token(ctx: Context<LiquidateAccountSameToken>,
+        repayment_sub_market_index: u16,
+        liquidation_sub_market_index: u16,
+        liquidati_account_index: u8,
+        liquidator_account_index: u8,
+        amount_to_repay: u64,
+        repay_max: bool,
+        paying_off_insolvent_account: bool,
+        send_reward_to_wallet: bool,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()>
+    {
+        let liquidator_monthly_statements_disabled = ctx.accounts.liquidator_lending_account.monthly_statements_disabled;
+
+        //While the Token Reserve is in its guarded launch period only allowlisted liquidators can liquidate
+        check_liquidator_allowlist(&ctx.accounts.token_reserve, &ctx.accounts.liquidator_allowlist_entry)?;
+
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let liquidati_lending_account = &mut ctx.accounts.liquidati_lending_account;
+        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
+        let liquidator_repayment_tab_account = &mut load_mut_or_init(&ctx.accounts.liquidator_repayment_tab_account)?;
+        let liquidator_liquidation_tab_account = &mut load_mut_or_init(&ctx.accounts.liquidator_liquidation_tab_account)?;
+        let mut liquidator_repayment_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidator_repayment_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidator_repayment_monthly_statement_account, &mut liquidator_repayment_monthly_statement_account_placeholder, liquidator_monthly_statements_disabled)?;
+        let mut liquidator_liquidation_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidator_liquidation_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidator_liquidation_monthly_statement_account, &mut liquidator_liquidation_monthly_statement_account_placeholder, liquidator_monthly_statements_disabled)?;
+        let clock_slot = Clock::get()?.slot;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        let repayment_sub_market_owner_address = ctx.accounts.repayment_sub_market_owner.key();
+        let liquidation_sub_market_owner_address = ctx.accounts.liquidation_sub_market_owner.key();
+        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        //Validate Accounts
+
+        ////////////////////////////
+        //Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        ///////////////
+        //Lending Stats
+        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+
+        /////////////////////////////
+        //Repayment SubMarket Account
+        let repayment_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut repayment_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+            repayment_sub_market_account_serialized,
+            token_reserve.token_id,
+            repayment_sub_market_owner_address,
+            repayment_sub_market_index)?;
+
+        ///////////////////////////////
+        //Liquidation SubMarket Account
+        let liquidation_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidation_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+            liquidation_sub_market_account_serialized,
+            token_reserve.token_id,
+            liquidation_sub_market_owner_address,
+            liquidation_sub_market_index)?;
+
+        /////////////////////////////////
+        //Liquidati Repayment Tab Account
+        let liquidati_repayment_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_repayment_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+            liquidati_repayment_tab_account_serialized,
+            token_reserve.token_id,
+            repayment_sub_market_owner_address,
+            repayment_sub_market_index,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        ///////////////////////////////////
+        //Liquidati Liquidation Tab Account
+        let liquidati_liquidation_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_liquidation_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+            liquidati_liquidation_tab_account_serialized,
+            token_reserve.token_id,
+            liquidation_sub_market_owner_address,
+            liquidation_sub_market_index,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        ///////////////////////////////////////////////
+        //Liquidati Repayment Monthly Statement Account
+        //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+        let liquidati_repayment_monthly_statement_account_serialized;
+        let mut liquidati_repayment_monthly_statement_account;
+        if liquidati_lending_account.monthly_statements_disabled
+        {
+            liquidati_repayment_monthly_statement_account_serialized = None;
+            liquidati_repayment_monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+        }
+        else
+        {
+            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            liquidati_repayment_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                monthly_statement_account_serialized,
+                lending_protocol.current_statement_month,
+                lending_protocol.current_statement_year,
+                token_reserve.token_id,
+                repayment_sub_market_owner_address,
+                repayment_sub_market_index,
+                liquidati_account_owner_address,
+                liquidati_account_index)?;
+            liquidati_repayment_monthly_statement_account_serialized = Some(monthly_statement_account_serialized);
+        }
+
+        ///////////////////////////////////////////////
+        //Liquidati Liquidation Monthly Statement Account
+        //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+        let liquidati_liquidation_monthly_statement_account_serialized;
+        let mut liquidati_liquidation_monthly_statement_account;
+        if liquidati_lending_account.monthly_statements_disabled
+        {
+            liquidati_liquidation_monthly_statement_account_serialized = None;
+            liquidati_liquidation_monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+        }
+        else
+        {
+            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            liquidati_liquidation_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                monthly_statement_account_serialized,
+                lending_protocol.current_statement_month,
+                lending_protocol.current_statement_year,
+                token_reserve.token_id,
+                liquidation_sub_market_owner_address,
+                liquidation_sub_market_index,
+                liquidati_account_owner_address,
+                liquidati_account_index)?;
+            liquidati_liquidation_monthly_statement_account_serialized = Some(monthly_statement_account_serialized);
+        }
+
+        let repayment_amount;
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //Get USD value of Repayment Amount
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+        let token_usd_value = temp_price_account.get_verified_token_usd_price(token_reserve.token_id, token_reserve.usd_quote_price_id)?;
+        let mut repayment_amount_usd_value = 0;
+
+        //Check if Account is liquidatable and set repayment_amount
+        if paying_off_insolvent_account
+        {
+            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+
+            if repay_max
+            {
+                repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
+                repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+                
+                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
+                //Use saturating_sub to safely deduct the value
+                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
+                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
+                    .total_borrowed_usd_value
+                    .saturating_sub(repayment_amount_usd_value);
+            }
+            else
+            {
+                if amount_to_repay > liquidati_repayment_tab_account.borrowed_amount
+                {
+                    //Can't pay more debt than the user has accumulated
+                    repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
+                }
+                else
+                {
+                    repayment_amount = amount_to_repay;
+                }  
+            }
+        }
+        else
+        {
+            //Multiply before dividing to help keep precision
+            let liquidati_liquidation_threshold_usd_value = (liquidati_lending_account.total_deposited_usd_value * get_liquidation_threshold_rate(liquidati_lending_account)) / DECIMAL_SCALING;
+
+            //You can't liquidate an account whose borrow liabilities aren't at or above the liquidation threshold of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_liquidation_threshold_usd_value, LendingError::NotLiquidatable);
+
+            //Multiply before dividing to help keep precision
+            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 50) / 100;
+
+            if repay_max
+            {
+                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
+            }
+            else
+            {
+                repayment_amount = amount_to_repay;
+            }
+
+            //You can't repay more than 50% of a liquidati's debt position
+            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
+        }
+
+        if repayment_amount_usd_value == 0
+        {
+            repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+        }
+
+        //Multiply before dividing to help keep precision
+        let ten_percent_of_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 10) / 100;
+
+        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
+        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
+        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if liquidator_lending_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                liquidator_lending_account,
+                ctx.bumps.liquidator_lending_account,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if liquidator_repayment_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                liquidator_lending_account,
+                liquidator_repayment_tab_account,
+                ctx.accounts.liquidator_repayment_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.liquidator_repayment_tab_account,
+                token_reserve.token_id,
+                repayment_sub_market_owner_address.key(),
+                repayment_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index
+            )?;
+        }
+        if liquidator_liquidation_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                liquidator_lending_account,
+                liquidator_liquidation_tab_account,
+                ctx.accounts.liquidator_liquidation_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.liquidator_liquidation_tab_account,
+                token_reserve.token_id,
+                liquidation_sub_market_owner_address.key(),
+                liquidation_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
+        if liquidator_repayment_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                liquidator_repayment_monthly_statement_account,
+                liquidator_repayment_tab_account,
+                lending_protocol,
+                ctx.bumps.liquidator_repayment_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                repayment_sub_market_owner_address,
+                repayment_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+            )?;
+        }
+        if liquidator_liquidation_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                liquidator_liquidation_monthly_statement_account,
+                liquidator_liquidation_tab_account,
+                lending_protocol,
+                ctx.bumps.liquidator_liquidation_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                liquidation_sub_market_owner_address,
+                liquidation_sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+            )?;
+        }
+
+        //Update interest earned and accrued for the liquidator
+        update_user_previous_interest_earned(
+            token_reserve,
+            &mut repayment_sub_market,
+            liquidator_repayment_tab_account,
+            liquidator_repayment_monthly_statement_account
+        )?;
+        update_user_previous_interest_accrued(
+            token_reserve,
+            &mut repayment_sub_market,
+            liquidator_repayment_tab_account,
+            liquidator_repayment_monthly_statement_account
+        )?;
+        update_user_previous_interest_earned(
+            token_reserve,
+            &mut liquidation_sub_market,
+            liquidator_liquidation_tab_account,
+            liquidator_liquidation_monthly_statement_account
+        )?;
+        update_user_previous_interest_accrued(
+            token_reserve,
+            &mut liquidation_sub_market,
+            liquidator_liquidation_tab_account,
+            liquidator_liquidation_monthly_statement_account
+        )?;
+
+        //Repay Liquidati's Debt
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
+        let should_close = user_ata_data.amount == 0;
+        let repayment_amount = deposit_tokens_into_token_reserve_from_user(
+            ctx.accounts.token_mint.key(),
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.liquidator_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            repayment_amount,
+            should_close,
+            &[]
+        )?;
+
+        //The seized collateral is sized off of what the Token Reserve actually received
+        repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+
+        //Get Amount to be Liquidated
+        let amount_to_be_liquidated = ((repayment_amount_usd_value * token_conversion_number) / token_usd_value) as u64;
+
+        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
+        //Multiply before dividing to help keep precision
+        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+
+        //Take a 1% liquidation fee
+        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+
+        //Check for underflow if liquidation isn't profitable
+        if liquidati_liquidation_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
+        {
+            //Take a 1% liquidation fee
+            liquidation_fee_amount = liquidati_liquidation_tab_account.deposited_amount / 100;
+            //Give remainder to liquidator
+            liquidation_amount_with_7_percent_bonus = liquidati_liquidation_tab_account.deposited_amount - liquidation_fee_amount;
+        }
+
+        //Update Repayment Values
+        token_reserve.borrowed_amount -= repayment_amount as u128;
+        token_reserve.repaid_debt_amount += repayment_amount as u128;
+        repayment_sub_market.borrowed_amount -= repayment_amount as u128;
+        repayment_sub_market.repaid_debt_amount += repayment_amount as u128;
+        liquidati_repayment_tab_account.borrowed_amount -= repayment_amount;
+        liquidator_repayment_tab_account.repaid_debt_amount += repayment_amount;
+        liquidati_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
+        liquidator_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        liquidati_repayment_monthly_statement_account.snap_shot_debt_amount = liquidati_repayment_tab_account.borrowed_amount;
+
+        //Update Liquidation and Fee Values
+        token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        token_reserve.liquidated_amount += liquidation_fee_amount as u128;
+        token_reserve.deposited_amount -= liquidation_fee_amount as u128;
+        token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
+        liquidation_sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        liquidation_sub_market.liquidated_amount += liquidation_fee_amount as u128;
+        liquidation_sub_market.deposited_amount -= liquidation_fee_amount as u128;
+        liquidation_sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
+        liquidati_liquidation_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_tab_account.deposited_amount -= liquidation_fee_amount;
+        liquidati_liquidation_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_tab_account.liquidated_amount += liquidation_fee_amount;
+        liquidator_liquidation_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_liquidation_tab_account.fees_generated_amount += liquidation_fee_amount;
+        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
+        liquidati_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidati_liquidation_tab_account.deposited_amount;
+        liquidator_liquidation_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_liquidation_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+
+        if send_reward_to_wallet
+        {
+            withdraw_tokens_from_token_reserve_to_user(
+                ctx.accounts.token_mint.key(),
+                token_reserve,
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.liquidator_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                liquidation_amount_with_7_percent_bonus,
+                None,
+                &[]
+            )?;
+
+            token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
+            liquidation_sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128; 
+        }
+        else
+        {
+            liquidator_liquidation_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
+            liquidator_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidator_liquidation_tab_account.deposited_amount;
+        }
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        
+        //Update Stat Listener
+        lending_stats.liquidations += 1;
+
+        invalidate_cached_user_health(liquidati_lending_account);
+        
+        sync_token_reserve_active_user_counts(token_reserve, &mut liquidati_repayment_tab_account);
+        sync_token_reserve_active_user_counts(token_reserve, &mut liquidati_liquidation_tab_account);
+        sync_token_reserve_active_user_counts(token_reserve, liquidator_liquidation_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY
+        update_token_reserve_rates(token_reserve)?;
+
+        //Update Repayment SubMarket/User time stamp based interest indexes
+        repayment_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        repayment_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidati_repayment_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidati_repayment_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidator_repayment_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidator_repayment_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update Liquidation SubMarket/User time stamp based interest indexes
+        liquidation_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidation_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidati_liquidation_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidati_liquidation_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidator_liquidation_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidator_liquidation_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
+        //token_reserve.last_lending_activity_amount = repayment_amount;
+        //token_reserve.last_lending_activity_type = Activity::Repay as u8; //Since the token is the same, make Liquidate the last activity on the token reserve
+        token_reserve.last_lending_activity_amount = liquidation_amount;
+        token_reserve.last_lending_activity_type = Activity::Liquidate as u8; //We'll let the Liquidate activity be the last activity since the repayment and liquidation token reserves are the same in this case
+        repayment_sub_market.last_lending_activity_amount = repayment_amount;
+        repayment_sub_market.last_lending_activity_type = Activity::Repay as u8;
+        repayment_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidation_sub_market.last_lending_activity_amount = liquidation_amount;
+        liquidation_sub_market.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidation_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        liquidati_repayment_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidati_liquidation_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        liquidator_repayment_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidator_liquidation_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        
+        //Save changes to passed in remaining accounts
+        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
+        if let Some(liquidati_repayment_monthly_statement_account_serialized) = liquidati_repayment_monthly_statement_account_serialized
+        {
+            liquidati_repayment_monthly_statement_account.serialize(&mut &mut liquidati_repayment_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+        }
+        if let Some(liquidati_liquidation_monthly_statement_account_serialized) = liquidati_liquidation_monthly_statement_account_serialized
+        {
+            liquidati_liquidation_monthly_statement_account.serialize(&mut &mut liquidati_liquidation_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+        }
+        
+        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+
+        verbose_msg!("Repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        token_reserve.token_id,
+        repayment_sub_market_owner_address.key(),
+        repayment_sub_market_index);
+
+        verbose_msg!("Liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        token_reserve.token_id,
+        liquidation_sub_market_owner_address.key(),
+        liquidation_sub_market_index);
+
+        Ok(())
+    }
+
+    
+    //This liquidation is for when the repayment and liquidation Sub Markets are the same. If the Sub Markets are the same, the tokens are also the same
+    //The only cases not covered is liquidating yourself. You can "liquidate yourself" still, but you have to do it with a 2nd account from the same wallet
+    pub fn liquidate_account_same_sub_market(ctx: Context<LiquidateAccountSameSubMarket>,
+        sub_market_index: u16,
+        liquidati_account_index: u8,
+        liquidator_account_index: u8,
+        amount_to_repay: u64,
+        repay_max: bool,
+        paying_off_insolvent_account: bool,
+        send_reward_to_wallet: bool,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()>
+    {
+        let liquidator_monthly_statements_disabled = ctx.accounts.liquidator_lending_account.monthly_statements_disabled;
+
+        //While the Token Reserve is in its guarded launch period only allowlisted liquidators can liquidate
+        check_liquidator_allowlist(&ctx.accounts.token_reserve, &ctx.accounts.liquidator_allowlist_entry)?;
+
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let liquidati_lending_account = &mut ctx.accounts.liquidati_lending_account;
+        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
+        let liquidator_tab_account = &mut load_mut_or_init(&ctx.accounts.liquidator_tab_account)?;
+        let mut liquidator_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidator_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidator_monthly_statement_account, &mut liquidator_monthly_statement_account_placeholder, liquidator_monthly_statements_disabled)?;
+
+        let clock_slot = Clock::get()?.slot;
+
+        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        //Validate Accounts
+
+        ////////////////////////////
+        //Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        ///////////////
+        //Lending Stats
+        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+
+        /////////////////////////////
+        //SubMarket Account
+        let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+            sub_market_account_serialized,
+            token_reserve.token_id,
+            sub_market_owner_address,
+            sub_market_index)?;
+
+        /////////////////////////////////
+        //Liquidati Tab Account
+        let liquidati_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let mut liquidati_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
+            liquidati_tab_account_serialized,
+            token_reserve.token_id,
+            sub_market_owner_address,
+            sub_market_index,
+            liquidati_account_owner_address,
+            liquidati_account_index)?;
+
+        ///////////////////////////////////////////////
+        //Liquidati Monthly Statement Account
+        //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+        let liquidati_monthly_statement_account_serialized;
+        let mut liquidati_monthly_statement_account;
+        if liquidati_lending_account.monthly_statements_disabled
+        {
+            liquidati_monthly_statement_account_serialized = None;
+            liquidati_monthly_statement_account = Structs::LendingUserMonthlyStatementAccount::default();
+        }
+        else
+        {
+            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            liquidati_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                monthly_statement_account_serialized,
+                lending_protocol.current_statement_month,
+                lending_protocol.current_statement_year,
+                token_reserve.token_id,
+                sub_market_owner_address,
+                sub_market_index,
+                liquidati_account_owner_address,
+                liquidati_account_index)?;
+            liquidati_monthly_statement_account_serialized = Some(monthly_statement_account_serialized);
+        }
+
+        let repayment_amount;
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //Get USD value of Repayment Amount
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+        let token_usd_value = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(&sub_market), token_reserve.usd_quote_price_id)?;
+        let mut repayment_amount_usd_value = 0;
+
+        //Check if Account is liquidatable and set repayment_amount
+        if paying_off_insolvent_account
+        {
+            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+
+            if repay_max
+            {
+                repayment_amount = liquidati_tab_account.borrowed_amount;
+                repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+                
+                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
+                //Use saturating_sub to safely deduct the value
+                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
+                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
+                    .total_borrowed_usd_value
+                    .saturating_sub(repayment_amount_usd_value);
+            }
+            else
+            {
+                if amount_to_repay > liquidati_tab_account.borrowed_amount
+                {
+                    //Can't pay more debt than the user has accumulated
+                    repayment_amount = liquidati_tab_account.borrowed_amount;
+                }
+                else
+                {
+                    repayment_amount = amount_to_repay;
+                }  
+            }
+        }
+        else
+        {
+            //Multiply before dividing to help keep precision
+            let liquidati_liquidation_threshold_usd_value = (liquidati_lending_account.total_deposited_usd_value * get_liquidation_threshold_rate(liquidati_lending_account)) / DECIMAL_SCALING;
+
+            //You can't liquidate an account whose borrow liabilities aren't at or above the liquidation threshold of their deposited collateral
+            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_liquidation_threshold_usd_value, LendingError::NotLiquidatable);
+
+            //Multiply before dividing to help keep precision
+            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_tab_account.borrowed_amount * 50) / 100;
+
+            if repay_max
+            {
+                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
+            }
+            else
+            {
+                repayment_amount = amount_to_repay;
+            }
+
+            //You can't repay more than 50% of a liquidati's debt position
+            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
+        }
+
+        if repayment_amount_usd_value == 0
+        {
+            repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+        }
+
+        //Multiply before dividing to help keep precision
+        let ten_percent_of_borrowed_amount = (liquidati_tab_account.borrowed_amount * 10) / 100;
+
+        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
+        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
+        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
         if liquidator_lending_account.lending_user_account_added == false
         {
-            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
-            if let Some(new_account_name) = account_name
-            {
-                if !new_account_name.is_empty()
-                {
-                    new_account_name_to_use = new_account_name;
-                }
-            }
+            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                liquidator_lending_account,
+                ctx.bumps.liquidator_lending_account,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if liquidator_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                liquidator_lending_account,
+                liquidator_tab_account,
+                ctx.accounts.liquidator_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.liquidator_tab_account,
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
+        if liquidator_monthly_statement_account.monthly_statement_account_added == false
+        {
+            initialize_lending_user_monthly_statement_account(
+                liquidator_monthly_statement_account,
+                liquidator_tab_account,
+                lending_protocol,
+                ctx.bumps.liquidator_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address,
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                liquidator_account_index,
+            )?;
+        }
+
+        //Update interest earned and accrued for the liquidator
+        update_user_previous_interest_earned(
+            token_reserve,
+            &mut sub_market,
+            liquidator_tab_account,
+            liquidator_monthly_statement_account
+        )?;
+        update_user_previous_interest_accrued(
+            token_reserve,
+            &mut sub_market,
+            liquidator_tab_account,
+            liquidator_monthly_statement_account
+        )?;
+
+        //Repay Liquidati's Debt
+        //Transfer fee mints deliver less than the amount sent, so only what the Token Reserve actually received gets credited
+        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
+        let should_close = user_ata_data.amount == 0;
+        let repayment_amount = deposit_tokens_into_token_reserve_from_user(
+            ctx.accounts.token_mint.key(),
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.liquidator_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            repayment_amount,
+            should_close,
+            &[]
+        )?;
+
+        //The seized collateral is sized off of what the Token Reserve actually received
+        repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+
+        //Get Amount to be Liquidated
+        let amount_to_be_liquidated = ((repayment_amount_usd_value * token_conversion_number) / token_usd_value) as u64;
+
+        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
+        //Multiply before dividing to help keep precision
+        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+
+        //Take a 1% liquidation fee
+        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+
+        //Check for underflow if liquidation isn't profitable
+        if liquidati_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
+        {
+            //Take a 1% liquidation fee
+            liquidation_fee_amount = liquidati_tab_account.deposited_amount / 100;
+            //Give remainder to liquidator
+            liquidation_amount_with_7_percent_bonus = liquidati_tab_account.deposited_amount - liquidation_fee_amount;
+        }
+
+        //Update Repayment Values
+        token_reserve.borrowed_amount -= repayment_amount as u128;
+        token_reserve.repaid_debt_amount += repayment_amount as u128;
+        sub_market.borrowed_amount -= repayment_amount as u128;
+        sub_market.repaid_debt_amount += repayment_amount as u128;
+        liquidati_tab_account.borrowed_amount -= repayment_amount;
+        liquidator_tab_account.repaid_debt_amount += repayment_amount;
+        liquidati_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
+        liquidator_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
+        liquidati_monthly_statement_account.snap_shot_debt_amount = liquidati_tab_account.borrowed_amount;
+
+        //Update Liquidation and Fee Values
+        token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        token_reserve.liquidated_amount += liquidation_fee_amount as u128;
+        token_reserve.deposited_amount -= liquidation_fee_amount as u128;
+        token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
+        sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
+        sub_market.liquidated_amount += liquidation_fee_amount as u128;
+        sub_market.deposited_amount -= liquidation_fee_amount as u128;
+        sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
+        liquidati_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
+        liquidati_tab_account.deposited_amount -= liquidation_fee_amount;
+        liquidati_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_tab_account.liquidated_amount += liquidation_fee_amount;
+        liquidator_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_tab_account.fees_generated_amount += liquidation_fee_amount;
+        liquidati_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
+        liquidati_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
+        liquidati_monthly_statement_account.snap_shot_balance_amount = liquidati_tab_account.deposited_amount;
+        liquidator_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
+        liquidator_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+
+        if send_reward_to_wallet
+        {
+            withdraw_tokens_from_token_reserve_to_user(
+                ctx.accounts.token_mint.key(),
+                token_reserve,
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.liquidator_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                liquidation_amount_with_7_percent_bonus,
+                None,
+                &[]
+            )?;
+
+            token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
+            sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128; 
+        }
+        else
+        {
+            liquidator_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
+            liquidator_monthly_statement_account.snap_shot_balance_amount = liquidator_tab_account.deposited_amount;
+        }
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        
+        //Update Stat Listener
+        lending_stats.liquidations += 1;
+
+        invalidate_cached_user_health(liquidati_lending_account);
+        
+        sync_token_reserve_active_user_counts(token_reserve, &mut liquidati_tab_account);
+        sync_token_reserve_active_user_counts(token_reserve, liquidator_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY
+        update_token_reserve_rates(token_reserve)?;
+
+        //Update Repayment SubMarket/User time stamp based interest indexes
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidati_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidati_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidator_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidator_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
+        //token_reserve.last_lending_activity_amount = repayment_amount;
+        //token_reserve.last_lending_activity_type = Activity::Repay as u8; //Since the token is the same, make Liquidate the last activity on the Token Reserve
+        token_reserve.last_lending_activity_amount = liquidation_amount;
+        token_reserve.last_lending_activity_type = Activity::Liquidate as u8; //We'll let the Liquidate activity be the last activity since the repayment and liquidation token reserves are the same in this case
+        //sub_market.last_lending_activity_amount = repayment_amount;
+        //sub_market.last_lending_activity_type = Activity::Repay as u8;
+        //sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Sub Market
+        sub_market.last_lending_activity_amount = liquidation_amount;
+        sub_market.last_lending_activity_type = Activity::Liquidate as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        //liquidati_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        //liquidati_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        //liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Monthly Statement
+        liquidati_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidati_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        //liquidator_monthly_statement_account.last_lending_activity_amount = repayment_amount;
+        //liquidator_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
+        //liquidator_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Monthly Statement
+        liquidator_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
+        liquidator_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidator_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        
+        //Save changes to passed in remaining accounts
+        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
+        if let Some(liquidati_monthly_statement_account_serialized) = liquidati_monthly_statement_account_serialized
+        {
+            liquidati_monthly_statement_account.serialize(&mut &mut liquidati_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+        }
+        
+        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+
+        verbose_msg!("Repaid debt and liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+
+        Ok(())
+    }
+
+    //You have to call this instruction for all user tab accounts before calling the withdraw, borrow, or liquidate functions in the same transaction.
+    //Feed in all of the Token Reserves remaining accounts as the same order as the token_reserve_mint_addresses input, then
+    //Repeating sets of these remaining accounts in this order (Successfully tested with 10 tab account sets at once): LendingUserTabAccount, Submarket, LendingUserMonthlyStatementAccount
+    //Leave out the LendingUserMonthlyStatementAccount from each set if the Lending User Account opted out of Monthly Statements
+    pub fn refresh_user_health_chunk_and_token_reserves(ctx: Context<RefreshUserHealthChunkAndTokenReserves>,
+        user_account_index: u8,
+        refresh_token_reserve_count: u8, //The number of token reserves being refreshed may not be the number of unverified_price_data, ie when borrowing from a token reserve the user has never interacted with before
+        set_count: u8, //The number of LendingUserTabAccount, Submarket, and LendingUserMonthlyStatementAccount sets being fed in
+        close_price_account: bool //When just wanting to refresh the account without borrowing, set this flag to true so it closes the price account since you don't have a burrow-like instruction that will need it.
+        //Note Withdraw, Borrow, Repay, and Liquidate will close the price account when they are done.
+    ) -> Result<()> 
+    {
+        let user_account_owner_address = ctx.accounts.lending_user_owner.key();
+
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let price_validator = &ctx.accounts.price_validator;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
+
+        //Return if User Lending Account is already updated to the current block slot
+        if lending_user_account.last_health_update_clock_slot == clock_slot
+        {
+            return Ok(())
+        }
+
+        //Check if this is an unfinished refresh or a brand new one.
+        //If the block has changed since we started refreshing, we MUST reset.
+        if lending_user_account.refresh_clock_slot != clock_slot
+        {
+            lending_user_account.temp_deposit_usd_value = 0;
+            lending_user_account.temp_borrow_usd_value = 0;
+            lending_user_account.next_tab_index_to_refresh = 0;
+            lending_user_account.refreshed_tab_account_flags = 0;
+            lending_user_account.temp_e_mode_eligible = true;
+            lending_user_account.temp_borrowed_tokens = Structs::BorrowedTokens::default();
+            lending_user_account.refresh_clock_slot = clock_slot;
+        }
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //The working set is bounded by the Tab Account registry and kept on the heap, so users at the max tabs per lending account can still refresh without blowing the 4KB stack frame
+        require!(set_count as usize <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT && refresh_token_reserve_count as usize <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, LendingError::TooManyHealthRefreshAccounts);
+
+        let mut token_reserves: Vec<(&AccountInfo, Box<Structs::TokenReserve>)> = Vec::with_capacity(refresh_token_reserve_count.into());
+        for _i in 0..refresh_token_reserve_count.into()
+        {
+            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let token_reserve = validate_and_return_token_reserve_account(*ctx.program_id,
+                token_reserve_account_serialized)?;
+
+            //A duplicated Token Reserve would be written back twice, with the stale copy overwriting the refreshed one
+            require!(!token_reserves.iter().any(|(_, existing_token_reserve)| existing_token_reserve.token_id == token_reserve.token_id), LendingError::DuplicateTokenReserveAccount);
+            token_reserves.push((token_reserve_account_serialized, Box::new(token_reserve)));
+        }
+
+        for _i in 0..set_count.into()
+        {
+            //Validate Remaining Accounts
+
+            /////////////
+            //Tab Account
+            //Tab Accounts are checked against the Lending User Account's Tab Account registry, so they can be fed in any order
+            let tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let tab_account_registry_index = lending_user_account.tab_account_addresses
+                .iter()
+                .position(|tab_account_address| *tab_account_address == tab_account_serialized.key())
+                .ok_or(LendingError::UnregisteredTabAccount)?;
+            let tab_account_refreshed_flag = 1u32 << tab_account_registry_index;
+
+            //Each Tab Account can only be counted once per refresh
+            require!(lending_user_account.refreshed_tab_account_flags & tab_account_refreshed_flag == 0, LendingError::TabAccountAlreadyRefreshed);
+            lending_user_account.refreshed_tab_account_flags |= tab_account_refreshed_flag;
+
+            let mut lending_user_tab_account = load_zero_copy_account_mut::<Structs::LendingUserTabAccount>(tab_account_serialized)?;
+
+            ///////////////////////
+            //Token Reserve Account
+            let token_reserve_entry = token_reserves.iter_mut()
+                .find(|(_, token_reserve)| token_reserve.token_id == lending_user_tab_account.token_id)
+                .ok_or(LendingError::MissingTokenReserveAccountForRefresh)?;
+            let (_, token_reserve) = token_reserve_entry;
+
+            ///////////////////
+            //SubMarket Account
+            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
+                sub_market_account_serialized,
+                lending_user_tab_account.token_id,
+                lending_user_tab_account.sub_market_owner_address,
+                lending_user_tab_account.sub_market_index)?;
+
+            ///////////////////////////
+            //Monthly Statement Account
+            //Opted out users don't have Monthly Statements, so an unsaved placeholder is used instead
+            let monthly_statement_account_serialized;
+            let mut monthly_statement_account;
+            if lending_user_account.monthly_statements_disabled
+            {
+                monthly_statement_account_serialized = None;
+                monthly_statement_account = Box::new(Structs::LendingUserMonthlyStatementAccount::default());
+            }
+            else
+            {
+                let statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                monthly_statement_account = Box::new(validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
+                    statement_account_serialized,
+                    lending_protocol.current_statement_month,
+                    lending_protocol.current_statement_year,
+                    lending_user_tab_account.token_id,
+                    lending_user_tab_account.sub_market_owner_address,
+                    lending_user_tab_account.sub_market_index,
+                    user_account_owner_address,
+                    user_account_index)?);
+                monthly_statement_account_serialized = Some(statement_account_serialized);
+            }
+
+            //Calculate Token Reserve Previously Earned And Accrued Interest
+            if token_reserve.last_health_update_clock_slot != clock_slot
+            {
+                update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, Some(clock_slot))?;
+            }
+            
+            lower_tab_fee_discount_rate(&lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value, &mut lending_user_tab_account);
+            update_user_previous_interest_earned(
+                token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account
+            )?;
+
+            //Unstaking lowers the discount before the accrual so it isn't applied to the time since. Staking only raises it after the accrual when the tab is stamped below
+            let borrow_rate_discount_rate = u16::from_le_bytes(lending_user_tab_account.borrow_rate_discount_rate).min(lending_user_account.borrow_rate_discount_rate);
+            lending_user_tab_account.borrow_rate_discount_rate = borrow_rate_discount_rate.to_le_bytes();
+
+            update_user_previous_interest_accrued(
+                token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account
+            )?;
+            
+            //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+            update_token_reserve_rates(token_reserve)?;
+            sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+            lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+            lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+            //Empty tabs are worth nothing at any price, so their price doesn't have to be in the Oracle price data
+            let tab_account_empty = lending_user_tab_account.deposited_amount == 0 && lending_user_tab_account.borrowed_amount == 0;
+            let mut deposited_usd_value = 0;
+            let mut borrowed_usd_value = 0;
+            if !tab_account_empty
+            {
+                //Get normalized price with 8 decimals. Uses the SubMarket's oracle override if the CEO approved one
+                let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(&sub_market), token_reserve.usd_quote_price_id)?;
+                
+                //Update temp deposited and borrow values. Raw amounts are scaled down by the token's decimals so every tab is summed in the same 18 decimal USD units
+                let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+                deposited_usd_value = (lending_user_tab_account.deposited_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+                borrowed_usd_value = (lending_user_tab_account.borrowed_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+                lending_user_account.temp_deposit_usd_value += get_collateral_usd_value(token_reserve, deposited_usd_value);
+                lending_user_account.temp_borrow_usd_value += borrowed_usd_value;
+
+                if token_reserve.asset_category != lending_user_account.e_mode_category
+                {
+                    lending_user_account.temp_e_mode_eligible = false;
+                }
+
+                if lending_user_tab_account.borrowed_amount > 0
+                {
+                    record_borrowed_token(&mut lending_user_account.temp_borrowed_tokens, token_reserve);
+                }
+
+                if let Some(protocol_tvl) = &mut ctx.accounts.protocol_tvl
+                {
+                    update_protocol_tvl(protocol_tvl, token_reserve, normalized_price_18_decimals, time_stamp)?;
+                }
+            }
+
+            lending_user_account.next_tab_index_to_refresh += 1;
+
+            //Stamp the tab's Fee Discount Tier from the Lending User's total deposited value as of the last finished refresh, less any withdrawals since
+            lending_user_tab_account.fee_discount_rate = get_fee_discount_rate(&lending_protocol.fee_discount_tiers, lending_user_account.total_deposited_usd_value).to_le_bytes();
+            lending_user_tab_account.borrow_rate_discount_rate = lending_user_account.borrow_rate_discount_rate.to_le_bytes();
+
+            //The tab's share of the Lending User's collateral and debt, along with the fresh balances and indexes the snap shot was taken at
+            emit!(TabAccountHealthSnapShot
+            {
+                owner: user_account_owner_address,
+                user_account_index,
+                token_id: lending_user_tab_account.token_id,
+                sub_market_owner_address: lending_user_tab_account.sub_market_owner_address,
+                sub_market_index: lending_user_tab_account.sub_market_index,
+                deposited_amount: lending_user_tab_account.deposited_amount,
+                borrowed_amount: lending_user_tab_account.borrowed_amount,
+                supply_interest_change_index: lending_user_tab_account.supply_interest_change_index,
+                borrow_interest_change_index: lending_user_tab_account.borrow_interest_change_index,
+                deposited_usd_value,
+                borrowed_usd_value,
+                clock_slot
+            });
+
+            //SubMarket and User Tab Account are zero copy, their changes were written in place.
+            //Token Reserves can be shared by several tabs, so they are saved once after the loop instead of being reserialized for every tab
+
+            //Save Monthly Statement (Skip 8 byte discriminator)
+            if let Some(monthly_statement_account_serialized) = monthly_statement_account_serialized
+            {
+                monthly_statement_account.serialize(&mut &mut monthly_statement_account_serialized.data.borrow_mut()[8..])?;
+            }
+        }
+
+        //Save Token Reserves (Skip 8 byte discriminator)
+        for (token_reserve_account_serialized, token_reserve) in token_reserves.iter()
+        {
+            token_reserve.serialize(&mut &mut token_reserve_account_serialized.data.borrow_mut()[8..])?;
+        }
+
+        //Finalize if we've covered all of the Lending User's Tab Accounts
+        if lending_user_account.next_tab_index_to_refresh == lending_user_account.tab_account_count
+        {
+            lending_user_account.total_deposited_usd_value = lending_user_account.temp_deposit_usd_value;
+            lending_user_account.total_borrowed_usd_value = lending_user_account.temp_borrow_usd_value;
+            lending_user_account.borrowed_tokens = lending_user_account.temp_borrowed_tokens;
+            lending_user_account.last_health_update_clock_slot = clock_slot;
+            lending_user_account.user_health_invalidated = false;
+
+            //E-Mode only applies while every tab with collateral or debt is in the Lending User's E-Mode asset category.
+            //The category's limits are picked up fresh every refresh so the CEO's changes apply to everyone in the category
+            lending_user_account.e_mode_active = false;
+            if lending_user_account.e_mode_category != 0
+            {
+                let e_mode_category = ctx.accounts.e_mode_category.as_ref().ok_or(LendingError::MissingEModeCategoryAccount)?;
+                lending_user_account.e_mode_loan_to_value_rate = e_mode_category.loan_to_value_rate;
+                lending_user_account.e_mode_liquidation_threshold_rate = e_mode_category.liquidation_threshold_rate;
+                lending_user_account.e_mode_active = lending_user_account.temp_e_mode_eligible;
+            }
+
+            msg!("{} updated the health factor for Account Address: {}, Account Index: {}",
+            ctx.accounts.signer.key(),
+            user_account_owner_address.key(),
+            user_account_index);
+        }
+
+        if close_price_account
+        {
+            //Refund Oracle price account fees back to Oracle
+            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        }
+
+        Ok(())
+    }
+
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
+    //Caches the finished health refresh in the User Health account, so withdraws and borrows within the Lending Protocol's max age can skip repricing every tab
+    pub fn refresh_user_health(ctx: Context<RefreshUserHealth>, user_account_index: u8) -> Result<()> 
+    {
+        let lending_user_account = &ctx.accounts.lending_user_account;
+        let user_health = &mut ctx.accounts.user_health;
+        let clock_slot = Clock::get()?.slot;
+
+        require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+        if user_health.version == 0
+        {
+            user_health.bump = ctx.bumps.user_health;
+            user_health.owner = ctx.accounts.lending_user_owner.key();
+            user_health.user_account_index = user_account_index;
+            user_health.version = CURRENT_ACCOUNT_VERSION;
+        }
+
+        user_health.total_deposited_usd_value = lending_user_account.total_deposited_usd_value;
+        user_health.total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value;
+        user_health.refresh_clock_slot = clock_slot;
+        user_health.refresh_time_stamp = Clock::get()?.unix_timestamp as u64;
+        user_health.expiry_clock_slot = clock_slot + ctx.accounts.lending_protocol.user_health_max_age_slots;
+        user_health.attested_prices.clear();
+
+        //Optionally attest the verified prices too, so the withdraw or borrow that follows can leave out the price accounts.
+        //The price accounts are passed in as the remaining accounts, the signer's temporary price account followed by the Oracle account
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        if let Some(temp_price_account_serialized) = remaining_accounts_iter.next()
+        {
+            let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+                temp_price_account_serialized,
+                ctx.accounts.signer.key())?;
+
+            check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+            require!(temp_price_account.data.len() <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, LendingError::TooManyAttestedPrices);
+
+            user_health.attested_prices = temp_price_account.data;
+
+            //Refund Oracle price account fees back to Oracle
+            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            require_keys_eq!(oracle_account_serialized.key(), ctx.accounts.price_validator.address, LendingError::PriceOracleKeyMisMatched);
+            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+        }
+
+        msg!("Cached User Health for: {}, Account Index: {}", user_health.owner, user_account_index);
+        verbose_msg!("Attested Prices: {}, Expiry Clock Slot: {}", user_health.attested_prices.len(), user_health.expiry_clock_slot);
+
+        Ok(())
+    }
+
+    pub fn create_new_monthly_statement(ctx: Context<CreateNewMonthlyStatement>, token_id: u8, sub_market_index: u16, user_account_index: u8) -> Result<()> 
+    {
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let user_account_owner_address = ctx.accounts.lending_user_owner.key();
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_user_tab_account = &ctx.accounts.lending_user_tab_account.load()?;
+        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
+
+        //Opted out users don't get Monthly Statements
+        require!(!ctx.accounts.lending_user_account.monthly_statements_disabled, LendingError::MonthlyStatementsDisabled);
+
+        initialize_lending_user_monthly_statement_account(
+            lending_user_monthly_statement_account,
+            lending_user_tab_account,
+            lending_protocol,
+            ctx.bumps.lending_user_monthly_statement_account,
+            token_id,
+            sub_market_owner_address.key(),
+            sub_market_index,
+            user_account_owner_address.key(),
+            user_account_index,
+        )?;
+
+        //Keepers rolling statements over for other users get the keeper bounty
+        let bounty_amount = if ctx.accounts.signer.key() != user_account_owner_address
+        {
+            pay_keeper_bounty(&mut ctx.accounts.keeper_bounty_vault, &ctx.accounts.signer.to_account_info())?
+        }
+        else
+        {
+            0
+        };
+
+        verbose_msg!("Bounty Amount: {}", bounty_amount);
+
+        Ok(())
+    }
+
+    pub fn claim_sub_market_fees(ctx: Context<ClaimSubMarketFees>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        //Only the Fee Collector can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), sub_market.fee_collector_address.key(), LendingError::NotFeeCollector);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if lending_user_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Generic Sub Fee Claimer");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                lending_user_account,
+                ctx.bumps.lending_user_account,
+                ctx.accounts.signer.key(),
+                user_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.lending_user_tab_account,
+                token_reserve.token_id, 
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed.
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        //Collect Fees
+        token_reserve.deposited_amount += sub_market.uncollected_sub_market_fees_amount;
+        token_reserve.uncollected_sub_market_fees_amount -= sub_market.uncollected_sub_market_fees_amount;
+        sub_market.deposited_amount += sub_market.uncollected_sub_market_fees_amount;
+        lending_user_tab_account.deposited_amount += sub_market.uncollected_sub_market_fees_amount as u64;
+        lending_user_monthly_statement_account.monthly_sub_market_fees_collected_amount += sub_market.uncollected_sub_market_fees_amount as u64;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Stat Listener
+        lending_stats.fee_collections += 1;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        sub_market.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
+        sub_market.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        lending_user_monthly_statement_account.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+        sub_market.uncollected_sub_market_fees_amount = 0;
+
+        msg!("{} Collected SubMarket Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+
+        verbose_msg!("FeeCollectorAccountIndex: {}", user_account_index);
+
+        Ok(())
+    }
+
+    pub fn claim_sub_market_fees_and_deposit_in_different_sub_market(ctx: Context<ClaimSubMarketFeesAndDepositInDifferentSubMarket>,
+        initial_sub_market_index: u16,
+        destination_sub_market_index: u16,
+        user_account_index: u8,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let initial_sub_market_owner_address = ctx.accounts.initial_sub_market_owner.key();
+        let destination_sub_market_owner_address = ctx.accounts.destination_sub_market_owner.key();
+        let initial_sub_market = &mut ctx.accounts.initial_sub_market.load_mut()?;
+        //Only the Fee Collector can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), initial_sub_market.fee_collector_address.key(), LendingError::NotFeeCollector);
+                
+        //Duplicate SubMarket Detected
+        //When accounts are the exact same, it can lead to unexpected behavior where only one of them gets updated and would require extra steps
+        require!(initial_sub_market_owner_address.key() != destination_sub_market_owner_address.key() ||
+        initial_sub_market_index != destination_sub_market_index, LendingError::DuplicateSubMarket);
+
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let destination_sub_market = &mut ctx.accounts.destination_sub_market.load_mut()?;
+        check_sub_market_allowlist(destination_sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let initial_lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.initial_lending_user_tab_account)?;
+        let destination_lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.destination_lending_user_tab_account)?;
+        let mut initial_lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let initial_lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.initial_lending_user_monthly_statement_account, &mut initial_lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let mut destination_lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let destination_lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.destination_lending_user_monthly_statement_account, &mut destination_lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if lending_user_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Generic Sub Fee Claimer");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                lending_user_account,
+                ctx.bumps.lending_user_account,
+                ctx.accounts.signer.key(),
+                user_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if initial_lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                initial_lending_user_tab_account,
+                ctx.accounts.initial_lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.initial_lending_user_tab_account,
+                token_reserve.token_id,
+                initial_sub_market_owner_address,
+                initial_sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+        if destination_lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                destination_lending_user_tab_account,
+                ctx.accounts.destination_lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.destination_lending_user_tab_account,
+                token_reserve.token_id,
+                destination_sub_market_owner_address,
+                destination_sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed.
+        if initial_lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                initial_lending_user_monthly_statement_account,
+                initial_lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.initial_lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                initial_sub_market_owner_address,
+                initial_sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+        if destination_lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                destination_lending_user_monthly_statement_account,
+                destination_lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.destination_lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                destination_sub_market_owner_address,
+                destination_sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            destination_sub_market,
+            destination_lending_user_tab_account,
+            destination_lending_user_monthly_statement_account
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            destination_sub_market,
+            destination_lending_user_tab_account,
+            destination_lending_user_monthly_statement_account
+        )?;
+
+        //Collect Fees
+        token_reserve.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount;
+        token_reserve.uncollected_sub_market_fees_amount -= initial_sub_market.uncollected_sub_market_fees_amount;
+        destination_sub_market.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount;
+        destination_lending_user_tab_account.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        initial_lending_user_monthly_statement_account.monthly_sub_market_fees_collected_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        initial_lending_user_monthly_statement_account.monthly_withdrawal_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64; //Treating this as a withdrawal from initial submarket. The fee collection and withdrawal cancel each other out, so no need to update snap shot balance for initial submarket.
+        destination_lending_user_monthly_statement_account.monthly_deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64; //Treating this as a deposit into destination submarket.
+        destination_lending_user_monthly_statement_account.snap_shot_balance_amount = destination_lending_user_tab_account.deposited_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, destination_lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        destination_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        destination_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        destination_lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        destination_lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Stat Listener
+        lending_stats.fee_collections += 1;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        initial_sub_market.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        initial_sub_market.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        initial_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        destination_sub_market.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        destination_sub_market.last_lending_activity_type = Activity::Deposit as u8;
+        destination_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        initial_lending_user_monthly_statement_account.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        initial_lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        initial_lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        destination_lending_user_monthly_statement_account.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
+        destination_lending_user_monthly_statement_account.last_lending_activity_type = Activity::Deposit as u8;
+        destination_lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+        initial_sub_market.uncollected_sub_market_fees_amount = 0;
+
+        msg!("{} Collected SubMarket Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        initial_sub_market_owner_address.key(),
+        initial_sub_market_index);
+
+        verbose_msg!("FeeCollectorAccountIndex: {}", user_account_index);
+
+        verbose_msg!("Fees Moved to DestinationSubMarketOwner: {}, DestinationSubMarketIndex: {}", destination_sub_market_owner_address.key(), destination_sub_market_index);
+
+        Ok(())
+    }
+
+    pub fn claim_solvency_insurance_fees(ctx: Context<ClaimSolvencyInsuranceFees>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if lending_user_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("Solvency Treasury");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                lending_user_account,
+                ctx.bumps.lending_user_account,
+                ctx.accounts.signer.key(),
+                user_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.lending_user_tab_account,
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed.
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        let amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.token_mint.key(),
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.treasurer_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            amount,
+            None,
+            &[]
+        )?;
+
+        //Record Solvency Insurance Fee Collection
+        lending_user_monthly_statement_account.monthly_solvency_insurance_fees_collected_amount += amount;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::CollectSolvencyFees as u8;
+        lending_user_monthly_statement_account.last_lending_activity_amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSolvencyFees as u8;
+        //No interest change calculated since the solvency fees are sent to the Solvency Wallet outside the protocol, so not using the time stamp on the Token Reserve
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = time_stamp;
+
+        token_reserve.uncollected_solvency_insurance_fees_amount = 0;
+
+        //Stat Listener
+        lending_stats.fee_collections += 1;
+
+        msg!("{} Collected Solvency Insurance Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+
+        verbose_msg!("FeeCollectorAccountIndex: {}", user_account_index);
+
+        Ok(())
+    }
+
+    pub fn claim_liquidation_fees(ctx: Context<ClaimLiquidationFees>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        account_name: Option<String>, //Optional variable. Use null on front end when not needed
+        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+    ) -> Result<()> 
+    {
+        let monthly_statements_disabled = ctx.accounts.lending_user_account.monthly_statements_disabled;
+        let liquidation_treasurer = &ctx.accounts.liquidation_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), liquidation_treasurer.address.key(), LendingError::NotLiquidationTreasurer);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let lending_user_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.lending_user_monthly_statement_account, &mut lending_user_monthly_statement_account_placeholder, monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        if lending_user_account.lending_user_account_added == false
+        {
+            let mut new_account_name_to_use: String = String::from("HODL Treasury");
+            if let Some(new_account_name) = account_name
+            {
+                if !new_account_name.is_empty()
+                {
+                    new_account_name_to_use = new_account_name;
+                }
+            }
+
+            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+
+            initialize_lending_user_account(
+                lending_user_account,
+                ctx.bumps.lending_user_account,
+                ctx.accounts.signer.key(),
+                user_account_index,
+                new_account_name_to_use,
+                lut_address
+            )?;
+        }
+
+        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
+        if lending_user_tab_account.user_tab_account_added == 0
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.lending_user_tab_account,
+                token_reserve.token_id, 
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+
+        //Initialize monthly statement account if the statement month/year has changed.
+        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        {
+            let lending_protocol = &ctx.accounts.lending_protocol;
+            initialize_lending_user_monthly_statement_account(
+                lending_user_monthly_statement_account,
+                lending_user_tab_account,
+                lending_protocol,
+                ctx.bumps.lending_user_monthly_statement_account.unwrap_or_default(),
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index,
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            lending_user_monthly_statement_account
+        )?;
+
+        //Collect Fees
+        token_reserve.deposited_amount += token_reserve.uncollected_liquidation_fees_amount;
+        sub_market.deposited_amount += token_reserve.uncollected_liquidation_fees_amount;
+        lending_user_tab_account.deposited_amount += token_reserve.uncollected_liquidation_fees_amount as u64;
+        lending_user_monthly_statement_account.monthly_liquidation_fees_collected_amount += token_reserve.uncollected_liquidation_fees_amount as u64;
+        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Stat Listener
+        lending_stats.fee_collections += 1;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
+        sub_market.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
+        sub_market.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        lending_user_monthly_statement_account.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
+        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
+        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+        token_reserve.uncollected_liquidation_fees_amount = 0;
+
+        msg!("{} Collected Liquidation Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+
+        verbose_msg!("FeeCollectorAccountIndex: {}", user_account_index);
+
+        Ok(())
+    }
+
+    //Credits the referrer with their slice of the SubMarket fees the referred Tab Account generated since the last claim, and pays it out of the SubMarket's uncollected fees.
+    //Whatever the SubMarket's uncollected fees can't cover stays claimable until more fees are generated
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>, sub_market_index: u16, user_account_index: u8) -> Result<()> 
+    {
+        let lending_user_referral = &mut ctx.accounts.lending_user_referral;
+        //Only the referrer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), lending_user_referral.referrer, LendingError::NotReferrer);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_tab_account = ctx.accounts.lending_user_tab_account.load()?;
+        let fees_generated_amount = lending_user_tab_account.fees_generated_amount;
+        let tab_sub_market_fee_rate = get_tab_sub_market_fee_rate(sub_market, &lending_user_tab_account, Clock::get()?.unix_timestamp as u64);
+
+        let new_fees_generated_amount = fees_generated_amount.saturating_sub(lending_user_referral.fees_generated_snap_shot_amount);
+        lending_user_referral.claimable_referral_fees_amount += get_referral_fees_amount(token_reserve, sub_market, tab_sub_market_fee_rate, new_fees_generated_amount);
+        lending_user_referral.fees_generated_snap_shot_amount = fees_generated_amount;
+
+        let claimed_amount = lending_user_referral.claimable_referral_fees_amount.min(sub_market.uncollected_sub_market_fees_amount as u64);
+        lending_user_referral.claimable_referral_fees_amount -= claimed_amount;
+        lending_user_referral.claimed_referral_fees_amount += claimed_amount;
+        sub_market.uncollected_sub_market_fees_amount -= claimed_amount as u128;
+        token_reserve.uncollected_sub_market_fees_amount -= claimed_amount as u128;
+
+        if claimed_amount > 0
+        {
+            withdraw_tokens_from_token_reserve_to_user(ctx.accounts.token_mint.key(),
+                token_reserve,
+                &ctx.accounts.token_reserve_ata.to_account_info(),
+                &ctx.accounts.referrer_ata.to_account_info(),
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                claimed_amount,
+                None,
+                ctx.remaining_accounts)?;
+        }
+
+        msg!("{} Claimed {} in Referral Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        claimed_amount,
+        token_reserve.token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index);
+
+        verbose_msg!("Referred User: {}", ctx.accounts.lending_user.key());
+        verbose_msg!("Still Claimable Amount: {}", lending_user_referral.claimable_referral_fees_amount);
+
+        emit!(ReferralFeesClaimed
+        {
+            referrer: ctx.accounts.signer.key(),
+            owner: ctx.accounts.lending_user.key(),
+            user_account_index,
+            token_id: token_reserve.token_id,
+            sub_market_owner_address: ctx.accounts.sub_market_owner.key(),
+            sub_market_index,
+            claimed_amount
+        });
+
+        Ok(())
+    }
+
+    //Starts emitting a reward token to the Token Reserve's depositors. The CEO funds the emissions by sending reward tokens to the reward vault
+    pub fn create_reward_emissions(ctx: Context<CreateRewardEmissions>, emissions_per_second: u64, borrow_rewards: bool) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let token_reserve = &ctx.accounts.token_reserve;
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+        reward_emissions.bump = ctx.bumps.reward_emissions;
+        reward_emissions.version = CURRENT_ACCOUNT_VERSION;
+        reward_emissions.token_id = token_reserve.token_id;
+        reward_emissions.token_reserve_address = token_reserve.key();
+        reward_emissions.reward_mint_address = ctx.accounts.reward_mint.key();
+        reward_emissions.emissions_per_second = emissions_per_second;
+        reward_emissions.borrow_rewards = borrow_rewards;
+        reward_emissions.reward_index_last_updated_time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        msg!("Created Reward Emissions for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Reward Mint: {}", reward_emissions.reward_mint_address);
+        verbose_msg!("Borrow Rewards: {}", borrow_rewards);
+        verbose_msg!("Emissions Per Second: {}", emissions_per_second);
+
+        Ok(())
+    }
+
+    pub fn update_reward_emissions(ctx: Context<UpdateRewardEmissions>, emissions_per_second: u64) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let token_reserve = &ctx.accounts.token_reserve;
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+
+        //Emit everything owed at the old rate before the rate changes
+        let total_earning_amount = get_reward_total_earning_amount(reward_emissions, token_reserve);
+        update_reward_emissions_index(reward_emissions, total_earning_amount, Clock::get()?.unix_timestamp as u64)?;
+        reward_emissions.emissions_per_second = emissions_per_second;
+
+        msg!("Updated Reward Emissions for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Emissions Per Second: {}", emissions_per_second);
+
+        Ok(())
+    }
+
+    //Locks rewards as they're earned so they unlock linearly over the vesting duration. Rewards earned before the schedule was set aren't locked
+    pub fn set_reward_vesting_schedule(ctx: Context<SetRewardVestingSchedule>, vesting_duration_seconds: u64) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+        reward_emissions.has_vesting_schedule = true;
+
+        let reward_vesting_schedule = &mut ctx.accounts.reward_vesting_schedule;
+        reward_vesting_schedule.bump = ctx.bumps.reward_vesting_schedule;
+        reward_vesting_schedule.version = CURRENT_ACCOUNT_VERSION;
+        reward_vesting_schedule.reward_emissions_address = reward_emissions.key();
+        reward_vesting_schedule.vesting_duration_seconds = vesting_duration_seconds;
+
+        msg!("Set Reward Vesting Schedule for Token ID: {}", reward_emissions.token_id);
+        verbose_msg!("Vesting Duration Seconds: {}", vesting_duration_seconds);
+
+        Ok(())
+    }
+
+    //Pays out a Tab Account's unlocked rewards. A Tab Account earns rewards on its deposit, or its debt for borrow rewards, from its first claim onwards,
+    //so depositors and borrowers call this once with nothing to claim to start earning. Rewards since the last claim are forfeited when the tab's balance changes,
+    //so frontends put a claim in front of every deposit, withdraw, borrow, and repay
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, sub_market_index: u16, user_account_index: u8) -> Result<()> 
+    {
+        //Rewards can't be claimed around the vesting schedule by leaving it out
+        require!(!ctx.accounts.reward_emissions.has_vesting_schedule || ctx.accounts.reward_vesting_schedule.is_some(), LendingError::MissingRewardVestingSchedule);
+        let vesting_duration_seconds = ctx.accounts.reward_vesting_schedule.as_ref().map_or(0, |reward_vesting_schedule| reward_vesting_schedule.vesting_duration_seconds);
+
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+        let earning_amount = get_reward_earning_amount(reward_emissions, &ctx.accounts.lending_user_tab_account.load()?);
+        let balance_changed_time_stamp = ctx.accounts.lending_user_tab_account.load()?.balance_changed_time_stamp;
+        let total_earning_amount = get_reward_total_earning_amount(reward_emissions, &ctx.accounts.token_reserve);
+        update_reward_emissions_index(reward_emissions, total_earning_amount, time_stamp)?;
+
+        let lending_user_reward_account = &mut ctx.accounts.lending_user_reward_account;
+        if lending_user_reward_account.lending_user_reward_account_added == false
+        {
+            lending_user_reward_account.bump = ctx.bumps.lending_user_reward_account;
+            lending_user_reward_account.version = CURRENT_ACCOUNT_VERSION;
+            lending_user_reward_account.owner = ctx.accounts.signer.key();
+            lending_user_reward_account.user_account_index = user_account_index;
+            lending_user_reward_account.sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+            lending_user_reward_account.sub_market_index = sub_market_index;
+            lending_user_reward_account.reward_emissions_address = reward_emissions.key();
+            lending_user_reward_account.reward_index = reward_emissions.reward_index;
+            lending_user_reward_account.snap_shot_earning_amount = earning_amount;
+            lending_user_reward_account.snap_shot_time_stamp = time_stamp;
+            lending_user_reward_account.lending_user_reward_account_added = true;
+        }
+
+        let earned_amount = update_lending_user_rewards(lending_user_reward_account, reward_emissions.reward_index, earning_amount, balance_changed_time_stamp, vesting_duration_seconds, time_stamp)?;
+
+        //Whatever the reward vault can't cover stays claimable until the vault is topped up
+        let claimed_amount = lending_user_reward_account.claimable_reward_amount.min(ctx.accounts.reward_vault.amount);
+        lending_user_reward_account.claimable_reward_amount -= claimed_amount;
+        lending_user_reward_account.claimed_reward_amount += claimed_amount;
+        reward_emissions.claimed_amount += claimed_amount as u128;
+
+        if claimed_amount > 0
+        {
+            withdraw_reward_tokens_to_user(&ctx.accounts.reward_emissions,
+                &ctx.accounts.reward_vault.to_account_info(),
+                &ctx.accounts.user_reward_ata.to_account_info(),
+                &ctx.accounts.reward_mint,
+                &ctx.accounts.token_program,
+                claimed_amount)?;
+        }
+
+        let lending_user_reward_account = &ctx.accounts.lending_user_reward_account;
+
+        msg!("{} Claimed {} Reward Tokens at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        claimed_amount,
+        ctx.accounts.reward_emissions.token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index);
+
+        verbose_msg!("Earned Amount: {}", earned_amount);
+        verbose_msg!("Locked Amount: {}", lending_user_reward_account.locked_reward_amount);
+        verbose_msg!("Claimable Amount: {}", lending_user_reward_account.claimable_reward_amount);
+
+        emit!(RewardsClaimed
+        {
+            owner: ctx.accounts.signer.key(),
+            user_account_index,
+            token_id: ctx.accounts.reward_emissions.token_id,
+            sub_market_owner_address: ctx.accounts.sub_market_owner.key(),
+            sub_market_index,
+            reward_mint_address: ctx.accounts.reward_emissions.reward_mint_address,
+            earned_amount,
+            claimed_amount,
+            locked_amount: lending_user_reward_account.locked_reward_amount
+        });
+
+        Ok(())
+    }
+
+    //Lets a SubMarket owner emit their own reward tokens to their SubMarket's depositors. The owner funds it by sending reward tokens to the reward vault
+    pub fn create_sub_market_reward_boost(ctx: Context<CreateSubMarketRewardBoost>,
+        token_id: u8,
+        sub_market_index: u16,
+        emissions_per_second: u64
+    ) -> Result<()> 
+    {
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+        reward_emissions.bump = ctx.bumps.reward_emissions;
+        reward_emissions.version = CURRENT_ACCOUNT_VERSION;
+        reward_emissions.token_id = token_id;
+        reward_emissions.sub_market_address = ctx.accounts.sub_market.key();
+        reward_emissions.reward_mint_address = ctx.accounts.reward_mint.key();
+        reward_emissions.emissions_per_second = emissions_per_second;
+        reward_emissions.reward_index_last_updated_time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        msg!("Created SubMarket Reward Boost");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Reward Mint: {}", reward_emissions.reward_mint_address);
+        verbose_msg!("Emissions Per Second: {}", emissions_per_second);
+
+        Ok(())
+    }
+
+    //Changes a SubMarket reward boost's emission rate and pays the SubMarket owner back whatever reward tokens in the vault haven't been emitted yet.
+    //Withdrawing the whole unemitted balance with an emission rate of 0 winds the reward boost down
+    pub fn update_sub_market_reward_boost(ctx: Context<UpdateSubMarketRewardBoost>,
+        token_id: u8,
+        sub_market_index: u16,
+        emissions_per_second: u64,
+        withdraw_amount: u64
+    ) -> Result<()> 
+    {
+        let sub_market_deposited_amount = ctx.accounts.sub_market.load()?.deposited_amount;
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+
+        //Emit everything owed at the old rate before the rate changes
+        update_reward_emissions_index(reward_emissions, sub_market_deposited_amount, Clock::get()?.unix_timestamp as u64)?;
+        reward_emissions.emissions_per_second = emissions_per_second;
+
+        //Rewards already emitted belong to the depositors, even the ones not claimed yet
+        let owed_reward_amount = reward_emissions.emitted_amount.saturating_sub(reward_emissions.claimed_amount);
+        let unemitted_reward_amount = (ctx.accounts.reward_vault.amount as u128).saturating_sub(owed_reward_amount);
+        require!(withdraw_amount as u128 <= unemitted_reward_amount, LendingError::RewardBoostWithdrawTooLarge);
+
+        if withdraw_amount > 0
+        {
+            withdraw_reward_tokens_to_user(&ctx.accounts.reward_emissions,
+                &ctx.accounts.reward_vault.to_account_info(),
+                &ctx.accounts.sub_market_owner_reward_ata.to_account_info(),
+                &ctx.accounts.reward_mint,
+                &ctx.accounts.token_program,
+                withdraw_amount)?;
+        }
+
+        msg!("Updated SubMarket Reward Boost");
+        verbose_msg!("Token ID: {}", token_id);
+        verbose_msg!("SubMarket Index: {}", sub_market_index);
+        verbose_msg!("Owner: {}", ctx.accounts.signer.key());
+        verbose_msg!("Emissions Per Second: {}", emissions_per_second);
+        verbose_msg!("Withdraw Amount: {}", withdraw_amount);
+
+        Ok(())
+    }
+
+    //SubMarket reward boosts are claimed the same way as Token Reserve rewards, without a vesting schedule
+    pub fn claim_sub_market_rewards(ctx: Context<ClaimSubMarketRewards>, token_id: u8, sub_market_index: u16, user_account_index: u8) -> Result<()> 
+    {
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let deposited_amount = ctx.accounts.lending_user_tab_account.load()?.deposited_amount;
+        let balance_changed_time_stamp = ctx.accounts.lending_user_tab_account.load()?.balance_changed_time_stamp;
+        let sub_market_deposited_amount = ctx.accounts.sub_market.load()?.deposited_amount;
+        let reward_emissions = &mut ctx.accounts.reward_emissions;
+        update_reward_emissions_index(reward_emissions, sub_market_deposited_amount, time_stamp)?;
+
+        let lending_user_reward_account = &mut ctx.accounts.lending_user_reward_account;
+        if lending_user_reward_account.lending_user_reward_account_added == false
+        {
+            lending_user_reward_account.bump = ctx.bumps.lending_user_reward_account;
+            lending_user_reward_account.version = CURRENT_ACCOUNT_VERSION;
+            lending_user_reward_account.owner = ctx.accounts.signer.key();
+            lending_user_reward_account.user_account_index = user_account_index;
+            lending_user_reward_account.sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+            lending_user_reward_account.sub_market_index = sub_market_index;
+            lending_user_reward_account.reward_emissions_address = reward_emissions.key();
+            lending_user_reward_account.reward_index = reward_emissions.reward_index;
+            lending_user_reward_account.snap_shot_earning_amount = deposited_amount;
+            lending_user_reward_account.snap_shot_time_stamp = time_stamp;
+            lending_user_reward_account.lending_user_reward_account_added = true;
+        }
 
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+        let earned_amount = update_lending_user_rewards(lending_user_reward_account, reward_emissions.reward_index, deposited_amount, balance_changed_time_stamp, 0, time_stamp)?;
 
-            initialize_lending_user_account(
-                liquidator_lending_account,
-                ctx.bumps.liquidator_lending_account,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
+        //Whatever the reward vault can't cover stays claimable until the vault is topped up
+        let claimed_amount = lending_user_reward_account.claimable_reward_amount.min(ctx.accounts.reward_vault.amount);
+        lending_user_reward_account.claimable_reward_amount -= claimed_amount;
+        lending_user_reward_account.claimed_reward_amount += claimed_amount;
+        reward_emissions.claimed_amount += claimed_amount as u128;
+
+        if claimed_amount > 0
+        {
+            withdraw_reward_tokens_to_user(&ctx.accounts.reward_emissions,
+                &ctx.accounts.reward_vault.to_account_info(),
+                &ctx.accounts.user_reward_ata.to_account_info(),
+                &ctx.accounts.reward_mint,
+                &ctx.accounts.token_program,
+                claimed_amount)?;
         }
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if liquidator_repayment_tab_account.user_tab_account_added == false
+        let lending_user_reward_account = &ctx.accounts.lending_user_reward_account;
+
+        msg!("{} Claimed {} SubMarket Reward Tokens at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        claimed_amount,
+        token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index);
+
+        verbose_msg!("Earned Amount: {}", earned_amount);
+        verbose_msg!("Claimable Amount: {}", lending_user_reward_account.claimable_reward_amount);
+
+        emit!(RewardsClaimed
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                liquidator_lending_account,
-                liquidator_repayment_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_repayment_tab_account,
-                repayment_token_reserve.token_id,
-                repayment_sub_market_owner_address.key(),
-                repayment_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index
-            )?;
+            owner: ctx.accounts.signer.key(),
+            user_account_index,
+            token_id,
+            sub_market_owner_address: ctx.accounts.sub_market_owner.key(),
+            sub_market_index,
+            reward_mint_address: ctx.accounts.reward_emissions.reward_mint_address,
+            earned_amount,
+            claimed_amount,
+            locked_amount: lending_user_reward_account.locked_reward_amount
+        });
+
+        Ok(())
+    }
+
+    //Publishes the Merkle root of a retroactive distribution. The CEO funds it by sending the reward tokens to the distribution vault
+    pub fn create_merkle_distribution(ctx: Context<CreateMerkleDistribution>,
+        distribution_index: u32,
+        merkle_root: [u8; 32],
+        total_claimable_amount: u64
+    ) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        let merkle_distribution = &mut ctx.accounts.merkle_distribution;
+        merkle_distribution.bump = ctx.bumps.merkle_distribution;
+        merkle_distribution.version = CURRENT_ACCOUNT_VERSION;
+        merkle_distribution.distribution_index = distribution_index;
+        merkle_distribution.merkle_root = merkle_root;
+        merkle_distribution.reward_mint_address = ctx.accounts.reward_mint.key();
+        merkle_distribution.total_claimable_amount = total_claimable_amount;
+
+        msg!("Created Merkle Distribution: {}", distribution_index);
+        verbose_msg!("Reward Mint: {}", merkle_distribution.reward_mint_address);
+        verbose_msg!("Total Claimable Amount: {}", total_claimable_amount);
+
+        Ok(())
+    }
+
+    //Pays the signer their allocation once they prove it's in the distribution's Merkle tree. The claim receipt makes each allocation claimable once
+    pub fn claim_merkle_distribution(ctx: Context<ClaimMerkleDistribution>,
+        distribution_index: u32,
+        amount: u64,
+        merkle_proof: Vec<[u8; 32]>
+    ) -> Result<()> 
+    {
+        require!(merkle_proof.len() <= MAX_MERKLE_PROOF_LENGTH, LendingError::MerkleProofTooLong);
+
+        let merkle_distribution = &mut ctx.accounts.merkle_distribution;
+        require!(verify_merkle_proof(&merkle_distribution.merkle_root, &merkle_proof, &ctx.accounts.signer.key(), amount), LendingError::InvalidMerkleProof);
+
+        //A bad root can't pay out more than the CEO published for the whole distribution
+        let new_claimed_amount = merkle_distribution.claimed_amount.checked_add(amount).ok_or(LendingError::MerkleDistributionOverClaimed)?;
+        require!(new_claimed_amount <= merkle_distribution.total_claimable_amount, LendingError::MerkleDistributionOverClaimed);
+        merkle_distribution.claimed_amount = new_claimed_amount;
+        merkle_distribution.claim_count += 1;
+
+        let merkle_claim_receipt = &mut ctx.accounts.merkle_claim_receipt;
+        merkle_claim_receipt.bump = ctx.bumps.merkle_claim_receipt;
+        merkle_claim_receipt.version = CURRENT_ACCOUNT_VERSION;
+        merkle_claim_receipt.merkle_distribution_address = merkle_distribution.key();
+        merkle_claim_receipt.claimant = ctx.accounts.signer.key();
+        merkle_claim_receipt.claimed_amount = amount;
+        merkle_claim_receipt.claim_time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        withdraw_merkle_distribution_tokens_to_user(&ctx.accounts.merkle_distribution,
+            &ctx.accounts.distribution_vault.to_account_info(),
+            &ctx.accounts.user_reward_ata.to_account_info(),
+            &ctx.accounts.reward_mint,
+            &ctx.accounts.token_program,
+            amount)?;
+
+        msg!("{} Claimed {} Tokens from Merkle Distribution: {}", ctx.accounts.signer.key(), amount, distribution_index);
+
+        emit!(MerkleDistributionClaimed
+        {
+            distribution_index,
+            claimant: ctx.accounts.signer.key(),
+            reward_mint_address: ctx.accounts.merkle_distribution.reward_mint_address,
+            amount
+        });
+
+        Ok(())
+    }
+
+    pub fn create_staking_pool(ctx: Context<CreateStakingPool>, full_discount_staked_amount: u64, max_borrow_rate_discount_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        //Borrow rate discount can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(max_borrow_rate_discount_rate <= 10_000, LendingError::InvalidBorrowRateDiscountRate);
+
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.bump = ctx.bumps.staking_pool;
+        staking_pool.version = CURRENT_ACCOUNT_VERSION;
+        staking_pool.stake_mint_address = ctx.accounts.stake_mint.key();
+        staking_pool.full_discount_staked_amount = full_discount_staked_amount;
+        staking_pool.max_borrow_rate_discount_rate = max_borrow_rate_discount_rate;
+
+        msg!("Created Staking Pool for Stake Mint: {}", staking_pool.stake_mint_address);
+        verbose_msg!("Full Discount Staked Amount: {}", full_discount_staked_amount);
+        verbose_msg!("Max Borrow Rate Discount Rate: {:.2}%", max_borrow_rate_discount_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    //Users pick up the new discount the next time they stake or unstake
+    pub fn update_staking_pool(ctx: Context<UpdateStakingPool>, full_discount_staked_amount: u64, max_borrow_rate_discount_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        //Borrow rate discount can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(max_borrow_rate_discount_rate <= 10_000, LendingError::InvalidBorrowRateDiscountRate);
+
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.full_discount_staked_amount = full_discount_staked_amount;
+        staking_pool.max_borrow_rate_discount_rate = max_borrow_rate_discount_rate;
+
+        msg!("Updated Staking Pool");
+        verbose_msg!("Full Discount Staked Amount: {}", full_discount_staked_amount);
+        verbose_msg!("Max Borrow Rate Discount Rate: {:.2}%", max_borrow_rate_discount_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    //Locks the protocol token for a discount off the interest the Lending User Account's debt accrues.
+    //The Tab Accounts pick up the new discount at their next health snap shot
+    pub fn stake_tokens(ctx: Context<StakeTokens>, user_account_index: u8, amount: u64) -> Result<()> 
+    {
+        require!(amount > 0, LendingError::InvalidStakeAmount);
+
+        let staked_amount = deposit_tokens_into_stake_vault(&ctx.accounts.stake_vault.to_account_info(),
+            &ctx.accounts.user_stake_ata.to_account_info(),
+            &ctx.accounts.stake_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.signer,
+            amount)?;
+
+        let lending_user_stake = &mut ctx.accounts.lending_user_stake;
+        if lending_user_stake.version == 0
+        {
+            lending_user_stake.bump = ctx.bumps.lending_user_stake;
+            lending_user_stake.version = CURRENT_ACCOUNT_VERSION;
+            lending_user_stake.owner = ctx.accounts.signer.key();
+            lending_user_stake.user_account_index = user_account_index;
         }
-        if liquidator_liquidation_tab_account.user_tab_account_added == false
+        lending_user_stake.staked_amount += staked_amount;
+
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.total_staked_amount += staked_amount;
+
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.borrow_rate_discount_rate = get_borrow_rate_discount_rate(staking_pool, lending_user_stake.staked_amount);
+
+        msg!("{} staked {} tokens for Account Index: {}", ctx.accounts.signer.key(), staked_amount, user_account_index);
+        verbose_msg!("Staked Amount: {}", lending_user_stake.staked_amount);
+        verbose_msg!("Borrow Rate Discount Rate: {:.2}%", lending_user_account.borrow_rate_discount_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    //Unlocks staked tokens. The discount drops right away on the Lending User Account and on every one of its Tab Accounts, which are passed in as remaining accounts.
+    //Lowering the Tab Accounts' stamped discount here keeps it from being applied to interest accrued after the unstake by instructions that don't refresh the user's health first
+    pub fn unstake_tokens<'info>(ctx: Context<'info, UnstakeTokens<'info>>, user_account_index: u8, amount: u64) -> Result<()> 
+    {
+        let lending_user_stake = &mut ctx.accounts.lending_user_stake;
+        require!(amount > 0 && amount <= lending_user_stake.staked_amount, LendingError::InvalidStakeAmount);
+
+        lending_user_stake.staked_amount -= amount;
+
+        let staking_pool = &mut ctx.accounts.staking_pool;
+        staking_pool.total_staked_amount -= amount;
+
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        lending_user_account.borrow_rate_discount_rate = get_borrow_rate_discount_rate(staking_pool, lending_user_stake.staked_amount);
+
+        //Every registered Tab Account has to be passed in so none of them keeps the higher discount
+        require!(ctx.remaining_accounts.len() == lending_user_account.tab_account_count as usize, LendingError::MissingRemainingAccount);
+        let mut lowered_tab_account_flags: u32 = 0;
+        for tab_account_serialized in ctx.remaining_accounts.iter()
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                liquidator_lending_account,
-                liquidator_liquidation_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_liquidation_tab_account,
-                liquidation_token_reserve.token_id,
-                liquidation_sub_market_owner_address.key(),
-                liquidation_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index
-            )?;
+            let tab_account_registry_index = lending_user_account.tab_account_addresses
+                .iter()
+                .position(|tab_account_address| *tab_account_address == tab_account_serialized.key())
+                .ok_or(LendingError::UnregisteredTabAccount)?;
+            let tab_account_lowered_flag = 1u32 << tab_account_registry_index;
+            require!(lowered_tab_account_flags & tab_account_lowered_flag == 0, LendingError::DuplicateTabAccount);
+            lowered_tab_account_flags |= tab_account_lowered_flag;
+
+            let mut lending_user_tab_account = load_zero_copy_account_mut::<Structs::LendingUserTabAccount>(tab_account_serialized)?;
+            let borrow_rate_discount_rate = u16::from_le_bytes(lending_user_tab_account.borrow_rate_discount_rate).min(lending_user_account.borrow_rate_discount_rate);
+            lending_user_tab_account.borrow_rate_discount_rate = borrow_rate_discount_rate.to_le_bytes();
         }
 
-        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
-        if liquidator_repayment_monthly_statement_account.monthly_statement_account_added == false
+        withdraw_tokens_from_stake_vault_to_user(&ctx.accounts.staking_pool,
+            &ctx.accounts.stake_vault.to_account_info(),
+            &ctx.accounts.user_stake_ata.to_account_info(),
+            &ctx.accounts.stake_mint,
+            &ctx.accounts.token_program,
+            amount)?;
+
+        msg!("{} unstaked {} tokens for Account Index: {}", ctx.accounts.signer.key(), amount, user_account_index);
+        verbose_msg!("Staked Amount: {}", ctx.accounts.lending_user_stake.staked_amount);
+        verbose_msg!("Borrow Rate Discount Rate: {:.2}%", ctx.accounts.lending_user_account.borrow_rate_discount_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    pub fn create_e_mode_category(ctx: Context<CreateEModeCategory>, asset_category: u8, loan_to_value_rate: u16, liquidation_threshold_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        require!(asset_category != 0, LendingError::InvalidAssetCategory);
+        check_e_mode_category_rates(loan_to_value_rate, liquidation_threshold_rate)?;
+
+        let e_mode_category = &mut ctx.accounts.e_mode_category;
+        e_mode_category.bump = ctx.bumps.e_mode_category;
+        e_mode_category.version = CURRENT_ACCOUNT_VERSION;
+        e_mode_category.asset_category = asset_category;
+        e_mode_category.loan_to_value_rate = loan_to_value_rate;
+        e_mode_category.liquidation_threshold_rate = liquidation_threshold_rate;
+
+        msg!("Created E-Mode Category: {}", asset_category);
+        verbose_msg!("Loan To Value Rate: {:.2}%", loan_to_value_rate as f64 / 100.0);
+        verbose_msg!("Liquidation Threshold Rate: {:.2}%", liquidation_threshold_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    //Lending Users in the category pick up the new limits at their next finished health refresh
+    pub fn update_e_mode_category(ctx: Context<UpdateEModeCategory>, asset_category: u8, loan_to_value_rate: u16, liquidation_threshold_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        //While the timelock is on, E-Mode category changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
+        check_e_mode_category_rates(loan_to_value_rate, liquidation_threshold_rate)?;
+
+        let e_mode_category = &mut ctx.accounts.e_mode_category;
+        e_mode_category.loan_to_value_rate = loan_to_value_rate;
+        e_mode_category.liquidation_threshold_rate = liquidation_threshold_rate;
+
+        msg!("Updated E-Mode Category: {}", asset_category);
+        verbose_msg!("Loan To Value Rate: {:.2}%", loan_to_value_rate as f64 / 100.0);
+        verbose_msg!("Liquidation Threshold Rate: {:.2}%", liquidation_threshold_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    //Opts the Lending User Account into an E-Mode asset category, or out of E-Mode with asset category 0.
+    //E-Mode stays off until the next finished health refresh finds all of the account's collateral and debt in the category,
+    //so an account with debt has to be healthy under the regular Loan To Value to switch
+    pub fn set_e_mode_category(ctx: Context<SetEModeCategory>, user_account_index: u8, asset_category: u8) -> Result<()> 
+    {
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let clock_slot = Clock::get()?.slot;
+
+        if lending_user_account.total_borrowed_usd_value > 0
         {
-            initialize_lending_user_monthly_statement_account(
-                liquidator_repayment_monthly_statement_account,
-                liquidator_repayment_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_repayment_monthly_statement_account,
-                repayment_token_reserve.token_id,
-                repayment_sub_market_owner_address,
-                repayment_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-            )?;
+            //This set_e_mode_category function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt
+            require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+
+            //Multiply before dividing to help keep precision
+            let max_borrowed_usd_value = (lending_user_account.total_deposited_usd_value * LOAN_TO_VALUE_RATE) / DECIMAL_SCALING;
+            require!(max_borrowed_usd_value >= lending_user_account.total_borrowed_usd_value, LendingError::LiquidationExposure);
         }
-        if liquidator_liquidation_monthly_statement_account.monthly_statement_account_added == false
+
+        if asset_category != 0
         {
-            initialize_lending_user_monthly_statement_account(
-                liquidator_liquidation_monthly_statement_account,
-                liquidator_liquidation_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_liquidation_monthly_statement_account,
-                liquidation_token_reserve.token_id,
-                liquidation_sub_market_owner_address,
-                liquidation_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-            )?;
+            require!(ctx.accounts.e_mode_category.is_some(), LendingError::MissingEModeCategoryAccount);
         }
 
-        //Update interest earned and accrued for the liquidator
-        update_user_previous_interest_earned(
-            repayment_token_reserve,
-            &mut repayment_sub_market,
-            liquidator_repayment_tab_account,
-            liquidator_repayment_monthly_statement_account
-        )?;
-        update_user_previous_interest_accrued(
-            repayment_token_reserve,
-            &mut repayment_sub_market,
-            liquidator_repayment_tab_account,
-            liquidator_repayment_monthly_statement_account
-        )?;
-        update_user_previous_interest_earned(
-            liquidation_token_reserve,
-            &mut liquidation_sub_market,
-            liquidator_liquidation_tab_account,
-            liquidator_liquidation_monthly_statement_account
-        )?;
-        update_user_previous_interest_accrued(
-            liquidation_token_reserve,
-            &mut liquidation_sub_market,
-            liquidator_liquidation_tab_account,
-            liquidator_liquidation_monthly_statement_account
-        )?;
+        lending_user_account.e_mode_category = asset_category;
+        lending_user_account.e_mode_active = false;
 
-        //Repay Liquidati's Debt
-        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_repayment_ata.to_account_info().data.borrow()[..])?;
-        let should_close = user_ata_data.amount == 0;
-        deposit_tokens_into_token_reserve_from_user(
-            ctx.accounts.repayment_mint.key(),
-            &repayment_token_reserve_ata_info,
-            &ctx.accounts.liquidator_repayment_ata.to_account_info(),
-            &ctx.accounts.repayment_mint,
-            &ctx.accounts.repayment_token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            repayment_amount,
-            should_close
-        )?;
+        //Restart any unfinished refresh so it can't finish with eligibility worked out for the old category
+        lending_user_account.refresh_clock_slot = 0;
+
+        msg!("{} set the E-Mode Category to {} for Account Index: {}", ctx.accounts.signer.key(), asset_category, user_account_index);
+
+        Ok(())
+    }
+
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>, insurance_fund_share_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
+
+        //Insurance Fund share of interest earned can't be greater than 10%, 0.1 in decimal form, 1,000 in fixed point notation
+        require!(insurance_fund_share_rate <= 1_000, LendingError::InvalidInsuranceFundShareRate);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest before the share rate changes
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        token_reserve.insurance_fund_share_rate = insurance_fund_share_rate;
+
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        insurance_fund.bump = ctx.bumps.insurance_fund;
+        insurance_fund.version = CURRENT_ACCOUNT_VERSION;
+        insurance_fund.token_id = token_reserve.token_id;
+        insurance_fund.token_mint_address = token_reserve.token_mint_address;
+
+        msg!("Initialized Insurance Fund for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Insurance Fund Share Rate: {:.2}%", insurance_fund_share_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    pub fn update_insurance_fund_share_rate(ctx: Context<UpdateInsuranceFundShareRate>, insurance_fund_share_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
+
+        //Insurance Fund share of interest earned can't be greater than 10%, 0.1 in decimal form, 1,000 in fixed point notation
+        require!(insurance_fund_share_rate <= 1_000, LendingError::InvalidInsuranceFundShareRate);
+
+        //While the timelock is on, Insurance Fund share rate changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
+
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest before the share rate changes
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        token_reserve.insurance_fund_share_rate = insurance_fund_share_rate;
 
-        //Get USD value of Liquidation Token
-        let liquidation_token_conversion_number = BASE_10_INT.pow(liquidation_token_reserve.token_decimal_amount as u32); 
-        let liquidation_token_usd_value = get_verified_token_price(&temp_price_account.data, liquidation_token_reserve.token_id)?;
+        msg!("Updated Insurance Fund Share Rate for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Insurance Fund Share Rate: {:.2}%", insurance_fund_share_rate as f64 / 100.0);
 
-        let amount_to_be_liquidated = ((repayment_amount_usd_value * liquidation_token_conversion_number) / liquidation_token_usd_value) as u64;
+        Ok(())
+    }
 
-        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
-        //Multiply before dividing to help keep precision
-        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+    //Writes off the debt left behind on an insolvent account after all of its collateral has been liquidated.
+    //The debt is moved into the Token Reserve and SubMarket bad debt amounts where it can be covered by the Insurance Fund or socialized.
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) for the insolvent account
+    pub fn write_off_bad_debt(ctx: Context<WriteOffBadDebt>,
+        sub_market_index: u16,
+        liquidati_account_index: u8
+    ) -> Result<()> 
+    {
+        let liquidati_monthly_statements_disabled = ctx.accounts.liquidati_lending_account.monthly_statements_disabled;
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
 
-        //Take a 1% liquidation fee
-        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+        let lending_protocol = &mut ctx.accounts.lending_protocol;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let liquidati_lending_account = &ctx.accounts.liquidati_lending_account;
+        let liquidati_tab_account = &mut ctx.accounts.liquidati_tab_account.load_mut()?;
+        let mut liquidati_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidati_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidati_monthly_statement_account, &mut liquidati_monthly_statement_account_placeholder, liquidati_monthly_statements_disabled)?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
 
-        //Check for underflow if liquidation isn't profitable
-        if liquidati_liquidation_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
-        {
-            //Take a 1% liquidation fee
-            liquidation_fee_amount = liquidati_liquidation_tab_account.deposited_amount / 100;
-            //Give remainder to liquidator
-            liquidation_amount_with_7_percent_bonus = liquidati_liquidation_tab_account.deposited_amount - liquidation_fee_amount;
-        }
+        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
 
-        //Update Repayment Values
-        repayment_sub_market.borrowed_amount -= repayment_amount as u128;
-        repayment_sub_market.repaid_debt_amount += repayment_amount as u128;
-        repayment_token_reserve.borrowed_amount -= repayment_amount as u128;
-        repayment_token_reserve.repaid_debt_amount += repayment_amount as u128;
-        liquidati_repayment_tab_account.borrowed_amount -= repayment_amount;
-        liquidator_repayment_tab_account.repaid_debt_amount += repayment_amount;
-        liquidati_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
-        liquidator_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
-        liquidati_repayment_monthly_statement_account.snap_shot_debt_amount = liquidati_repayment_tab_account.borrowed_amount;
+        //Only debt that has no collateral left behind it can be written off
+        require!(liquidati_lending_account.total_deposited_usd_value == 0 && liquidati_lending_account.total_borrowed_usd_value > 0, LendingError::NotBadDebt);
 
-        //Update Liquidation and Fee Values
-        liquidation_sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        liquidation_sub_market.liquidated_amount += liquidation_fee_amount as u128;
-        liquidation_sub_market.deposited_amount -= liquidation_fee_amount as u128;
-        liquidation_sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
-        liquidation_token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        liquidation_token_reserve.liquidated_amount += liquidation_fee_amount as u128;
-        liquidation_token_reserve.deposited_amount -= liquidation_fee_amount as u128;
-        liquidation_token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
-        liquidati_liquidation_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_tab_account.deposited_amount -= liquidation_fee_amount;
-        liquidati_liquidation_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_tab_account.liquidated_amount += liquidation_fee_amount;
-        liquidator_liquidation_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_liquidation_tab_account.fees_generated_amount += liquidation_fee_amount;
-        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
-        liquidati_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidati_liquidation_tab_account.deposited_amount;
-        liquidator_liquidation_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_liquidation_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
-        if send_reward_to_wallet
-        {
-            let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_liquidation_ata.to_account_info().data.borrow()[..])?;
-            let balance_after_withdrawal = user_token_data.amount.saturating_sub(liquidation_amount_with_7_percent_bonus);
-            let should_close = balance_after_withdrawal == 0;
-            withdraw_tokens_from_token_reserve_to_user(
-                ctx.accounts.liquidation_mint.key(),
-                liquidation_token_reserve,
-                &liquidation_token_reserve_ata_info.clone(),
-                &ctx.accounts.liquidator_liquidation_ata.to_account_info(),
-                &ctx.accounts.liquidation_mint,
-                &ctx.accounts.liquidation_token_program,
-                &ctx.accounts.signer,
-                &ctx.accounts.system_program,
-                liquidation_amount_with_7_percent_bonus,
-                should_close
-            )?;
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            liquidati_tab_account,
+            liquidati_monthly_statement_account
+        )?;
 
-            liquidation_sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
-            liquidation_token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
+        let bad_debt_amount = liquidati_tab_account.borrowed_amount;
+
+        //Move the debt off the books and into bad debt
+        token_reserve.borrowed_amount -= bad_debt_amount as u128;
+        token_reserve.bad_debt_amount += bad_debt_amount as u128;
+        sub_market.borrowed_amount -= bad_debt_amount as u128;
+        sub_market.bad_debt_amount += bad_debt_amount as u128;
+        liquidati_tab_account.borrowed_amount = 0;
+        liquidati_monthly_statement_account.snap_shot_debt_amount = 0;
+
+        //Start a new bad debt window once the current one has run its course
+        if time_stamp >= token_reserve.bad_debt_window_start_time_stamp + lending_protocol.bad_debt_window_seconds
+        {
+            token_reserve.bad_debt_window_start_time_stamp = time_stamp;
+            token_reserve.bad_debt_window_amount = 0;
         }
-        else
+        token_reserve.bad_debt_window_amount += bad_debt_amount as u128;
+
+        //Trip the circuit breaker and pause new borrows if the window's bad debt is more than the configured share of the Insurance Fund. A Token Reserve without an Insurance Fund has nothing to cover it
+        let insurance_fund_balance_amount = ctx.accounts.insurance_fund.as_ref().map_or(0, |insurance_fund| insurance_fund.balance_amount);
+        if lending_protocol.bad_debt_circuit_breaker_rate > 0 &&
+            token_reserve.bad_debt_window_amount * DECIMAL_SCALING > insurance_fund_balance_amount * lending_protocol.bad_debt_circuit_breaker_rate as u128
         {
-            liquidator_liquidation_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
-            liquidator_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidator_liquidation_tab_account.deposited_amount;
+            if !lending_protocol.borrows_paused
+            {
+                lending_protocol.borrows_paused = true;
+
+                emit!(Structs::BadDebtCircuitBreakerTripped
+                {
+                    token_id: token_reserve.token_id,
+                    bad_debt_window_amount: token_reserve.bad_debt_window_amount,
+                    insurance_fund_balance_amount,
+                    bad_debt_circuit_breaker_rate: lending_protocol.bad_debt_circuit_breaker_rate,
+                    time_stamp
+                });
+            }
+
+            msg!("🚨 Bad Debt Circuit Breaker tripped, new borrows are paused");
         }
 
-        //Refund Oracle price account fees back to Oracle
-        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
-        
-        //Update Stat Listener
-        lending_stats.liquidations += 1;
-        
-        //Update Repayment Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(repayment_token_reserve)?;
-        repayment_sub_market.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
-        repayment_sub_market.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
-        liquidati_repayment_tab_account.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
-        liquidati_repayment_tab_account.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
-        liquidator_repayment_tab_account.supply_interest_change_index = repayment_token_reserve.supply_interest_change_index;
-        liquidator_repayment_tab_account.borrow_interest_change_index = repayment_token_reserve.borrow_interest_change_index;
+        sync_token_reserve_active_user_counts(token_reserve, liquidati_tab_account);
 
-        //Update Liquidation Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(liquidation_token_reserve)?;
-        liquidation_sub_market.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
-        liquidation_sub_market.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
-        liquidati_liquidation_tab_account.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
-        liquidati_liquidation_tab_account.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
-        liquidator_liquidation_tab_account.supply_interest_change_index = liquidation_token_reserve.supply_interest_change_index;
-        liquidator_liquidation_tab_account.borrow_interest_change_index = liquidation_token_reserve.borrow_interest_change_index;
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidati_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidati_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
 
         //Update last activity on accounts
-        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
-        repayment_token_reserve.last_lending_activity_amount = repayment_amount;
-        repayment_token_reserve.last_lending_activity_type = Activity::Repay as u8;
-        liquidation_token_reserve.last_lending_activity_amount = liquidation_amount;
-        liquidation_token_reserve.last_lending_activity_type = Activity::Liquidate as u8;
-        repayment_sub_market.last_lending_activity_amount = repayment_amount;
-        repayment_sub_market.last_lending_activity_type = Activity::Repay as u8;
-        repayment_sub_market.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
-        liquidation_sub_market.last_lending_activity_amount = liquidation_amount;
-        liquidation_sub_market.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidation_sub_market.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_time_stamp = repayment_token_reserve.last_lending_activity_time_stamp;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_time_stamp = liquidation_token_reserve.last_lending_activity_time_stamp;
-        
-        //Save changes to passed in remaining accounts
-        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
-        repayment_sub_market.serialize(&mut &mut repayment_sub_market_account_serialized.data.borrow_mut()[8..])?;
-        liquidation_sub_market.serialize(&mut &mut liquidation_sub_market_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_repayment_tab_account.serialize(&mut &mut liquidati_repayment_tab_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_liquidation_tab_account.serialize(&mut &mut liquidati_liquidation_tab_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_repayment_monthly_statement_account.serialize(&mut &mut liquidati_repayment_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_liquidation_monthly_statement_account.serialize(&mut &mut liquidati_liquidation_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        
-        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+        token_reserve.last_lending_activity_amount = bad_debt_amount;
+        token_reserve.last_lending_activity_type = Activity::WriteOffBadDebt as u8;
+        sub_market.last_lending_activity_amount = bad_debt_amount;
+        sub_market.last_lending_activity_type = Activity::WriteOffBadDebt as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidati_monthly_statement_account.last_lending_activity_amount = bad_debt_amount;
+        liquidati_monthly_statement_account.last_lending_activity_type = Activity::WriteOffBadDebt as u8;
+        liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
 
-        msg!("Repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        repayment_token_reserve.token_id,
-        repayment_sub_market_owner_address.key(),
-        repayment_sub_market_index);
+        verbose_msg!("{} wrote off the bad debt of {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
 
-        msg!("Liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        liquidation_token_reserve.token_id,
-        liquidation_sub_market_owner_address.key(),
-        liquidation_sub_market_index);
+        verbose_msg!("Wrote off bad debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, Amount: {}",
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index,
+        bad_debt_amount);
+
+        verbose_msg!("LiquidatiAccountIndex: {}", liquidati_account_index);
 
         Ok(())
     }
 
-    //This liquidation is for when the repayment and liquidation tokens are the same
-    pub fn liquidate_account_same_token(ctx: Context<LiquidateAccountSameToken>,
-        repayment_sub_market_index: u16,
-        liquidation_sub_market_index: u16,
-        liquidati_account_index: u8,
-        liquidator_account_index: u8,
-        amount_to_repay: u64,
-        repay_max: bool,
-        paying_off_insolvent_account: bool,
-        send_reward_to_wallet: bool,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()>
+    //Lets the Solvency Treasurer use the Insurance Fund to cover bad debt recorded by write_off_bad_debt
+    pub fn deploy_insurance_fund(ctx: Context<DeployInsuranceFund>,
+        sub_market_index: u16,
+        amount: u64,
+        cover_max: bool
+    ) -> Result<()> 
     {
-        let lending_protocol = &ctx.accounts.lending_protocol;
-        let price_validator = &ctx.accounts.price_validator;
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
         let token_reserve = &mut ctx.accounts.token_reserve;
-        let liquidati_lending_account = &mut ctx.accounts.liquidati_lending_account;
-        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
-        let liquidator_repayment_tab_account = &mut ctx.accounts.liquidator_repayment_tab_account;
-        let liquidator_liquidation_tab_account = &mut ctx.accounts.liquidator_liquidation_tab_account;
-        let liquidator_repayment_monthly_statement_account = &mut ctx.accounts.liquidator_repayment_monthly_statement_account;
-        let liquidator_liquidation_monthly_statement_account = &mut ctx.accounts.liquidator_liquidation_monthly_statement_account;
-        let clock_slot = Clock::get()?.slot;
+        let insurance_fund = &mut ctx.accounts.insurance_fund;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
 
-        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        //Sweep the Insurance Fund share of interest earned into the fund balance
+        insurance_fund.balance_amount += token_reserve.unswept_insurance_fund_amount;
+        insurance_fund.funded_amount += token_reserve.unswept_insurance_fund_amount;
+        token_reserve.unswept_insurance_fund_amount = 0;
 
-        let repayment_sub_market_owner_address = ctx.accounts.repayment_sub_market_owner.key();
-        let liquidation_sub_market_owner_address = ctx.accounts.liquidation_sub_market_owner.key();
-        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+        let cover_amount;
+        if cover_max
+        {
+            cover_amount = std::cmp::min(sub_market.bad_debt_amount, insurance_fund.balance_amount);
+        }
+        else
+        {
+            cover_amount = amount as u128;
+        }
 
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        //You can't cover more than the SubMarket's bad debt
+        require!(sub_market.bad_debt_amount >= cover_amount, LendingError::TooManyFunds);
 
-        //Validate Accounts
+        //You can't cover more bad debt than the Insurance Fund holds
+        require!(insurance_fund.balance_amount >= cover_amount, LendingError::InsufficientInsuranceFund);
 
-        ////////////////////////////
-        //Oracle Price Data
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+        //The fund's tokens are already in the Token Reserve ATA, so covering the bad debt is just moving the amount from the fund balance back into the reserve's liquidity
+        insurance_fund.balance_amount -= cover_amount;
+        insurance_fund.deployed_amount += cover_amount;
+        insurance_fund.deployment_count += 1;
+        token_reserve.bad_debt_amount -= cover_amount;
+        sub_market.bad_debt_amount -= cover_amount;
 
-        ///////////////
-        //Lending Stats
-        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = cover_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::DeployInsuranceFund as u8;
+        sub_market.last_lending_activity_amount = cover_amount as u64;
+        sub_market.last_lending_activity_type = Activity::DeployInsuranceFund as u8;
 
-        /////////////////////////////
-        //Repayment SubMarket Account
-        let repayment_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut repayment_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-            repayment_sub_market_account_serialized,
-            token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index)?;
+        msg!("{} covered bad debt with the Insurance Fund at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, Amount: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index,
+        cover_amount);
 
-        ///////////////////////////////
-        //Liquidation SubMarket Account
-        let liquidation_sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidation_sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-            liquidation_sub_market_account_serialized,
-            token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index)?;
+        Ok(())
+    }
 
-        /////////////////////////////////
-        //Liquidati Repayment Tab Account
-        let liquidati_repayment_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_repayment_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-            liquidati_repayment_tab_account_serialized,
-            token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+    //Once the Insurance Fund is exhausted, the SubMarket's remaining bad debt is pro-rated across all of the Token Reserve's depositors by lowering the supply index.
+    //Depositor balances are lowered the next time their tab is updated, the same way interest earned is applied.
+    pub fn socialize_loss(ctx: Context<SocializeLoss>, sub_market_index: u16) -> Result<()> 
+    {
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let insurance_fund = &ctx.accounts.insurance_fund;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        //Losses can only be socialized after the Insurance Fund has nothing left to cover them with
+        require!(insurance_fund.balance_amount == 0 && token_reserve.unswept_insurance_fund_amount == 0, LendingError::InsuranceFundNotExhausted);
+        require!(sub_market.bad_debt_amount > 0 && token_reserve.deposited_amount > 0, LendingError::NotBadDebt);
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
-        ///////////////////////////////////
-        //Liquidati Liquidation Tab Account
-        let liquidati_liquidation_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_liquidation_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-            liquidati_liquidation_tab_account_serialized,
-            token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        //Depositors can't lose more than what has been deposited
+        let loss_amount = std::cmp::min(sub_market.bad_debt_amount, token_reserve.deposited_amount);
+
+        //New Supply Index = Old Supply Index * (Deposited Amount - Loss) / Deposited Amount
+        //The Token Reserve deposited amount doesn't include interest that hasn't been applied to tabs yet, so this slightly over-applies the loss rather than leaving any of it behind
+        //Use ra_solana_math library FixedPoint for fixed point math. Multiply before dividing to help keep precision
+        let old_supply_interest_index_fp = FixedPoint::from_scaled_u128(token_reserve.supply_interest_change_index);
+        let remaining_deposited_amount_fp = FixedPoint::from_int((token_reserve.deposited_amount - loss_amount) as u64);
+        let deposited_amount_fp = FixedPoint::from_int(token_reserve.deposited_amount as u64);
+        token_reserve.supply_interest_change_index = old_supply_interest_index_fp.mul(&remaining_deposited_amount_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+            .div(&deposited_amount_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+            .value.as_u128();
+        token_reserve.bad_debt_amount -= loss_amount;
+        token_reserve.socialized_loss_amount += loss_amount;
+        sub_market.bad_debt_amount -= loss_amount;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
 
-        ///////////////////////////////////////////////
-        //Liquidati Repayment Monthly Statement Account
-        let liquidati_repayment_monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_repayment_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-            liquidati_repayment_monthly_statement_account_serialized,
-            lending_protocol.current_statement_month,
-            lending_protocol.current_statement_year,
-            token_reserve.token_id,
-            repayment_sub_market_owner_address,
-            repayment_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = loss_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::SocializeLoss as u8;
+        sub_market.last_lending_activity_amount = loss_amount as u64;
+        sub_market.last_lending_activity_type = Activity::SocializeLoss as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
 
-        ///////////////////////////////////////////////
-        //Liquidati Liquidation Monthly Statement Account
-        let liquidati_liquidation_monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_liquidation_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-            liquidati_liquidation_monthly_statement_account_serialized,
-            lending_protocol.current_statement_month,
-            lending_protocol.current_statement_year,
-            token_reserve.token_id,
-            liquidation_sub_market_owner_address,
-            liquidation_sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        msg!("{} socialized bad debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, Amount: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index,
+        loss_amount);
 
-        let repayment_amount;
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+        verbose_msg!("New Supply Index: {}", token_reserve.supply_interest_change_index);
 
-        //Get USD value of Repayment Amount
-        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
-        let token_usd_value = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
-        let mut repayment_amount_usd_value = 0;
+        Ok(())
+    }
 
-        //Check if Account is liquidatable and set repayment_amount
-        if paying_off_insolvent_account
-        {
-            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+    //Lets the Solvency Treasurer seize the remaining collateral of an insolvent account that liquidators won't take at the standard bonus.
+    //The collateral is held in its Token Reserve ATA and sold over time by bid_collateral_auction to recapitalize the proceeds token's Insurance Fund.
+    //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) for the insolvent account
+    pub fn seize_collateral_for_auction(ctx: Context<SeizeCollateralForAuction>,
+        sub_market_index: u16,
+        liquidati_account_index: u8,
+        duration_seconds: u64,
+        max_discount_rate: u16
+    ) -> Result<()> 
+    {
+        let liquidati_monthly_statements_disabled = ctx.accounts.liquidati_lending_account.monthly_statements_disabled;
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
 
-            if repay_max
-            {
-                repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
-                repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
-                
-                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
-                //Use saturating_sub to safely deduct the value
-                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
-                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
-                    .total_borrowed_usd_value
-                    .saturating_sub(repayment_amount_usd_value);
-            }
-            else
-            {
-                if amount_to_repay > liquidati_repayment_tab_account.borrowed_amount
-                {
-                    //Can't pay more debt than the user has accumulated
-                    repayment_amount = liquidati_repayment_tab_account.borrowed_amount;
-                }
-                else
-                {
-                    repayment_amount = amount_to_repay;
-                }  
-            }
-        }
-        else
-        {
-            //Multiply before dividing to help keep precision
-            let eighty_percent_of_liquidati_deposited_usd_value = (liquidati_lending_account.total_deposited_usd_value * 80) / 100;
+        //Auction max discount can't be greater than 50%, 0.5 in decimal form, 5,000 in fixed point notation
+        require!(duration_seconds > 0 && max_discount_rate <= 5_000, LendingError::InvalidAuctionSettings);
 
-            //You can't liquidate an account whose borrow liabilities aren't 80% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= eighty_percent_of_liquidati_deposited_usd_value, LendingError::NotLiquidatable);
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let proceeds_insurance_fund = &ctx.accounts.proceeds_insurance_fund;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let liquidati_lending_account = &mut ctx.accounts.liquidati_lending_account;
+        let liquidati_tab_account = &mut ctx.accounts.liquidati_tab_account.load_mut()?;
+        let mut liquidati_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let liquidati_monthly_statement_account = get_lending_user_monthly_statement_account(&mut ctx.accounts.liquidati_monthly_statement_account, &mut liquidati_monthly_statement_account_placeholder, liquidati_monthly_statements_disabled)?;
+        let collateral_auction = &mut ctx.accounts.collateral_auction;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
 
-            //Multiply before dividing to help keep precision
-            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 50) / 100;
+        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
 
-            if repay_max
-            {
-                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
-            }
-            else
-            {
-                repayment_amount = amount_to_repay;
-            }
+        //You can't seize collateral from an account whose borrow liabilities aren't 100% or more of their deposited collateral
+        require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
 
-            //You can't repay more than 50% of a liquidati's debt position
-            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
-        }
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
-        if repayment_amount_usd_value == 0
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            liquidati_tab_account,
+            liquidati_monthly_statement_account
+        )?;
+
+        let seized_amount = liquidati_tab_account.deposited_amount;
+        require!(seized_amount > 0, LendingError::InsufficientFunds);
+
+        //Populate Collateral Auction if being newly initialized
+        if collateral_auction.collateral_auction_added == false
         {
-            repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+            collateral_auction.bump = ctx.bumps.collateral_auction;
+            collateral_auction.version = CURRENT_ACCOUNT_VERSION;
+            collateral_auction.collateral_token_id = token_reserve.token_id;
+            collateral_auction.collateral_mint_address = token_reserve.token_mint_address;
+            collateral_auction.proceeds_token_id = proceeds_insurance_fund.token_id;
+            collateral_auction.proceeds_mint_address = proceeds_insurance_fund.token_mint_address;
+            collateral_auction.collateral_auction_added = true;
         }
 
-        //Multiply before dividing to help keep precision
-        let ten_percent_of_borrowed_amount = (liquidati_repayment_tab_account.borrowed_amount * 10) / 100;
+        //Restart the auction so newly seized collateral starts at the full oracle price
+        collateral_auction.start_time_stamp = time_stamp;
+        collateral_auction.duration_seconds = duration_seconds;
+        collateral_auction.max_discount_rate = max_discount_rate;
+        collateral_auction.remaining_amount += seized_amount;
+        collateral_auction.seized_amount += seized_amount as u128;
+
+        //Move the collateral off the books. The tokens stay in the Token Reserve ATA until they are sold
+        token_reserve.deposited_amount -= seized_amount as u128;
+        token_reserve.auction_collateral_amount += seized_amount as u128;
+        token_reserve.liquidated_amount += seized_amount as u128;
+        sub_market.deposited_amount -= seized_amount as u128;
+        sub_market.liquidated_amount += seized_amount as u128;
+        liquidati_tab_account.deposited_amount -= seized_amount;
+        liquidati_tab_account.liquidated_amount += seized_amount;
+        liquidati_monthly_statement_account.monthly_liquidated_amount += seized_amount;
+        liquidati_monthly_statement_account.snap_shot_balance_amount = liquidati_tab_account.deposited_amount;
+        invalidate_cached_user_health(liquidati_lending_account);
 
-        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
-        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
-        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+        sync_token_reserve_active_user_counts(token_reserve, liquidati_tab_account);
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if liquidator_lending_account.lending_user_account_added == false
-        {
-            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
-            if let Some(new_account_name) = account_name
-            {
-                if !new_account_name.is_empty()
-                {
-                    new_account_name_to_use = new_account_name;
-                }
-            }
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        liquidati_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        liquidati_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
 
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = seized_amount;
+        token_reserve.last_lending_activity_type = Activity::Liquidate as u8;
+        sub_market.last_lending_activity_amount = seized_amount;
+        sub_market.last_lending_activity_type = Activity::Liquidate as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        liquidati_monthly_statement_account.last_lending_activity_amount = seized_amount;
+        liquidati_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
+        liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
 
-            initialize_lending_user_account(
-                liquidator_lending_account,
-                ctx.bumps.liquidator_lending_account,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
-        }
+        msg!("{} seized collateral from {} for auction", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if liquidator_repayment_tab_account.user_tab_account_added == false
-        {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                liquidator_lending_account,
-                liquidator_repayment_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_repayment_tab_account,
-                token_reserve.token_id,
-                repayment_sub_market_owner_address.key(),
-                repayment_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index
-            )?;
-        }
-        if liquidator_liquidation_tab_account.user_tab_account_added == false
-        {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                liquidator_lending_account,
-                liquidator_liquidation_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_liquidation_tab_account,
-                token_reserve.token_id,
-                liquidation_sub_market_owner_address.key(),
-                liquidation_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index
-            )?;
-        }
+        verbose_msg!("Seized collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, Amount: {}",
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index,
+        seized_amount);
 
-        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
-        if liquidator_repayment_monthly_statement_account.monthly_statement_account_added == false
+        verbose_msg!("LiquidatiAccountIndex: {}", liquidati_account_index);
+
+        Ok(())
+    }
+
+    //Dutch auction for seized collateral. The discount off the oracle price grows linearly from 0 to the max discount over the auction duration.
+    //Remaining accounts: Oracle Price Data, then the Oracle Price Validator wallet to refund the price account fees to
+    pub fn bid_collateral_auction(ctx: Context<BidCollateralAuction>, amount: u64, buy_max: bool) -> Result<()> 
+    {
+        let price_validator = &ctx.accounts.price_validator;
+        let collateral_token_reserve = &mut ctx.accounts.collateral_token_reserve;
+        let proceeds_token_reserve = &ctx.accounts.proceeds_token_reserve;
+        let proceeds_insurance_fund = &mut ctx.accounts.proceeds_insurance_fund;
+        let collateral_auction = &mut ctx.accounts.collateral_auction;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
+
+        let buy_amount;
+        if buy_max
         {
-            initialize_lending_user_monthly_statement_account(
-                liquidator_repayment_monthly_statement_account,
-                liquidator_repayment_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_repayment_monthly_statement_account,
-                token_reserve.token_id,
-                repayment_sub_market_owner_address,
-                repayment_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-            )?;
+            buy_amount = collateral_auction.remaining_amount;
         }
-        if liquidator_liquidation_monthly_statement_account.monthly_statement_account_added == false
+        else
         {
-            initialize_lending_user_monthly_statement_account(
-                liquidator_liquidation_monthly_statement_account,
-                liquidator_liquidation_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_liquidation_monthly_statement_account,
-                token_reserve.token_id,
-                liquidation_sub_market_owner_address,
-                liquidation_sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-            )?;
+            buy_amount = amount;
         }
 
-        //Update interest earned and accrued for the liquidator
-        update_user_previous_interest_earned(
-            token_reserve,
-            &mut repayment_sub_market,
-            liquidator_repayment_tab_account,
-            liquidator_repayment_monthly_statement_account
+        require!(buy_amount > 0 && buy_amount <= collateral_auction.remaining_amount, LendingError::InsufficientAuctionCollateral);
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        let collateral_token_usd_value = get_verified_token_usd_price(&temp_price_account.data, collateral_token_reserve.token_id, collateral_token_reserve.usd_quote_price_id)?;
+        let proceeds_token_usd_value = get_verified_token_usd_price(&temp_price_account.data, proceeds_token_reserve.token_id, proceeds_token_reserve.usd_quote_price_id)?;
+
+        //Discount grows linearly over the auction duration and stays at the max discount after it ends
+        let elapsed_seconds = std::cmp::min(time_stamp.saturating_sub(collateral_auction.start_time_stamp), collateral_auction.duration_seconds);
+        //Multiply before dividing to help keep precision
+        let discount_rate = (elapsed_seconds as u128 * collateral_auction.max_discount_rate as u128) / collateral_auction.duration_seconds as u128;
+
+        let collateral_token_conversion_number = BASE_10_INT.pow(collateral_token_reserve.token_decimal_amount as u32);
+        let proceeds_token_conversion_number = BASE_10_INT.pow(proceeds_token_reserve.token_decimal_amount as u32);
+        let collateral_usd_value = (buy_amount as u128 * collateral_token_usd_value) / collateral_token_conversion_number;
+        let discounted_usd_value = (collateral_usd_value * (10_000 - discount_rate)) / 10_000;
+
+        //Round up so the bidder never underpays the Insurance Fund
+        let auction_price_amount = ((discounted_usd_value * proceeds_token_conversion_number + proceeds_token_usd_value - 1) / proceeds_token_usd_value) as u64;
+
+        //Pay the proceeds into the proceeds Token Reserve ATA
+        //Transfer fee mints deliver less than the amount sent, so the bidder sends enough to cover the fee and only what the Token Reserve actually received gets credited
+        let payment_amount = get_amount_with_transfer_fee(&ctx.accounts.proceeds_mint, auction_price_amount)?;
+        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.bidder_proceeds_ata.to_account_info().data.borrow()[..])?;
+        let should_close = user_ata_data.amount == 0;
+        let payment_amount = deposit_tokens_into_token_reserve_from_user(
+            ctx.accounts.proceeds_mint.key(),
+            &ctx.accounts.proceeds_token_reserve_ata.to_account_info(),
+            &ctx.accounts.bidder_proceeds_ata.to_account_info(),
+            &ctx.accounts.proceeds_mint,
+            &ctx.accounts.proceeds_token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            payment_amount,
+            should_close,
+            &[]
         )?;
-        update_user_previous_interest_accrued(
-            token_reserve,
-            &mut repayment_sub_market,
-            liquidator_repayment_tab_account,
-            liquidator_repayment_monthly_statement_account
+        require!(payment_amount >= auction_price_amount, LendingError::AuctionPaymentShortfall);
+
+        //Send the seized collateral to the bidder
+        withdraw_tokens_from_token_reserve_to_user(
+            ctx.accounts.collateral_mint.key(),
+            collateral_token_reserve,
+            &ctx.accounts.collateral_token_reserve_ata.to_account_info(),
+            &ctx.accounts.bidder_collateral_ata.to_account_info(),
+            &ctx.accounts.collateral_mint,
+            &ctx.accounts.collateral_token_program,
+            &ctx.accounts.signer,
+            &ctx.accounts.system_program,
+            buy_amount,
+            None,
+            &[]
         )?;
+
+        //Recapitalize the Insurance Fund
+        collateral_auction.remaining_amount -= buy_amount;
+        collateral_token_reserve.auction_collateral_amount -= buy_amount as u128;
+        collateral_auction.sold_amount += buy_amount as u128;
+        collateral_auction.proceeds_amount += payment_amount as u128;
+        proceeds_insurance_fund.balance_amount += payment_amount as u128;
+        proceeds_insurance_fund.funded_amount += payment_amount as u128;
+
+        //Refund Oracle price account fees back to Oracle
+        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+
+        msg!("{} bought {} auctioned collateral at Token ID: {} for {} of Token ID: {}",
+        ctx.accounts.signer.key(),
+        buy_amount,
+        collateral_token_reserve.token_id,
+        payment_amount,
+        proceeds_token_reserve.token_id);
+
+        verbose_msg!("Auction Discount: {:.2}%", discount_rate as f64 / 100.0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "compute-metrics")]
+    pub fn measure_compute_units(ctx: Context<MeasureComputeUnits>, _sub_market_index: u16, _user_account_index: u8) -> Result<()> 
+    {
+        let compute_metrics = &mut ctx.accounts.compute_metrics;
+        let clock = Clock::get()?;
+        let time_stamp = clock.unix_timestamp as u64;
+        let clock_slot = clock.slot;
+
+        //Work on copies of the accounts so measuring never changes the real Token Reserve, SubMarket, or User balances
+        let mut token_reserve = ctx.accounts.token_reserve.deref().clone();
+        let mut sub_market = *ctx.accounts.sub_market.load()?;
+        let mut lending_user_tab_account = *ctx.accounts.lending_user_tab_account.load()?;
+        let mut monthly_statement_account = ctx.accounts.lending_user_monthly_statement_account.deref().clone();
+
+        //1. Interest Sync
+        let start_compute_units = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+        update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, time_stamp, Some(clock_slot))?;
+        let interest_sync_compute_units = start_compute_units - anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+
+        //2. Rate Update
+        let start_compute_units = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+        update_token_reserve_rates(&mut token_reserve)?;
+        let rate_update_compute_units = start_compute_units - anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+
+        //3. Health Check work done for each Tab Account in refresh_user_health_chunk_and_token_reserves
+        let start_compute_units = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
         update_user_previous_interest_earned(
-            token_reserve,
-            &mut liquidation_sub_market,
-            liquidator_liquidation_tab_account,
-            liquidator_liquidation_monthly_statement_account
+            &mut token_reserve,
+            &mut sub_market,
+            &mut lending_user_tab_account,
+            &mut monthly_statement_account
         )?;
+
         update_user_previous_interest_accrued(
-            token_reserve,
-            &mut liquidation_sub_market,
-            liquidator_liquidation_tab_account,
-            liquidator_liquidation_monthly_statement_account
+            &mut token_reserve,
+            &mut sub_market,
+            &mut lending_user_tab_account,
+            &mut monthly_statement_account
         )?;
 
-        //Repay Liquidati's Debt
-        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
-        let should_close = user_ata_data.amount == 0;
+        //Use a price of 1 with 18 decimals since no Oracle price is needed to measure the math
+        let normalized_price_18_decimals = BASE_10_INT.pow(18);
+        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+        let _deposit_usd_value = (lending_user_tab_account.deposited_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        let _borrow_usd_value = (lending_user_tab_account.borrowed_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        let health_check_per_tab_compute_units = start_compute_units - anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+
+        //4. CPI Token Transfer (0 tokens from the signer back to the signer)
+        let start_compute_units = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
         deposit_tokens_into_token_reserve_from_user(
             ctx.accounts.token_mint.key(),
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.liquidator_ata.to_account_info(),
+            &ctx.accounts.user_ata.to_account_info(),
+            &ctx.accounts.user_ata.to_account_info(),
             &ctx.accounts.token_mint,
             &ctx.accounts.token_program,
             &ctx.accounts.signer,
             &ctx.accounts.system_program,
-            repayment_amount,
-            should_close
+            0,
+            false,
+            &[]
         )?;
+        let cpi_transfer_compute_units = start_compute_units - anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
 
-        //Get Amount to be Liquidated
-        let amount_to_be_liquidated = ((repayment_amount_usd_value * token_conversion_number) / token_usd_value) as u64;
+        compute_metrics.bump = ctx.bumps.compute_metrics;
 
-        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
-        //Multiply before dividing to help keep precision
-        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+        compute_metrics.version = CURRENT_ACCOUNT_VERSION;
+        compute_metrics.interest_sync_compute_units = interest_sync_compute_units;
+        compute_metrics.rate_update_compute_units = rate_update_compute_units;
+        compute_metrics.health_check_per_tab_compute_units = health_check_per_tab_compute_units;
+        compute_metrics.cpi_transfer_compute_units = cpi_transfer_compute_units;
+        compute_metrics.measured_clock_slot = clock_slot;
+        compute_metrics.measurement_count += 1;
 
-        //Take a 1% liquidation fee
-        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+        msg!("Interest Sync Compute Units: {}", interest_sync_compute_units);
+        verbose_msg!("Rate Update Compute Units: {}", rate_update_compute_units);
+        verbose_msg!("Health Check Per Tab Compute Units: {}", health_check_per_tab_compute_units);
+        verbose_msg!("CPI Transfer Compute Units: {}", cpi_transfer_compute_units);
 
-        //Check for underflow if liquidation isn't profitable
-        if liquidati_liquidation_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
+        Ok(())
+    }
+
+    //Dev builds only. Runs the canonical accrual scenarios through the on-chain interest math and returns pass/fail for each, so every deployment can be smoke tested against the reference vectors
+    #[cfg(feature = "dev")]
+    pub fn run_accrual_test_vectors(_ctx: Context<RunAccrualTestVectors>) -> Result<Structs::AccrualTestVectorReport> 
+    {
+        let mut results = Vec::with_capacity(ACCRUAL_TEST_VECTORS.len());
+        let mut passed_count = 0;
+        let mut failed_count = 0;
+
+        for (scenario_index, (borrow_apy, supply_apy, duration_seconds, borrowed_amount, starting_index, expected_supply_index, expected_borrow_index)) in ACCRUAL_TEST_VECTORS.iter().enumerate()
         {
-            //Take a 1% liquidation fee
-            liquidation_fee_amount = liquidati_liquidation_tab_account.deposited_amount / 100;
-            //Give remainder to liquidator
-            liquidation_amount_with_7_percent_bonus = liquidati_liquidation_tab_account.deposited_amount - liquidation_fee_amount;
+            //Run each scenario against a scratch Token Reserve so the real accrual function is what gets tested
+            let mut token_reserve = Structs::TokenReserve::default();
+            token_reserve.borrow_apy = *borrow_apy;
+            token_reserve.supply_apy = *supply_apy;
+            token_reserve.borrowed_amount = *borrowed_amount;
+            token_reserve.supply_interest_change_index = *starting_index;
+            token_reserve.borrow_interest_change_index = *starting_index;
+
+            update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, *duration_seconds, None)?;
+
+            let passed = token_reserve.supply_interest_change_index.abs_diff(*expected_supply_index) <= ACCRUAL_TEST_VECTOR_TOLERANCE &&
+                token_reserve.borrow_interest_change_index.abs_diff(*expected_borrow_index) <= ACCRUAL_TEST_VECTOR_TOLERANCE;
+
+            if passed
+            {
+                passed_count += 1;
+            }
+            else
+            {
+                failed_count += 1;
+                msg!("🚨 Accrual Test Vector {} failed", scenario_index);
+            }
+
+            results.push(Structs::AccrualTestVectorResult
+            {
+                scenario_index: scenario_index as u8,
+                expected_supply_index: *expected_supply_index,
+                actual_supply_index: token_reserve.supply_interest_change_index,
+                expected_borrow_index: *expected_borrow_index,
+                actual_borrow_index: token_reserve.borrow_interest_change_index,
+                passed
+            });
         }
 
-        //Update Repayment Values
-        token_reserve.borrowed_amount -= repayment_amount as u128;
-        token_reserve.repaid_debt_amount += repayment_amount as u128;
-        repayment_sub_market.borrowed_amount -= repayment_amount as u128;
-        repayment_sub_market.repaid_debt_amount += repayment_amount as u128;
-        liquidati_repayment_tab_account.borrowed_amount -= repayment_amount;
-        liquidator_repayment_tab_account.repaid_debt_amount += repayment_amount;
-        liquidati_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
-        liquidator_repayment_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
-        liquidati_repayment_monthly_statement_account.snap_shot_debt_amount = liquidati_repayment_tab_account.borrowed_amount;
+        verbose_msg!("Accrual Test Vectors Passed: {}, Failed: {}", passed_count, failed_count);
 
-        //Update Liquidation and Fee Values
-        token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        token_reserve.liquidated_amount += liquidation_fee_amount as u128;
-        token_reserve.deposited_amount -= liquidation_fee_amount as u128;
-        token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
-        liquidation_sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        liquidation_sub_market.liquidated_amount += liquidation_fee_amount as u128;
-        liquidation_sub_market.deposited_amount -= liquidation_fee_amount as u128;
-        liquidation_sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
-        liquidati_liquidation_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_tab_account.deposited_amount -= liquidation_fee_amount;
-        liquidati_liquidation_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_tab_account.liquidated_amount += liquidation_fee_amount;
-        liquidator_liquidation_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_liquidation_tab_account.fees_generated_amount += liquidation_fee_amount;
-        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_liquidation_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
-        liquidati_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidati_liquidation_tab_account.deposited_amount;
-        liquidator_liquidation_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_liquidation_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+        Ok(Structs::AccrualTestVectorReport
+        {
+            passed_count,
+            failed_count,
+            results
+        })
+    }
 
-        if send_reward_to_wallet
+    //Dev builds only. Runs the rate scenarios through update_token_reserve_rates and returns pass/fail for each, so high Utilization Rate edge cases are smoke tested on every deployment
+    #[cfg(feature = "dev")]
+    pub fn run_rate_test_vectors(_ctx: Context<RunAccrualTestVectors>) -> Result<Structs::RateTestVectorReport> 
+    {
+        let mut results = Vec::with_capacity(RATE_TEST_VECTORS.len());
+        let mut passed_count = 0;
+        let mut failed_count = 0;
+
+        for (scenario_index, (base_borrow_apy, use_fixed_borrow_apy, deposited_amount, borrowed_amount, expected_utilization_rate, expected_borrow_apy, expected_supply_apy)) in RATE_TEST_VECTORS.iter().enumerate()
         {
-            let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
-            let balance_after_withdrawal = user_token_data.amount.saturating_sub(liquidation_amount_with_7_percent_bonus);
-            let should_close = balance_after_withdrawal == 0;
-            withdraw_tokens_from_token_reserve_to_user(
-                ctx.accounts.token_mint.key(),
-                token_reserve,
-                &ctx.accounts.token_reserve_ata.to_account_info(),
-                &ctx.accounts.liquidator_ata.to_account_info(),
-                &ctx.accounts.token_mint,
-                &ctx.accounts.token_program,
-                &ctx.accounts.signer,
-                &ctx.accounts.system_program,
-                liquidation_amount_with_7_percent_bonus,
-                should_close
-            )?;
+            //Run each scenario against a scratch Token Reserve so the real rate function is what gets tested
+            let mut token_reserve = Structs::TokenReserve::default();
+            token_reserve.base_borrow_apy = *base_borrow_apy;
+            token_reserve.use_fixed_borrow_apy = *use_fixed_borrow_apy;
+            token_reserve.deposited_amount = *deposited_amount;
+            token_reserve.borrowed_amount = *borrowed_amount;
 
-            token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
-            liquidation_sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128; 
+            update_token_reserve_rates(&mut token_reserve)?;
+
+            let passed = token_reserve.utilization_rate == *expected_utilization_rate &&
+                token_reserve.borrow_apy == *expected_borrow_apy &&
+                token_reserve.supply_apy == *expected_supply_apy;
+
+            if passed
+            {
+                passed_count += 1;
+            }
+            else
+            {
+                failed_count += 1;
+                msg!("🚨 Rate Test Vector {} failed", scenario_index);
+            }
+
+            results.push(Structs::RateTestVectorResult
+            {
+                scenario_index: scenario_index as u8,
+                expected_utilization_rate: *expected_utilization_rate,
+                actual_utilization_rate: token_reserve.utilization_rate,
+                expected_borrow_apy: *expected_borrow_apy,
+                actual_borrow_apy: token_reserve.borrow_apy,
+                expected_supply_apy: *expected_supply_apy,
+                actual_supply_apy: token_reserve.supply_apy,
+                passed
+            });
         }
-        else
+
+        verbose_msg!("Rate Test Vectors Passed: {}, Failed: {}", passed_count, failed_count);
+
+        Ok(Structs::RateTestVectorReport
         {
-            liquidator_liquidation_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
-            liquidator_liquidation_monthly_statement_account.snap_shot_balance_amount = liquidator_liquidation_tab_account.deposited_amount;
+            passed_count,
+            failed_count,
+            results
+        })
+    }
+
+    pub fn get_token_reserve_rate_curve(ctx: Context<GetTokenReserveRateCurve>, sample_point_count: u8) -> Result<Structs::TokenReserveRateCurve> 
+    {
+        let token_reserve = &ctx.accounts.token_reserve;
+
+        //Return data is limited to 1,024 bytes, so the curve can be sampled at 101 points max (every 1% Utilization Rate)
+        require!(sample_point_count >= 2 && sample_point_count <= 101, LendingError::InvalidRateCurveSampleCount);
+
+        //Sample the curve evenly from 0% to 100% Utilization Rate
+        let mut points = Vec::with_capacity(sample_point_count as usize);
+        for i in 0..sample_point_count as u128
+        {
+            let utilization_rate = (i * DECIMAL_SCALING) / (sample_point_count as u128 - 1);
+            let borrow_apy = calculate_borrow_apy(token_reserve, utilization_rate);
+            let supply_apy = calculate_supply_apy(borrow_apy, utilization_rate as u16);
+
+            points.push(Structs::RateCurvePoint
+            {
+                utilization_rate: utilization_rate as u16,
+                borrow_apy,
+                supply_apy
+            });
         }
 
-        //Refund Oracle price account fees back to Oracle
-        let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-        refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
-        
-        //Update Stat Listener
-        lending_stats.liquidations += 1;
-        
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY
-        update_token_reserve_rates(token_reserve)?;
+        msg!("Returned {} Rate Curve points for Token ID: {}", sample_point_count, token_reserve.token_id);
 
-        //Update Repayment SubMarket/User time stamp based interest indexes
-        repayment_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        repayment_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidati_repayment_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidati_repayment_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidator_repayment_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidator_repayment_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        Ok(Structs::TokenReserveRateCurve
+        {
+            token_id: token_reserve.token_id,
+            base_borrow_apy: token_reserve.base_borrow_apy,
+            use_fixed_borrow_apy: token_reserve.use_fixed_borrow_apy,
+            optimal_utilization_rate: OPTIMAL_UTILIZATION_RATE as u16,
+            borrow_apy_slope2: BORROW_APY_SLOPE2 as u16,
+            utilization_rate: token_reserve.utilization_rate,
+            borrow_apy: token_reserve.borrow_apy,
+            supply_apy: token_reserve.supply_apy,
+            points
+        })
+    }
 
-        //Update Liquidation SubMarket/User time stamp based interest indexes
-        liquidation_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidation_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidati_liquidation_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidati_liquidation_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidator_liquidation_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidator_liquidation_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+    //Returns a tab's balances along with the supply APY it's actually earning and the borrow APY it's actually paying.
+    //Supply APY is net of the fees taken out of interest earned, and borrow APY is net of the tab's staking discount
+    pub fn get_lending_user_tab_position(ctx: Context<GetLendingUserTabPosition>,
+        sub_market_index: u16,
+        _user_account_index: u8
+    ) -> Result<Structs::LendingUserTabPosition> 
+    {
+        let token_reserve = &ctx.accounts.token_reserve;
+        let sub_market = &ctx.accounts.sub_market.load()?;
+        let lending_user_tab_account = &ctx.accounts.lending_user_tab_account.load()?;
 
-        //Update last activity on accounts
-        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
-        //token_reserve.last_lending_activity_amount = repayment_amount;
-        //token_reserve.last_lending_activity_type = Activity::Repay as u8; //Since the token is the same, make Liquidate the last activity on the token reserve
-        token_reserve.last_lending_activity_amount = liquidation_amount;
-        token_reserve.last_lending_activity_type = Activity::Liquidate as u8; //We'll let the Liquidate activity be the last activity since the repayment and liquidation token reserves are the same in this case
-        repayment_sub_market.last_lending_activity_amount = repayment_amount;
-        repayment_sub_market.last_lending_activity_type = Activity::Repay as u8;
-        repayment_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        liquidation_sub_market.last_lending_activity_amount = liquidation_amount;
-        liquidation_sub_market.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidation_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        liquidati_repayment_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidati_liquidation_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        liquidator_repayment_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidator_liquidation_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        
-        //Save changes to passed in remaining accounts
-        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
-        repayment_sub_market.serialize(&mut &mut repayment_sub_market_account_serialized.data.borrow_mut()[8..])?;
-        liquidation_sub_market.serialize(&mut &mut liquidation_sub_market_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_repayment_tab_account.serialize(&mut &mut liquidati_repayment_tab_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_liquidation_tab_account.serialize(&mut &mut liquidati_liquidation_tab_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_repayment_monthly_statement_account.serialize(&mut &mut liquidati_repayment_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_liquidation_monthly_statement_account.serialize(&mut &mut liquidati_liquidation_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        
-        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+        //Fees are taken out of interest earned the same way update_user_previous_interest_earned caps them at 100%
+        let sub_market_fee_rate = get_tab_sub_market_fee_rate(sub_market, lending_user_tab_account, Clock::get()?.unix_timestamp as u64);
+        let total_fee_rate = (sub_market_fee_rate + token_reserve.solvency_insurance_fee_rate + token_reserve.insurance_fund_share_rate).min(10_000);
+        let net_supply_apy = ((token_reserve.supply_apy as u128 * (10_000 - total_fee_rate) as u128) / DECIMAL_SCALING) as u16;
 
-        msg!("Repaid debt at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        token_reserve.token_id,
-        repayment_sub_market_owner_address.key(),
-        repayment_sub_market_index);
+        let borrow_rate_discount_rate = u16::from_le_bytes(lending_user_tab_account.borrow_rate_discount_rate).min(10_000);
+        let net_borrow_apy = ((token_reserve.borrow_apy as u128 * (10_000 - borrow_rate_discount_rate) as u128) / DECIMAL_SCALING) as u16;
 
-        msg!("Liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        token_reserve.token_id,
-        liquidation_sub_market_owner_address.key(),
-        liquidation_sub_market_index);
+        msg!("Net Supply APY: {}, Net Borrow APY: {}", net_supply_apy, net_borrow_apy);
 
-        Ok(())
+        Ok(Structs::LendingUserTabPosition
+        {
+            token_id: token_reserve.token_id,
+            sub_market_owner_address: sub_market.owner,
+            sub_market_index,
+            deposited_amount: lending_user_tab_account.deposited_amount,
+            borrowed_amount: lending_user_tab_account.borrowed_amount,
+            net_supply_apy,
+            net_borrow_apy
+        })
     }
 
-    
-    //This liquidation is for when the repayment and liquidation Sub Markets are the same. If the Sub Markets are the same, the tokens are also the same
-    //The only cases not covered is liquidating yourself. You can "liquidate yourself" still, but you have to do it with a 2nd account from the same wallet
-    pub fn liquidate_account_same_sub_market(ctx: Context<LiquidateAccountSameSubMarket>,
+    //Previews a tab's balances after accruing interest up to elapsed_seconds past the current time stamp at the Token Reserve's current rates. Pass 0 for the pending interest as of now.
+    //Interest is accrued on copies of the accounts, so nothing is written
+    pub fn simulate_accrual(ctx: Context<GetLendingUserTabPosition>,
         sub_market_index: u16,
-        liquidati_account_index: u8,
-        liquidator_account_index: u8,
-        amount_to_repay: u64,
-        repay_max: bool,
-        paying_off_insolvent_account: bool,
-        send_reward_to_wallet: bool,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()>
+        _user_account_index: u8,
+        elapsed_seconds: u64
+    ) -> Result<Structs::TabAccrualPreview> 
     {
-        let lending_protocol = &ctx.accounts.lending_protocol;
-        let price_validator = &ctx.accounts.price_validator;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let liquidati_lending_account = &mut ctx.accounts.liquidati_lending_account;
-        let liquidator_lending_account = &mut ctx.accounts.liquidator_lending_account;
-        let liquidator_tab_account = &mut ctx.accounts.liquidator_tab_account;
-        let liquidator_monthly_statement_account = &mut ctx.accounts.liquidator_monthly_statement_account;
+        let mut token_reserve = (**ctx.accounts.token_reserve).clone();
+        let mut sub_market = *ctx.accounts.sub_market.load()?;
+        let mut lending_user_tab_account = *ctx.accounts.lending_user_tab_account.load()?;
+        let mut monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let preview_time_stamp = (Clock::get()?.unix_timestamp as u64).checked_add(elapsed_seconds).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let deposited_amount_before_accrual = lending_user_tab_account.deposited_amount;
+        let borrowed_amount_before_accrual = lending_user_tab_account.borrowed_amount;
+
+        update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, preview_time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            &mut token_reserve,
+            &mut sub_market,
+            &mut lending_user_tab_account,
+            &mut monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_accrued(
+            &mut token_reserve,
+            &mut sub_market,
+            &mut lending_user_tab_account,
+            &mut monthly_statement_account_placeholder
+        )?;
+
+        let pending_interest_earned_amount = lending_user_tab_account.deposited_amount.saturating_sub(deposited_amount_before_accrual);
+        let pending_interest_accrued_amount = lending_user_tab_account.borrowed_amount.saturating_sub(borrowed_amount_before_accrual);
+
+        msg!("Preview Time Stamp: {}", preview_time_stamp);
+        verbose_msg!("Pending Interest Earned Amount: {}, Pending Interest Accrued Amount: {}", pending_interest_earned_amount, pending_interest_accrued_amount);
+
+        Ok(Structs::TabAccrualPreview
+        {
+            token_id: token_reserve.token_id,
+            sub_market_owner_address: sub_market.owner,
+            sub_market_index,
+            preview_time_stamp,
+            deposited_amount: lending_user_tab_account.deposited_amount,
+            borrowed_amount: lending_user_tab_account.borrowed_amount,
+            pending_interest_earned_amount,
+            pending_interest_accrued_amount
+        })
+    }
+
+    //Read only version of the health refresh. Interest is accrued on copies of the accounts so nothing is written, and the totals come back as return data.
+    //Remaining accounts: the signer's temp price account, then a Tab Account, Token Reserve, and SubMarket set for every one of the Lending User's Tab Accounts
+    pub fn get_user_health(ctx: Context<GetUserHealth>, user_account_index: u8) -> Result<Structs::UserHealthView> 
+    {
+        let lending_user_account = &ctx.accounts.lending_user_account;
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
+
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
+
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+
+        //Every tab is walked in this one instruction, so the working copies live on the heap and the tab count is bounded by the Tab Account registry
+        require!(lending_user_account.tab_account_count as usize <= MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, LendingError::TooManyHealthRefreshAccounts);
+
+        let mut viewed_tab_account_flags: u32 = 0;
+        let mut total_deposited_usd_value: u128 = 0;
+        let mut total_borrowed_usd_value: u128 = 0;
+
+        for _i in 0..lending_user_account.tab_account_count
+        {
+            /////////////
+            //Tab Account
+            let tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingUserHealthTabAccounts)?;
+            let tab_account_registry_index = lending_user_account.tab_account_addresses
+                .iter()
+                .position(|tab_account_address| *tab_account_address == tab_account_serialized.key())
+                .ok_or(LendingError::UnregisteredTabAccount)?;
+            let tab_account_viewed_flag = 1u32 << tab_account_registry_index;
+
+            //Each Tab Account can only be counted once
+            require!(viewed_tab_account_flags & tab_account_viewed_flag == 0, LendingError::TabAccountAlreadyRefreshed);
+            viewed_tab_account_flags |= tab_account_viewed_flag;
+
+            let mut lending_user_tab_account = Box::new(*load_zero_copy_account::<Structs::LendingUserTabAccount>(tab_account_serialized)?);
+
+            ///////////////////////
+            //Token Reserve Account
+            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingUserHealthTabAccounts)?;
+            let mut token_reserve = Box::new(validate_and_return_token_reserve_account(*ctx.program_id, token_reserve_account_serialized)?);
+            require!(token_reserve.token_id == lending_user_tab_account.token_id, LendingError::MissingTokenReserveAccountForRefresh);
+
+            ///////////////////
+            //SubMarket Account
+            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingUserHealthTabAccounts)?;
+            let mut sub_market = Box::new(*validate_and_return_sub_market_account(*ctx.program_id,
+                sub_market_account_serialized,
+                lending_user_tab_account.token_id,
+                lending_user_tab_account.sub_market_owner_address,
+                lending_user_tab_account.sub_market_index)?);
+
+            //Only the copies are updated, so the accrued interest matches what a refresh would see without saving anything
+            let mut monthly_statement_account_placeholder = Box::new(Structs::LendingUserMonthlyStatementAccount::default());
+            update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, time_stamp, None)?;
+
+            update_user_previous_interest_earned(
+                &mut token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account_placeholder
+            )?;
 
-        let clock_slot = Clock::get()?.slot;
+            update_user_previous_interest_accrued(
+                &mut token_reserve,
+                &mut sub_market,
+                &mut lending_user_tab_account,
+                &mut monthly_statement_account_placeholder
+            )?;
 
-        //This function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s)
-        require!(liquidati_lending_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+            //Empty tabs are worth nothing at any price, so their price doesn't have to be in the Oracle price data
+            if lending_user_tab_account.deposited_amount == 0 && lending_user_tab_account.borrowed_amount == 0
+            {
+                continue;
+            }
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
-        let liquidati_account_owner_address = ctx.accounts.liquidati_account_owner.key();
+            //Get normalized price with 8 decimals. Uses the SubMarket's oracle override if the CEO approved one
+            let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, get_sub_market_oracle_price_id(&sub_market), token_reserve.usd_quote_price_id)?;
+            
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
+            total_deposited_usd_value += get_collateral_usd_value(&token_reserve, (lending_user_tab_account.deposited_amount as u128 * normalized_price_18_decimals) / token_conversion_number);
+            total_borrowed_usd_value += (lending_user_tab_account.borrowed_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+        }
 
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let loan_to_value_rate = if total_deposited_usd_value == 0
+        {
+            if total_borrowed_usd_value == 0 { 0 } else { u64::MAX }
+        }
+        else
+        {
+            u64::try_from((total_borrowed_usd_value * 10_000) / total_deposited_usd_value).unwrap_or(u64::MAX)
+        };
 
-        //Validate Accounts
+        //Multiply before dividing to help keep precision
+        let health_factor_rate = if total_borrowed_usd_value == 0
+        {
+            u64::MAX
+        }
+        else
+        {
+            u64::try_from((total_deposited_usd_value * get_liquidation_threshold_rate(lending_user_account)) / total_borrowed_usd_value).unwrap_or(u64::MAX)
+        };
 
-        ////////////////////////////
-        //Oracle Price Data
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+        msg!("User Health for: {}, Account Index: {}", ctx.accounts.lending_user.key(), user_account_index);
+        verbose_msg!("Loan To Value Rate: {}, Health Factor Rate: {}", loan_to_value_rate, health_factor_rate);
 
-        ///////////////
-        //Lending Stats
-        let lending_stats_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut lending_stats = validate_and_return_lending_stats_account(*ctx.program_id, lending_stats_serialized)?;
+        Ok(Structs::UserHealthView
+        {
+            lending_user_owner: ctx.accounts.lending_user.key(),
+            user_account_index,
+            total_deposited_usd_value,
+            total_borrowed_usd_value,
+            loan_to_value_rate,
+            health_factor_rate
+        })
+    }
 
-        /////////////////////////////
-        //SubMarket Account
-        let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-            sub_market_account_serialized,
-            token_reserve.token_id,
-            sub_market_owner_address,
-            sub_market_index)?;
+    //Tokens sent directly to the Token Reserve ATA (donations, airdrops) are invisible to the accounting. This compares the ATA balance against what the accounting says it should hold
+    //and either sweeps the surplus into the Solvency Insurance fees or distributes it to depositors through the supply index.
+    //Requires the Token Reserve's Insurance Fund to be initialized since its tokens are held in the same ATA.
+    pub fn sync_reserve(ctx: Context<SyncReserve>, distribute_to_depositors: bool) -> Result<()> 
+    {
+        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
+        //Only the Treasurer can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
 
-        /////////////////////////////////
-        //Liquidati Tab Account
-        let liquidati_tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-            liquidati_tab_account_serialized,
-            token_reserve.token_id,
-            sub_market_owner_address,
-            sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let insurance_fund = &ctx.accounts.insurance_fund;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
 
-        ///////////////////////////////////////////////
-        //Liquidati Monthly Statement Account
-        let liquidati_monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let mut liquidati_monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-            liquidati_monthly_statement_account_serialized,
-            lending_protocol.current_statement_month,
-            lending_protocol.current_statement_year,
-            token_reserve.token_id,
-            sub_market_owner_address,
-            sub_market_index,
-            liquidati_account_owner_address,
-            liquidati_account_index)?;
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
-        let repayment_amount;
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+        let expected_balance_amount = get_expected_token_reserve_ata_balance(token_reserve, insurance_fund.balance_amount);
 
-        //Get USD value of Repayment Amount
-        let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
-        let token_usd_value = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
-        let mut repayment_amount_usd_value = 0;
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        let token_reserve_ata_balance_amount = token_reserve_ata_data.amount as u128;
 
-        //Check if Account is liquidatable and set repayment_amount
-        if paying_off_insolvent_account
-        {
-            //You can't zero out an account whose borrow liabilities aren't 100% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= liquidati_lending_account.total_deposited_usd_value, LendingError::NotInsolvent);
+        //There has to be a surplus to sweep
+        require!(token_reserve_ata_balance_amount > expected_balance_amount, LendingError::NoReserveSurplus);
+        let surplus_amount = token_reserve_ata_balance_amount - expected_balance_amount;
 
-            if repay_max
-            {
-                repayment_amount = liquidati_tab_account.borrowed_amount;
-                repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
-                
-                //Since all of this borrowed amount is being repaid, check if liquidati's total borrowed value would go to zero for cheaper withdrawals for them
-                //Use saturating_sub to safely deduct the value
-                //If lending_user_account.total_borrowed_usd_value falls to zero here, it just allows the user to withdraw without having to check their user health before hand. Otherwise this would get set to zero when calling withdraw again when the borrowed amounts are zero just incase this check fails.
-                liquidati_lending_account.total_borrowed_usd_value = liquidati_lending_account
-                    .total_borrowed_usd_value
-                    .saturating_sub(repayment_amount_usd_value);
-            }
-            else
-            {
-                if amount_to_repay > liquidati_tab_account.borrowed_amount
-                {
-                    //Can't pay more debt than the user has accumulated
-                    repayment_amount = liquidati_tab_account.borrowed_amount;
-                }
-                else
-                {
-                    repayment_amount = amount_to_repay;
-                }  
-            }
+        if distribute_to_depositors && token_reserve.deposited_amount > 0
+        {
+            //New Supply Index = Old Supply Index * (Deposited Amount + Surplus) / Deposited Amount
+            //The surplus is applied to depositor balances the next time their tabs are updated, the same way interest earned is applied
+            //Use ra_solana_math library FixedPoint for fixed point math. Multiply before dividing to help keep precision
+            let old_supply_interest_index_fp = FixedPoint::from_scaled_u128(token_reserve.supply_interest_change_index);
+            let new_deposited_amount_fp = FixedPoint::from_int((token_reserve.deposited_amount + surplus_amount) as u64);
+            let deposited_amount_fp = FixedPoint::from_int(token_reserve.deposited_amount as u64);
+            token_reserve.supply_interest_change_index = old_supply_interest_index_fp.mul(&new_deposited_amount_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+                .div(&deposited_amount_fp).map_err(|_| anchor_lang::prelude::ProgramError::ArithmeticOverflow)?
+                .value.as_u128();
         }
         else
         {
-            //Multiply before dividing to help keep precision
-            let eighty_percent_of_liquidati_deposited_usd_value = (liquidati_lending_account.total_deposited_usd_value * 80) / 100;
+            token_reserve.uncollected_solvency_insurance_fees_amount += surplus_amount;
+        }
 
-            //You can't liquidate an account whose borrow liabilities aren't 80% or more of their deposited collateral
-            require!(liquidati_lending_account.total_borrowed_usd_value >= eighty_percent_of_liquidati_deposited_usd_value, LendingError::NotLiquidatable);
+        token_reserve.swept_surplus_amount += surplus_amount;
 
-            //Multiply before dividing to help keep precision
-            let fifty_percent_of_liquidati_borrowed_amount = (liquidati_tab_account.borrowed_amount * 50) / 100;
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = surplus_amount as u64;
+        token_reserve.last_lending_activity_type = Activity::SyncReserve as u8;
+        token_reserve.last_lending_activity_time_stamp = time_stamp;
 
-            if repay_max
-            {
-                repayment_amount = fifty_percent_of_liquidati_borrowed_amount;
-            }
-            else
-            {
-                repayment_amount = amount_to_repay;
-            }
+        msg!("{} synced the Token Reserve at Token ID: {}", ctx.accounts.signer.key(), token_reserve.token_id);
+        verbose_msg!("Token Reserve ATA Balance: {}, Expected Balance: {}", token_reserve_ata_balance_amount, expected_balance_amount);
+        verbose_msg!("Surplus Amount: {}, Distributed To Depositors: {}", surplus_amount, distribute_to_depositors && token_reserve.deposited_amount > 0);
 
-            //You can't repay more than 50% of a liquidati's debt position
-            require!(repayment_amount <= fifty_percent_of_liquidati_borrowed_amount, LendingError::OverLiquidation);
-        }
+        Ok(())
+    }
 
-        if repayment_amount_usd_value == 0
+    //Permissionless, checks that the Token Reserve's books reconcile without changing anything. The Token Reserve ATA has to hold at least the expected balance,
+    //and the SubMarkets fed in through the remaining accounts have to add up to the Token Reserve's deposited and borrowed totals, so every one of its SubMarkets has to be passed in.
+    //Drift emits a ReserveInvariantDrift event instead of failing, so monitoring picks it up
+    pub fn verify_reserve_invariants(ctx: Context<VerifyReserveInvariants>, sub_market_count: u16) -> Result<()> 
+    {
+        let token_reserve = &ctx.accounts.token_reserve;
+        let insurance_fund = &ctx.accounts.insurance_fund;
+        let clock_slot = Clock::get()?.slot;
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+
+        //Interest only moves the indexes until tabs are updated, so the amounts can be compared without accruing first
+        let expected_balance_amount = get_expected_token_reserve_ata_balance(token_reserve, insurance_fund.balance_amount);
+        let token_reserve_ata_balance_amount = ctx.accounts.token_reserve_ata.amount as u128;
+
+        let mut sub_market_addresses: Vec<Pubkey> = Vec::with_capacity(sub_market_count.into());
+        let mut sub_markets_deposited_amount: u128 = 0;
+        let mut sub_markets_borrowed_amount: u128 = 0;
+        for _i in 0..sub_market_count
         {
-            repayment_amount_usd_value = (repayment_amount as u128 * token_usd_value) / token_conversion_number;
+            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let sub_market = validate_and_return_read_only_sub_market_account(*ctx.program_id,
+                sub_market_account_serialized,
+                token_reserve.token_id)?;
+
+            //A duplicated SubMarket would be counted twice
+            require!(!sub_market_addresses.contains(&sub_market_account_serialized.key()), LendingError::DuplicateSubMarketAccount);
+            sub_market_addresses.push(sub_market_account_serialized.key());
+
+            sub_markets_deposited_amount += sub_market.deposited_amount;
+            sub_markets_borrowed_amount += sub_market.borrowed_amount;
         }
 
-        //Multiply before dividing to help keep precision
-        let ten_percent_of_borrowed_amount = (liquidati_tab_account.borrowed_amount * 10) / 100;
+        let balance_reconciled = token_reserve_ata_balance_amount >= expected_balance_amount;
+        let sub_markets_reconciled = sub_markets_deposited_amount == token_reserve.deposited_amount && sub_markets_borrowed_amount == token_reserve.borrowed_amount;
 
-        //You must repay atleast 10% of the borrow position when the account is in an unhealthy state. This prevents "griefing".
-        //IE: Only repaying $1 (or just the smallest enough amount to be in a healthy state), front running other liquidators so their transaction fails and holding the protocol's solvency hostage!
-        require!(repayment_amount >= ten_percent_of_borrowed_amount, LendingError::GriefingRepayment);
+        msg!("Verified the Token Reserve invariants at Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Token Reserve ATA Balance: {}, Expected Balance: {}", token_reserve_ata_balance_amount, expected_balance_amount);
+        verbose_msg!("Deposited Amount: {}, SubMarkets Deposited Amount: {}", token_reserve.deposited_amount, sub_markets_deposited_amount);
+        verbose_msg!("Borrowed Amount: {}, SubMarkets Borrowed Amount: {}", token_reserve.borrowed_amount, sub_markets_borrowed_amount);
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if liquidator_lending_account.lending_user_account_added == false
+        if !balance_reconciled || !sub_markets_reconciled
         {
-            let mut new_account_name_to_use: String = String::from("Generic Liquidator");
-            if let Some(new_account_name) = account_name
+            msg!("Token Reserve invariant drift detected. Balance Reconciled: {}, SubMarkets Reconciled: {}", balance_reconciled, sub_markets_reconciled);
+
+            emit!(ReserveInvariantDrift
             {
-                if !new_account_name.is_empty()
-                {
-                    new_account_name_to_use = new_account_name;
-                }
-            }
+                token_id: token_reserve.token_id,
+                token_mint_address: token_reserve.token_mint_address,
+                token_reserve_ata_balance_amount,
+                expected_balance_amount,
+                deposited_amount: token_reserve.deposited_amount,
+                sub_markets_deposited_amount,
+                borrowed_amount: token_reserve.borrowed_amount,
+                sub_markets_borrowed_amount,
+                sub_market_count,
+                clock_slot
+            });
+        }
 
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+        Ok(())
+    }
 
-            initialize_lending_user_account(
-                liquidator_lending_account,
-                ctx.bumps.liquidator_lending_account,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
-        }
+    //Freezes a copy of the Token Reserve's aggregates at the current clock slot along with a hash of them, so periodic audits can anchor against an on-chain snapshot.
+    //The Token Reserve itself isn't frozen, lending activity carries on as usual.
+    pub fn create_token_reserve_audit_snapshot(ctx: Context<CreateTokenReserveAuditSnapshot>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if liquidator_tab_account.user_tab_account_added == false
-        {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                liquidator_lending_account,
-                liquidator_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_tab_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index
-            )?;
-        }
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let token_reserve_audit_snapshot = &mut ctx.accounts.token_reserve_audit_snapshot;
+        let clock = Clock::get()?;
+        let time_stamp = clock.unix_timestamp as u64;
 
-        //Initialize monthly statement account if the statement month/year has changed or brand new sub user account.
-        if liquidator_monthly_statement_account.monthly_statement_account_added == false
-        {
-            initialize_lending_user_monthly_statement_account(
-                liquidator_monthly_statement_account,
-                liquidator_tab_account,
-                lending_protocol,
-                ctx.bumps.liquidator_monthly_statement_account,
-                token_reserve.token_id,
-                sub_market_owner_address,
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                liquidator_account_index,
-            )?;
-        }
+        //Calculate Token Reserve Previously Earned And Accrued Interest so the snapshot is current to the slot
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
-        //Update interest earned and accrued for the liquidator
-        update_user_previous_interest_earned(
-            token_reserve,
-            &mut sub_market,
-            liquidator_tab_account,
-            liquidator_monthly_statement_account
-        )?;
-        update_user_previous_interest_accrued(
-            token_reserve,
-            &mut sub_market,
-            liquidator_tab_account,
-            liquidator_monthly_statement_account
-        )?;
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
 
-        //Repay Liquidati's Debt
-        let user_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
-        let should_close = user_ata_data.amount == 0;
-        deposit_tokens_into_token_reserve_from_user(
-            ctx.accounts.token_mint.key(),
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.liquidator_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            repayment_amount,
-            should_close
-        )?;
+        token_reserve_audit_snapshot.bump = ctx.bumps.token_reserve_audit_snapshot;
+
+        token_reserve_audit_snapshot.version = CURRENT_ACCOUNT_VERSION;
+        token_reserve_audit_snapshot.token_id = token_reserve.token_id;
+        token_reserve_audit_snapshot.token_mint_address = token_reserve.token_mint_address;
+        token_reserve_audit_snapshot.snapshot_index = token_reserve.audit_snapshot_count;
+        token_reserve_audit_snapshot.clock_slot = clock.slot;
+        token_reserve_audit_snapshot.time_stamp = time_stamp;
+        token_reserve_audit_snapshot.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        token_reserve_audit_snapshot.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        token_reserve_audit_snapshot.deposited_amount = token_reserve.deposited_amount;
+        token_reserve_audit_snapshot.interest_earned_amount = token_reserve.interest_earned_amount;
+        token_reserve_audit_snapshot.borrowed_amount = token_reserve.borrowed_amount;
+        token_reserve_audit_snapshot.interest_accrued_amount = token_reserve.interest_accrued_amount;
+        token_reserve_audit_snapshot.repaid_debt_amount = token_reserve.repaid_debt_amount;
+        token_reserve_audit_snapshot.liquidated_amount = token_reserve.liquidated_amount;
+        token_reserve_audit_snapshot.bad_debt_amount = token_reserve.bad_debt_amount;
+        token_reserve_audit_snapshot.socialized_loss_amount = token_reserve.socialized_loss_amount;
+        token_reserve_audit_snapshot.uncollected_solvency_insurance_fees_amount = token_reserve.uncollected_solvency_insurance_fees_amount;
+        token_reserve_audit_snapshot.uncollected_liquidation_fees_amount = token_reserve.uncollected_liquidation_fees_amount;
+        token_reserve_audit_snapshot.uncollected_sub_market_fees_amount = token_reserve.uncollected_sub_market_fees_amount;
+        token_reserve_audit_snapshot.token_reserve_ata_balance_amount = token_reserve_ata_data.amount;
+        token_reserve_audit_snapshot.snapshot_hash = get_token_reserve_audit_snapshot_hash(token_reserve_audit_snapshot);
+
+        token_reserve.audit_snapshot_count += 1;
+
+        msg!("Created Audit Snapshot {} for Token Reserve at Token ID: {}", token_reserve_audit_snapshot.snapshot_index, token_reserve.token_id);
+        verbose_msg!("Clock Slot: {}, Snapshot Hash: {:?}", token_reserve_audit_snapshot.clock_slot, token_reserve_audit_snapshot.snapshot_hash);
 
-        //Get Amount to be Liquidated
-        let amount_to_be_liquidated = ((repayment_amount_usd_value * token_conversion_number) / token_usd_value) as u64;
+        Ok(())
+    }
 
-        //Liquidate part of the Liquidati's Collateral and Transfer it plus a 7% bonus to the Liquidator
-        //Multiply before dividing to help keep precision
-        let mut liquidation_amount_with_7_percent_bonus = (amount_to_be_liquidated * 107) / 100;
+    pub fn initialize_protocol_tvl(ctx: Context<InitializeProtocolTvl>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Take a 1% liquidation fee
-        let mut liquidation_fee_amount = amount_to_be_liquidated / 100;
+        let protocol_tvl = &mut ctx.accounts.protocol_tvl;
+        protocol_tvl.bump = ctx.bumps.protocol_tvl;
+        protocol_tvl.version = CURRENT_ACCOUNT_VERSION;
 
-        //Check for underflow if liquidation isn't profitable
-        if liquidati_tab_account.deposited_amount < liquidation_amount_with_7_percent_bonus + liquidation_fee_amount
-        {
-            //Take a 1% liquidation fee
-            liquidation_fee_amount = liquidati_tab_account.deposited_amount / 100;
-            //Give remainder to liquidator
-            liquidation_amount_with_7_percent_bonus = liquidati_tab_account.deposited_amount - liquidation_fee_amount;
-        }
+        msg!("Initialized Protocol TVL");
+
+        Ok(())
+    }
+
+    //Permissionless, anyone can reprice Token Reserves in the Protocol TVL account. Interest is accrued on copies of the Token Reserves, so they aren't written.
+    //Remaining accounts: the signer's temp price account, the Token Reserves to reprice, then the Oracle account to refund the temp price account fees to
+    pub fn refresh_protocol_tvl(ctx: Context<RefreshProtocolTvl>, token_reserve_count: u8) -> Result<()> 
+    {
+        let protocol_tvl = &mut ctx.accounts.protocol_tvl;
+        let price_validator = &ctx.accounts.price_validator;
+        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let clock_slot = Clock::get()?.slot;
 
-        //Update Repayment Values
-        token_reserve.borrowed_amount -= repayment_amount as u128;
-        token_reserve.repaid_debt_amount += repayment_amount as u128;
-        sub_market.borrowed_amount -= repayment_amount as u128;
-        sub_market.repaid_debt_amount += repayment_amount as u128;
-        liquidati_tab_account.borrowed_amount -= repayment_amount;
-        liquidator_tab_account.repaid_debt_amount += repayment_amount;
-        liquidati_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount; //Update liquidati monthly statement repayment amount, but not the tab. The tab is for the leader board and the liquidati shouldn't get credit for repayment in this case, but updating the monthly statement atleast for visibility to the liquidati.
-        liquidator_monthly_statement_account.monthly_repaid_debt_amount += repayment_amount;
-        liquidati_monthly_statement_account.snap_shot_debt_amount = liquidati_tab_account.borrowed_amount;
+        ////////////////////////////
+        //Validate Oracle Price Data
+        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
+            temp_price_account_serialized,
+            ctx.accounts.signer.key())?;
 
-        //Update Liquidation and Fee Values
-        token_reserve.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        token_reserve.liquidated_amount += liquidation_fee_amount as u128;
-        token_reserve.deposited_amount -= liquidation_fee_amount as u128;
-        token_reserve.uncollected_liquidation_fees_amount += liquidation_fee_amount as u128;
-        sub_market.liquidated_amount += liquidation_amount_with_7_percent_bonus as u128;
-        sub_market.liquidated_amount += liquidation_fee_amount as u128;
-        sub_market.deposited_amount -= liquidation_fee_amount as u128;
-        sub_market.liquidation_fees_generated_amount += liquidation_fee_amount as u128;
-        liquidati_tab_account.deposited_amount -= liquidation_amount_with_7_percent_bonus;
-        liquidati_tab_account.deposited_amount -= liquidation_fee_amount;
-        liquidati_tab_account.liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_tab_account.liquidated_amount += liquidation_fee_amount;
-        liquidator_tab_account.liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_tab_account.fees_generated_amount += liquidation_fee_amount;
-        liquidati_monthly_statement_account.monthly_liquidated_amount += liquidation_amount_with_7_percent_bonus;
-        liquidati_monthly_statement_account.monthly_liquidated_amount += liquidation_fee_amount;
-        liquidati_monthly_statement_account.snap_shot_balance_amount = liquidati_tab_account.deposited_amount;
-        liquidator_monthly_statement_account.monthly_liquidator_amount += liquidation_amount_with_7_percent_bonus;
-        liquidator_monthly_statement_account.monthly_fees_generated_amount += liquidation_fee_amount;
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
 
-        if send_reward_to_wallet
+        for _i in 0..token_reserve_count
         {
-            let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.liquidator_ata.to_account_info().data.borrow()[..])?;
-            let balance_after_withdrawal = user_token_data.amount.saturating_sub(liquidation_amount_with_7_percent_bonus);
-            let should_close = balance_after_withdrawal == 0;
-            withdraw_tokens_from_token_reserve_to_user(
-                ctx.accounts.token_mint.key(),
-                token_reserve,
-                &ctx.accounts.token_reserve_ata.to_account_info(),
-                &ctx.accounts.liquidator_ata.to_account_info(),
-                &ctx.accounts.token_mint,
-                &ctx.accounts.token_program,
-                &ctx.accounts.signer,
-                &ctx.accounts.system_program,
-                liquidation_amount_with_7_percent_bonus,
-                should_close
-            )?;
+            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+            let mut token_reserve = validate_and_return_token_reserve_account(*ctx.program_id, token_reserve_account_serialized)?;
 
-            token_reserve.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128;
-            sub_market.deposited_amount -= liquidation_amount_with_7_percent_bonus as u128; 
-        }
-        else
-        {
-            liquidator_tab_account.deposited_amount += liquidation_amount_with_7_percent_bonus;
-            liquidator_monthly_statement_account.snap_shot_balance_amount = liquidator_tab_account.deposited_amount;
+            //Calculate Token Reserve Previously Earned And Accrued Interest so the values are current to the time stamp
+            update_token_reserve_supply_and_borrow_interest_change_index(&mut token_reserve, time_stamp, None)?;
+
+            let normalized_price_18_decimals = get_verified_token_usd_price(&temp_price_account.data, token_reserve.token_id, token_reserve.usd_quote_price_id)?;
+            update_protocol_tvl(protocol_tvl, &token_reserve, normalized_price_18_decimals, time_stamp)?;
         }
 
         //Refund Oracle price account fees back to Oracle
         let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
         require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
         refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
-        
-        //Update Stat Listener
-        lending_stats.liquidations += 1;
-        
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY
+
+        msg!("Refreshed Protocol TVL for {} Token Reserves", token_reserve_count);
+        verbose_msg!("Total Deposited USD Value: {}, Total Borrowed USD Value: {}", protocol_tvl.total_deposited_usd_value, protocol_tvl.total_borrowed_usd_value);
+
+        Ok(())
+    }
+
+    //Permissionless, anyone can pay the rent to write the current day's snapshot for a Token Reserve. Each day can only be written once
+    pub fn snapshot_reserve_day(ctx: Context<SnapshotReserveDay>, day: u32) -> Result<()> 
+    {
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let token_reserve_day_snapshot = &mut ctx.accounts.token_reserve_day_snapshot;
+        let clock = Clock::get()?;
+        let time_stamp = clock.unix_timestamp as u64;
+
+        //Only the current day can be written, so past days can't be backfilled with today's values
+        require!(day as u64 == time_stamp / SECONDS_PER_DAY, LendingError::InvalidSnapshotDay);
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest so the snapshot is current to the slot
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, and, Supply APY
         update_token_reserve_rates(token_reserve)?;
 
-        //Update Repayment SubMarket/User time stamp based interest indexes
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidati_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidati_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        liquidator_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        liquidator_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        token_reserve_day_snapshot.bump = ctx.bumps.token_reserve_day_snapshot;
+        token_reserve_day_snapshot.version = CURRENT_ACCOUNT_VERSION;
+        token_reserve_day_snapshot.token_id = token_reserve.token_id;
+        token_reserve_day_snapshot.token_mint_address = token_reserve.token_mint_address;
+        token_reserve_day_snapshot.day = day;
+        token_reserve_day_snapshot.clock_slot = clock.slot;
+        token_reserve_day_snapshot.time_stamp = time_stamp;
+        token_reserve_day_snapshot.deposited_amount = token_reserve.deposited_amount;
+        token_reserve_day_snapshot.borrowed_amount = token_reserve.borrowed_amount;
+        token_reserve_day_snapshot.utilization_rate = token_reserve.utilization_rate;
+        token_reserve_day_snapshot.supply_apy = token_reserve.supply_apy;
+        token_reserve_day_snapshot.borrow_apy = token_reserve.borrow_apy;
+        token_reserve_day_snapshot.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        token_reserve_day_snapshot.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        msg!("Created Day {} Snapshot for Token Reserve at Token ID: {}", day, token_reserve.token_id);
+        verbose_msg!("Deposited Amount: {}, Borrowed Amount: {}, Utilization Rate: {:.2}%", token_reserve.deposited_amount, token_reserve.borrowed_amount, token_reserve.utilization_rate as f64 / 100.0);
+
+        //Each day can only be snapshotted once, so the keeper bounty is paid once per Token Reserve per day
+        let bounty_amount = pay_keeper_bounty(&mut ctx.accounts.keeper_bounty_vault, &ctx.accounts.signer.to_account_info())?;
+        verbose_msg!("Bounty Amount: {}", bounty_amount);
 
-        //Update last activity on accounts
-        let liquidation_amount = liquidation_amount_with_7_percent_bonus + liquidation_fee_amount;
-        //token_reserve.last_lending_activity_amount = repayment_amount;
-        //token_reserve.last_lending_activity_type = Activity::Repay as u8; //Since the token is the same, make Liquidate the last activity on the Token Reserve
-        token_reserve.last_lending_activity_amount = liquidation_amount;
-        token_reserve.last_lending_activity_type = Activity::Liquidate as u8; //We'll let the Liquidate activity be the last activity since the repayment and liquidation token reserves are the same in this case
-        //sub_market.last_lending_activity_amount = repayment_amount;
-        //sub_market.last_lending_activity_type = Activity::Repay as u8;
-        //sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Sub Market
-        sub_market.last_lending_activity_amount = liquidation_amount;
-        sub_market.last_lending_activity_type = Activity::Liquidate as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        //liquidati_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        //liquidati_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        //liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Monthly Statement
-        liquidati_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidati_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidati_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        //liquidator_monthly_statement_account.last_lending_activity_amount = repayment_amount;
-        //liquidator_monthly_statement_account.last_lending_activity_type = Activity::Repay as u8;
-        //liquidator_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp; //Since the token is the same, make Liquidate the last activity on the Monthly Statement
-        liquidator_monthly_statement_account.last_lending_activity_amount = liquidation_amount;
-        liquidator_monthly_statement_account.last_lending_activity_type = Activity::Liquidate as u8;
-        liquidator_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        
-        //Save changes to passed in remaining accounts
-        lending_stats.serialize(&mut &mut lending_stats_serialized.data.borrow_mut()[8..])?;
-        sub_market.serialize(&mut &mut sub_market_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_tab_account.serialize(&mut &mut liquidati_tab_account_serialized.data.borrow_mut()[8..])?;
-        liquidati_monthly_statement_account.serialize(&mut &mut liquidati_monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        
-        msg!("{} liquidated {}", ctx.accounts.signer.key(), liquidati_account_owner_address.key());
+        Ok(())
+    }
 
-        msg!("Repaid debt and liquidated collateral at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+    //Permissionless, accrues a Token Reserve's interest and updates its rates so quiet Token Reserves keep up with their rate curve between user activity
+    pub fn refresh_token_reserve_interest(ctx: Context<RefreshTokenReserveInterest>) -> Result<()> 
+    {
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let interest_refresh_due = time_stamp.saturating_sub(token_reserve.last_lending_activity_time_stamp) >= KEEPER_INTEREST_REFRESH_INTERVAL_SECONDS;
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, and, Supply APY
+        update_token_reserve_rates(token_reserve)?;
+
+        //Only refreshes that were due pay the keeper bounty, so the vault can't be drained by refreshing the same Token Reserve over and over
+        let bounty_amount = if interest_refresh_due
+        {
+            pay_keeper_bounty(&mut ctx.accounts.keeper_bounty_vault, &ctx.accounts.signer.to_account_info())?
+        }
+        else
+        {
+            0
+        };
+
+        msg!("{} refreshed the Token Reserve interest at Token ID: {}", ctx.accounts.signer.key(), ctx.accounts.token_reserve.token_id);
+        verbose_msg!("Bounty Amount: {}", bounty_amount);
 
         Ok(())
     }
 
-    //You have to call this instruction for all user tab accounts before calling the withdraw, borrow, or liquidate functions in the same transaction.
-    //Feed in all of the Token Reserves remaining accounts as the same order as the token_reserve_mint_addresses input, then
-    //Repeating sets of these remaining accounts in this order (Successfully tested with 10 tab account sets at once): LendingUserTabAccount, Submarket, LendingUserMonthlyStatementAccount
-    pub fn refresh_user_health_chunk_and_token_reserves(ctx: Context<RefreshUserHealthChunkAndTokenReserves>,
-        user_account_index: u8,
-        refresh_token_reserve_count: u8, //The number of token reserves being refreshed may not be the number of unverified_price_data, ie when borrowing from a token reserve the user has never interacted with before
-        set_count: u8, //The number of LendingUserTabAccount, Submarket, and LendingUserMonthlyStatementAccount sets being fed in
-        close_price_account: bool //When just wanting to refresh the account without borrowing, set this flag to true so it closes the price account since you don't have a burrow-like instruction that will need it.
-        //Note Withdraw, Borrow, Repay, and Liquidate will close the price account when they are done.
-    ) -> Result<()> 
+    pub fn set_keeper_bounty(ctx: Context<SetKeeperBounty>, bounty_lamports: u64) -> Result<()> 
     {
-        let user_account_owner_address = ctx.accounts.lending_user_owner.key();
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+        let keeper_bounty_vault = &mut ctx.accounts.keeper_bounty_vault;
+        keeper_bounty_vault.bump = ctx.bumps.keeper_bounty_vault;
+        keeper_bounty_vault.version = CURRENT_ACCOUNT_VERSION;
+        keeper_bounty_vault.bounty_lamports = bounty_lamports;
 
-        let lending_protocol = &ctx.accounts.lending_protocol;
-        let price_validator = &ctx.accounts.price_validator;
+        msg!("Set the Keeper Bounty");
+        verbose_msg!("Bounty Lamports: {}", bounty_lamports);
+
+        Ok(())
+    }
+
+    //Permissionless garbage collection for a zero balance tab that has been inactive for a year. The caller gets a bounty out of the reclaimed rent and the rest goes back to the owner.
+    //Tabs have to be refreshed in the order they were created, so only the Lending User Account's last tab can be collected. The tab before it can be collected next.
+    pub fn collect_dead_tab_account(ctx: Context<CollectDeadTabAccount>,
+        token_id: u8,
+        sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<()> 
+    {
         let lending_user_account = &mut ctx.accounts.lending_user_account;
         let time_stamp = Clock::get()?.unix_timestamp as u64;
-        let clock_slot = Clock::get()?.slot;
 
-        //Return if User Lending Account is already updated to the current block slot
-        if lending_user_account.last_health_update_clock_slot == clock_slot
+        //Scoped so the Tab Account data is released before the account gets closed
         {
-            return Ok(())
+            let lending_user_tab_account = ctx.accounts.lending_user_tab_account.load()?;
+            require!(lending_user_tab_account.deposited_amount == 0 && lending_user_tab_account.borrowed_amount == 0, LendingError::TabAccountNotDead);
+            require!(time_stamp.saturating_sub(lending_user_tab_account.last_lending_activity_time_stamp) >= DEAD_TAB_INACTIVE_SECONDS, LendingError::TabAccountNotDead);
+            require!(lending_user_tab_account.user_tab_account_index + 1 == lending_user_account.tab_account_count, LendingError::TabAccountNotLast);
         }
 
-        //Check if this is an unfinished refresh or a brand new one.
-        //If the block has changed since we started refreshing, we MUST reset.
-        if lending_user_account.refresh_clock_slot != clock_slot
+        lending_user_account.tab_account_count -= 1;
+        lending_user_account.tab_account_addresses.pop();
+        if lending_user_account.next_tab_index_to_refresh >= lending_user_account.tab_account_count
         {
-            lending_user_account.temp_deposit_usd_value = 0;
-            lending_user_account.temp_borrow_usd_value = 0;
             lending_user_account.next_tab_index_to_refresh = 0;
-            lending_user_account.refresh_clock_slot = clock_slot;
+            lending_user_account.refreshed_tab_account_flags = 0;
         }
 
-        ////////////////////////////
-        //Validate Oracle Price Data
-        let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-        let temp_price_account = validate_and_return_temp_price_account(*ctx.program_id,
-            temp_price_account_serialized,
-            ctx.accounts.signer.key())?;
+        let bounty_amount = pay_garbage_collection_bounty(&ctx.accounts.lending_user_tab_account.to_account_info(), &ctx.accounts.signer.to_account_info(), GARBAGE_COLLECTION_BOUNTY_RATE)?;
+        ctx.accounts.lending_user_tab_account.close(ctx.accounts.lending_user.to_account_info())?;
 
-        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
+        msg!("{} collected the dead Tab Account of {}", ctx.accounts.signer.key(), ctx.accounts.lending_user.key());
+        verbose_msg!("Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, UserAccountIndex: {}", token_id, ctx.accounts.sub_market_owner.key(), sub_market_index, user_account_index);
+        verbose_msg!("Bounty Amount: {}", bounty_amount);
 
-        let mut token_reserves: Vec<(&AccountInfo, Structs::TokenReserve)> = Vec::with_capacity(refresh_token_reserve_count.into());
-        for _i in 0..refresh_token_reserve_count.into()
-        {
-            let token_reserve_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            let token_reserve = validate_and_return_token_reserve_account(*ctx.program_id,
-                token_reserve_account_serialized)?;
-            token_reserves.push((token_reserve_account_serialized, token_reserve)); 
-        }
+        Ok(())
+    }
 
-        for _i in 0..set_count.into()
-        {
-            //Validate Remaining Accounts
+    //Permissionless garbage collection for a Monthly Statement that is older than the archival window. The caller gets a bounty out of the reclaimed rent and the rest goes back to the owner.
+    pub fn collect_archived_monthly_statement_account(ctx: Context<CollectArchivedMonthlyStatementAccount>,
+        statement_month: u8,
+        statement_year: u16,
+        token_id: u8,
+        sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<()> 
+    {
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_user_monthly_statement_account = &ctx.accounts.lending_user_monthly_statement_account;
 
-            /////////////
-            //Tab Account
-            let tab_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            let data_ref = tab_account_serialized.data.borrow();
-            let mut data_slice: &[u8] = data_ref.deref();
+        //Statements are finalized once the month is over. Count how many months old this one is
+        let current_statement_months = lending_protocol.current_statement_year as u32 * 12 + lending_protocol.current_statement_month as u32;
+        let statement_months = statement_year as u32 * 12 + statement_month as u32;
+        require!(current_statement_months.saturating_sub(statement_months) > MONTHLY_STATEMENT_ARCHIVAL_MONTHS, LendingError::MonthlyStatementNotArchived);
 
-            let unvalidated_lending_user_tab_account = Structs::LendingUserTabAccount::try_deserialize(&mut data_slice)?;
+        let bounty_amount = pay_garbage_collection_bounty(&lending_user_monthly_statement_account.to_account_info(), &ctx.accounts.signer.to_account_info(), GARBAGE_COLLECTION_BOUNTY_RATE)?;
+        ctx.accounts.lending_user_monthly_statement_account.close(ctx.accounts.lending_user.to_account_info())?;
 
-            let mut lending_user_tab_account = validate_and_return_lending_user_tab_account(*ctx.program_id,
-                tab_account_serialized,
-                unvalidated_lending_user_tab_account.token_id,
-                unvalidated_lending_user_tab_account.sub_market_owner_address,
-                unvalidated_lending_user_tab_account.sub_market_index,
-                user_account_owner_address,
-                user_account_index)?;
+        msg!("{} collected the archived Monthly Statement of {}", ctx.accounts.signer.key(), ctx.accounts.lending_user.key());
+        verbose_msg!("Statement Month: {}, Year: {}", statement_month, statement_year);
+        verbose_msg!("Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, UserAccountIndex: {}", token_id, ctx.accounts.sub_market_owner.key(), sub_market_index, user_account_index);
+        verbose_msg!("Bounty Amount: {}", bounty_amount);
 
-            //You must provide all of the sub user's tab accounts ordered by user_tab_account_index
-            require!(lending_user_account.next_tab_index_to_refresh == lending_user_tab_account.user_tab_account_index, LendingError::IncorrectOrderOfTabAccounts);
-            
-            drop(data_ref);
+        Ok(())
+    }
 
-            ///////////////////////
-            //Token Reserve Account
-            let token_reserve_entry = token_reserves.iter_mut()
-                .find(|(_, token_reserve)| token_reserve.token_id == lending_user_tab_account.token_id)
-                .ok_or(LendingError::MissingTokenReserveAccountForRefresh)?;
-            let (token_reserve_account_serialized, token_reserve) = token_reserve_entry;
+    //Only the successor program can call this function instruction, through import_position, since the successor's Token Reserve has to sign.
+    //Moves a Tab Account's deposits and debt out of this program. The net balance is sent to the successor's Token Reserve and the Position Attestation is returned as return data.
+    //Debt only moves with enough of its own tab's collateral to back it, so the successor never takes on debt backed by collateral it doesn't hold
+    pub fn export_position(ctx: Context<ExportPosition>,
+        sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<Structs::PositionAttestation> 
+    {
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let price_validator = &ctx.accounts.price_validator;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let clock_slot = Clock::get()?.slot;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
 
-            ///////////////////
-            //SubMarket Account
-            let sub_market_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            let mut sub_market = validate_and_return_sub_market_account(*ctx.program_id,
-                sub_market_account_serialized,
-                lending_user_tab_account.token_id,
-                lending_user_tab_account.sub_market_owner_address,
-                lending_user_tab_account.sub_market_index)?;
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let token_mint_address = ctx.accounts.token_mint.key();
 
-            ///////////////////////////
-            //Monthly Statement Account
-            let monthly_statement_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            let mut monthly_statement_account = validate_and_return_lending_user_monthly_state_account(*ctx.program_id,
-                monthly_statement_account_serialized,
-                lending_protocol.current_statement_month,
-                lending_protocol.current_statement_year,
-                lending_user_tab_account.token_id,
-                lending_user_tab_account.sub_market_owner_address,
-                lending_user_tab_account.sub_market_index,
-                user_account_owner_address,
-                user_account_index)?;
+        //Positions can only be exported once the CEO has registered a successor program
+        require_keys_neq!(lending_protocol.successor_program_id, Pubkey::default(), LendingError::PositionMigrationNotEnabled);
+
+        //Only the successor program can sign for its Token Reserve
+        let successor_token_reserve_address = Pubkey::find_program_address(&[b"tokenReserve".as_ref(), token_mint_address.as_ref()], &lending_protocol.successor_program_id).0;
+        require_keys_eq!(ctx.accounts.successor_token_reserve.key(), successor_token_reserve_address, LendingError::InvalidSuccessorTokenReserve);
+
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This export_position function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt, unless their cached User Health is still fresh
+            check_user_health_freshness(lending_user_account, &ctx.accounts.user_health, lending_protocol.user_health_max_age_slots, clock_slot)?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        let deposited_amount = lending_user_tab_account.deposited_amount;
+        let borrowed_amount = lending_user_tab_account.borrowed_amount;
 
-            //Calculate Token Reserve Previously Earned And Accrued Interest
-            if token_reserve.last_health_update_clock_slot != clock_slot
-            {
-                update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, Some(clock_slot))?;
-            }
-            
-            update_user_previous_interest_earned(
-                token_reserve,
-                &mut sub_market,
-                &mut lending_user_tab_account,
-                &mut monthly_statement_account
-            )?;
+        //The tab's debt can't exceed the max Loan To Value of the tab's own deposits
+        check_moved_position_collateral(lending_user_account, token_reserve, deposited_amount, borrowed_amount)?;
 
-            update_user_previous_interest_accrued(
-                token_reserve,
-                &mut sub_market,
-                &mut lending_user_tab_account,
-                &mut monthly_statement_account
-            )?;
-            
-            //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-            update_token_reserve_rates(token_reserve)?;
-            sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-            sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-            lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-            lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        //Users with debt elsewhere need what's left behind to stay within the max Loan To Value, so the tab is priced and taken off of the health totals.
+        //The price accounts come first in the remaining accounts when they're passed in. They can be left out when the User Health attestation from refresh_user_health still has the prices
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            let price_accounts_passed_in = ctx.remaining_accounts.first().is_some_and(|account| is_temp_price_account(*ctx.program_id, account));
+            let normalized_price_18_decimals = if price_accounts_passed_in
+            {
+                ////////////////////////////
+                //Validate Oracle Price Data
+                let mut remaining_accounts_iter = ctx.remaining_accounts.iter();
+                let temp_price_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                let temp_price_account = validate_and_return_temp_price_account_reader(*ctx.program_id,
+                    temp_price_account_serialized,
+                    ctx.accounts.signer.key())?;
 
-            //Get normalized price with 8 decimals
-            let normalized_price_18_decimals = get_verified_token_price(&temp_price_account.data, token_reserve.token_id)?;
-            
-            //Update temp deposited and borrow values
-            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
-            lending_user_account.temp_deposit_usd_value += (lending_user_tab_account.deposited_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
-            lending_user_account.temp_borrow_usd_value += (lending_user_tab_account.borrowed_amount as u128 * normalized_price_18_decimals) / token_conversion_number;
+                check_token_price_staleness(temp_price_account.slot, clock_slot)?;
 
-            lending_user_account.next_tab_index_to_refresh += 1;
+                let normalized_price_18_decimals = temp_price_account.get_verified_token_usd_price(get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?;
 
-            //1. Save Token Reserve (Skip 8 byte discriminator)
-            token_reserve.serialize(&mut &mut token_reserve_account_serialized.data.borrow_mut()[8..])?;
+                //Refund Oracle price account fees back to Oracle
+                let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
+                require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
+                refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
 
-            //2. Save SubMarket (Skip 8 byte discriminator)
-            sub_market.serialize(&mut &mut sub_market_account_serialized.data.borrow_mut()[8..])?;
+                normalized_price_18_decimals
+            }
+            else
+            {
+                get_attested_user_health_usd_price(&ctx.accounts.user_health, clock_slot, get_sub_market_oracle_price_id(sub_market), token_reserve.usd_quote_price_id)?
+            };
+            let token_conversion_number = BASE_10_INT.pow(token_reserve.token_decimal_amount as u32); 
 
-            //3. Save User Tab Account (Skip 8 byte discriminator)
-            lending_user_tab_account.serialize(&mut &mut tab_account_serialized.data.borrow_mut()[8..])?;
+            let new_total_deposited_usd_value = lending_user_account.total_deposited_usd_value.saturating_sub(get_collateral_usd_value(token_reserve, (deposited_amount as u128 * normalized_price_18_decimals) / token_conversion_number));
+            let new_total_borrowed_usd_value = lending_user_account.total_borrowed_usd_value.saturating_sub((borrowed_amount as u128 * normalized_price_18_decimals) / token_conversion_number);
 
-            //4. Save Monthly Statement (Skip 8 byte discriminator)
-            monthly_statement_account.serialize(&mut &mut monthly_statement_account_serialized.data.borrow_mut()[8..])?;
-        }
+            //Multiply before dividing to help keep precision
+            let max_borrowed_usd_value = (new_total_deposited_usd_value * get_loan_to_value_rate(lending_user_account)) / DECIMAL_SCALING;
 
-        //Finalize if we've covered all of the Lending User's Tab Accounts
-        if lending_user_account.next_tab_index_to_refresh == lending_user_account.tab_account_count
-        {
-            lending_user_account.total_deposited_usd_value = lending_user_account.temp_deposit_usd_value;
-            lending_user_account.total_borrowed_usd_value = lending_user_account.temp_borrow_usd_value;
-            lending_user_account.last_health_update_clock_slot = clock_slot;
+            //You can't export a tab whose collateral is backing debt in the rest of the Lending User Account
+            require!(max_borrowed_usd_value >= new_total_borrowed_usd_value, LendingError::LiquidationExposure);
 
-            msg!("{} updated the health factor for Account Address: {}, Account Index: {}",
-            ctx.accounts.signer.key(),
-            user_account_owner_address.key(),
-            user_account_index);
+            //Take the tab off of the health totals so the same collateral can't be moved twice before the next refresh
+            lending_user_account.total_deposited_usd_value = new_total_deposited_usd_value;
+            lending_user_account.total_borrowed_usd_value = new_total_borrowed_usd_value;
+            update_cached_user_health(lending_user_account, &mut ctx.accounts.user_health);
         }
-
-        if close_price_account
+        else
         {
-            //Refund Oracle price account fees back to Oracle
-            let oracle_account_serialized = remaining_accounts_iter.next().ok_or(LendingError::MissingRemainingAccount)?;
-            require_keys_eq!(oracle_account_serialized.key(), price_validator.address, LendingError::PriceOracleKeyMisMatched);
-            refund_oracle_temp_account_fees(temp_price_account_serialized, oracle_account_serialized);
+            invalidate_cached_user_health(lending_user_account);
         }
 
-        Ok(())
-    }
+        //The debt moves with the deposits, so only the difference leaves the Token Reserve
+        let transfer_amount = deposited_amount - borrowed_amount;
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(token_reserve_ata_data.amount >= transfer_amount, LendingError::InsufficientLiquidity);
 
-    pub fn create_new_monthly_statement(ctx: Context<CreateNewMonthlyStatement>, token_id: u8, sub_market_index: u16, user_account_index: u8) -> Result<()> 
-    {
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
-        let user_account_owner_address = ctx.accounts.lending_user_owner.key();
-        let lending_protocol = &ctx.accounts.lending_protocol;
-        let lending_user_tab_account = &ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
+        transfer_tokens_from_token_reserve_to_successor_token_reserve(
+            token_mint_address,
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.successor_token_reserve_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            transfer_amount
+        )?;
 
-        initialize_lending_user_monthly_statement_account(
-            lending_user_monthly_statement_account,
-            lending_user_tab_account,
-            lending_protocol,
-            ctx.bumps.lending_user_monthly_statement_account,
-            token_id,
-            sub_market_owner_address.key(),
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        sub_market.deposited_amount -= deposited_amount as u128;
+        sub_market.borrowed_amount -= borrowed_amount as u128;
+        token_reserve.deposited_amount -= deposited_amount as u128;
+        token_reserve.borrowed_amount -= borrowed_amount as u128;
+        lending_user_tab_account.deposited_amount = 0;
+        lending_user_tab_account.borrowed_amount = 0;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = transfer_amount;
+        token_reserve.last_lending_activity_type = Activity::ExportPosition as u8;
+        sub_market.last_lending_activity_amount = transfer_amount;
+        sub_market.last_lending_activity_type = Activity::ExportPosition as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+        msg!("{} exported a position at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Deposited Amount: {}, Borrowed Amount: {}", deposited_amount, borrowed_amount);
+
+        Ok(Structs::PositionAttestation
+        {
+            token_mint_address,
+            sub_market_owner_address,
             sub_market_index,
-            user_account_owner_address.key(),
+            owner: ctx.accounts.signer.key(),
             user_account_index,
-        )?;
-
-        Ok(())
+            deposited_amount,
+            borrowed_amount,
+            exported_clock_slot: clock_slot
+        })
     }
 
-    pub fn claim_sub_market_fees(ctx: Context<ClaimSubMarketFees>,
+    //Pulls a position out of the predecessor program by CPIing into its export_position with this program's Token Reserve signing.
+    //The remaining accounts are the predecessor's export_position accounts in order (plus its price accounts if needed), followed by the predecessor program.
+    //The returned Position Attestation is checked against the tokens that actually landed in the Token Reserve before the deposits and debt are credited here
+    pub fn import_position<'info>(ctx: Context<'info, ImportPosition<'info>>,
         sub_market_index: u16,
         user_account_index: u8,
         account_name: Option<String>, //Optional variable. Use null on front end when not needed
         look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
     ) -> Result<()> 
     {
-        let sub_market = &mut ctx.accounts.sub_market;
-        //Only the Fee Collector can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), sub_market.fee_collector_address.key(), LendingError::NotFeeCollector);
-
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let lending_protocol = &ctx.accounts.lending_protocol;
         let lending_stats = &mut ctx.accounts.lending_stats;
         let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        check_sub_market_allowlist(sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
         let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
+        let lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
         let time_stamp = Clock::get()?.unix_timestamp as u64;
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let token_mint_address = ctx.accounts.token_mint.key();
+
+        //Positions can only be imported once the CEO has registered a predecessor program
+        require_keys_neq!(lending_protocol.predecessor_program_id, Pubkey::default(), LendingError::PositionMigrationNotEnabled);
+
+        //Populate lending user account if being newly initialized
         if lending_user_account.lending_user_account_added == false
         {
-            let mut new_account_name_to_use: String = String::from("Generic Sub Fee Claimer");
+            let mut new_account_name_to_use: String = String::from("Generic Depositer");
             if let Some(new_account_name) = account_name
             {
-                if !new_account_name.is_empty()
+                if !new_account_name.is_empty()//Check for empty string ""
                 {
                     new_account_name_to_use = new_account_name;
                 }
@@ -2656,16 +9001,149 @@ pub mod lending_protocol
             )?;
         }
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if lending_user_tab_account.user_tab_account_added == false
+        //Populate tab account if being newly initialized
+        if lending_user_tab_account.user_tab_account_added == 0
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
             initialize_lending_user_tab_account(
                 lending_user_account,
                 lending_user_tab_account,
+                ctx.accounts.lending_user_tab_account.key(),
                 lending_protocol,
                 ctx.bumps.lending_user_tab_account,
-                token_reserve.token_id, 
+                token_reserve.token_id,
+                sub_market_owner_address.key(),
+                sub_market_index,
+                ctx.accounts.signer.key(),
+                user_account_index
+            )?;
+        }
+
+        //Calculate Token Reserve Previously Earned And Accrued Interest
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        //The predecessor program has to be passed in so it can be invoked
+        let predecessor_program_serialized = ctx.remaining_accounts.last().ok_or(LendingError::MissingRemainingAccount)?;
+        require_keys_eq!(predecessor_program_serialized.key(), lending_protocol.predecessor_program_id, LendingError::InvalidMigrationProgramId);
+
+        let token_reserve_ata_amount_before_import = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?.amount;
+
+        let position_attestation = export_position_from_predecessor_program(
+            lending_protocol.predecessor_program_id,
+            token_mint_address,
+            token_reserve,
+            ctx.remaining_accounts,
+            sub_market_index,
+            user_account_index
+        )?;
+
+        //The attested position has to belong to the signer and match this Tab Account's Token and SubMarket
+        require_keys_eq!(position_attestation.owner, ctx.accounts.signer.key(), LendingError::PositionAttestationMismatch);
+        require_keys_eq!(position_attestation.token_mint_address, token_mint_address, LendingError::PositionAttestationMismatch);
+        require_keys_eq!(position_attestation.sub_market_owner_address, sub_market_owner_address, LendingError::PositionAttestationMismatch);
+        require!(position_attestation.sub_market_index == sub_market_index && position_attestation.user_account_index == user_account_index, LendingError::PositionAttestationMismatch);
+
+        //The net balance the predecessor sent over has to match what it attested to
+        let token_reserve_ata_amount_after_import = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?.amount;
+        let imported_amount = token_reserve_ata_amount_after_import - token_reserve_ata_amount_before_import;
+        require!(imported_amount == position_attestation.deposited_amount - position_attestation.borrowed_amount, LendingError::PositionAttestationMismatch);
+
+        let new_token_reserve_deposited_amount = position_attestation.deposited_amount as u128 + token_reserve.deposited_amount;
+        //You can't import more than the global limit
+        require!(new_token_reserve_deposited_amount <= token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+
+        //Update Values and Stat Listener
+        lending_stats.deposits += 1;
+        sub_market.deposited_amount += position_attestation.deposited_amount as u128;
+        sub_market.borrowed_amount += position_attestation.borrowed_amount as u128;
+        token_reserve.deposited_amount += position_attestation.deposited_amount as u128;
+        token_reserve.borrowed_amount += position_attestation.borrowed_amount as u128;
+        lending_user_tab_account.deposited_amount += position_attestation.deposited_amount;
+        lending_user_tab_account.borrowed_amount += position_attestation.borrowed_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = imported_amount;
+        token_reserve.last_lending_activity_type = Activity::ImportPosition as u8;
+        sub_market.last_lending_activity_amount = imported_amount;
+        sub_market.last_lending_activity_type = Activity::ImportPosition as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+
+        msg!("{} imported a position at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Deposited Amount: {}, Borrowed Amount: {}", position_attestation.deposited_amount, position_attestation.borrowed_amount);
+
+        Ok(())
+    }
+
+    //Moves a Tab Account's deposits and debt from a Token Reserve to a newer version of the same mint's Token Reserve, the same way export_position and import_position move them between programs.
+    //The CEO can set the old version's global limit to 0 to stop new deposits while users move over
+    pub fn migrate_position_to_token_reserve_version(ctx: Context<MigratePositionToTokenReserveVersion>,
+        sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<()> 
+    {
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let new_token_reserve = &mut ctx.accounts.new_token_reserve;
+        let new_sub_market = &mut ctx.accounts.new_sub_market.load_mut()?;
+        check_sub_market_allowlist(new_sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        require!(lending_user_tab_account.borrowed_amount == 0 || new_sub_market.borrows_disabled == 0, LendingError::SubMarketBorrowsDisabled);
+        let new_lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.new_lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let clock_slot = Clock::get()?.slot;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let token_mint_address = ctx.accounts.token_mint.key();
+
+        //Positions can only move forward to a newer version of the Token Reserve
+        require!(new_token_reserve.reserve_version > token_reserve.reserve_version, LendingError::InvalidTokenReserveVersion);
+
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This migrate_position_to_token_reserve_version function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt
+            require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        }
+
+        //Populate the new tab account if being newly initialized
+        if new_lending_user_tab_account.user_tab_account_added == 0
+        {
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                new_lending_user_tab_account,
+                ctx.accounts.new_lending_user_tab_account.key(),
+                lending_protocol,
+                ctx.bumps.new_lending_user_tab_account,
+                new_token_reserve.token_id,
                 sub_market_owner_address.key(),
                 sub_market_index,
                 ctx.accounts.signer.key(),
@@ -2673,516 +9151,701 @@ pub mod lending_protocol
             )?;
         }
 
-        //Initialize monthly statement account if the statement month/year has changed.
-        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        //Calculate Token Reserve Previously Earned And Accrued Interest on both versions
+        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        update_token_reserve_supply_and_borrow_interest_change_index(new_token_reserve, time_stamp, None)?;
+
+        update_user_previous_interest_earned(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_accrued(
+            token_reserve,
+            sub_market,
+            lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_earned(
+            new_token_reserve,
+            new_sub_market,
+            new_lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        update_user_previous_interest_accrued(
+            new_token_reserve,
+            new_sub_market,
+            new_lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        let deposited_amount = lending_user_tab_account.deposited_amount;
+        let borrowed_amount = lending_user_tab_account.borrowed_amount;
+
+        //The tab's debt can't exceed the max Loan To Value of the tab's own deposits, so tokens only ever flow from the old version to the new one
+        check_moved_position_collateral(lending_user_account, new_token_reserve, deposited_amount, borrowed_amount)?;
+
+        let new_token_reserve_deposited_amount = deposited_amount as u128 + new_token_reserve.deposited_amount;
+        //You can't migrate more than the new version's global limit
+        require!(new_token_reserve_deposited_amount <= new_token_reserve.global_limit, LendingError::GlobalLimitExceeded);
+
+        //The debt moves with the deposits, so only the difference moves between the Token Reserves
+        let transfer_amount = deposited_amount - borrowed_amount;
+        let token_reserve_ata_data = TokenAccount::try_deserialize(&mut &ctx.accounts.token_reserve_ata.to_account_info().data.borrow()[..])?;
+        require!(token_reserve_ata_data.amount >= transfer_amount, LendingError::InsufficientLiquidity);
+
+        transfer_tokens_from_token_reserve_to_successor_token_reserve(
+            token_mint_address,
+            token_reserve,
+            &ctx.accounts.token_reserve_ata.to_account_info(),
+            &ctx.accounts.new_token_reserve_ata.to_account_info(),
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+            transfer_amount
+        )?;
+
+        //Update Values and Stat Listener
+        lending_stats.withdrawals += 1;
+        lending_stats.deposits += 1;
+        sub_market.deposited_amount -= deposited_amount as u128;
+        sub_market.borrowed_amount -= borrowed_amount as u128;
+        token_reserve.deposited_amount -= deposited_amount as u128;
+        token_reserve.borrowed_amount -= borrowed_amount as u128;
+        lending_user_tab_account.deposited_amount = 0;
+        lending_user_tab_account.borrowed_amount = 0;
+        new_sub_market.deposited_amount += deposited_amount as u128;
+        new_sub_market.borrowed_amount += borrowed_amount as u128;
+        new_token_reserve.deposited_amount += deposited_amount as u128;
+        new_token_reserve.borrowed_amount += borrowed_amount as u128;
+        new_lending_user_tab_account.deposited_amount += deposited_amount;
+        new_lending_user_tab_account.borrowed_amount += borrowed_amount;
+
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+        sync_token_reserve_active_user_counts(new_token_reserve, new_lending_user_tab_account);
+
+        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes on both versions
+        update_token_reserve_rates(token_reserve)?;
+        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+
+        update_token_reserve_rates(new_token_reserve)?;
+        new_sub_market.supply_interest_change_index = new_token_reserve.supply_interest_change_index;
+        new_sub_market.borrow_interest_change_index = new_token_reserve.borrow_interest_change_index;
+        new_lending_user_tab_account.supply_interest_change_index = new_token_reserve.supply_interest_change_index;
+        new_lending_user_tab_account.borrow_interest_change_index = new_token_reserve.borrow_interest_change_index;
+
+        //Update last activity on accounts
+        token_reserve.last_lending_activity_amount = transfer_amount;
+        token_reserve.last_lending_activity_type = Activity::ExportPosition as u8;
+        sub_market.last_lending_activity_amount = transfer_amount;
+        sub_market.last_lending_activity_type = Activity::ExportPosition as u8;
+        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        new_token_reserve.last_lending_activity_amount = transfer_amount;
+        new_token_reserve.last_lending_activity_type = Activity::ImportPosition as u8;
+        new_sub_market.last_lending_activity_amount = transfer_amount;
+        new_sub_market.last_lending_activity_type = Activity::ImportPosition as u8;
+        new_sub_market.last_lending_activity_time_stamp = new_token_reserve.last_lending_activity_time_stamp;
+
+        msg!("{} migrated a position from Token ID: {} to Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        ctx.accounts.signer.key(),
+        token_reserve.token_id,
+        new_token_reserve.token_id,
+        sub_market_owner_address.key(),
+        sub_market_index);
+        verbose_msg!("Reserve Version: {} -> {}", token_reserve.reserve_version, new_token_reserve.reserve_version);
+        verbose_msg!("Deposited Amount: {}, Borrowed Amount: {}", deposited_amount, borrowed_amount);
+
+        Ok(())
+    }
+
+    //Moves a Tab Account's deposits and debt to a different SubMarket of the same Token Reserve, so users can leave a high fee SubMarket without withdrawing and redepositing.
+    //No tokens move since both SubMarkets share the Token Reserve's liquidity
+    pub fn migrate_sub_market(ctx: Context<MigrateSubMarket>,
+        sub_market_index: u16,
+        new_sub_market_index: u16,
+        user_account_index: u8
+    ) -> Result<()> 
+    {
+        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
+        let new_sub_market_owner_address = ctx.accounts.new_sub_market_owner.key();
+
+        //The position has to move to a different SubMarket
+        require!(sub_market_owner_address != new_sub_market_owner_address || sub_market_index != new_sub_market_index, LendingError::InvalidSubMarketMigration);
+
+        let lending_protocol = &ctx.accounts.lending_protocol;
+        let lending_stats = &mut ctx.accounts.lending_stats;
+        let lending_user_account = &mut ctx.accounts.lending_user_account;
+        let token_reserve = &mut ctx.accounts.token_reserve;
+        let sub_market = &mut ctx.accounts.sub_market.load_mut()?;
+        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account.load_mut()?;
+        let new_sub_market = &mut ctx.accounts.new_sub_market.load_mut()?;
+        check_sub_market_allowlist(new_sub_market, &ctx.accounts.sub_market_allowlist_entry)?;
+        require!(lending_user_tab_account.borrowed_amount == 0 || new_sub_market.borrows_disabled == 0, LendingError::SubMarketBorrowsDisabled);
+        let new_lending_user_tab_account = &mut load_mut_or_init(&ctx.accounts.new_lending_user_tab_account)?;
+        let mut lending_user_monthly_statement_account_placeholder = Structs::LendingUserMonthlyStatementAccount::default();
+        let clock_slot = Clock::get()?.slot;
+        let time_stamp = Clock::get()?.unix_timestamp as u64;
+
+        if lending_user_account.total_borrowed_usd_value > 0
+        {
+            //This migrate_sub_market function instruction must be called in the same transaction after the refresh_user_health_chunk function instruction(s) if the user has debt
+            require!(lending_user_account.last_health_update_clock_slot == clock_slot, LendingError::StaleTokenReserveOrLendingUser);
+        }
+
+        //Populate the new tab account if being newly initialized
+        if new_lending_user_tab_account.user_tab_account_added == 0
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                lending_user_monthly_statement_account,
-                lending_user_tab_account,
+            initialize_lending_user_tab_account(
+                lending_user_account,
+                new_lending_user_tab_account,
+                ctx.accounts.new_lending_user_tab_account.key(),
                 lending_protocol,
-                ctx.bumps.lending_user_monthly_statement_account,
+                ctx.bumps.new_lending_user_tab_account,
                 token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
+                new_sub_market_owner_address.key(),
+                new_sub_market_index,
                 ctx.accounts.signer.key(),
-                user_account_index,
+                user_account_index
             )?;
         }
 
-        //Calculate Token Reserve Previously Earned And Accrued Interest
+        //Calculate Token Reserve Previously Earned And Accrued Interest, so each SubMarket's fees are settled before the balances move
         update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
 
         update_user_previous_interest_earned(
             token_reserve,
             sub_market,
             lending_user_tab_account,
-            lending_user_monthly_statement_account
+            &mut lending_user_monthly_statement_account_placeholder
         )?;
 
         update_user_previous_interest_accrued(
             token_reserve,
             sub_market,
             lending_user_tab_account,
-            lending_user_monthly_statement_account
+            &mut lending_user_monthly_statement_account_placeholder
         )?;
 
-        //Collect Fees
-        token_reserve.deposited_amount += sub_market.uncollected_sub_market_fees_amount;
-        sub_market.deposited_amount += sub_market.uncollected_sub_market_fees_amount;
-        lending_user_tab_account.deposited_amount += sub_market.uncollected_sub_market_fees_amount as u64;
-        lending_user_monthly_statement_account.monthly_sub_market_fees_collected_amount += sub_market.uncollected_sub_market_fees_amount as u64;
-        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        update_user_previous_interest_earned(
+            token_reserve,
+            new_sub_market,
+            new_lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
+        update_user_previous_interest_accrued(
+            token_reserve,
+            new_sub_market,
+            new_lending_user_tab_account,
+            &mut lending_user_monthly_statement_account_placeholder
+        )?;
+
+        let deposited_amount = lending_user_tab_account.deposited_amount;
+        let borrowed_amount = lending_user_tab_account.borrowed_amount;
+
+        //Update Values and Stat Listener
+        //The Token Reserve totals don't change since the position never leaves the Token Reserve
+        lending_stats.withdrawals += 1;
+        lending_stats.deposits += 1;
+        sub_market.deposited_amount -= deposited_amount as u128;
+        sub_market.borrowed_amount -= borrowed_amount as u128;
+        lending_user_tab_account.deposited_amount = 0;
+        lending_user_tab_account.borrowed_amount = 0;
+        new_sub_market.deposited_amount += deposited_amount as u128;
+        new_sub_market.borrowed_amount += borrowed_amount as u128;
+        new_lending_user_tab_account.deposited_amount += deposited_amount;
+        new_lending_user_tab_account.borrowed_amount += borrowed_amount;
+        sync_token_reserve_active_user_counts(token_reserve, lending_user_tab_account);
+        sync_token_reserve_active_user_counts(token_reserve, new_lending_user_tab_account);
+
+        //Update the SubMarket/User time stamp based interest indexes on both SubMarkets
         sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
         sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
         lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
         lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-
-        //Stat Listener
-        lending_stats.fee_collections += 1;
+        new_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        new_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        new_lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
+        new_lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
 
         //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
-        token_reserve.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
-        sub_market.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
-        sub_market.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
+        sub_market.last_lending_activity_amount = deposited_amount;
+        sub_market.last_lending_activity_type = Activity::ExportPosition as u8;
         sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        lending_user_monthly_statement_account.last_lending_activity_amount = sub_market.uncollected_sub_market_fees_amount as u64;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-
-        sub_market.uncollected_sub_market_fees_amount = 0;
+        new_sub_market.last_lending_activity_amount = deposited_amount;
+        new_sub_market.last_lending_activity_type = Activity::ImportPosition as u8;
+        new_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
 
-        msg!("{} Collected SubMarket Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
+        msg!("{} migrated a position at Token ID: {} from SubMarketOwner: {}, SubMarketIndex: {}",
         ctx.accounts.signer.key(),
         token_reserve.token_id,
         sub_market_owner_address.key(),
         sub_market_index);
-
-        msg!("FeeCollectorAccountIndex: {}", user_account_index);
+        verbose_msg!("To SubMarketOwner: {}, SubMarketIndex: {}", new_sub_market_owner_address.key(), new_sub_market_index);
+        verbose_msg!("Deposited Amount: {}, Borrowed Amount: {}", deposited_amount, borrowed_amount);
 
         Ok(())
     }
 
-    pub fn claim_sub_market_fees_and_deposit_in_different_sub_market(ctx: Context<ClaimSubMarketFeesAndDepositInDifferentSubMarket>,
-        initial_sub_market_index: u16,
-        destination_sub_market_index: u16,
-        user_account_index: u8,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()> 
+    //Migrates an existing account to the current account layout: grows it to the current space and backfills fields added since it was created, so layout changes don't orphan existing accounts.
+    //Temp Oracle Price Accounts are closed in the same transaction they're created in, so they never need migrating.
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> 
     {
-        let initial_sub_market_owner_address = ctx.accounts.initial_sub_market_owner.key();
-        let destination_sub_market_owner_address = ctx.accounts.destination_sub_market_owner.key();
-        let initial_sub_market = &mut ctx.accounts.initial_sub_market;
-        //Only the Fee Collector can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), initial_sub_market.fee_collector_address.key(), LendingError::NotFeeCollector);
-                
-        //Duplicate SubMarket Detected
-        //When accounts are the exact same, it can lead to unexpected behavior where only one of them gets updated and would require extra steps
-        require!(initial_sub_market_owner_address.key() != destination_sub_market_owner_address.key() ||
-        initial_sub_market_index != destination_sub_market_index, LendingError::DuplicateSubMarket);
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let destination_sub_market = &mut ctx.accounts.destination_sub_market;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let initial_lending_user_tab_account = &mut ctx.accounts.initial_lending_user_tab_account;
-        let destination_lending_user_tab_account = &mut ctx.accounts.destination_lending_user_tab_account;
-        let initial_lending_user_monthly_statement_account = &mut ctx.accounts.initial_lending_user_monthly_statement_account;
-        let destination_lending_user_monthly_statement_account = &mut ctx.accounts.destination_lending_user_monthly_statement_account;
-        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        let account_info = &ctx.accounts.account_to_migrate.to_account_info();
+        let payer_info = &ctx.accounts.signer.to_account_info();
+        let system_program_info = &ctx.accounts.system_program.to_account_info();
+        let discriminator = account_info.data.borrow()[..8].to_vec();
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if lending_user_account.lending_user_account_added == false
+        if discriminator == Structs::LendingProtocolCEO::DISCRIMINATOR
         {
-            let mut new_account_name_to_use: String = String::from("Generic Sub Fee Claimer");
-            if let Some(new_account_name) = account_name
+            migrate_account_layout::<Structs::LendingProtocolCEO>(account_info, payer_info, system_program_info, Structs::LendingProtocolCEO::INIT_SPACE + 8, |account|
             {
-                if !new_account_name.is_empty()
+                account.bump = Pubkey::find_program_address(&[b"lendingProtocolCEO".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::SolvencyTreasurer::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::SolvencyTreasurer>(account_info, payer_info, system_program_info, Structs::SolvencyTreasurer::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"solvencyTreasurer".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::LiquidationTreasurer::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::LiquidationTreasurer>(account_info, payer_info, system_program_info, Structs::LiquidationTreasurer::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"liquidationTreasurer".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::OraclePriceValidator::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::OraclePriceValidator>(account_info, payer_info, system_program_info, Structs::OraclePriceValidator::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::LendingProtocol::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::LendingProtocol>(account_info, payer_info, system_program_info, Structs::LendingProtocol::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"lendingProtocol".as_ref()], ctx.program_id).1;
+                if account.sub_market_fee_ramp_seconds == 0
                 {
-                    new_account_name_to_use = new_account_name;
+                    account.sub_market_fee_ramp_seconds = INITIAL_SUB_MARKET_FEE_RAMP_SECONDS;
                 }
-            }
-
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
-
-            initialize_lending_user_account(
-                lending_user_account,
-                ctx.bumps.lending_user_account,
-                ctx.accounts.signer.key(),
-                user_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
+                set_current_account_version(&mut account.version)
+            })?;
         }
-
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if initial_lending_user_tab_account.user_tab_account_added == false
+        else if discriminator == Structs::TokenReserveStats::DISCRIMINATOR
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                initial_lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.initial_lending_user_tab_account,
-                token_reserve.token_id,
-                initial_sub_market_owner_address,
-                initial_sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
+            migrate_account_layout::<Structs::TokenReserveStats>(account_info, payer_info, system_program_info, Structs::TokenReserveStats::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"tokenReserveStats".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
         }
-        if destination_lending_user_tab_account.user_tab_account_added == false
+        else if discriminator == Structs::SubMarketStats::DISCRIMINATOR
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                destination_lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.destination_lending_user_tab_account,
-                token_reserve.token_id,
-                destination_sub_market_owner_address,
-                destination_sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
+            migrate_account_layout::<Structs::SubMarketStats>(account_info, payer_info, system_program_info, Structs::SubMarketStats::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"subMarketStats".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
         }
-
-        //Initialize monthly statement account if the statement month/year has changed.
-        if initial_lending_user_monthly_statement_account.monthly_statement_account_added == false
+        else if discriminator == Structs::LendingStats::DISCRIMINATOR
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                initial_lending_user_monthly_statement_account,
-                initial_lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.initial_lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                initial_sub_market_owner_address,
-                initial_sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
+            migrate_account_layout::<Structs::LendingStats>(account_info, payer_info, system_program_info, Structs::LendingStats::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
         }
-        if destination_lending_user_monthly_statement_account.monthly_statement_account_added == false
+        else if discriminator == Structs::LendingUserStats::DISCRIMINATOR
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                destination_lending_user_monthly_statement_account,
-                destination_lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.destination_lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                destination_sub_market_owner_address,
-                destination_sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
+            migrate_account_layout::<Structs::LendingUserStats>(account_info, payer_info, system_program_info, Structs::LendingUserStats::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"lendingUserStats".as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::TokenReserve::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::TokenReserve>(account_info, payer_info, system_program_info, Structs::TokenReserve::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::TokenReserveAuditSnapshot::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::TokenReserveAuditSnapshot>(account_info, payer_info, system_program_info, Structs::TokenReserveAuditSnapshot::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::InsuranceFund::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::InsuranceFund>(account_info, payer_info, system_program_info, Structs::InsuranceFund::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::CollateralAuction::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::CollateralAuction>(account_info, payer_info, system_program_info, Structs::CollateralAuction::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::SubMarket::DISCRIMINATOR
+        {
+            migrate_zero_copy_account_layout::<Structs::SubMarket>(account_info, payer_info, system_program_info, Structs::SubMarket::INIT_SPACE + 8, |account|
+            {
+                //SubMarkets created before fee ramping have no pending fee rate. Without a ramp in progress the pending rate is the current rate
+                if account.fee_ramp_end_time_stamp == 0
+                {
+                    account.pending_fee_on_interest_earned_rate = account.fee_on_interest_earned_rate;
+                }
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::SubMarketOwnerLookUpTable::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::SubMarketOwnerLookUpTable>(account_info, payer_info, system_program_info, Structs::SubMarketOwnerLookUpTable::INIT_SPACE + 8, |account|
+            {
+                account.bump = Pubkey::find_program_address(&[b"subMarketOwnerLookUpTable".as_ref(), account.owner.as_ref()], ctx.program_id).1;
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::LendingUserAccount::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::LendingUserAccount>(account_info, payer_info, system_program_info, Structs::LendingUserAccount::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::LendingUserTabAccount::DISCRIMINATOR
+        {
+            migrate_zero_copy_account_layout::<Structs::LendingUserTabAccount>(account_info, payer_info, system_program_info, Structs::LendingUserTabAccount::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::LendingUserMonthlyStatementAccount::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::LendingUserMonthlyStatementAccount>(account_info, payer_info, system_program_info, Structs::LendingUserMonthlyStatementAccount::INIT_SPACE + 8, |account|
+            {
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else if discriminator == Structs::UserHealth::DISCRIMINATOR
+        {
+            migrate_account_layout::<Structs::UserHealth>(account_info, payer_info, system_program_info, Structs::UserHealth::INIT_SPACE + 8, |account|
+            {
+                //The old version byte lands in the new expiry, so start the attestation expired
+                account.expiry_clock_slot = 0;
+                account.attested_prices.clear();
+                set_current_account_version(&mut account.version)
+            })?;
+        }
+        else
+        {
+            return Err(error!(LendingError::UnknownAccountType));
         }
 
-        //Calculate Token Reserve Previously Earned And Accrued Interest
-        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        msg!("Migrated Account: {} to Account Version: {}", account_info.key(), CURRENT_ACCOUNT_VERSION);
 
-        update_user_previous_interest_earned(
-            token_reserve,
-            destination_sub_market,
-            destination_lending_user_tab_account,
-            destination_lending_user_monthly_statement_account
-        )?;
+        Ok(())
+    }
 
-        update_user_previous_interest_accrued(
-            token_reserve,
-            destination_sub_market,
-            destination_lending_user_tab_account,
-            destination_lending_user_monthly_statement_account
-        )?;
+    //Grows the Lending Protocol account to the current layout after an upgrade appends new fields. New fields start zeroed
+    pub fn realloc_lending_protocol(ctx: Context<ReallocLendingProtocol>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Collect Fees
-        token_reserve.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount;
-        destination_sub_market.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount;
-        destination_lending_user_tab_account.deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        initial_lending_user_monthly_statement_account.monthly_sub_market_fees_collected_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        initial_lending_user_monthly_statement_account.monthly_withdrawal_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64; //Treating this as a withdrawal from initial submarket. The fee collection and withdrawal cancel each other out, so no need to update snap shot balance for initial submarket.
-        destination_lending_user_monthly_statement_account.monthly_deposited_amount += initial_sub_market.uncollected_sub_market_fees_amount as u64; //Treating this as a deposit into destination submarket.
-        destination_lending_user_monthly_statement_account.snap_shot_balance_amount = destination_lending_user_tab_account.deposited_amount;
+        let new_space = Structs::LendingProtocol::INIT_SPACE + 8;
+        grow_account::<Structs::LendingProtocol>(&ctx.accounts.lending_protocol.to_account_info(),
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space)?;
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        destination_sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        destination_sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        destination_lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        destination_lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        msg!("Grew Lending Protocol account to {} bytes", new_space);
 
-        //Stat Listener
-        lending_stats.fee_collections += 1;
+        Ok(())
+    }
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        token_reserve.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
-        initial_sub_market.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        initial_sub_market.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
-        initial_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        destination_sub_market.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        destination_sub_market.last_lending_activity_type = Activity::Deposit as u8;
-        destination_sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        initial_lending_user_monthly_statement_account.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        initial_lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSubMarketFees as u8;
-        initial_lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        destination_lending_user_monthly_statement_account.last_lending_activity_amount = initial_sub_market.uncollected_sub_market_fees_amount as u64;
-        destination_lending_user_monthly_statement_account.last_lending_activity_type = Activity::Deposit as u8;
-        destination_lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+    //Grows a Token Reserve account to the current layout after an upgrade appends new fields. New fields start zeroed
+    pub fn realloc_token_reserve(ctx: Context<ReallocTokenReserve>) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        initial_sub_market.uncollected_sub_market_fees_amount = 0;
+        let new_space = Structs::TokenReserve::INIT_SPACE + 8;
+        grow_account::<Structs::TokenReserve>(&ctx.accounts.token_reserve.to_account_info(),
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space)?;
 
-        msg!("{} Collected SubMarket Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        initial_sub_market_owner_address.key(),
-        initial_sub_market_index);
+        msg!("Grew Token Reserve account for Token Mint: {} to {} bytes", ctx.accounts.token_mint_address.key(), new_space);
+
+        Ok(())
+    }
 
-        msg!("FeeCollectorAccountIndex: {}", user_account_index);
+    //Lets a Lending User grow their own Lending User Account to the current layout after an upgrade appends new fields. New fields start zeroed
+    pub fn realloc_lending_user_account(ctx: Context<ReallocLendingUserAccount>, user_account_index: u8) -> Result<()> 
+    {
+        let new_space = Structs::LendingUserAccount::INIT_SPACE + 8;
+        grow_account::<Structs::LendingUserAccount>(&ctx.accounts.lending_user_account.to_account_info(),
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space)?;
 
-        msg!("Fees Moved to DestinationSubMarketOwner: {}, DestinationSubMarketIndex: {}", destination_sub_market_owner_address.key(), destination_sub_market_index);
+        msg!("Grew Lending User Account Index: {} to {} bytes", user_account_index, new_space);
 
         Ok(())
     }
 
-    pub fn claim_solvency_insurance_fees(ctx: Context<ClaimSolvencyInsuranceFees>,
+    //Lets a Lending User grow their own Tab Account to the current layout after an upgrade appends new fields. New fields start zeroed
+    pub fn realloc_lending_user_tab_account(ctx: Context<ReallocLendingUserTabAccount>,
+        token_id: u8,
         sub_market_index: u16,
-        user_account_index: u8,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
+        user_account_index: u8
     ) -> Result<()> 
     {
-        let solvency_treasurer = &ctx.accounts.solvency_treasurer;
-        //Only the Treasurer can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), solvency_treasurer.address.key(), LendingError::NotSolvencyTreasurer);
+        let new_space = Structs::LendingUserTabAccount::INIT_SPACE + 8;
+        grow_account::<Structs::LendingUserTabAccount>(&ctx.accounts.lending_user_tab_account.to_account_info(),
+            &ctx.accounts.signer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_space)?;
+
+        msg!("Grew Tab Account at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}, UserAccountIndex: {} to {} bytes",
+        token_id,
+        ctx.accounts.sub_market_owner.key(),
+        sub_market_index,
+        user_account_index,
+        new_space);
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        Ok(())
+    }
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if lending_user_account.lending_user_account_added == false
-        {
-            let mut new_account_name_to_use: String = String::from("Solvency Treasury");
-            if let Some(new_account_name) = account_name
-            {
-                if !new_account_name.is_empty()
-                {
-                    new_account_name_to_use = new_account_name;
-                }
-            }
+    //Lists an AMM LP token's pool with the constituent token price IDs it's valued with. The LP token needs its own Token Reserve first.
+    //Leave out the Token B mint and vault to list an external receipt token backed by a single vault instead
+    pub fn add_lp_token_price_adapter(ctx: Context<AddLpTokenPriceAdapter>,
+        token_a_price_id: u8,
+        token_b_price_id: u8,
+        haircut_rate: u16
+    ) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+        //Haircut rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(haircut_rate <= 10_000, LendingError::InvalidLpTokenHaircutRate);
 
-            initialize_lending_user_account(
-                lending_user_account,
-                ctx.bumps.lending_user_account,
-                ctx.accounts.signer.key(),
-                user_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
-        }
+        let token_reserve = &ctx.accounts.token_reserve;
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if lending_user_tab_account.user_tab_account_added == false
-        {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_tab_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
-        }
+        //The vaults have to hold the constituent tokens
+        require_keys_eq!(ctx.accounts.token_a_vault.mint, ctx.accounts.token_a_mint.key(), LendingError::LpTokenVaultMintMisMatched);
 
-        //Initialize monthly statement account if the statement month/year has changed.
-        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+        //The constituent prices can't be missing or be the LP token's own derived price
+        require!(token_a_price_id != 0 && token_a_price_id != token_reserve.token_id, LendingError::InvalidLpTokenPriceId);
+
+        let mut token_b_vault_address = Pubkey::default();
+        let mut token_b_decimal_amount = 0;
+        match (&ctx.accounts.token_b_mint, &ctx.accounts.token_b_vault)
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                lending_user_monthly_statement_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
+            (Some(token_b_mint), Some(token_b_vault)) =>
+            {
+                require_keys_eq!(token_b_vault.mint, token_b_mint.key(), LendingError::LpTokenVaultMintMisMatched);
+                require!(token_b_price_id != 0 && token_b_price_id != token_reserve.token_id, LendingError::InvalidLpTokenPriceId);
+
+                token_b_vault_address = token_b_vault.key();
+                token_b_decimal_amount = token_b_mint.decimals;
+            },
+            //Receipt tokens are backed by a single vault, so there's no Token B price
+            (None, None) => require!(token_b_price_id == 0, LendingError::InvalidLpTokenPriceId),
+            _ => return err!(LendingError::LpTokenVaultMisMatched)
         }
 
-        let amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
-        let user_token_data = TokenAccount::try_deserialize(&mut &ctx.accounts.treasurer_ata.to_account_info().data.borrow()[..])?;
-        let balance_after_withdrawal = user_token_data.amount.saturating_sub(amount);
-        let should_close = balance_after_withdrawal == 0;
-        withdraw_tokens_from_token_reserve_to_user(
-            ctx.accounts.token_mint.key(),
-            token_reserve,
-            &ctx.accounts.token_reserve_ata.to_account_info(),
-            &ctx.accounts.treasurer_ata.to_account_info(),
-            &ctx.accounts.token_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.signer,
-            &ctx.accounts.system_program,
-            amount,
-            should_close
-        )?;
+        let lp_token_price_adapter = &mut ctx.accounts.lp_token_price_adapter;
+        lp_token_price_adapter.bump = ctx.bumps.lp_token_price_adapter;
+        lp_token_price_adapter.version = CURRENT_ACCOUNT_VERSION;
+        lp_token_price_adapter.token_id = token_reserve.token_id;
+        lp_token_price_adapter.lp_token_mint_address = ctx.accounts.lp_token_mint.key();
+        lp_token_price_adapter.token_a_vault_address = ctx.accounts.token_a_vault.key();
+        lp_token_price_adapter.token_b_vault_address = token_b_vault_address;
+        lp_token_price_adapter.token_a_price_id = token_a_price_id;
+        lp_token_price_adapter.token_b_price_id = token_b_price_id;
+        lp_token_price_adapter.token_a_decimal_amount = ctx.accounts.token_a_mint.decimals;
+        lp_token_price_adapter.token_b_decimal_amount = token_b_decimal_amount;
+        lp_token_price_adapter.haircut_rate = haircut_rate;
+
+        msg!("Added LP Token Price Adapter for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Token A Vault: {}, Price ID: {}", lp_token_price_adapter.token_a_vault_address, token_a_price_id);
+        verbose_msg!("Token B Vault: {}, Price ID: {}", lp_token_price_adapter.token_b_vault_address, token_b_price_id);
+        verbose_msg!("Haircut Rate: {:.2}%", haircut_rate as f64 / 100.0);
 
-        //Record Solvency Insurance Fee Collection
-        lending_user_monthly_statement_account.monthly_solvency_insurance_fees_collected_amount += amount;
+        Ok(())
+    }
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
-        token_reserve.last_lending_activity_type = Activity::CollectSolvencyFees as u8;
-        lending_user_monthly_statement_account.last_lending_activity_amount = token_reserve.uncollected_solvency_insurance_fees_amount as u64;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectSolvencyFees as u8;
-        //No interest change calculated since the solvency fees are sent to the Solvency Wallet outside the protocol, so not using the time stamp on the Token Reserve
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = time_stamp;
+    pub fn update_lp_token_price_haircut_rate(ctx: Context<UpdateLpTokenPriceAdapter>, haircut_rate: u16) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the Risk Admin or the CEO can call this function
+        check_ceo_or_risk_admin(ctx.accounts.signer.key(), ceo.address.key(), &ctx.accounts.roles)?;
 
-        token_reserve.uncollected_solvency_insurance_fees_amount = 0;
+        //Haircut rate can't be greater than 100%, 1.00 in decimal form, 10,000 in fixed point notation
+        require!(haircut_rate <= 10_000, LendingError::InvalidLpTokenHaircutRate);
 
-        //Stat Listener
-        lending_stats.fee_collections += 1;
+        //While the timelock is on, LP Token haircut changes have to go through queue_timelocked_change
+        require!(ctx.accounts.lending_protocol.timelock_delay_seconds == 0, LendingError::TimelockRequired);
 
-        msg!("{} Collected Solvency Insurance Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+        let lp_token_price_adapter = &mut ctx.accounts.lp_token_price_adapter;
+        lp_token_price_adapter.haircut_rate = haircut_rate;
 
-        msg!("FeeCollectorAccountIndex: {}", user_account_index);
+        msg!("Updated LP Token Price Haircut Rate for Token ID: {}", lp_token_price_adapter.token_id);
+        verbose_msg!("Haircut Rate: {:.2}%", haircut_rate as f64 / 100.0);
 
         Ok(())
     }
 
-    pub fn claim_liquidation_fees(ctx: Context<ClaimLiquidationFees>,
-        sub_market_index: u16,
-        user_account_index: u8,
-        account_name: Option<String>, //Optional variable. Use null on front end when not needed
-        look_up_table_address: Option<Pubkey> //Needed when a user initializes their Lending User Account
-    ) -> Result<()> 
+    //Permissionless. Derives an LP token's fair price from its pool and the verified constituent prices, and appends it to the Lending User's price data under the LP token's Token ID.
+    //Call it after create_temp_oracle_price_data and before the instructions that need the LP token price, so the health loop values the LP token like any other token
+    pub fn refresh_lp_token_price(ctx: Context<RefreshLpTokenPrice>) -> Result<()> 
     {
-        let liquidation_treasurer = &ctx.accounts.liquidation_treasurer;
-        //Only the Treasurer can call this function
-        require_keys_eq!(ctx.accounts.signer.key(), liquidation_treasurer.address.key(), LendingError::NotLiquidationTreasurer);
+        let lp_token_price_adapter = &ctx.accounts.lp_token_price_adapter;
+        let temp_price_account = &mut ctx.accounts.temp_price_account;
+        let clock_slot = Clock::get()?.slot;
 
-        let sub_market_owner_address = ctx.accounts.sub_market_owner.key();
-        let lending_stats = &mut ctx.accounts.lending_stats;
-        let token_reserve = &mut ctx.accounts.token_reserve;
-        let sub_market = &mut ctx.accounts.sub_market;
-        let lending_user_account = &mut ctx.accounts.lending_user_account;
-        let lending_user_tab_account = &mut ctx.accounts.lending_user_tab_account;
-        let lending_user_monthly_statement_account = &mut ctx.accounts.lending_user_monthly_statement_account;
-        let time_stamp = Clock::get()?.unix_timestamp as u64;
+        require_keys_eq!(ctx.accounts.token_a_vault.key(), lp_token_price_adapter.token_a_vault_address, LendingError::LpTokenVaultMisMatched);
 
-        //Populate lending user account if being newly initialized. A user can have multiple accounts based on their account index. 
-        if lending_user_account.lending_user_account_added == false
-        {
-            let mut new_account_name_to_use: String = String::from("HODL Treasury");
-            if let Some(new_account_name) = account_name
-            {
-                if !new_account_name.is_empty()
-                {
-                    new_account_name_to_use = new_account_name;
-                }
-            }
+        check_token_price_staleness(temp_price_account.slot, clock_slot)?;
 
-            let lut_address = look_up_table_address.ok_or(LendingError::MissingLendingUserLookUpTable)?;
+        //The LP token price can only be added once per price data account
+        require!(!temp_price_account.data.iter().any(|data| data.token_id == lp_token_price_adapter.token_id), LendingError::LpTokenPriceAlreadyRefreshed);
 
-            initialize_lending_user_account(
-                lending_user_account,
-                ctx.bumps.lending_user_account,
-                ctx.accounts.signer.key(),
-                user_account_index,
-                new_account_name_to_use,
-                lut_address
-            )?;
-        }
+        let token_a_price_18_decimals = get_verified_token_price(&temp_price_account.data, lp_token_price_adapter.token_a_price_id)?;
 
-        //Populate tab account if being newly initialized. Every token the lending user interacts with has its own tab account tied to that sub user and their account index.
-        if lending_user_tab_account.user_tab_account_added == false
+        let fair_lp_token_price_18_decimals = if lp_token_price_adapter.token_b_price_id == 0
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_tab_account(
-                lending_user_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_tab_account,
-                token_reserve.token_id, 
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index
-            )?;
+            calculate_receipt_token_price(ctx.accounts.token_a_vault.amount,
+                lp_token_price_adapter.token_a_decimal_amount,
+                token_a_price_18_decimals,
+                ctx.accounts.lp_token_mint.supply,
+                ctx.accounts.lp_token_mint.decimals)?
         }
+        else
+        {
+            let token_b_vault = ctx.accounts.token_b_vault.as_ref().ok_or(LendingError::LpTokenVaultMisMatched)?;
+            require_keys_eq!(token_b_vault.key(), lp_token_price_adapter.token_b_vault_address, LendingError::LpTokenVaultMisMatched);
 
-        //Initialize monthly statement account if the statement month/year has changed.
-        if lending_user_monthly_statement_account.monthly_statement_account_added == false
+            let token_b_price_18_decimals = get_verified_token_price(&temp_price_account.data, lp_token_price_adapter.token_b_price_id)?;
+
+            calculate_fair_lp_token_price(ctx.accounts.token_a_vault.amount,
+                lp_token_price_adapter.token_a_decimal_amount,
+                token_a_price_18_decimals,
+                token_b_vault.amount,
+                lp_token_price_adapter.token_b_decimal_amount,
+                token_b_price_18_decimals,
+                ctx.accounts.lp_token_mint.supply,
+                ctx.accounts.lp_token_mint.decimals)?
+        };
+
+        //Take the haircut off of the fair price to cover pool risks the fair value can't see
+        let lp_token_price_18_decimals = fair_lp_token_price_18_decimals * (10_000 - lp_token_price_adapter.haircut_rate as u128) / 10_000;
+
+        temp_price_account.data.push(Structs::VerifiedPriceData
         {
-            let lending_protocol = &ctx.accounts.lending_protocol;
-            initialize_lending_user_monthly_statement_account(
-                lending_user_monthly_statement_account,
-                lending_user_tab_account,
-                lending_protocol,
-                ctx.bumps.lending_user_monthly_statement_account,
-                token_reserve.token_id,
-                sub_market_owner_address.key(),
-                sub_market_index,
-                ctx.accounts.signer.key(),
-                user_account_index,
-            )?;
-        }
+            token_id: lp_token_price_adapter.token_id,
+            normalized_price_18_decimals: lp_token_price_18_decimals
+        });
 
-        //Calculate Token Reserve Previously Earned And Accrued Interest
-        update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, time_stamp, None)?;
+        msg!("Refreshed LP Token Price for Token ID: {}, Price: {}", lp_token_price_adapter.token_id, lp_token_price_18_decimals);
 
-        update_user_previous_interest_earned(
-            token_reserve,
-            sub_market,
-            lending_user_tab_account,
-            lending_user_monthly_statement_account
-        )?;
+        Ok(())
+    }
 
-        update_user_previous_interest_accrued(
-            token_reserve,
-            sub_market,
-            lending_user_tab_account,
-            lending_user_monthly_statement_account
-        )?;
+    //Turns a Token Reserve into a liquid staking token priced off of its SPL Stake Pool's exchange rate and the SOL price
+    pub fn add_lst_token_price_adapter(ctx: Context<AddLstTokenPriceAdapter>, sol_price_id: u8) -> Result<()> 
+    {
+        let ceo = &ctx.accounts.ceo;
+        //Only the CEO can call this function
+        require_keys_eq!(ctx.accounts.signer.key(), ceo.address.key(), LendingError::NotCEO);
 
-        //Collect Fees
-        token_reserve.deposited_amount += token_reserve.uncollected_liquidation_fees_amount;
-        sub_market.deposited_amount += token_reserve.uncollected_liquidation_fees_amount;
-        lending_user_tab_account.deposited_amount += token_reserve.uncollected_liquidation_fees_amount as u64;
-        lending_user_monthly_statement_account.monthly_liquidation_fees_collected_amount += token_reserve.uncollected_liquidation_fees_amount as u64;
-        lending_user_monthly_statement_account.snap_shot_balance_amount = lending_user_tab_account.deposited_amount;
+        let token_reserve = &mut ctx.accounts.token_reserve;
 
-        //Update Token Reserve Global Utilization Rate, Borrow APY, Supply APY, and the SubMarket/User time stamp based interest indexes
-        update_token_reserve_rates(token_reserve)?;
-        sub_market.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        sub_market.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
-        lending_user_tab_account.supply_interest_change_index = token_reserve.supply_interest_change_index;
-        lending_user_tab_account.borrow_interest_change_index = token_reserve.borrow_interest_change_index;
+        //The stake pool has to mint the LST
+        let (pool_mint_address, _, _, _) = get_stake_pool_balances(&ctx.accounts.stake_pool.to_account_info())?;
+        require_keys_eq!(pool_mint_address, ctx.accounts.lst_token_mint.key(), LendingError::LstStakePoolMisMatched);
 
-        //Stat Listener
-        lending_stats.fee_collections += 1;
+        //The SOL price can't be missing or be the LST's own derived price
+        require!(sol_price_id != 0 && sol_price_id != token_reserve.token_id, LendingError::InvalidLstTokenPriceId);
 
-        //Update last activity on accounts
-        token_reserve.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
-        token_reserve.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
-        sub_market.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
-        sub_market.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
-        sub_market.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
-        lending_user_monthly_statement_account.last_lending_activity_amount = token_reserve.uncollected_liquidation_fees_amount as u64;
-        lending_user_monthly_statement_account.last_lending_activity_type = Activity::CollectLiquidationFees as u8;
-        lending_user_monthly_statement_account.last_lending_activity_time_stamp = token_reserve.last_lending_activity_time_stamp;
+        let lst_token_price_adapter = &mut ctx.accounts.lst_token_price_adapter;
+        lst_token_price_adapter.bump = ctx.bumps.lst_token_price_adapter;
+        lst_token_price_adapter.version = CURRENT_ACCOUNT_VERSION;
+        lst_token_price_adapter.token_id = token_reserve.token_id;
+        lst_token_price_adapter.lst_token_mint_address = ctx.accounts.lst_token_mint.key();
+        lst_token_price_adapter.stake_pool_address = ctx.accounts.stake_pool.key();
+        lst_token_price_adapter.sol_price_id = sol_price_id;
 
-        token_reserve.uncollected_liquidation_fees_amount = 0;
+        token_reserve.is_liquid_staking_token = true;
 
-        msg!("{} Collected Liquidation Fees at Token ID: {}, SubMarketOwner: {}, SubMarketIndex: {}",
-        ctx.accounts.signer.key(),
-        token_reserve.token_id,
-        sub_market_owner_address.key(),
-        sub_market_index);
+        msg!("Added LST Token Price Adapter for Token ID: {}", token_reserve.token_id);
+        verbose_msg!("Stake Pool: {}, SOL Price ID: {}", lst_token_price_adapter.stake_pool_address, sol_price_id);
+
+        Ok(())
+    }
+
+    //Permissionless. Derives a liquid staking token's price from the verified SOL price and its stake pool's exchange rate, and appends it to the Lending User's price data under the LST's Token ID.
+    //Call it after create_temp_oracle_price_data and before the instructions that need the LST price, the same as refresh_lp_token_price
+    pub fn refresh_lst_token_price(ctx: Context<RefreshLstTokenPrice>) -> Result<()> 
+    {
+        let lst_token_price_adapter = &ctx.accounts.lst_token_price_adapter;
+        let temp_price_account = &mut ctx.accounts.temp_price_account;
+        let clock = Clock::get()?;
+
+        require_keys_eq!(ctx.accounts.stake_pool.key(), lst_token_price_adapter.stake_pool_address, LendingError::LstStakePoolMisMatched);
+
+        check_token_price_staleness(temp_price_account.slot, clock.slot)?;
+
+        //The LST price can only be added once per price data account
+        require!(!temp_price_account.data.iter().any(|data| data.token_id == lst_token_price_adapter.token_id), LendingError::LstTokenPriceAlreadyRefreshed);
+
+        let (_, total_lamports_amount, pool_token_supply_amount, last_update_epoch) = get_stake_pool_balances(&ctx.accounts.stake_pool.to_account_info())?;
+
+        //The exchange rate is only current once the stake pool has been updated for this epoch's rewards
+        require!(last_update_epoch == clock.epoch, LendingError::StakePoolNotUpdated);
+
+        let sol_price_18_decimals = get_verified_token_price(&temp_price_account.data, lst_token_price_adapter.sol_price_id)?;
+
+        let lst_token_price_18_decimals = calculate_lst_token_price(sol_price_18_decimals,
+            total_lamports_amount,
+            pool_token_supply_amount,
+            ctx.accounts.lst_token_mint.decimals)?;
+
+        temp_price_account.data.push(Structs::VerifiedPriceData
+        {
+            token_id: lst_token_price_adapter.token_id,
+            normalized_price_18_decimals: lst_token_price_18_decimals
+        });
 
-        msg!("FeeCollectorAccountIndex: {}", user_account_index);
+        msg!("Refreshed LST Token Price for Token ID: {}, Price: {}", lst_token_price_adapter.token_id, lst_token_price_18_decimals);
 
         Ok(())
     }
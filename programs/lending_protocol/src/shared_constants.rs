@@ -1 +1,13 @@
-pub const MAX_ACCOUNT_NAME_LENGTH: usize = 25;
\ No newline at end of file
+pub const MAX_ACCOUNT_NAME_LENGTH: usize = 25;
+pub const CURRENT_ACCOUNT_VERSION: u8 = 1; //Accounts created before versioning read as 0. Bump this and add a backfill to migrate_account when an account layout changes
+pub const MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT: usize = 16; //Room reserved for the Tab Account registry. The CEO's max tabs per lending account can't go above it. It's also the most Tab Accounts a health refresh walks, and has to stay at 32 or less for the refreshed tab bitmask
+pub const MAX_LENDING_USER_ACTIVITY_RECORDS: usize = 16; //How many of the most recent activities the Lending User Activity ring buffer keeps before overwriting the oldest
+pub const MAX_PROTOCOL_TVL_TOKEN_RESERVES: usize = 64; //Room reserved for Token Reserve entries in the Protocol TVL account
+pub const MAX_FEE_DISCOUNT_TIERS: usize = 4; //Room reserved for Fee Discount Tiers in the Lending Protocol account
+pub const MAX_MERKLE_PROOF_LENGTH: usize = 24; //Enough for Merkle Distributions of over 16 million allocations while keeping proof checks well inside the compute budget
+pub const TOKEN_RESERVE_LISTING_BOND_LAMPORTS: u64 = 10_000_000_000; //10 SOL bond posted by permissionless Token Reserve listers. Refunded after the veto window unless the CEO vetoes the listing
+pub const TOKEN_RESERVE_LISTING_VETO_WINDOW_SECONDS: u64 = 604_800; //The CEO has 7 days to veto a permissionless listing
+pub const TOKEN_RESERVE_LISTING_GLOBAL_LIMIT_TOKENS: u128 = 100_000; //Whole token global limit for permissionless listings until the CEO or Risk Admin raises it
+pub const TOKEN_RESERVE_LISTING_COLLATERAL_HAIRCUT_RATE: u16 = 10_000; //Permissionless listings don't count toward user health until the CEO or Risk Admin lowers their collateral haircut after the veto window
+pub const FLASH_LOAN_FEE_RATE: u16 = 9; //0.09% of the loan in fixed point notation where 10,000 is 100%. Goes to the Token Reserve's solvency insurance fees
+pub const FLASH_LOAN_RECEIVER_DISCRIMINATOR: [u8; 8] = [195, 212, 238, 236, 80, 204, 73, 167]; //Anchor discriminator for an on_flash_loan(amount: u64, fee_amount: u64, receiver_data: Vec<u8>) instruction, so Anchor programs can implement receivers as a normal instruction
\ No newline at end of file
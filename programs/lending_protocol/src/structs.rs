@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::shared_constants::{MAX_ACCOUNT_NAME_LENGTH, MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT, MAX_LENDING_USER_ACTIVITY_RECORDS, MAX_PROTOCOL_TVL_TOKEN_RESERVES, MAX_FEE_DISCOUNT_TIERS};
 
 //Internal Structs
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -8,37 +9,278 @@ pub struct PriceDataPayload
     pub slot: u64
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct VerifiedPriceData
 {
     pub token_id: u8,
     pub normalized_price_18_decimals: u128
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FlashLoanReceiverPayload //Serialized after FLASH_LOAN_RECEIVER_DISCRIMINATOR as the receiver's instruction data
+{
+    pub amount: u64,
+    pub fee_amount: u64, //The receiver has to pay amount + fee_amount back to the Token Reserve ATA before returning
+    pub receiver_data: Vec<u8>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LiquidationSwap //Routes the liquidation reward through the Lending Protocol's swap program into the repayment token
+{
+    pub min_repayment_token_amount: u64, //Slippage guard on what the swap pays out to the liquidator's repayment ATA
+    pub swap_instruction_data: Vec<u8>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct FeeDiscountTier //Lending Users whose total deposited value reaches the minimum get the discount taken off their SubMarket fee on interest earned rate
+{
+    pub minimum_deposited_usd_value: u128, //18 decimals
+    pub fee_discount_rate: u16 //Fixed point notation from 0 to 10,000 (0 to 100% off the fee)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RateCurvePoint
+{
+    pub utilization_rate: u16,
+    pub borrow_apy: u16,
+    pub supply_apy: u16
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenReserveRateCurve //Returned as return data by get_token_reserve_rate_curve so integrators can render the rate curve
+{
+    pub token_id: u8,
+    pub base_borrow_apy: u16,
+    pub use_fixed_borrow_apy: bool,
+    pub optimal_utilization_rate: u16,
+    pub borrow_apy_slope2: u16,
+    pub utilization_rate: u16,
+    pub borrow_apy: u16,
+    pub supply_apy: u16,
+    pub points: Vec<RateCurvePoint>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PositionAttestation //Returned as return data by export_position so the successor program can verify the exported balances and debt through its CPI
+{
+    pub token_mint_address: Pubkey,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub deposited_amount: u64,
+    pub borrowed_amount: u64,
+    pub exported_clock_slot: u64
+}
+
+#[cfg(feature = "dev")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchDeposit //One of the deposits passed into deposit_many
+{
+    pub token_mint_address: Pubkey,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub amount: u64
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RepaymentBudget //The most repay_all can spend on one token across all of a user's tabs for that token
+{
+    pub token_mint_address: Pubkey,
+    pub budget_amount: u64
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AccrualTestVectorResult
+{
+    pub scenario_index: u8,
+    pub expected_supply_index: u128,
+    pub actual_supply_index: u128,
+    pub expected_borrow_index: u128,
+    pub actual_borrow_index: u128,
+    pub passed: bool
+}
+
+#[cfg(feature = "dev")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AccrualTestVectorReport //Returned as return data by run_accrual_test_vectors so a deployment can be smoke tested against the reference accrual math
+{
+    pub passed_count: u8,
+    pub failed_count: u8,
+    pub results: Vec<AccrualTestVectorResult>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RateTestVectorResult
+{
+    pub scenario_index: u8,
+    pub expected_utilization_rate: u16,
+    pub actual_utilization_rate: u16,
+    pub expected_borrow_apy: u16,
+    pub actual_borrow_apy: u16,
+    pub expected_supply_apy: u16,
+    pub actual_supply_apy: u16,
+    pub passed: bool
+}
+
+#[cfg(feature = "dev")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RateTestVectorReport //Returned as return data by run_rate_test_vectors so a deployment can be smoke tested against the reference rate curve
+{
+    pub passed_count: u8,
+    pub failed_count: u8,
+    pub results: Vec<RateTestVectorResult>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LendingUserTabPosition //Returned as return data by get_lending_user_tab_position so users can see what they're actually earning/paying
+{
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub deposited_amount: u64,
+    pub borrowed_amount: u64,
+    pub net_supply_apy: u16, //Net of SubMarket, Solvency Insurance, and Insurance Fund fees
+    pub net_borrow_apy: u16 //Net of the tab's staking discount
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TabAccrualPreview //Returned as return data by simulate_accrual so UIs can show pending interest without waiting for an activity to update the tab
+{
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub preview_time_stamp: u64,
+    pub deposited_amount: u64,
+    pub borrowed_amount: u64,
+    pub pending_interest_earned_amount: u64, //Interest earned after fees since the tab was last updated
+    pub pending_interest_accrued_amount: u64 //Interest accrued on debt since the tab was last updated
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UserHealthView //Returned as return data by get_user_health so frontends and bots can quote health without replicating the on-chain math
+{
+    pub lending_user_owner: Pubkey,
+    pub user_account_index: u8,
+    pub total_deposited_usd_value: u128, //18 decimals, with interest accrued up to the current time stamp
+    pub total_borrowed_usd_value: u128, //18 decimals, with interest accrued up to the current time stamp
+    pub loan_to_value_rate: u64, //Borrowed value over deposited value in fixed point notation, 10,000 is 100%
+    pub health_factor_rate: u64 //Deposited value at the liquidation threshold over borrowed value in fixed point notation. Liquidatable at or below 10,000, u64::MAX with no debt
+}
+
 //Accounts
 #[account]
+#[derive(InitSpace)]
 pub struct LendingProtocolCEO
 {
-    pub address: Pubkey
+    pub bump: u8,
+    pub address: Pubkey,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct SolvencyTreasurer
 {
-    pub address: Pubkey
+    pub bump: u8,
+    pub address: Pubkey,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LiquidationTreasurer
 {
-    pub address: Pubkey
+    pub bump: u8,
+    pub address: Pubkey,
+    pub version: u8
+}
+
+//Roles the CEO hands out so day to day admin work doesn't need the CEO's key. The CEO can still act for any role.
+//Treasury work is already split out to the Solvency and Liquidation Treasurers. Default Pubkey means the role isn't assigned
+#[account]
+#[derive(InitSpace)]
+pub struct LendingProtocolRoles
+{
+    pub bump: u8,
+    pub guardian_address: Pubkey, //Can only pause new borrows
+    pub risk_admin_address: Pubkey, //Can change rates, limits, and other risk parameters
+    pub version: u8
+}
+
+//New values for a Timelocked Change. Only the fields that match the change type are used
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TimelockedChangeParameters
+{
+    pub base_borrow_apy: u16,
+    pub use_fixed_borrow_apy: bool,
+    pub global_limit: u128,
+    pub solvency_insurance_fee_rate: u16,
+    pub insurance_fund_share_rate: u16,
+    pub new_address: Pubkey,
+    pub timelock_delay_seconds: u64,
+    pub oracle_price_id_override: u8, //Has to match the SubMarket's requested override when the approval is queued and executed
+    pub approved: bool,
+    pub haircut_rate: u16,
+    pub collateral_haircut_rate: u16,
+    pub asset_category: u8,
+    pub loan_to_value_rate: u16,
+    pub liquidation_threshold_rate: u16
+}
+
+//A risk parameter change waiting out the Lending Protocol timelock delay, so users get notice before it takes effect
+#[account]
+#[derive(InitSpace)]
+pub struct TimelockedChange
+{
+    pub bump: u8,
+    pub change_id: u32,
+    pub change_type: u8,
+    pub target_address: Pubkey, //Token Reserve the change applies to. Default Pubkey for protocol level changes
+    pub proposer_address: Pubkey,
+    pub parameters: TimelockedChangeParameters,
+    pub queued_time_stamp: u64,
+    pub executable_time_stamp: u64,
+    pub version: u8
 }
 
+//New values for a staged Admin Action. Only the fields that match the action type are used
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct AdminActionParameters
+{
+    pub statement_month: u8,
+    pub statement_year: u16,
+    pub max_tab_amount: u8,
+    pub sub_market_fee_ramp_seconds: u64,
+    pub bad_debt_circuit_breaker_rate: u16,
+    pub bad_debt_window_seconds: u64,
+    pub borrows_paused: bool,
+    pub user_health_max_age_slots: u64,
+    pub primary_address: Pubkey, //New CEO, Swap Program, or Successor Program address
+    pub secondary_address: Pubkey //Predecessor Program address
+}
+
+//A CEO instruction staged on chain so a multisig or governance program can review it before the CEO signs the execute
 #[account]
+#[derive(InitSpace)]
+pub struct AdminAction
+{
+    pub bump: u8,
+    pub action_id: u32,
+    pub action_type: u8,
+    pub proposer_address: Pubkey,
+    pub parameters: AdminActionParameters,
+    pub created_time_stamp: u64,
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct OraclePriceValidator
 {
     pub bump: u8,
-    pub address: Pubkey
+    pub address: Pubkey,
+    pub version: u8
 }
 
 #[account]
@@ -46,33 +288,92 @@ pub struct TempOraclePriceAccount
 {
     pub bump: u8,
     pub data: Vec<VerifiedPriceData>,
-    pub slot: u64
+    pub slot: u64,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LendingProtocol
 {
+    pub bump: u8,
     pub current_statement_month: u8,
     pub current_statement_year: u16,
     pub max_tabs_per_lending_account: u8,
-    pub look_up_table_address: Pubkey
+    pub look_up_table_address: Pubkey,
+    pub sub_market_fee_ramp_seconds: u64, //How long SubMarket fee increases take to fully ramp up
+    pub borrows_paused: bool, //Set automatically by the bad debt circuit breaker or manually by the Guardian, Risk Admin, or CEO. Only the CEO can unpause borrows
+    pub bad_debt_circuit_breaker_rate: u16, //Bad debt written off within one window can't exceed this share of the Token Reserve's Insurance Fund balance, in fixed point notation from 0 to 10,000 (0 to 100%). 0 turns the circuit breaker off
+    pub bad_debt_window_seconds: u64,
+    pub user_health_max_age_slots: u64, //How many slots a cached User Health can be used by withdraws and borrows in place of a health refresh. 0 turns the cache off
+    pub successor_program_id: Pubkey, //Program that positions can be exported to. Default Pubkey means position exports are turned off
+    pub predecessor_program_id: Pubkey, //Program that positions can be imported from. Default Pubkey means position imports are turned off
+    pub swap_program_id: Pubkey, //Program that deleverage swaps are routed through. Default Pubkey means swaps are turned off
+    pub timelock_delay_seconds: u64, //How long queued risk parameter changes wait before they can be executed. 0 turns the timelock off and lets changes apply immediately
+    pub timelocked_change_count: u32,
+    pub admin_action_count: u32,
+    #[max_len(MAX_FEE_DISCOUNT_TIERS)]
+    pub fee_discount_tiers: Vec<FeeDiscountTier>, //Ascending by minimum deposited value. Empty turns fee discounts off
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidatorAllowlistEntry
+{
+    pub bump: u8,
+    pub token_reserve_address: Pubkey,
+    pub liquidator_address: Pubkey,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
+pub struct SubMarketAllowlistEntry
+{
+    pub bump: u8,
+    pub sub_market_address: Pubkey,
+    pub user_address: Pubkey,
+    pub version: u8
+}
+
+//Holds the SOL bond for a Token Reserve listed without the CEO. The bond sits in this account on top of its rent
+#[account]
+#[derive(InitSpace)]
+pub struct TokenReserveListing
+{
+    pub bump: u8,
+    pub token_reserve_address: Pubkey,
+    pub lister_address: Pubkey,
+    pub bond_amount: u64,
+    pub listed_time_stamp: u64,
+    pub veto_deadline_time_stamp: u64,
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct TokenReserveStats
 {
+    pub bump: u8,
     pub token_reserve_count: u8,
-    pub token_reserves_updated_count: u32
+    pub token_reserves_updated_count: u32,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct SubMarketStats //Moved these lending protocol variables here to help stream line the listeners on the front end, so that when ever there is any change what so ever on this account, we can be sure that we need to do a .all() for the SubMarket accounts on the front end without having to fetch some other account to check a different number before hand. Less fetches/alls, the better.
 {
+    pub bump: u8,
     pub sub_market_creation_count: u32,
-    pub sub_market_edit_count: u32
+    pub sub_market_edit_count: u32,
+    pub sub_market_retired_count: u32,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LendingStats
 {
     pub bump: u8,
@@ -82,16 +383,21 @@ pub struct LendingStats
     pub repayments: u128,
     pub liquidations: u128,
     pub snap_shots: u128,
-    pub fee_collections: u128
+    pub fee_collections: u128,
+    pub version: u8
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LendingUserStats
 {
-    pub name_change_count: u128
+    pub bump: u8,
+    pub name_change_count: u128,
+    pub version: u8
 }
 
 #[account]
+#[derive(Default, InitSpace)]
 pub struct TokenReserve
 {
     pub bump: u8,
@@ -118,19 +424,316 @@ pub struct TokenReserve
     pub last_lending_activity_amount: u64,
     pub last_lending_activity_type: u8,
     pub last_lending_activity_time_stamp: u64,
-    pub last_health_update_clock_slot: u64
+    pub last_health_update_clock_slot: u64,
+    pub insurance_fund_share_rate: u16, //Share of interest earned set aside for the Insurance Fund, in fixed point notation from 0 to 1,000 (0 to 10%)
+    pub unswept_insurance_fund_amount: u128, //Insurance Fund share that is still sitting in the Token Reserve waiting to be swept into the Insurance Fund balance
+    pub bad_debt_amount: u128, //Debt written off from insolvent accounts that hasn't been covered by the Insurance Fund or socialized yet
+    pub socialized_loss_amount: u128,
+    pub uncollected_sub_market_fees_amount: u128, //All of the Token Reserve's SubMarkets' uncollected fees combined, so the Token Reserve ATA balance can be reconciled
+    pub auction_collateral_amount: u128, //Seized collateral still sitting in the Token Reserve ATA waiting to be sold at auction
+    pub swept_surplus_amount: u128, //Tokens sent directly to the Token Reserve ATA (donations, airdrops) that sync_reserve has accounted for
+    pub audit_snapshot_count: u32,
+    pub usd_quote_price_id: u8, //Price ID of the feed converting this Token Reserve's quote currency to USD. 0 means its price is already quoted in USD
+    pub bad_debt_window_start_time_stamp: u64,
+    pub bad_debt_window_amount: u128, //Bad debt written off since the current bad debt window started
+    pub borrow_intent_threshold_amount: u64, //Borrows of this amount or more must be posted as a Borrow Intent first. 0 turns borrow intents off
+    pub borrow_intent_delay_slots: u64, //How many clock slots a Borrow Intent has to wait before it can be executed
+    pub borrow_intent_max_price_deviation_rate: u16, //How far the price can move from the posted Borrow Intent price, in fixed point notation from 0 to 10,000 (0 to 100%)
+    pub is_liquid_staking_token: bool, //Liquid staking tokens are priced as the SOL price times their stake pool's exchange rate by refresh_lst_token_price instead of an LST/USD feed
+    pub reserve_version: u8, //0 for the original Token Reserve of a mint. Newer versions are seeded with it so a fresh Token Reserve can be spun up for the same mint
+    pub active_depositor_count: u32, //Tabs with a deposit in this Token Reserve. A user in more than one SubMarket counts once per SubMarket
+    pub active_borrower_count: u32, //Tabs with debt in this Token Reserve
+    pub liquidator_allowlist_enabled: bool, //Guarded launch mode. Only allowlisted liquidators can repay this Token Reserve's debt while it's on
+    pub liquidator_allowlist_deadline_time_stamp: u64, //Liquidations fall back to permissionless after this time even if the allowlist is still on. 0 means no deadline
+    pub interest_index_epoch: u8, //Bumped every time the interest indexes are rescaled down by INTEREST_INDEX_RESCALE_FACTOR
+    pub use_slot_based_accrual: bool, //Accrues interest per clock slot instead of per second, for when the cluster's time stamps can't be trusted to move forward
+    pub last_accrual_clock_slot: u64,
+    pub asset_category: u8, //E-Mode asset category, like stables or SOL derivatives. 0 means the Token Reserve isn't in one
+    pub siloed_borrowing: bool, //Siloed tokens can't be borrowed alongside other tokens under the same Lending User Account, limiting contagion from thinly traded tokens
+    pub collateral_haircut_rate: u16, //Taken off of the deposited value counted toward user health, in fixed point notation from 0 to 10,000 (0 to 100%). Permissionless listings start at 100%
+    pub listing_veto_deadline_time_stamp: u64, //0 for Token Reserves the CEO added. A listing's collateral haircut can't be lowered until its veto window closes
+    pub listing_vetoed: bool, //Vetoed listings can't be borrowed from and their deposits don't count toward user health
+    pub version: u8
 }
 
+//A frozen copy of a Token Reserve's aggregates at a clock slot. Activity on the Token Reserve carries on as usual, audits anchor against the snapshot instead
 #[account]
-pub struct SubMarket
+#[derive(InitSpace)]
+pub struct TokenReserveAuditSnapshot
+{
+    pub bump: u8,
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub snapshot_index: u32,
+    pub clock_slot: u64,
+    pub time_stamp: u64,
+    pub supply_interest_change_index: u128,
+    pub borrow_interest_change_index: u128,
+    pub deposited_amount: u128,
+    pub interest_earned_amount: u128,
+    pub borrowed_amount: u128,
+    pub interest_accrued_amount: u128,
+    pub repaid_debt_amount: u128,
+    pub liquidated_amount: u128,
+    pub bad_debt_amount: u128,
+    pub socialized_loss_amount: u128,
+    pub uncollected_solvency_insurance_fees_amount: u128,
+    pub uncollected_liquidation_fees_amount: u128,
+    pub uncollected_sub_market_fees_amount: u128,
+    pub token_reserve_ata_balance_amount: u64,
+    pub snapshot_hash: [u8; 32], //SHA-256 of the Token Reserve key fields above, so an auditor can recompute it off chain and confirm nothing was altered
+    pub version: u8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TokenReserveTvl
+{
+    pub token_id: u8,
+    pub deposited_usd_value: u128,
+    pub borrowed_usd_value: u128,
+    pub time_stamp: u64 //When this Token Reserve's values were last priced
+}
+
+//Lazily updated USD TVL of every Token Reserve, so integrators can read the headline TVL number on chain.
+//A Token Reserve's entry is updated whenever it's priced in a health refresh or with refresh_protocol_tvl
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolTvl
+{
+    pub bump: u8,
+    pub total_deposited_usd_value: u128, //18 decimals
+    pub total_borrowed_usd_value: u128, //18 decimals
+    pub last_update_time_stamp: u64,
+    #[max_len(MAX_PROTOCOL_TVL_TOKEN_RESERVES)]
+    pub token_reserve_tvls: Vec<TokenReserveTvl>,
+    pub version: u8
+}
+
+//One per Token Reserve per day (unix time stamp / 86,400), so dashboards can chart TVL and rates straight from chain state
+#[account]
+#[derive(InitSpace)]
+pub struct TokenReserveDaySnapshot
+{
+    pub bump: u8,
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub day: u32,
+    pub clock_slot: u64,
+    pub time_stamp: u64,
+    pub deposited_amount: u128,
+    pub borrowed_amount: u128,
+    pub utilization_rate: u16,
+    pub supply_apy: u16,
+    pub borrow_apy: u16,
+    pub supply_interest_change_index: u128,
+    pub borrow_interest_change_index: u128,
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceFund
+{
+    pub bump: u8,
+    pub token_id: u8,
+    pub token_mint_address: Pubkey,
+    pub balance_amount: u128, //The fund's tokens are held in the Token Reserve ATA, this is the amount set aside for covering bad debt
+    pub funded_amount: u128,
+    pub deployed_amount: u128,
+    pub deployment_count: u32,
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollateralAuction
+{
+    pub bump: u8,
+    pub collateral_token_id: u8,
+    pub collateral_mint_address: Pubkey,
+    pub proceeds_token_id: u8, //Proceeds are paid in this token and recapitalize its Insurance Fund
+    pub proceeds_mint_address: Pubkey,
+    pub collateral_auction_added: bool,
+    pub start_time_stamp: u64, //Restarted every time more collateral is seized into the auction
+    pub duration_seconds: u64,
+    pub max_discount_rate: u16, //The discount off the oracle price grows linearly from 0 to this rate over the duration, in fixed point notation from 0 to 5,000 (0 to 50%)
+    pub remaining_amount: u64, //Seized collateral tokens held in the collateral Token Reserve ATA that haven't been sold yet
+    pub seized_amount: u128,
+    pub sold_amount: u128,
+    pub proceeds_amount: u128,
+    pub version: u8
+}
+
+//A CEO published Merkle root of retroactive allocations (airdrops, compensation) paid out of the distribution's ATA of the reward mint.
+//Only the root is stored on chain, and each Lending User proves their own allocation when they claim it
+#[account]
+#[derive(InitSpace)]
+pub struct MerkleDistribution
+{
+    pub bump: u8,
+    pub distribution_index: u32,
+    pub merkle_root: [u8; 32],
+    pub reward_mint_address: Pubkey,
+    pub total_claimable_amount: u64, //Sum of every allocation in the Merkle tree. Claims can never pay out more than this
+    pub claimed_amount: u64,
+    pub claim_count: u64,
+    pub version: u8
+}
+
+//Created when an allocation is claimed so it can't be claimed twice
+#[account]
+#[derive(InitSpace)]
+pub struct MerkleClaimReceipt
+{
+    pub bump: u8,
+    pub merkle_distribution_address: Pubkey,
+    pub claimant: Pubkey,
+    pub claimed_amount: u64,
+    pub claim_time_stamp: u64,
+    pub version: u8
+}
+
+//Higher Loan To Value and liquidation threshold for Lending User Accounts whose collateral and debt are all in the same asset category, since correlated assets move together
+#[account]
+#[derive(InitSpace)]
+pub struct EModeCategory
+{
+    pub bump: u8,
+    pub asset_category: u8,
+    pub loan_to_value_rate: u16, //Fixed point notation from 0 to 10,000 (0 to 100%)
+    pub liquidation_threshold_rate: u16, //Fixed point notation from 0 to 10,000 (0 to 100%)
+    pub version: u8
+}
+
+//Where users lock the protocol token for a discount off the interest their debt accrues. The discount climbs linearly with the staked amount up to the max
+#[account]
+#[derive(InitSpace)]
+pub struct StakingPool
+{
+    pub bump: u8,
+    pub stake_mint_address: Pubkey,
+    pub full_discount_staked_amount: u64, //Staked amount that earns the max discount
+    pub max_borrow_rate_discount_rate: u16, //Fixed point notation from 0 to 10,000 (0 to 100% off interest accrued)
+    pub total_staked_amount: u64,
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LendingUserStake
 {
     pub bump: u8,
-    pub id: u32,
     pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub staked_amount: u64,
+    pub version: u8
+}
+
+//Holds the SOL paid to keepers for calling permissionless maintenance cranks. The Solvency Treasurer tops it up out of protocol fees by sending it SOL
+#[account]
+#[derive(InitSpace)]
+pub struct KeeperBountyVault
+{
+    pub bump: u8,
+    pub bounty_lamports: u64, //Paid per crank that did work. Keep it below a Monthly Statement's rent so keepers can't farm statements for their own Tab Accounts
+    pub paid_bounty_count: u64,
+    pub paid_bounty_lamports: u64,
+    pub version: u8
+}
+
+//Emissions of a reward token to a Token Reserve's depositors. The reward index grows by emissions_per_second spread across the Token Reserve's deposits,
+//and rewards are paid out of the Reward Emissions' ATA of the reward mint, which anyone can top up.
+//SubMarket owners can run their own reward boosts the same way, spread across just their SubMarket's deposits.
+//Borrow Reward Emissions work the same way over the Token Reserve's borrows, to bootstrap utilization on new Token Reserves
+#[account]
+#[derive(InitSpace)]
+pub struct RewardEmissions
+{
+    pub bump: u8,
     pub token_id: u8,
+    pub token_reserve_address: Pubkey,
+    pub reward_mint_address: Pubkey,
+    pub emissions_per_second: u64,
+    pub reward_index: u128, //Reward tokens earned per deposited token since the emissions started, scaled by REWARD_INDEX_SCALING
+    pub reward_index_last_updated_time_stamp: u64,
+    pub has_vesting_schedule: bool, //Once the CEO sets a Reward Vesting Schedule, claims have to pass it in
+    pub emitted_amount: u128,
+    pub claimed_amount: u128,
+    pub sub_market_address: Pubkey, //Set for SubMarket reward boosts, which leave the Token Reserve address as the default Pubkey
+    pub borrow_rewards: bool, //Rewards are earned on borrowed amounts instead of deposited amounts
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardVestingSchedule
+{
+    pub bump: u8,
+    pub reward_emissions_address: Pubkey,
+    pub vesting_duration_seconds: u64, //Earned rewards are locked and unlock linearly over this long. 0 makes them claimable as soon as they're earned
+    pub version: u8
+}
+
+//A Tab Account's share of a Reward Emissions
+#[account]
+#[derive(InitSpace)]
+pub struct LendingUserRewardAccount
+{
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub sub_market_owner_address: Pubkey,
     pub sub_market_index: u16,
-    pub fee_collector_address: Pubkey,
-    pub fee_on_interest_earned_rate: u16,
+    pub reward_emissions_address: Pubkey,
+    pub lending_user_reward_account_added: bool,
+    pub reward_index: u128, //The Reward Emissions index rewards were last earned up to
+    pub snap_shot_earning_amount: u64, //Rewards are earned on the lower of this and the current deposit (or debt for borrow rewards), so depositing right before a claim doesn't earn rewards for time the tokens weren't deposited
+    pub claimable_reward_amount: u64,
+    pub locked_reward_amount: u64,
+    pub vesting_last_updated_time_stamp: u64,
+    pub vesting_end_time_stamp: u64,
+    pub claimed_reward_amount: u64,
+    pub snap_shot_time_stamp: u64, //When the snap shot was taken. Rewards since then are forfeited if the Tab Account's balance changed after it
+    pub version: u8
+}
+
+//Values an AMM LP token or external receipt token Token Reserve from its vaults and underlying token prices, so they can be used as collateral in the same health loop as single assets
+#[account]
+#[derive(InitSpace)]
+pub struct LpTokenPriceAdapter
+{
+    pub bump: u8,
+    pub token_id: u8, //The LP token's Token Reserve token_id, its derived price is added to the price data under this ID
+    pub lp_token_mint_address: Pubkey,
+    pub token_a_vault_address: Pubkey,
+    pub token_b_vault_address: Pubkey, //Default Pubkey for receipt tokens backed by a single vault
+    pub token_a_price_id: u8,
+    pub token_b_price_id: u8, //0 for receipt tokens backed by a single vault
+    pub token_a_decimal_amount: u8,
+    pub token_b_decimal_amount: u8,
+    pub haircut_rate: u16, //Taken off of the derived fair price, in fixed point notation from 0 to 10,000 (0 to 100%)
+    pub version: u8
+}
+
+//Values a liquid staking token Token Reserve (jitoSOL and other SPL Stake Pool tokens) as the SOL price times the stake pool's on-chain exchange rate, instead of relying on a thin LST/USD feed
+#[account]
+#[derive(InitSpace)]
+pub struct LstTokenPriceAdapter
+{
+    pub bump: u8,
+    pub token_id: u8, //The LST's Token Reserve token_id, its derived price is added to the price data under this ID
+    pub lst_token_mint_address: Pubkey,
+    pub stake_pool_address: Pubkey,
+    pub sol_price_id: u8,
+    pub version: u8
+}
+
+//Zero copy so the health loop can read and write it in place instead of deserializing it every instruction.
+//Fields are grouped largest to smallest so the layout has no padding, new fields go at the end of their size group
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct SubMarket
+{
     pub supply_interest_change_index: u128, //This index is set to match the token reserve index after previously earned interest is updated. This is only used in the frontend for calculating the 7 day projection rate
     pub borrow_interest_change_index: u128, //This index is set to match the token reserve index after previously accured interest is updated. This is only used in the frontend for calculating the 7 day projection rate
     pub deposited_amount: u128,
@@ -143,25 +746,107 @@ pub struct SubMarket
     pub interest_accrued_amount: u128,
     pub repaid_debt_amount: u128,
     pub liquidated_amount: u128,
+    pub bad_debt_amount: u128,
+    pub owner: Pubkey,
+    pub fee_collector_address: Pubkey,
+    pub pending_fee_collector_address: Pubkey, //The proposed fee collector has to accept before it can collect fees. Default Pubkey means there is no pending fee collector
     pub last_lending_activity_amount: u64,
+    pub last_lending_activity_time_stamp: u64,
+    pub fee_ramp_start_time_stamp: u64,
+    pub fee_ramp_end_time_stamp: u64, //0 means there is no fee ramp in progress
+    pub id: u32,
+    pub sub_market_index: u16,
+    pub fee_on_interest_earned_rate: u16,
+    pub pending_fee_on_interest_earned_rate: u16, //Fee increases ramp linearly from fee_on_interest_earned_rate to this rate between the ramp start and end time stamps
+    pub bump: u8,
+    pub token_id: u8,
     pub last_lending_activity_type: u8,
-    pub last_lending_activity_time_stamp: u64
+    pub oracle_price_id_override: u8, //0 means no override. Otherwise the price ID the Oracle Price Validator publishes the alternative feed under, ie a wrapped/bridged variant of the token priced off a different feed
+    pub oracle_override_approved: u8, //1 when the CEO approved the override. The override is only used in health checks after the CEO approves it
+    pub version: u8,
+    pub depositor_allowlist_enabled: u8, //1 when only wallets on the SubMarket owner's allowlist can deposit or borrow in this SubMarket
+    pub borrows_disabled: u8, //1 for supply only SubMarkets. Stored inverted so existing SubMarkets keep allowing borrows
+    pub referral_fee_share_rate: u16, //Slice of the SubMarket fees a referred Tab Account generates that goes to its referrer, in fixed point notation from 0 to 10,000 (0 to 100%). Taken out of the reserved bytes so existing SubMarkets keep their layout
+    pub reserved: [u8; 12] //Keeps the size a multiple of 16 bytes so the layout has no padding
+}
+
+//Attached to a Tab Account when the user's first deposit into the SubMarket names a referrer
+#[account]
+#[derive(InitSpace)]
+pub struct LendingUserReferral
+{
+    pub bump: u8,
+    pub referrer: Pubkey,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub fees_generated_snap_shot_amount: u64, //The Tab Account's fees generated amount the referrer has been credited up to
+    pub claimable_referral_fees_amount: u64,
+    pub claimed_referral_fees_amount: u64,
+    pub version: u8
+}
+
+//A large borrow posted ahead of time. It can only be executed after its delay if the price stayed within bounds of the posted price, so one block of oracle manipulation can't drain a Token Reserve
+#[account]
+#[derive(InitSpace)]
+pub struct BorrowIntent
+{
+    pub bump: u8,
+    pub token_id: u8,
+    pub sub_market_owner_address: Pubkey,
+    pub sub_market_index: u16,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub amount: u64,
+    pub posted_price_18_decimals: u128,
+    pub posted_clock_slot: u64,
+    pub version: u8
 }
 
+//A depositor's approval for a delegate to borrow one token against the depositor's collateral. The debt is recorded on the depositor's Tab Account
 #[account]
+#[derive(InitSpace)]
+pub struct CreditDelegation
+{
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub delegate: Pubkey,
+    pub token_mint_address: Pubkey,
+    pub borrow_allowance_amount: u64, //How much more the delegate can borrow. Each delegated borrow uses it up
+    pub borrowed_amount: u64, //Total the delegate has borrowed under this delegation
+    pub version: u8
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct SubMarketOwnerLookUpTable
 {
+    pub bump: u8,
     pub owner: Pubkey,
     pub look_up_table_address: Pubkey,
-    pub look_up_table_added: bool
+    pub look_up_table_added: bool,
+    pub version: u8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct BorrowedTokens //Which tokens a Lending User Account has debt in, so siloed tokens can be kept from being borrowed alongside other tokens
+{
+    pub token_id: u8, //The token the debt is in when it's all in one token
+    pub token_count: u8, //0 with no debt, 1 when all of the debt is in token_id, 2 when it's in two or more tokens
+    pub has_siloed_token: bool //Whether any of the debt is in a siloed Token Reserve
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct LendingUserAccount
 {
     pub bump: u8,
     pub owner: Pubkey,
     pub user_account_index: u8, //Giving the lending account an index to allow users to have multiple lending accounts if they so choose, so they don't have to use multiple wallets
+    #[max_len(MAX_ACCOUNT_NAME_LENGTH)]
     pub account_name: String,
     pub lending_user_account_added: bool,
     pub tab_account_count: u8,
@@ -171,23 +856,74 @@ pub struct LendingUserAccount
     pub last_health_update_clock_slot: u64,
     pub temp_deposit_usd_value: u128,
     pub temp_borrow_usd_value: u128,
-    pub next_tab_index_to_refresh: u8,
-    pub look_up_table_address: Pubkey
+    pub next_tab_index_to_refresh: u8, //How many Tab Accounts have been refreshed so far in the current refresh
+    pub look_up_table_address: Pubkey,
+    pub monthly_statements_disabled: bool, //Opted out users don't pay rent for Monthly Statements. Their totals are still tracked on their Tab Accounts.
+    pub co_signer_address: Pubkey, //Default Pubkey means there is no co-signer
+    pub co_signer_threshold_usd_value: u128, //Withdrawals and borrows worth more than this USD value (18 decimals) also need the co-signer's signature
+    #[max_len(MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT)]
+    pub tab_account_addresses: Vec<Pubkey>, //Tab Account registry indexed by user_tab_account_index, so Tab Accounts fed into the health refresh are checked against it instead of rederiving their PDAs
+    pub refreshed_tab_account_flags: u32, //Bit i is set once the Tab Account at registry index i has been refreshed in the current refresh, so Tab Accounts can be fed in any order
+    pub borrow_rate_discount_rate: u16, //Staking discount off interest accrued, set when the user stakes or unstakes. Stamped on the Tab Accounts at their health snap shots
+    pub e_mode_category: u8, //E-Mode asset category the user opted into. 0 means E-Mode is off
+    pub e_mode_loan_to_value_rate: u16, //The E-Mode category's limits as of the last finished refresh
+    pub e_mode_liquidation_threshold_rate: u16,
+    pub e_mode_active: bool, //Set by each finished refresh when all of the user's collateral and debt is in their E-Mode category
+    pub temp_e_mode_eligible: bool, //Whether every tab with collateral or debt has been in the E-Mode category so far in the current refresh
+    pub borrowed_tokens: BorrowedTokens, //As of the last finished refresh plus any borrows since. Repaid debt drops off at the next finished refresh
+    pub temp_borrowed_tokens: BorrowedTokens,
+    pub user_health_invalidated: bool, //Set when collateral is taken or debt is added without updating the cached User Health, so it can't be used until the next finished refresh
+    pub version: u8
 }
 
 #[account]
-pub struct LendingUserTabAccount
+#[derive(InitSpace)]
+pub struct UserHealth //Cached totals from the Lending User Account's last finished health refresh, so withdraws and borrows don't have to reprice every tab in the same transaction
 {
     pub bump: u8,
-    pub token_id: u8,
-    pub sub_market_owner_address: Pubkey,
-    pub sub_market_index: u16,
     pub owner: Pubkey,
     pub user_account_index: u8,
-    pub user_tab_account_index: u8,
-    pub user_tab_account_added: bool,
+    pub total_deposited_usd_value: u128,
+    pub total_borrowed_usd_value: u128,
+    pub refresh_clock_slot: u64,
+    pub refresh_time_stamp: u64,
+    pub expiry_clock_slot: u64, //Last slot withdraws and borrows can use the attested prices below
+    #[max_len(MAX_TAB_ACCOUNTS_PER_LENDING_USER_ACCOUNT)]
+    pub attested_prices: Vec<VerifiedPriceData>, //Verified prices the health was refreshed with, so withdraws and borrows can leave out the price accounts
+    pub version: u8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct LendingUserActivityRecord
+{
+    pub activity_type: u8,
+    pub token_mint_address: Pubkey,
+    pub amount: u64,
+    pub time_stamp: u64
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LendingUserActivityAccount //Opt in ring buffer of the Lending User Account's most recent activities, so wallets can show recent history without running an indexer
+{
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub user_account_index: u8,
+    pub next_activity_index: u8, //Where the next activity gets written. Once the buffer is full this is also the oldest activity
+    pub activity_count: u64, //Total activities ever recorded, so readers can tell if the buffer has wrapped around
+    pub activities: [LendingUserActivityRecord; MAX_LENDING_USER_ACTIVITY_RECORDS],
+    pub version: u8
+}
+
+//Zero copy for the same reason as SubMarket, every Tab Account is loaded in the health loop
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct LendingUserTabAccount
+{
     pub supply_interest_change_index: u128, //This index is set to match the token reserve index after previously earned interest is updated
     pub borrow_interest_change_index: u128, //This index is set to match the token reserve index after previously accured interest is updated
+    pub sub_market_owner_address: Pubkey,
+    pub owner: Pubkey,
     pub deposited_amount: u64,
     pub interest_earned_amount: u64,
     pub fees_generated_amount: u64,
@@ -196,10 +932,30 @@ pub struct LendingUserTabAccount
     pub repaid_debt_amount: u64,
     pub liquidated_amount: u64,
     pub liquidator_amount: u64,
-    pub interest_change_last_updated_clock_slot: u64
+    pub interest_change_last_updated_clock_slot: u64,
+    pub last_lending_activity_time_stamp: u64,
+    pub sub_market_index: u16,
+    pub bump: u8,
+    pub token_id: u8,
+    pub user_account_index: u8,
+    pub user_tab_account_index: u8,
+    pub user_tab_account_added: u8, //1 once the Tab Account has been populated
+    pub version: u8,
+    pub counted_as_depositor: u8, //1 while the tab is counted in its Token Reserve's active_depositor_count
+    pub counted_as_borrower: u8, //1 while the tab is counted in its Token Reserve's active_borrower_count
+    pub interest_index_epoch: u8, //Token Reserve interest index epoch this tab's indexes were last synced to
+    pub fee_discount_rate: [u8; 2], //Little endian u16 Fee Discount Tier discount stamped at the tab's last health snap shot. Kept as bytes since a u16 here would need padding
+    pub borrow_rate_discount_rate: [u8; 2], //Little endian u16 staking discount off interest accrued, stamped at the tab's last health snap shot
+    pub reserved: [u8; 1], //Keeps the size a multiple of 16 bytes so the layout has no padding
+    pub balance_changed_time_stamp: u64, //Last time the deposited or borrowed amount changed other than by interest. Rewards earned before it are forfeited, see update_lending_user_rewards
+    pub reserved_2: [u8; 8]
 }
 
+//Token amounts on Tab Accounts and Monthly Statements are u64, with u128 only for the interest indexes. This keeps the per user rent from creeping back up
+const _: () = assert!(core::mem::size_of::<LendingUserTabAccount>() == 208);
+
 #[account]
+#[derive(Default, InitSpace)]
 pub struct LendingUserMonthlyStatementAccount
 {
     pub bump: u8,
@@ -227,5 +983,32 @@ pub struct LendingUserMonthlyStatementAccount
     pub monthly_liquidator_amount: u64,
     pub last_lending_activity_amount: u64,
     pub last_lending_activity_type: u8,
-    pub last_lending_activity_time_stamp: u64 
+    pub last_lending_activity_time_stamp: u64,
+    pub version: u8
+}
+
+#[cfg(feature = "compute-metrics")]
+#[account]
+#[derive(InitSpace)]
+pub struct ComputeMetrics
+{
+    pub bump: u8,
+    pub interest_sync_compute_units: u64,
+    pub rate_update_compute_units: u64,
+    pub health_check_per_tab_compute_units: u64,
+    pub cpi_transfer_compute_units: u64,
+    pub measured_clock_slot: u64,
+    pub measurement_count: u32,
+    pub version: u8
+}
+
+//Events
+#[event]
+pub struct BadDebtCircuitBreakerTripped
+{
+    pub token_id: u8,
+    pub bad_debt_window_amount: u128,
+    pub insurance_fund_balance_amount: u128,
+    pub bad_debt_circuit_breaker_rate: u16,
+    pub time_stamp: u64
 }
\ No newline at end of file
@@ -1,8 +1,66 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{TokenAccount};
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions, default_account_state::DefaultAccountState};
+use anchor_spl::token_2022::spl_token_2022::state::{AccountState, Mint as SplMint};
 use crate::errors::LendingError;
 use crate::structs as Structs;
+use crate::lending_helpers::{get_token_reserve_version_seed, convert_quoted_price_to_usd};
+use core::mem::size_of;
+use std::cell::{Ref, RefMut};
 
+//Loads a zero copy account fed in through remaining accounts in place, the same checks AccountLoader::load does
+pub fn load_zero_copy_account<'a, 'info, T: ZeroCopy + Owner>(account_serialized: &'a AccountInfo<'info>) -> Result<Ref<'a, T>>
+{
+    require_keys_eq!(*account_serialized.owner, T::owner(), anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+
+    let data = account_serialized.try_borrow_data()?;
+    require!(data.len() >= size_of::<T>() + 8 && &data[..8] == T::DISCRIMINATOR, anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+
+    Ok(Ref::map(data, |data| bytemuck::from_bytes(&data[8..size_of::<T>() + 8])))
+}
+
+//Mutable version of load_zero_copy_account. Changes are written straight to the account data, so there is nothing to serialize afterwards
+pub fn load_zero_copy_account_mut<'a, 'info, T: ZeroCopy + Owner>(account_serialized: &'a AccountInfo<'info>) -> Result<RefMut<'a, T>>
+{
+    require_keys_eq!(*account_serialized.owner, T::owner(), anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram);
+
+    let data = account_serialized.try_borrow_mut_data()?;
+    require!(data.len() >= size_of::<T>() + 8 && &data[..8] == T::DISCRIMINATOR, anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+
+    Ok(RefMut::map(data, |data| bytemuck::from_bytes_mut(&mut data[8..size_of::<T>() + 8])))
+}
+
+//Permanent delegate, non-transferable, default-frozen, and confidential transfer mints can move or lock Token Reserve tokens outside of the Lending Protocol's accounting
+pub fn check_token_mint_extensions<'info>(token_mint_serialized: &AccountInfo<'info>, allow_restricted_mint_extensions: bool) -> Result<()>
+{
+    //Mints owned by the original Token Program don't have extensions
+    if *token_mint_serialized.owner != anchor_spl::token_2022::ID || allow_restricted_mint_extensions
+    {
+        return Ok(());
+    }
+
+    let data = token_mint_serialized.try_borrow_data()?;
+    let token_mint = StateWithExtensions::<SplMint>::unpack(&data)?;
+
+    for extension_type in token_mint.get_extension_types()?
+    {
+        match extension_type
+        {
+            ExtensionType::PermanentDelegate | ExtensionType::NonTransferable | ExtensionType::ConfidentialTransferMint =>
+            {
+                return Err(LendingError::RestrictedMintExtension.into());
+            },
+            ExtensionType::DefaultAccountState =>
+            {
+                let default_account_state = token_mint.get_extension::<DefaultAccountState>()?;
+                require!(default_account_state.state != AccountState::Frozen as u8, LendingError::RestrictedMintExtension);
+            },
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
 
 pub fn validate_and_return_price_validator_account<'info>(
     program_id: Pubkey,
@@ -55,6 +113,100 @@ pub fn validate_and_return_temp_price_account<'info>(
     Ok(temp_oracle_price_account)
 }
 
+//Temp Oracle Price Account layout after the 8 byte discriminator: bump (1), price count (4), prices (token_id 1 + normalized_price_18_decimals 16 each), slot (8), version (1)
+const TEMP_PRICE_ACCOUNT_PRICE_COUNT_OFFSET: usize = 9;
+const TEMP_PRICE_ACCOUNT_PRICES_OFFSET: usize = 13;
+const VERIFIED_PRICE_DATA_SIZE: usize = 17;
+
+//Reads prices straight out of the Temp Oracle Price Account's data instead of deserializing its whole price list.
+//The data is only borrowed while a price is read, so the account can still be refunded to the Oracle afterwards
+pub struct TempPriceAccountReader<'a, 'info>
+{
+    temp_price_account_serialized: &'a AccountInfo<'info>,
+    price_count: usize,
+    pub slot: u64
+}
+
+impl<'a, 'info> TempPriceAccountReader<'a, 'info>
+{
+    pub fn get_verified_token_price(&self, token_id: u8) -> Result<u128>
+    {
+        let data = self.temp_price_account_serialized.try_borrow_data()?;
+        let prices = &data[TEMP_PRICE_ACCOUNT_PRICES_OFFSET..TEMP_PRICE_ACCOUNT_PRICES_OFFSET + self.price_count * VERIFIED_PRICE_DATA_SIZE];
+
+        //Search the prices for the first item matching the target token_id
+        match prices.chunks_exact(VERIFIED_PRICE_DATA_SIZE).find(|price| price[0] == token_id)
+        {
+            Some(price) => Ok(u128::from_le_bytes(price[1..VERIFIED_PRICE_DATA_SIZE].try_into().unwrap())),
+            None =>
+            {
+                msg!("🚨 Requested Token ID not found in verified prices: {}", token_id);
+                Err(error!(LendingError::OraclePriceNotFound))
+            }
+        }
+    }
+
+    //Same as get_verified_token_usd_price, chaining the USD quote price for Token Reserves quoted in another currency
+    pub fn get_verified_token_usd_price(&self, price_id: u8, usd_quote_price_id: u8) -> Result<u128>
+    {
+        let normalized_price_18_decimals = self.get_verified_token_price(price_id)?;
+
+        if usd_quote_price_id == 0
+        {
+            return Ok(normalized_price_18_decimals)
+        }
+
+        let quote_normalized_price_18_decimals = self.get_verified_token_price(usd_quote_price_id)?;
+        convert_quoted_price_to_usd(normalized_price_18_decimals, quote_normalized_price_18_decimals)
+    }
+}
+
+//Lets withdraws and borrows tell whether the price accounts were passed in or the User Health's attested prices should be used
+pub fn is_temp_price_account(program_id: Pubkey, account_serialized: &AccountInfo) -> bool
+{
+    *account_serialized.owner == program_id &&
+    account_serialized.try_borrow_data().is_ok_and(|data| data.len() >= 8 && &data[..8] == Structs::TempOraclePriceAccount::DISCRIMINATOR)
+}
+
+//Offset based version of validate_and_return_temp_price_account for the instructions that only need a price or two
+pub fn validate_and_return_temp_price_account_reader<'a, 'info>(
+    program_id: Pubkey,
+    temp_price_account_serialized: &'a AccountInfo<'info>,
+    signer_address: Pubkey) -> Result<TempPriceAccountReader<'a, 'info>>
+{
+    require_keys_eq!(*temp_price_account_serialized.owner, program_id, LendingError::UnexpectedOraclePriceDataAccount);
+
+    let data = temp_price_account_serialized.try_borrow_data()?;
+    require!(data.len() >= TEMP_PRICE_ACCOUNT_PRICES_OFFSET && &data[..8] == Structs::TempOraclePriceAccount::DISCRIMINATOR, anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+
+    let bump = data[8];
+    let price_count = u32::from_le_bytes(data[TEMP_PRICE_ACCOUNT_PRICE_COUNT_OFFSET..TEMP_PRICE_ACCOUNT_PRICES_OFFSET].try_into().unwrap()) as usize;
+    let slot_offset = TEMP_PRICE_ACCOUNT_PRICES_OFFSET + price_count * VERIFIED_PRICE_DATA_SIZE;
+    require!(data.len() >= slot_offset + 8, anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+    let slot = u64::from_le_bytes(data[slot_offset..slot_offset + 8].try_into().unwrap());
+
+    let seeds = &
+    [
+        b"oraclePriceData".as_ref(),
+        signer_address.as_ref(),
+        &[bump]
+    ];
+
+    //Verify Oracle Price Account PDA is a valid PDA
+    let expected_pda = Pubkey::create_program_address(seeds, &program_id)
+    .map_err(|_| LendingError::UnexpectedOraclePriceDataAccount)?;
+
+    //Verify Oracle Price Account Address is the expected PDA
+    require_keys_eq!(expected_pda.key(), temp_price_account_serialized.key(), LendingError::UnexpectedOraclePriceDataAccount);
+
+    Ok(TempPriceAccountReader
+    {
+        temp_price_account_serialized,
+        price_count,
+        slot
+    })
+}
+
 pub fn validate_and_return_lending_stats_account<'info>(program_id: Pubkey, lending_stats_serialized: &AccountInfo<'info>) -> Result<Structs::LendingStats>
 {
     let mut data_slice: &[u8] = &lending_stats_serialized.data.borrow();
@@ -90,6 +242,7 @@ pub fn validate_and_return_token_reserve_account<'info>(
     [
         b"tokenReserve",
         token_reserve.token_mint_address.as_ref(), //Using the mint address from the account. Token Reserve accounts can only be created by the CEO and checks in refresh_user_health_chunk_and_token_reserves that ensure the token_mint_address is correct one by cross references it with the lending user tab account
+        get_token_reserve_version_seed(&token_reserve),
         &[token_reserve.bump]
     ];
 
@@ -121,17 +274,15 @@ pub fn validate_token_reserve_ata<'info>(
     Ok(())
 }
 
-pub fn validate_and_return_sub_market_account<'info>(
+pub fn validate_and_return_sub_market_account<'a, 'info>(
     program_id: Pubkey,
-    sub_market_account_serialized: &AccountInfo<'info>,
+    sub_market_account_serialized: &'a AccountInfo<'info>,
     token_id: u8,
     sub_market_owner_address: Pubkey,
-    sub_market_index: u16) -> Result<Structs::SubMarket>
+    sub_market_index: u16) -> Result<RefMut<'a, Structs::SubMarket>>
 {
-    let mut data_slice: &[u8] = &sub_market_account_serialized.data.borrow();
-
     let token_id_to_le_bytes = token_id.to_le_bytes();
-    let sub_market = Structs::SubMarket::try_deserialize(&mut data_slice)?;
+    let sub_market = load_zero_copy_account_mut::<Structs::SubMarket>(sub_market_account_serialized)?;
     let sub_market_index_to_le_bytes = sub_market_index.to_le_bytes();
 
     let seeds = &
@@ -153,6 +304,35 @@ pub fn validate_and_return_sub_market_account<'info>(
     Ok(sub_market)
 }
 
+//Read only version of validate_and_return_sub_market_account for when the SubMarket's owner and index aren't known ahead of time. The PDA is checked against the SubMarket's own fields
+pub fn validate_and_return_read_only_sub_market_account<'a, 'info>(
+    program_id: Pubkey,
+    sub_market_account_serialized: &'a AccountInfo<'info>,
+    token_id: u8) -> Result<Ref<'a, Structs::SubMarket>>
+{
+    let token_id_to_le_bytes = token_id.to_le_bytes();
+    let sub_market = load_zero_copy_account::<Structs::SubMarket>(sub_market_account_serialized)?;
+    let sub_market_index_to_le_bytes = sub_market.sub_market_index.to_le_bytes();
+
+    let seeds = &
+    [
+        b"subMarket",
+        token_id_to_le_bytes.as_ref(),
+        sub_market.owner.as_ref(),
+        sub_market_index_to_le_bytes.as_ref(),
+        &[sub_market.bump]
+    ];
+
+    //Verify SubMarket PDA is a valid PDA
+    let expected_pda = Pubkey::create_program_address(seeds, &program_id)
+    .map_err(|_| LendingError::UnexpectedSubMarketAccount)?;
+
+    //Verify SubMarket Address is the expected PDA
+    require_keys_eq!(expected_pda.key(), sub_market_account_serialized.key(), LendingError::UnexpectedSubMarketAccount);
+
+    Ok(sub_market)
+}
+
 pub fn validate_and_return_lending_user_account<'info>(
     program_id: Pubkey,
     lending_user_account_serialized: &AccountInfo<'info>,
@@ -183,18 +363,16 @@ pub fn validate_and_return_lending_user_account<'info>(
     Ok(lending_user_account)
 }
 
-pub fn validate_and_return_lending_user_tab_account<'info>(
+pub fn validate_and_return_lending_user_tab_account<'a, 'info>(
     program_id: Pubkey,
-    tab_account_serialized: &AccountInfo<'info>,
+    tab_account_serialized: &'a AccountInfo<'info>,
     token_id: u8,
     sub_market_owner_address: Pubkey,
     sub_market_index: u16,
     user_account_owner_address: Pubkey,
-    user_account_index: u8) -> Result<Structs::LendingUserTabAccount>
+    user_account_index: u8) -> Result<RefMut<'a, Structs::LendingUserTabAccount>>
 {
-    let mut data_slice: &[u8] = &tab_account_serialized.data.borrow();
-
-    let lending_user_tab_account = Structs::LendingUserTabAccount::try_deserialize(&mut data_slice)?;
+    let lending_user_tab_account = load_zero_copy_account_mut::<Structs::LendingUserTabAccount>(tab_account_serialized)?;
 
     let token_id_to_le_bytes = token_id.to_le_bytes();
     let user_account_index_to_le_bytes = user_account_index.to_le_bytes();
@@ -261,4 +439,63 @@ pub fn validate_and_return_lending_user_monthly_state_account<'info>(
     require_keys_eq!(expected_pda.key(), monthly_statement_account_serialized.key(), LendingError::UnexpectedMonthlyStatementAccount);
 
     Ok(monthly_statement_account)
+}
+
+//The CEO can always act for a role. Anyone else has to pass in the roles account to show they hold the role, otherwise the call is treated as CEO only
+pub fn check_ceo_or_guardian<'info>(signer_address: Pubkey, ceo_address: Pubkey, roles: &Option<Account<'info, Structs::LendingProtocolRoles>>) -> Result<()>
+{
+    if signer_address == ceo_address
+    {
+        return Ok(());
+    }
+
+    let roles = roles.as_ref().ok_or(LendingError::NotCEO)?;
+    require_keys_eq!(signer_address, roles.guardian_address, LendingError::NotGuardian);
+
+    Ok(())
+}
+
+pub fn check_ceo_or_risk_admin<'info>(signer_address: Pubkey, ceo_address: Pubkey, roles: &Option<Account<'info, Structs::LendingProtocolRoles>>) -> Result<()>
+{
+    if signer_address == ceo_address
+    {
+        return Ok(());
+    }
+
+    let roles = roles.as_ref().ok_or(LendingError::NotCEO)?;
+    require_keys_eq!(signer_address, roles.risk_admin_address, LendingError::NotRiskAdmin);
+
+    Ok(())
+}
+
+//While a Token Reserve is in its guarded launch period only allowlisted liquidators can repay its debt. Once the deadline passes anyone can
+pub fn check_liquidator_allowlist<'info>(token_reserve: &Structs::TokenReserve, liquidator_allowlist_entry: &Option<Account<'info, Structs::LiquidatorAllowlistEntry>>) -> Result<()>
+{
+    if !token_reserve.liquidator_allowlist_enabled
+    {
+        return Ok(());
+    }
+
+    let time_stamp = Clock::get()?.unix_timestamp as u64;
+    if token_reserve.liquidator_allowlist_deadline_time_stamp != 0 && time_stamp >= token_reserve.liquidator_allowlist_deadline_time_stamp
+    {
+        return Ok(());
+    }
+
+    require!(liquidator_allowlist_entry.is_some(), LendingError::LiquidatorNotAllowlisted);
+
+    Ok(())
+}
+
+//Private SubMarkets only let wallets on the owner's allowlist deposit or borrow. Withdrawing and repaying are always open
+pub fn check_sub_market_allowlist<'info>(sub_market: &Structs::SubMarket, sub_market_allowlist_entry: &Option<Account<'info, Structs::SubMarketAllowlistEntry>>) -> Result<()>
+{
+    if sub_market.depositor_allowlist_enabled == 0
+    {
+        return Ok(());
+    }
+
+    require!(sub_market_allowlist_entry.is_some(), LendingError::NotOnSubMarketAllowlist);
+
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use lending_protocol::money_market::{self, cpi_accounts, LendingProtocol};
+
+declare_id!("FKeK8yYkBTZzoATtAnrxD9Z13JNSyxd5zHeFpEgVbXyv");
+
+//Test integrator that opens and manages a Lending Protocol position with its own PDA, the way a vault program would.
+//The vault PDA is a system owned account holding lamports, so it can sign as the Lending Protocol signer and pay rent for the accounts its first deposit opens
+#[program]
+pub mod money_market_vault
+{
+    use super::*;
+
+    //Sends lamports to the vault PDA so it can pay rent for its Lending User, Tab, and token accounts
+    pub fn fund_vault(ctx: Context<FundVault>, lamports: u64) -> Result<()>
+    {
+        let cpi_accounts = anchor_lang::system_program::Transfer
+        {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.vault.to_account_info()
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, lamports)?;
+
+        msg!("Funded Vault: {}", ctx.accounts.vault.key());
+
+        Ok(())
+    }
+
+    //The first deposit opens the vault's Lending User Account. The Monthly Statement account is left out so the vault opts out of statements
+    pub fn deposit(ctx: Context<VaultDeposit>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64,
+        look_up_table_address: Option<Pubkey>
+    ) -> Result<()>
+    {
+        let authority_address = ctx.accounts.authority.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault".as_ref(), authority_address.as_ref(), &[ctx.bumps.vault]]];
+
+        let cpi_accounts = cpi_accounts::DepositTokens
+        {
+            sub_market_owner: ctx.accounts.sub_market_owner.to_account_info(),
+            lending_protocol: ctx.accounts.lending_protocol.to_account_info(),
+            lending_stats: ctx.accounts.lending_stats.to_account_info(),
+            token_reserve: ctx.accounts.token_reserve.to_account_info(),
+            sub_market: ctx.accounts.sub_market.to_account_info(),
+            sub_market_allowlist_entry: None,
+            lending_user_account: ctx.accounts.lending_user_account.to_account_info(),
+            lending_user_tab_account: ctx.accounts.lending_user_tab_account.to_account_info(),
+            lending_user_monthly_statement_account: None,
+            user_ata: ctx.accounts.vault_ata.to_account_info(),
+            delegated_token_account: None,
+            token_reserve_ata: ctx.accounts.token_reserve_ata.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            lending_user_activity_account: None,
+            referrer: None,
+            lending_user_referral: None,
+            signer: ctx.accounts.vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info()
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.lending_protocol_program.key(), cpi_accounts, signer_seeds);
+        money_market::cpi::deposit_tokens(cpi_ctx, sub_market_index, user_account_index, amount, None, look_up_table_address)?;
+
+        msg!("Vault Deposited: {}", amount);
+
+        Ok(())
+    }
+
+    //Withdraws back into the vault's token account. Vaults with debt pass the price accounts through as remaining accounts
+    pub fn withdraw<'info>(ctx: Context<'_, '_, 'info, 'info, VaultWithdraw<'info>>,
+        sub_market_index: u16,
+        user_account_index: u8,
+        amount: u64,
+        withdraw_max: bool
+    ) -> Result<()>
+    {
+        let authority_address = ctx.accounts.authority.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault".as_ref(), authority_address.as_ref(), &[ctx.bumps.vault]]];
+
+        let cpi_accounts = cpi_accounts::WithdrawTokens
+        {
+            sub_market_owner: ctx.accounts.sub_market_owner.to_account_info(),
+            lending_protocol: ctx.accounts.lending_protocol.to_account_info(),
+            lending_stats: ctx.accounts.lending_stats.to_account_info(),
+            price_validator: ctx.accounts.price_validator.to_account_info(),
+            token_reserve: ctx.accounts.token_reserve.to_account_info(),
+            sub_market: ctx.accounts.sub_market.to_account_info(),
+            lending_user_account: ctx.accounts.lending_user_account.to_account_info(),
+            lending_user_tab_account: ctx.accounts.lending_user_tab_account.to_account_info(),
+            lending_user_monthly_statement_account: None,
+            user_ata: ctx.accounts.vault_ata.to_account_info(),
+            token_reserve_ata: ctx.accounts.token_reserve_ata.to_account_info(),
+            token_mint: ctx.accounts.token_mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            temporary_wsol_account: None,
+            recipient_token_account: None,
+            user_health: None,
+            co_signer: None,
+            lending_user_activity_account: None,
+            signer: ctx.accounts.vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info()
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.lending_protocol_program.key(), cpi_accounts, signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        money_market::cpi::withdraw_tokens(cpi_ctx, sub_market_index, user_account_index, amount, withdraw_max)?;
+
+        msg!("Vault Withdrew: {}", amount);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct FundVault<'info>
+{
+    ///CHECK: System owned PDA that holds the vault's Lending Protocol position
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), authority.key().as_ref()],
+        bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+//The Lending Protocol validates its own accounts, so they're passed through unchecked
+#[derive(Accounts)]
+pub struct VaultDeposit<'info>
+{
+    ///CHECK: System owned PDA that holds the vault's Lending Protocol position
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), authority.key().as_ref()],
+        bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    ///CHECK: The vault's token account for the Token Reserve's mint
+    #[account(mut)]
+    pub vault_ata: UncheckedAccount<'info>,
+
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub lending_protocol: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub lending_stats: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub token_reserve: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub sub_market: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol. Seeded by the vault PDA
+    #[account(mut)]
+    pub lending_user_account: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol. Seeded by the vault PDA
+    #[account(mut)]
+    pub lending_user_tab_account: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub token_reserve_ata: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub token_mint: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub token_program: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub associated_token_program: UncheckedAccount<'info>,
+
+    pub lending_protocol_program: Program<'info, LendingProtocol>,
+
+    //Only the vault's authority can move its position
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
+
+//The Lending Protocol validates its own accounts, so they're passed through unchecked
+#[derive(Accounts)]
+pub struct VaultWithdraw<'info>
+{
+    ///CHECK: System owned PDA that holds the vault's Lending Protocol position
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), authority.key().as_ref()],
+        bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    ///CHECK: The vault's token account for the Token Reserve's mint
+    #[account(mut)]
+    pub vault_ata: UncheckedAccount<'info>,
+
+    ///CHECK: This is the wallet address of the user who owns the Sub Market
+    pub sub_market_owner: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub lending_protocol: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub lending_stats: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub price_validator: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub token_reserve: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub sub_market: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol. Seeded by the vault PDA
+    #[account(mut)]
+    pub lending_user_account: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol. Seeded by the vault PDA
+    #[account(mut)]
+    pub lending_user_tab_account: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    #[account(mut)]
+    pub token_reserve_ata: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub token_mint: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub token_program: UncheckedAccount<'info>,
+
+    ///CHECK: Validated by the Lending Protocol
+    pub associated_token_program: UncheckedAccount<'info>,
+
+    pub lending_protocol_program: Program<'info, LendingProtocol>,
+
+    //Only the vault's authority can move its position
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
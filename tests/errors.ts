@@ -29,5 +29,19 @@ export const errors =
   notFeeCollectorErrorMsg: "Only the Fee Collector can claim the fees",
   staleTokenReserveOrLendingUserErrorMsg: "Token Reserve or lending user health data was stale",
   oraclePriceNotFoundErrorMsg: "Oracle price not found",
-  invalidOracleSignatureErrorMsg: "This price wasn't signed by the Oracle"
+  invalidOracleSignatureErrorMsg: "This price wasn't signed by the Oracle",
+  notPendingFeeCollectorErrorMsg: "Only the pending fee collector can accept the SubMarket fee collector role",
+  missingCoSignerErrorMsg: "This action needs the Lending User Account's co-signer to sign",
+  invalidUsdQuotePriceIdErrorMsg: "A Token Reserve can't use its own price feed as its USD quote price feed",
+  lendingUserAccountHasTabAccountsErrorMsg: "A Lending User Account can't be closed while it has Tab Accounts",
+  tabAccountNotDeadErrorMsg: "Only Tab Accounts with no balance or debt that have been inactive for a year can be collected",
+  accountAlreadyMigratedErrorMsg: "This account is already on the current account version",
+  borrowIntentNotRequiredErrorMsg: "This borrow is below the Token Reserve's Borrow Intent threshold and can be made directly",
+  invalidMaxTabAmountErrorMsg: "Max tabs per lending account can't be more than the Tab Account registry has room for",
+  invalidTokenReserveVersionErrorMsg: "Token Reserve versions have to be added in order and positions can only move to a newer version",
+  invalidCreditDelegateErrorMsg: "A Lending User can't delegate credit to themselves",
+  invalidSwapProgramErrorMsg: "Swap program doesn't match the Lending Protocol's swap program or swaps are turned off",
+  emptyBatchDepositErrorMsg: "A batch deposit needs atleast one deposit",
+  tokenReserveListingVetoedErrorMsg: "This Token Reserve's listing was vetoed, so it can't be borrowed from or count as collateral",
+  timelockRequiredErrorMsg: "This change has to be queued through the timelock while the timelock delay is turned on"
 }
\ No newline at end of file
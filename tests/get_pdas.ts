@@ -126,15 +126,18 @@ export function getPriceAccountPDA(lendingUserAddress: PublicKey)
   return oraclePriceAccountPDA
 }
 
-export function getTokenReservePDA(tokenMintAddress: PublicKey)
+export function getTokenReservePDA(tokenMintAddress: PublicKey, reserveVersion: number = 0)
 {
+  //The original Token Reserve of a mint is version 0 and doesn't have a version seed
+  const seeds = [new TextEncoder().encode("tokenReserve"), tokenMintAddress.toBuffer()]
+  if (reserveVersion > 0)
+  {
+    seeds.push(new anchor.BN(reserveVersion).toBuffer('le', 1))
+  }
+
   const [tokenReservePDA] = PublicKey.findProgramAddressSync
   (
-    [
-      new TextEncoder().encode("tokenReserve"),
-      tokenMintAddress.toBuffer()
-
-    ],
+    seeds,
     programId
   )
   return tokenReservePDA
@@ -213,4 +216,17 @@ export function getlendingUserMonthlyStatementAccountPDA(statementMonth: number,
     programId
   )
   return lendingUserMonthlyStatementAccountPDA
-}
\ No newline at end of file
+}
+export function getTokenReserveAuditSnapshotPDA(tokenMintAddress: PublicKey, snapshotIndex: number)
+{
+  const [tokenReserveAuditSnapshotPDA] = PublicKey.findProgramAddressSync
+  (
+    [
+      new TextEncoder().encode("tokenReserveAuditSnapshot"),
+      tokenMintAddress.toBuffer(),
+      new anchor.BN(snapshotIndex).toBuffer('le', 4)
+    ],
+    programId
+  )
+  return tokenReserveAuditSnapshotPDA
+}
@@ -0,0 +1,113 @@
+//Reference model for the Token Reserve interest engine. It works the same 4th order Taylor Series compounding as the on-chain FixedPoint math,
+//but in f64 without truncating between terms, so drift or rounding regressions in the on-chain index math show up as a growing gap between the two
+use lending_protocol::lending_helpers::{update_token_reserve_rates, update_token_reserve_supply_and_borrow_interest_change_index,
+    INTEREST_INDEX_RESCALE_FACTOR, SECONDS_IN_A_YEAR};
+use lending_protocol::structs::TokenReserve;
+
+pub const INDEX_SCALING: u128 = 1_000_000_000_000_000_000; //Interest change indexes are 18 decimal fixed point numbers that start at 1
+pub const RELATIVE_TOLERANCE: f64 = 0.000_000_001; //FixedPoint truncates every term to 18 decimals, which adds up over many updates but stays far below this
+
+#[derive(Debug, Clone)]
+pub enum LendingStep
+{
+    Deposit(u64),
+    Borrow(u64),
+    Repay(u64),
+    Withdraw(u64),
+    TimeJump(u64)
+}
+
+pub struct ReferenceModel
+{
+    pub supply_interest_change_index: f64,
+    pub borrow_interest_change_index: f64,
+    pub time_stamp: u64
+}
+
+impl ReferenceModel
+{
+    pub fn new(time_stamp: u64) -> Self
+    {
+        ReferenceModel
+        {
+            supply_interest_change_index: 1.0,
+            borrow_interest_change_index: 1.0,
+            time_stamp
+        }
+    }
+
+    //Mirrors update_token_reserve_supply_and_borrow_interest_change_index using the APYs the Token Reserve had going into the update
+    pub fn accrue(&mut self, supply_apy: u16, borrow_apy: u16, borrowed_amount: u128, new_time_stamp: u64)
+    {
+        if borrowed_amount != 0 && new_time_stamp > self.time_stamp
+        {
+            let change_in_time = (new_time_stamp - self.time_stamp) as f64;
+            self.supply_interest_change_index *= taylor_compounding_factor(supply_apy, change_in_time);
+            self.borrow_interest_change_index *= taylor_compounding_factor(borrow_apy, change_in_time);
+        }
+
+        self.time_stamp = self.time_stamp.max(new_time_stamp);
+    }
+}
+
+//e^x = 1 + x + (x^2 / 2!) + (x^3 / 3!) + (x^4 / 4!) where x = APY * Δt / seconds_in_a_year
+pub fn taylor_compounding_factor(apy: u16, change_in_time: f64) -> f64
+{
+    let x = (apy as f64 / 10_000.0) * change_in_time / SECONDS_IN_A_YEAR as f64;
+    1.0 + x + (x * x / 2.0) + (x * x * x / 6.0) + (x * x * x * x / 24.0)
+}
+
+pub fn new_token_reserve(base_borrow_apy: u16, use_fixed_borrow_apy: bool) -> TokenReserve
+{
+    let mut token_reserve = TokenReserve::default();
+    token_reserve.base_borrow_apy = base_borrow_apy;
+    token_reserve.use_fixed_borrow_apy = use_fixed_borrow_apy;
+    token_reserve.borrow_apy = base_borrow_apy;
+    token_reserve.supply_interest_change_index = INDEX_SCALING;
+    token_reserve.borrow_interest_change_index = INDEX_SCALING;
+    token_reserve
+}
+
+//Runs one step through the on-chain math and the reference model the same way an instruction would: accrue interest, change the amounts, then reprice the rates
+pub fn apply_step(token_reserve: &mut TokenReserve, reference_model: &mut ReferenceModel, step: &LendingStep)
+{
+    let new_time_stamp = match step
+    {
+        LendingStep::TimeJump(seconds) => token_reserve.last_lending_activity_time_stamp + seconds,
+        _ => token_reserve.last_lending_activity_time_stamp
+    };
+
+    reference_model.accrue(token_reserve.supply_apy, token_reserve.borrow_apy, token_reserve.borrowed_amount, new_time_stamp);
+    update_token_reserve_supply_and_borrow_interest_change_index(token_reserve, new_time_stamp, None).unwrap();
+
+    match step
+    {
+        LendingStep::Deposit(amount) => token_reserve.deposited_amount += *amount as u128,
+        LendingStep::Borrow(amount) =>
+        {
+            //Borrows can only come out of what is sitting in the Token Reserve
+            let available_amount = token_reserve.deposited_amount.saturating_sub(token_reserve.borrowed_amount);
+            token_reserve.borrowed_amount += (*amount as u128).min(available_amount);
+        },
+        LendingStep::Repay(amount) => token_reserve.borrowed_amount -= (*amount as u128).min(token_reserve.borrowed_amount),
+        LendingStep::Withdraw(amount) =>
+        {
+            let available_amount = token_reserve.deposited_amount.saturating_sub(token_reserve.borrowed_amount);
+            token_reserve.deposited_amount -= (*amount as u128).min(available_amount);
+        },
+        LendingStep::TimeJump(_) => {}
+    }
+
+    update_token_reserve_rates(token_reserve).unwrap();
+}
+
+//Undoes the rescaling so the on-chain index can be compared against the reference model, which never rescales
+pub fn unscaled_index(index: u128, interest_index_epoch: u8) -> f64
+{
+    (index as f64 / INDEX_SCALING as f64) * (INTEREST_INDEX_RESCALE_FACTOR as f64).powi(interest_index_epoch as i32)
+}
+
+pub fn relative_difference(actual: f64, expected: f64) -> f64
+{
+    (actual - expected).abs() / expected
+}
@@ -0,0 +1,87 @@
+use interest_engine_tests::*;
+use lending_protocol::lending_helpers::{calculate_borrow_apy, calculate_supply_apy, update_token_reserve_rates, DECIMAL_SCALING};
+use proptest::prelude::*;
+
+const MAX_AMOUNT: u64 = 1_000_000_000_000_000; //1 billion tokens at 6 decimals
+const MAX_TIME_JUMP_SECONDS: u64 = 2_592_000; //30 days
+
+fn lending_step() -> impl Strategy<Value = LendingStep>
+{
+    prop_oneof![
+        (1..MAX_AMOUNT).prop_map(LendingStep::Deposit),
+        (1..MAX_AMOUNT).prop_map(LendingStep::Borrow),
+        (1..MAX_AMOUNT).prop_map(LendingStep::Repay),
+        (1..MAX_AMOUNT).prop_map(LendingStep::Withdraw),
+        (0..MAX_TIME_JUMP_SECONDS).prop_map(LendingStep::TimeJump)
+    ]
+}
+
+proptest!
+{
+    //Random deposits, borrows, and time jumps never let the on-chain indexes drift away from the reference model
+    #[test]
+    fn indexes_track_reference_model(base_borrow_apy in 0u16..=500,
+        use_fixed_borrow_apy in any::<bool>(),
+        steps in prop::collection::vec(lending_step(), 1..64))
+    {
+        let mut token_reserve = new_token_reserve(base_borrow_apy, use_fixed_borrow_apy);
+        let mut reference_model = ReferenceModel::new(token_reserve.last_lending_activity_time_stamp);
+
+        for step in steps.iter()
+        {
+            apply_step(&mut token_reserve, &mut reference_model, step);
+
+            let supply_index = unscaled_index(token_reserve.supply_interest_change_index, token_reserve.interest_index_epoch);
+            let borrow_index = unscaled_index(token_reserve.borrow_interest_change_index, token_reserve.interest_index_epoch);
+            prop_assert!(relative_difference(supply_index, reference_model.supply_interest_change_index) <= RELATIVE_TOLERANCE,
+                "Supply index drifted. On-chain: {}, Reference: {}", supply_index, reference_model.supply_interest_change_index);
+            prop_assert!(relative_difference(borrow_index, reference_model.borrow_interest_change_index) <= RELATIVE_TOLERANCE,
+                "Borrow index drifted. On-chain: {}, Reference: {}", borrow_index, reference_model.borrow_interest_change_index);
+        }
+    }
+
+    //Indexes only ever go up, and suppliers never earn a higher rate than borrowers pay
+    #[test]
+    fn indexes_are_monotonic(base_borrow_apy in 0u16..=500,
+        steps in prop::collection::vec(lending_step(), 1..64))
+    {
+        let mut token_reserve = new_token_reserve(base_borrow_apy, false);
+        let mut reference_model = ReferenceModel::new(token_reserve.last_lending_activity_time_stamp);
+        let mut last_supply_index = 1.0;
+        let mut last_borrow_index = 1.0;
+
+        for step in steps.iter()
+        {
+            apply_step(&mut token_reserve, &mut reference_model, step);
+
+            let supply_index = unscaled_index(token_reserve.supply_interest_change_index, token_reserve.interest_index_epoch);
+            let borrow_index = unscaled_index(token_reserve.borrow_interest_change_index, token_reserve.interest_index_epoch);
+            prop_assert!(supply_index >= last_supply_index * (1.0 - RELATIVE_TOLERANCE));
+            prop_assert!(borrow_index >= last_borrow_index * (1.0 - RELATIVE_TOLERANCE));
+            prop_assert!(token_reserve.supply_apy <= token_reserve.borrow_apy);
+            prop_assert!(token_reserve.utilization_rate as u128 <= DECIMAL_SCALING);
+
+            last_supply_index = supply_index;
+            last_borrow_index = borrow_index;
+        }
+    }
+
+    //Interest inflated borrows can outgrow deposits. The rate curve has to stay clamped at 100% Utilization Rate instead of truncating
+    #[test]
+    fn rates_are_clamped_past_full_utilization(base_borrow_apy in 0u16..=500,
+        deposited_amount in 0u128..u64::MAX as u128,
+        borrowed_amount in 1u128..u128::MAX)
+    {
+        let mut token_reserve = new_token_reserve(base_borrow_apy, false);
+        token_reserve.deposited_amount = deposited_amount;
+        token_reserve.borrowed_amount = borrowed_amount;
+
+        update_token_reserve_rates(&mut token_reserve).unwrap();
+
+        let max_borrow_apy = calculate_borrow_apy(&token_reserve, DECIMAL_SCALING);
+        prop_assert!(token_reserve.utilization_rate as u128 <= DECIMAL_SCALING);
+        prop_assert!(token_reserve.borrow_apy <= max_borrow_apy);
+        prop_assert!(token_reserve.supply_apy <= token_reserve.borrow_apy);
+        prop_assert_eq!(token_reserve.supply_apy, calculate_supply_apy(token_reserve.borrow_apy, token_reserve.utilization_rate));
+    }
+}
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor"
 import { Program } from "@coral-xyz/anchor"
 import { LendingProtocol } from "../target/types/lending_protocol"
+import { MoneyMarketVault } from "../target/types/money_market_vault"
 import { assert } from "chai"
 import * as fs from 'fs'
 import { PublicKey,
@@ -23,12 +24,14 @@ import { getLendingProtocolPDA,
   getSubMarketPDA,
   getLendingUserAccountPDA,
   getLendingUserTabAccountPDA,
-  getlendingUserMonthlyStatementAccountPDA } from "./get_pdas"
+  getlendingUserMonthlyStatementAccountPDA,
+  getTokenReserveAuditSnapshotPDA } from "./get_pdas"
 import { Token, ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token"
 import { errors } from "./errors"
 import { borrowWaitTimeInSeconds, useUSDCFixedBorrowAPY, baseBorrowAPY, runInsolventTest } from "./test_settings"
 import type { PriceDataPayload } from "./types"
-import { solTestPriceDataPayload,
+import { getTestPriceDataPayload,
+  solTestPriceDataPayload,
   solAndUSDCTestPriceDataPayload,
   solLiquidatePriceWithUSDCDataPayload,
   usdcTestPriceDataPayload } from "./test_prices"
@@ -42,6 +45,7 @@ describe("lending_protocol", () =>
   anchor.setProvider(anchor.AnchorProvider.env())
 
   const program = anchor.workspace.LendingProtocol as Program<LendingProtocol>
+  const vaultProgram = anchor.workspace.MoneyMarketVault as Program<MoneyMarketVault>
 
   //Just getting rid of some IDE red line errors
   if(!program.provider)
@@ -133,6 +137,17 @@ describe("lending_protocol", () =>
   var supplierWBtcMonthlyStatementRemainingAccount: { pubkey: anchor.web3.PublicKey; isSigner: boolean; isWritable: boolean }
   var borrowerWBtcMonthlyStatementRemainingAccount: { pubkey: anchor.web3.PublicKey; isSigner: boolean; isWritable: boolean }
 
+  var listedMint: Token
+  var listedTokenId: number
+  const listedTokenDecimalAmount = 6
+  const listedTokenDepositAmount = new anchor.BN(1_000_000_000)
+
+  const eModeAssetCategory = 1
+  const eModeLoanToValueRate90Percent = 9000 //90.00%
+  const eModeLiquidationThresholdRate95Percent = 9500 //95.00%
+
+  const vaultUSDCDepositAmount = new anchor.BN(1_000_000)
+
   const borrowAPY4Percent = 400 //4.00%
   const globalLimitLow = new anchor.BN(1)
   const globalLimit1 = new anchor.BN(10_000_000_000)
@@ -142,6 +157,9 @@ describe("lending_protocol", () =>
   const baseBorrowAPYBelove0Percent = -1 //-0.01%
   const solvencyInsuranceFeeRateAbove4Percent = 401 //4.01%
   const solvencyInsuranceFeeRateBelove0Percent = -1 //-0.01%
+  const usdQuotePriceId = 0 //Test token prices are already quoted in USD
+  const badDebtCircuitBreakerRate50Percent = 5_000 //50%
+  const oneDayInSeconds = new anchor.BN(86_400)
   const solvencyInsuranceFeeRate4Percent = 400 //4.00%
   const solvencyInsuranceFeeRate1Percent = 100 //1.00%
 
@@ -167,6 +185,8 @@ describe("lending_protocol", () =>
   const testingWalletKeypair = Keypair.fromSecretKey(Uint8Array.from(keypairData))
   const successorWalletKeypair = anchor.web3.Keypair.generate()
   const borrowerWalletKeypair = anchor.web3.Keypair.generate()
+  const listerWalletKeypair = anchor.web3.Keypair.generate()
+  const vaultAuthorityWalletKeypair = anchor.web3.Keypair.generate()
   const priceValidatorKeypair = anchor.web3.Keypair.generate()
 
   //Populate Oracle Address remaining account
@@ -487,13 +507,152 @@ describe("lending_protocol", () =>
     assert(lendingProtocol.currentStatementYear == newStatementYear)
   })
 
+  it("Verifies That Only the CEO Can Update the Bad Debt Circuit Breaker", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updateBadDebtCircuitBreaker(badDebtCircuitBreakerRate50Percent, oneDayInSeconds, false)
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notCEOErrorMsg)
+  })
+
+  it("Updates the Bad Debt Circuit Breaker", async () => 
+  {
+    await program.methods.updateBadDebtCircuitBreaker(badDebtCircuitBreakerRate50Percent, oneDayInSeconds, false).rpc()
+
+    var lendingProtocol = await program.account.lendingProtocol.fetch(getLendingProtocolPDA())
+
+    assert(lendingProtocol.badDebtCircuitBreakerRate == badDebtCircuitBreakerRate50Percent)
+    assert(lendingProtocol.badDebtWindowSeconds.eq(oneDayInSeconds))
+    assert(lendingProtocol.borrowsPaused == false)
+  })
+
+  it("Verifies That Only the CEO Can Update the User Health Max Age Slots", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updateUserHealthMaxAgeSlots(new anchor.BN(10))
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notCEOErrorMsg)
+  })
+
+  it("Verifies That Only the CEO Can Update the Timelock Delay", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updateTimelockDelaySeconds(new anchor.BN(86_400))
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notCEOErrorMsg)
+  })
+
+  it("Verifies That Only the CEO Can Update the Position Migration Program IDs", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updatePositionMigrationProgramIds(successorWalletKeypair.publicKey, successorWalletKeypair.publicKey)
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notCEOErrorMsg)
+  })
+
+  it("Verifies the Lending Protocol Can't be its Own Swap Program", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updateSwapProgramId(program.programId).rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.invalidSwapProgramErrorMsg)
+  })
+
+  it("Verifies the Max Tabs Per Lending Account Can't be Set Higher Than the Tab Account Registry Allows", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.updateMaxTabAmount(17).rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.invalidMaxTabAmountErrorMsg)
+  })
+
+  it("Verifies an Account on the Current Version Can't be Migrated Again", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.migrateAccount()
+      .accounts({ accountToMigrate: getLendingProtocolPDA() })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    const lendingProtocol = await program.account.lendingProtocol.fetch(getLendingProtocolPDA())
+    assert(lendingProtocol.version == 1)
+    assert(errorMessage == errors.accountAlreadyMigratedErrorMsg)
+  })
+
   it("Verifies That Only the CEO Can Add a Token Reserve", async () => 
   {
     var errorMessage = ""
 
     try
     {
-      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimit1, solvencyInsuranceFeeRate4Percent)
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimit1, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
       .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
       .signers([successorWalletKeypair])
       .rpc()
@@ -512,7 +671,7 @@ describe("lending_protocol", () =>
  
     try
     {
-      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPYAbove5Percent, true, globalLimitLow, solvencyInsuranceFeeRate4Percent)
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPYAbove5Percent, true, globalLimitLow, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
       .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc()
     }
@@ -530,7 +689,7 @@ describe("lending_protocol", () =>
 
     try
     {
-      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPYBelove0Percent, true, globalLimitLow, solvencyInsuranceFeeRate4Percent)
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPYBelove0Percent, true, globalLimitLow, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
       .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc()
     }
@@ -548,7 +707,7 @@ describe("lending_protocol", () =>
 
     try
     {
-      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRateAbove4Percent)
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRateAbove4Percent, usdQuotePriceId, false)
       .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc()
     }
@@ -566,7 +725,7 @@ describe("lending_protocol", () =>
 
     try
     {
-      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRateBelove0Percent)
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRateBelove0Percent, usdQuotePriceId, false)
       .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
       .rpc()
     }
@@ -578,9 +737,28 @@ describe("lending_protocol", () =>
     assert(errorMessage == errors.outOfRangeError)
   })
   
+  it("Verifies That a Token Reserve Can't be Quoted With its Own Price Feed", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      //The wSOL Token Reserve will be Token ID 1
+      await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRate4Percent, 1, false)
+      .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.invalidUsdQuotePriceIdErrorMsg)
+  })
+  
   it("Adds a wSOL Token Reserve", async () => 
   {
-    await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRate4Percent)
+    await program.methods.addTokenReserve(solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
     .accounts({ tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
     .rpc()
     
@@ -592,6 +770,7 @@ describe("lending_protocol", () =>
     assert(tokenReserve.borrowApy == baseBorrowAPY)
     assert(tokenReserve.globalLimit.eq(globalLimitLow))
     assert(tokenReserve.solvencyInsuranceFeeRate == solvencyInsuranceFeeRate4Percent)
+    assert(tokenReserve.usdQuotePriceId == usdQuotePriceId)
 
     //Populate SOL Token Reserve remaining account
     const solTokenReservePDA = getTokenReservePDA(solTokenMintAddress)
@@ -616,6 +795,24 @@ describe("lending_protocol", () =>
     protocolLookUpTableAccount = (await program.provider.connection.getAddressLookupTable(protocolLookUpTableAddress)).value
   })
 
+  it("Verifies Token Reserve Versions Have to be Added in Order", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.addTokenReserveVersion(2, solTokenDecimalAmount, baseBorrowAPY, true, globalLimitLow, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
+      .accounts({ previousTokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMint: solTokenMintAddress, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.invalidTokenReserveVersionErrorMsg)
+  })
+
   it("Verifies That a SubMarket Can't be Created With a Fee on Interest Rate Higher than 100%", async () => 
   {
     var errorMessage = ""
@@ -623,7 +820,7 @@ describe("lending_protocol", () =>
     try
     {
       await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRateAbove100Percent, null)
-      .accounts({ tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
       .rpc()
     }
     catch(error: any)
@@ -641,7 +838,7 @@ describe("lending_protocol", () =>
     try
     {
       await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRateBelove0Percent, null)
-      .accounts({ tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
       .rpc()
     }
     catch(error: any)
@@ -659,7 +856,7 @@ describe("lending_protocol", () =>
     try
     {
       await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
-      .accounts({ tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
       .rpc()
     }
     catch(error: any)
@@ -675,7 +872,7 @@ describe("lending_protocol", () =>
     mainSubMarketOwnerLookUpTableAddress = await initLookUpTable()
     
     await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, mainSubMarketOwnerLookUpTableAddress)
-    .accounts({ tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, feeCollectorAddress: programProviderPublicKey })
     .rpc()
 
     const subMarket = await program.account.subMarket.fetch(getSubMarketPDA(solTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex))
@@ -710,13 +907,49 @@ describe("lending_protocol", () =>
 
     const subMarket = await program.account.subMarket.fetch(getSubMarketPDA(solTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex))
     
+    //The new fee collector has to accept before it takes effect
     assert(subMarket.owner.toBase58() == programProviderPublicKeyString)
-    assert(subMarket.feeCollectorAddress.toBase58() == successorWalletKeypair.publicKey.toBase58())
-    assert(subMarket.feeOnInterestEarnedRate == subMarketFeeRate100Percent)
+    assert(subMarket.feeCollectorAddress.toBase58() == programProviderPublicKeyString)
+    assert(subMarket.pendingFeeCollectorAddress.toBase58() == successorWalletKeypair.publicKey.toBase58())
+    //Fee increases ramp up linearly over the Lending Protocol's SubMarket Fee Ramp Seconds instead of taking effect right away
+    const lendingProtocol = await program.account.lendingProtocol.fetch(getLendingProtocolPDA())
+    assert(subMarket.feeOnInterestEarnedRate == subMarketFeeRate8Percent)
+    assert(subMarket.pendingFeeOnInterestEarnedRate == subMarketFeeRate100Percent)
+    assert(subMarket.feeRampEndTimeStamp.eq(subMarket.feeRampStartTimeStamp.add(lendingProtocol.subMarketFeeRampSeconds)))
     assert(subMarket.tokenId == solTestPriceDataPayload.data[0].tokenId)
     assert(subMarket.subMarketIndex == testSubMarketIndex)
   })
 
+  it("Verifies That Only the Pending Fee Collector Can Accept the SubMarket Fee Collector Role", async () =>
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.acceptSubMarketFeeCollector(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex)
+      .accounts({ subMarketOwner: programProviderPublicKey })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notPendingFeeCollectorErrorMsg)
+  })
+
+  it("Accepts the wSOL SubMarket Fee Collector Role", async () =>
+  {
+    await program.methods.acceptSubMarketFeeCollector(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex)
+    .accounts({ subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    const subMarket = await program.account.subMarket.fetch(getSubMarketPDA(solTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex))
+    assert(subMarket.feeCollectorAddress.toBase58() == successorWalletKeypair.publicKey.toBase58())
+    assert(subMarket.pendingFeeCollectorAddress.toBase58() == PublicKey.default.toBase58())
+  })
+
   //Because the SubMarket account is derived from the signer calling the function (and not passed into the function based on trust), it's never possible to even try to edit someone else's Sub Market
   it("Verifies That a SubMarket Can Only be Edited by the Owner", async () => 
   {
@@ -737,7 +970,52 @@ describe("lending_protocol", () =>
     assert(errorMessage == errors.expectedThisAccountToExistErrorMsg)
   })
 
-  it("Verifies you can't Deposit Over the Global Limit", async () => 
+  it("Verifies That Only the CEO Can Approve a SubMarket Oracle Override", async () =>
+  {
+    const overridePriceId = 200
+    var errorMessage = ""
+
+    await program.methods.setSubMarketOracleOverride(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex, overridePriceId)
+    .rpc()
+
+    try
+    {
+      await program.methods.approveSubMarketOracleOverride(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex, overridePriceId, true)
+      .accounts({ subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.notCEOErrorMsg)
+  })
+
+  it("Approves and Removes a SubMarket Oracle Override", async () =>
+  {
+    const overridePriceId = 200
+    const subMarketPDA = getSubMarketPDA(solTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex)
+
+    await program.methods.approveSubMarketOracleOverride(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex, overridePriceId, true)
+    .accounts({ subMarketOwner: programProviderPublicKey })
+    .rpc()
+
+    var subMarket = await program.account.subMarket.fetch(subMarketPDA)
+    assert(subMarket.oraclePriceIdOverride == overridePriceId)
+    assert(subMarket.oracleOverrideApproved == 1)
+
+    //Removing the override also resets the approval so the rest of the tests price the SubMarket off the Token Reserve
+    await program.methods.setSubMarketOracleOverride(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex, 0)
+    .rpc()
+
+    subMarket = await program.account.subMarket.fetch(subMarketPDA)
+    assert(subMarket.oraclePriceIdOverride == 0)
+    assert(subMarket.oracleOverrideApproved == 0)
+  })
+
+  it("Verifies you can't Deposit Over the Global Limit", async () =>
   {
     var errorMessage = ""
 
@@ -745,6 +1023,7 @@ describe("lending_protocol", () =>
     {
       await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, twoSol, accountName, null)
       .accounts({
+        tokenReserve: getTokenReservePDA(solTokenMintAddress),
         tokenMint: solTokenMintAddress,
         subMarketOwner: programProviderPublicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -767,7 +1046,7 @@ describe("lending_protocol", () =>
     try
     {
       await program.methods.updateTokenReserve(borrowAPY4Percent, true, globalLimit1, solvencyInsuranceFeeRate4Percent)
-      .accounts({ tokenMintAddress: solTokenMintAddress, signer: successorWalletKeypair.publicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, signer: successorWalletKeypair.publicKey })
       .signers([successorWalletKeypair])
       .rpc()
     }
@@ -782,7 +1061,7 @@ describe("lending_protocol", () =>
   it("Updates Token Reserve Borrow APY, Global Limit, and Solvency Insurance Rate", async () => 
   {
     await program.methods.updateTokenReserve(borrowAPY4Percent, true, globalLimit2, solvencyInsuranceFeeRate1Percent)
-    .accounts({ tokenMintAddress: solTokenMintAddress })
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress })
     .rpc()
 
     const tokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(solTokenMintAddress))
@@ -791,12 +1070,30 @@ describe("lending_protocol", () =>
     assert(tokenReserve.solvencyInsuranceFeeRate == solvencyInsuranceFeeRate1Percent)
   })
 
+  it("Returns the wSOL Token Reserve Rate Curve", async () =>
+  {
+    const rateCurve = await program.methods.getTokenReserveRateCurve(11)
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMint: solTokenMintAddress })
+    .view()
+
+    assert(rateCurve.useFixedBorrowApy == true)
+    assert(rateCurve.points.length == 11)
+    assert(rateCurve.points[0].utilizationRate == 0)
+    assert(rateCurve.points[10].utilizationRate == 10_000)
+    for(const point of rateCurve.points)
+    {
+      assert(point.borrowApy == borrowAPY4Percent)
+      assert(point.supplyApy == Math.floor(borrowAPY4Percent * point.utilizationRate / 10_000))
+    }
+  })
+
   it("Deposits wSOL Into the Token Reserve", async () => 
   {
     supplierLookUpTableAddress = await initLookUpTable()
 
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, twoSol, accountName, supplierLookUpTableAddress)
     .accounts({
+        tokenReserve: getTokenReservePDA(solTokenMintAddress),
         tokenMint: solTokenMintAddress,
         subMarketOwner: programProviderPublicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -838,7 +1135,7 @@ describe("lending_protocol", () =>
     assert(lendingUserTabAccount.subMarketOwnerAddress.toBase58() == programProviderPublicKeyString)
     assert(lendingUserTabAccount.subMarketIndex == testSubMarketIndex)
     assert(lendingUserTabAccount.userTabAccountIndex == 0)
-    assert(lendingUserTabAccount.userTabAccountAdded == true)
+    assert(lendingUserTabAccount.userTabAccountAdded == 1)
     assert(lendingUserTabAccount.depositedAmount.eq(twoSol))
 
     const supplierSOLMonthlyStatementPDA = getlendingUserMonthlyStatementAccountPDA
@@ -868,30 +1165,175 @@ describe("lending_protocol", () =>
     //Populate Supplier SOL Monthly Statement Remaining Account
     supplierSOLMonthlyStatementRemainingAccount = 
     {
-      pubkey: supplierSOLMonthlyStatementPDA,
-      isSigner: false,
-      isWritable: true
+      pubkey: supplierSOLMonthlyStatementPDA,
+      isSigner: false,
+      isWritable: true
+    }
+
+    //Add Lending User Tab and Monthly Statment Accounts to Address Lookup Table
+    await addAddressToLookUpTable
+    (
+      supplierLookUpTableAddress,
+      [supplierLendingUserAccountPDA, successorSOLLendingUserTabAccountPDA, supplierSOLMonthlyStatementPDA],
+      "Lending User Tab and Monthly Statement"
+    )
+
+    //Get latest lookup table
+    supplierLookUpTableAccount = (await program.provider.connection.getAddressLookupTable(supplierLookUpTableAddress)).value
+  })
+
+  it("Verifies a User Can't Have an Account Name Longer Than 25 Characters", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.editLendingUserAccountName(testUserAccountIndex, accountName26Characters)
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.accountNameTooLongErrorMsg)
+  })
+
+  it("Verifies a User Can Change Their Account Names", async () => 
+  {
+    await program.methods.editLendingUserAccountName(testUserAccountIndex, accountName25Characters)
+    .accounts({ signer: successorWalletKeypair.publicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    const lendingUserAccount = await program.account.lendingUserAccount.fetch(getLendingUserAccountPDA
+    (
+      successorWalletKeypair.publicKey,
+      testUserAccountIndex
+    ))
+    assert(lendingUserAccount.accountName == accountName25Characters)
+  })
+
+  it("Verifies a User Can't Delegate Credit to Themselves", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.approveCreditDelegation(testUserAccountIndex, twoSol)
+      .accounts({ delegate: successorWalletKeypair.publicKey, tokenMint: solTokenMintAddress, signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.invalidCreditDelegateErrorMsg)
+  })
+
+  it("Verifies a Batch Deposit Needs Atleast One Deposit", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.depositMany(testUserAccountIndex, [])
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.emptyBatchDepositErrorMsg)
+  })
+
+  it("Verifies a User Can Opt Out of and Back Into Monthly Statements", async () =>
+  {
+    const lendingUserAccountPDA = getLendingUserAccountPDA(successorWalletKeypair.publicKey, testUserAccountIndex)
+
+    await program.methods.setMonthlyStatementsDisabled(testUserAccountIndex, true)
+    .accounts({ signer: successorWalletKeypair.publicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    var lendingUserAccount = await program.account.lendingUserAccount.fetch(lendingUserAccountPDA)
+    assert(lendingUserAccount.monthlyStatementsDisabled == true)
+
+    await program.methods.setMonthlyStatementsDisabled(testUserAccountIndex, false)
+    .accounts({ signer: successorWalletKeypair.publicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    lendingUserAccount = await program.account.lendingUserAccount.fetch(lendingUserAccountPDA)
+    assert(lendingUserAccount.monthlyStatementsDisabled == false)
+  })
+
+  it("Verifies a Co-Signer Has to Sign to Remove Itself", async () =>
+  {
+    const lendingUserAccountPDA = getLendingUserAccountPDA(successorWalletKeypair.publicKey, testUserAccountIndex)
+    const coSignerThresholdUSDValue = new anchor.BN(1000).mul(new anchor.BN(10).pow(new anchor.BN(18))) //$1,000
+
+    await program.methods.setCoSigner(testUserAccountIndex, programProviderPublicKey, coSignerThresholdUSDValue)
+    .accounts({ signer: successorWalletKeypair.publicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    var lendingUserAccount = await program.account.lendingUserAccount.fetch(lendingUserAccountPDA)
+    assert(lendingUserAccount.coSignerAddress.toBase58() == programProviderPublicKeyString)
+    assert(lendingUserAccount.coSignerThresholdUsdValue.eq(coSignerThresholdUSDValue))
+
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.setCoSigner(testUserAccountIndex, null, bnZero)
+      .accounts({ signer: successorWalletKeypair.publicKey })
+      .signers([successorWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
     }
 
-    //Add Lending User Tab and Monthly Statment Accounts to Address Lookup Table
-    await addAddressToLookUpTable
-    (
-      supplierLookUpTableAddress,
-      [supplierLendingUserAccountPDA, successorSOLLendingUserTabAccountPDA, supplierSOLMonthlyStatementPDA],
-      "Lending User Tab and Monthly Statement"
-    )
+    assert(errorMessage == errors.missingCoSignerErrorMsg)
 
-    //Get latest lookup table
-    supplierLookUpTableAccount = (await program.provider.connection.getAddressLookupTable(supplierLookUpTableAddress)).value
+    await program.methods.setCoSigner(testUserAccountIndex, null, bnZero)
+    .accounts({ signer: successorWalletKeypair.publicKey, currentCoSigner: programProviderPublicKey })
+    .signers([successorWalletKeypair])
+    .rpc()
+
+    lendingUserAccount = await program.account.lendingUserAccount.fetch(lendingUserAccountPDA)
+    assert(lendingUserAccount.coSignerAddress.toBase58() == PublicKey.default.toBase58())
   })
 
-  it("Verifies a User Can't Have an Account Name Longer Than 25 Characters", async () => 
+  it("Returns a wSOL Lending User Tab Position", async () =>
+  {
+    const tabPosition = await program.methods.getLendingUserTabPosition(testSubMarketIndex, testUserAccountIndex)
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMint: solTokenMintAddress, subMarketOwner: programProviderPublicKey, lendingUser: successorWalletKeypair.publicKey })
+    .view()
+
+    const tokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(solTokenMintAddress))
+
+    assert(tabPosition.depositedAmount.eq(twoSol))
+    assert(tabPosition.netSupplyApy <= tokenReserve.supplyApy)
+    assert(tabPosition.netBorrowApy == tokenReserve.borrowApy)
+  })
+
+  it("Verifies a Lending User Account With Tab Accounts Can't be Closed", async () =>
   {
     var errorMessage = ""
 
     try
     {
-      await program.methods.editLendingUserAccountName(testUserAccountIndex, accountName26Characters)
+      await program.methods.closeLendingUserAccount(testUserAccountIndex)
       .accounts({ signer: successorWalletKeypair.publicKey })
       .signers([successorWalletKeypair])
       .rpc()
@@ -901,25 +1343,28 @@ describe("lending_protocol", () =>
       errorMessage = error.error.errorMessage
     }
 
-    assert(errorMessage == errors.accountNameTooLongErrorMsg)
+    assert(errorMessage == errors.lendingUserAccountHasTabAccountsErrorMsg)
   })
 
-  it("Verifies a User Can Change Their Account Names", async () => 
+  it("Verifies a Tab Account With a Balance Can't be Garbage Collected", async () =>
   {
-    await program.methods.editLendingUserAccountName(testUserAccountIndex, accountName25Characters)
-    .accounts({ signer: successorWalletKeypair.publicKey })
-    .signers([successorWalletKeypair])
-    .rpc()
+    var errorMessage = ""
 
-    const lendingUserAccount = await program.account.lendingUserAccount.fetch(getLendingUserAccountPDA
-    (
-      successorWalletKeypair.publicKey,
-      testUserAccountIndex
-    ))
-    assert(lendingUserAccount.accountName == accountName25Characters)
+    try
+    {
+      await program.methods.collectDeadTabAccount(solTestPriceDataPayload.data[0].tokenId, testSubMarketIndex, testUserAccountIndex)
+      .accounts({ subMarketOwner: programProviderPublicKey, lendingUser: successorWalletKeypair.publicKey })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.tabAccountNotDeadErrorMsg)
   })
 
-  it("Verifies a User Can't Withdraw More wSOL Than They Deposited", async () => 
+  it("Verifies a User Can't Withdraw More wSOL Than They Deposited", async () =>
   {
     var errorMessage = ""
     const tooMuchSol = twoSol.add(new anchor.BN(1))
@@ -932,6 +1377,7 @@ describe("lending_protocol", () =>
         tooMuchSol,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(solTokenMintAddress),
         tokenMint: solTokenMintAddress,
         subMarketOwner: programProviderPublicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -957,6 +1403,7 @@ describe("lending_protocol", () =>
       twoSol,
       true)
     .accounts({
+      tokenReserve: getTokenReservePDA(solTokenMintAddress),
       tokenMint: solTokenMintAddress,
       subMarketOwner: programProviderPublicKey,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -986,7 +1433,7 @@ describe("lending_protocol", () =>
     assert(lendingUserTabAccount.subMarketOwnerAddress.toBase58() == programProviderPublicKeyString)
     assert(lendingUserTabAccount.subMarketIndex == testSubMarketIndex)
     assert(lendingUserTabAccount.userTabAccountIndex == 0)
-    assert(lendingUserTabAccount.userTabAccountAdded == true)
+    assert(lendingUserTabAccount.userTabAccountAdded == 1)
     assert(lendingUserTabAccount.depositedAmount.eq(bnZero))
 
     const tokenReserveATA = await deriveATA(getTokenReservePDA(solTokenMintAddress), solTokenMintAddress, true)
@@ -1030,7 +1477,7 @@ describe("lending_protocol", () =>
   
   it("Adds a USDC Token Reserve", async () => 
   {
-    await program.methods.addTokenReserve(usdcTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent)
+    await program.methods.addTokenReserve(usdcTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
     .accounts({ tokenMint: usdcMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
     .rpc()
     
@@ -1069,7 +1516,7 @@ describe("lending_protocol", () =>
   it("Creates a USDC SubMarket", async () => 
   {
     await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
-    .accounts({ tokenMintAddress: usdcMint.publicKey, feeCollectorAddress: programProviderPublicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMintAddress: usdcMint.publicKey, feeCollectorAddress: programProviderPublicKey })
     .rpc()
 
     const subMarket = await program.account.subMarket.fetch(getSubMarketPDA(usdcTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex))
@@ -1099,6 +1546,7 @@ describe("lending_protocol", () =>
   {
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, supplierUSDCAmount, null, null)
     .accounts({
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
       tokenMint: usdcMint.publicKey,
       subMarketOwner: programProviderPublicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1133,7 +1581,7 @@ describe("lending_protocol", () =>
     assert(lendingUserTabAccount.subMarketOwnerAddress.toBase58() == programProviderPublicKeyString)
     assert(lendingUserTabAccount.subMarketIndex == testSubMarketIndex)
     assert(lendingUserTabAccount.userTabAccountIndex == 1)
-    assert(lendingUserTabAccount.userTabAccountAdded == true)
+    assert(lendingUserTabAccount.userTabAccountAdded == 1)
     assert(lendingUserTabAccount.depositedAmount.eq(supplierUSDCAmount))
 
     const lendingUserMonthlyStatementAccount = await program.account.lendingUserMonthlyStatementAccount.fetch(getlendingUserMonthlyStatementAccountPDA
@@ -1204,6 +1652,7 @@ describe("lending_protocol", () =>
     //Depositing 1 Sol as Collateral
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, oneSol, accountName, borrowerLookUpTableAddress)
     .accounts({
+      tokenReserve: getTokenReservePDA(solTokenMintAddress),
       tokenMint: solTokenMintAddress,
       subMarketOwner: programProviderPublicKey,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -1309,6 +1758,30 @@ describe("lending_protocol", () =>
     borrowerLookUpTableAccount = (await program.provider.connection.getAddressLookupTable(borrowerLookUpTableAddress)).value
   })
 
+  it("Verifies a User Can't Transfer More of a Deposit Than They Deposited", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.transferDeposit(testSubMarketIndex, testUserAccountIndex, testUserAccountIndex, twoSol)
+      .accounts({
+        tokenReserve: getTokenReservePDA(solTokenMintAddress),
+        subMarketOwner: programProviderPublicKey,
+        recipient: successorWalletKeypair.publicKey,
+        tokenMint: solTokenMintAddress,
+        signer: borrowerWalletKeypair.publicKey })
+      .signers([borrowerWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.insufficientFundsErrorMsg)
+  })
+
   it("Verifies only the Oracle Price Validator can set prices", async () => 
   {
     var errorMessage = ""
@@ -1479,6 +1952,7 @@ describe("lending_protocol", () =>
         overBorrowUSDCAmount,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1494,6 +1968,32 @@ describe("lending_protocol", () =>
     assert(errorMessage == errors.staleTokenReserveOrLendingUserErrorMsg)
   })
 
+  it("Verifies a Borrow Intent Can't be Posted While Borrow Intents Are Turned Off", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.postBorrowIntent(
+        testSubMarketIndex,
+        testUserAccountIndex,
+        overBorrowUSDCAmount)
+      .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
+        subMarketOwner: programProviderPublicKey,
+        tokenMint: usdcMint.publicKey,
+        signer: borrowerWalletKeypair.publicKey })
+      .signers([borrowerWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.borrowIntentNotRequiredErrorMsg)
+  })
+
   it("Verifies that you can't Borrow More than 70% of the Value of your Collateral", async () => 
   {
     var errorMessage = ""
@@ -1523,6 +2023,7 @@ describe("lending_protocol", () =>
         overBorrowUSDCAmount,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1574,6 +2075,7 @@ describe("lending_protocol", () =>
         borrowerUSDCAmount,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1621,6 +2123,7 @@ describe("lending_protocol", () =>
       borrowerUSDCAmount,
       false)
     .accounts({
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: usdcMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1690,6 +2193,7 @@ describe("lending_protocol", () =>
         new anchor.BN(1),
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(solTokenMintAddress),
         subMarketOwner: programProviderPublicKey,
         tokenMint: solTokenMintAddress,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -1753,6 +2257,7 @@ describe("lending_protocol", () =>
         borrowerUSDCAmount,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1812,6 +2317,7 @@ describe("lending_protocol", () =>
         borrowerUSDCAmount,
         false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -1890,8 +2396,11 @@ describe("lending_protocol", () =>
         false,
         false,
         null,
+        null,
         null)
       .accounts({
+        repaymentTokenReserve: getTokenReservePDA(usdcMint.publicKey),
+        liquidationTokenReserve: getTokenReservePDA(solTokenMintAddress),
         liquidatiAccountOwner: borrowerWalletKeypair.publicKey,
         repaymentSubMarketOwner: programProviderPublicKey,
         liquidationSubMarketOwner: programProviderPublicKey,
@@ -1974,8 +2483,11 @@ describe("lending_protocol", () =>
         true,
         false,
         null,
+        null,
         null)
       .accounts({
+        repaymentTokenReserve: getTokenReservePDA(usdcMint.publicKey),
+        liquidationTokenReserve: getTokenReservePDA(solTokenMintAddress),
         liquidatiAccountOwner: borrowerWalletKeypair.publicKey,
         repaymentSubMarketOwner: programProviderPublicKey,
         liquidationSubMarketOwner: programProviderPublicKey,
@@ -2058,8 +2570,11 @@ describe("lending_protocol", () =>
         false,
         false,
         null,
+        null,
         null)
       .accounts({
+        repaymentTokenReserve: getTokenReservePDA(usdcMint.publicKey),
+        liquidationTokenReserve: getTokenReservePDA(solTokenMintAddress),
         liquidatiAccountOwner: borrowerWalletKeypair.publicKey,
         repaymentSubMarketOwner: programProviderPublicKey,
         liquidationSubMarketOwner: programProviderPublicKey,
@@ -2098,6 +2613,7 @@ describe("lending_protocol", () =>
       false,
       false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2117,6 +2633,25 @@ describe("lending_protocol", () =>
     assert(errorMessage.includes(errors.staleTokenReserveOrLendingUserErrorMsg))
   })
 
+  it("Verifies a User Can't Repay All When the Lending User's Health Data is Stale", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.repayAll(testUserAccountIndex, 0, [])
+      .accounts({ signer: borrowerWalletKeypair.publicKey })
+      .signers([borrowerWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.staleTokenReserveOrLendingUserErrorMsg)
+  })
+
   it("Verifies a Borrower can't Repay less than 10% when their account is in a bad state to prevent 'griefing'.", async () => 
   {
     var errorMessage = ""
@@ -2158,6 +2693,7 @@ describe("lending_protocol", () =>
       false,
       false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2237,8 +2773,11 @@ describe("lending_protocol", () =>
         false,
         false,
         null,
+        null,
         null)
       .accounts({
+        repaymentTokenReserve: getTokenReservePDA(usdcMint.publicKey),
+        liquidationTokenReserve: getTokenReservePDA(solTokenMintAddress),
         liquidatiAccountOwner: borrowerWalletKeypair.publicKey,
         repaymentSubMarketOwner: programProviderPublicKey,
         liquidationSubMarketOwner: programProviderPublicKey,
@@ -2363,8 +2902,11 @@ describe("lending_protocol", () =>
       runInsolventTest,
       false,
       null,
-      liquidatorLookUpTableAddress)
+      liquidatorLookUpTableAddress,
+      null)
     .accounts({
+      repaymentTokenReserve: getTokenReservePDA(usdcMint.publicKey),
+      liquidationTokenReserve: getTokenReservePDA(solTokenMintAddress),
       liquidatiAccountOwner: borrowerWalletKeypair.publicKey,
       repaymentSubMarketOwner: programProviderPublicKey,
       liquidationSubMarketOwner: programProviderPublicKey,
@@ -2653,6 +3195,7 @@ describe("lending_protocol", () =>
       false,
       false)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2723,6 +3266,7 @@ describe("lending_protocol", () =>
     true,
     false)
     .accounts({
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: usdcMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2800,6 +3344,7 @@ describe("lending_protocol", () =>
         borrowerUSDCAmount,
         true)
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         subMarketOwner: programProviderPublicKey,
         tokenMint: usdcMint.publicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2854,6 +3399,7 @@ describe("lending_protocol", () =>
       borrowerUSDCAmount,
       true)
     .accounts({
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: usdcMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -2879,7 +3425,7 @@ describe("lending_protocol", () =>
     assert(lendingUserTabAccount.subMarketOwnerAddress.toBase58() == programProviderPublicKeyString)
     assert(lendingUserTabAccount.subMarketIndex == testSubMarketIndex)
     assert(lendingUserTabAccount.userTabAccountIndex == 1)
-    assert(lendingUserTabAccount.userTabAccountAdded == true)
+    assert(lendingUserTabAccount.userTabAccountAdded == 1)
     assert(lendingUserTabAccount.depositedAmount.eq(bnZero))
 
     const tokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(usdcMint.publicKey))
@@ -2933,7 +3479,7 @@ describe("lending_protocol", () =>
       null,
       null
       )
-      .accounts({ tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
       .signers([successorWalletKeypair])
       .rpc()
     }
@@ -2953,7 +3499,7 @@ describe("lending_protocol", () =>
     null,
     null
     )
-    .accounts({ tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey })
     .rpc()
 
     const tokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(usdcMint.publicKey))
@@ -2989,6 +3535,7 @@ describe("lending_protocol", () =>
       null
       )
       .accounts({
+        tokenReserve: getTokenReservePDA(usdcMint.publicKey),
         tokenMint: usdcMint.publicKey,
         subMarketOwner: programProviderPublicKey,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -3012,7 +3559,7 @@ describe("lending_protocol", () =>
     null,
     null
     )
-    .accounts({ tokenMint: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
+    .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMint: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
     .rpc()
 
     const tokenReserveUSDCATA = await deriveATA(getTokenReservePDA(usdcMint.publicKey), usdcMint.publicKey, true)
@@ -3045,7 +3592,7 @@ describe("lending_protocol", () =>
       null,
       null
       )
-      .accounts({ tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
+      .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMintAddress: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, signer: successorWalletKeypair.publicKey })
       .signers([successorWalletKeypair])
       .rpc()
     }
@@ -3065,7 +3612,7 @@ describe("lending_protocol", () =>
     null,
     null
     )
-    .accounts({ tokenMintAddress: solTokenMintAddress, subMarketOwner: programProviderPublicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMintAddress: solTokenMintAddress, subMarketOwner: programProviderPublicKey })
     .rpc()
 
     const lendingUserTabAccount = await program.account.lendingUserTabAccount.fetch(getLendingUserTabAccountPDA
@@ -3094,17 +3641,37 @@ describe("lending_protocol", () =>
     assert(tokenReserve.uncollectedLiquidationFeesAmount.eq(bnZero))
   })
 
+  it("Creates a USDC Token Reserve Audit Snapshot", async () => 
+  {
+    await program.methods.createTokenReserveAuditSnapshot()
+    .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMint: usdcMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
+    .rpc()
+
+    const tokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(usdcMint.publicKey))
+    const tokenReserveAuditSnapshot = await program.account.tokenReserveAuditSnapshot.fetch(getTokenReserveAuditSnapshotPDA(usdcMint.publicKey, 0))
+    const tokenReserveUSDCATA = await deriveATA(getTokenReservePDA(usdcMint.publicKey), usdcMint.publicKey, true)
+    const tokenReserveUSDCATABalance = await program.provider.connection.getTokenAccountBalance(tokenReserveUSDCATA)
+
+    assert(tokenReserve.auditSnapshotCount == 1)
+    assert(tokenReserveAuditSnapshot.snapshotIndex == 0)
+    assert(tokenReserveAuditSnapshot.depositedAmount.eq(tokenReserve.depositedAmount))
+    assert(tokenReserveAuditSnapshot.borrowedAmount.eq(tokenReserve.borrowedAmount))
+    assert(tokenReserveAuditSnapshot.supplyInterestChangeIndex.eq(tokenReserve.supplyInterestChangeIndex))
+    assert(tokenReserveAuditSnapshot.tokenReserveAtaBalanceAmount.toString() == tokenReserveUSDCATABalance.value.amount)
+    assert(tokenReserveAuditSnapshot.snapshotHash.some((byte: number) => byte != 0))
+  })
+
   it("Adds a DAI, WEth, and WBtc Token Reserves", async () => 
   {
-    await program.methods.addTokenReserve(daiTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent)
+    await program.methods.addTokenReserve(daiTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
     .accounts({ tokenMint: daiMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
     .rpc()
 
-    await program.methods.addTokenReserve(wethTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent)
+    await program.methods.addTokenReserve(wethTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
     .accounts({ tokenMint: wethMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
     .rpc()
 
-    await program.methods.addTokenReserve(wbtcTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent)
+    await program.methods.addTokenReserve(wbtcTokenDecimalAmount, baseBorrowAPY, useUSDCFixedBorrowAPY, globalLimit1, solvencyInsuranceFeeRate4Percent, usdQuotePriceId, false)
     .accounts({ tokenMint: wbtcMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID })
     .rpc()
 
@@ -3174,11 +3741,11 @@ describe("lending_protocol", () =>
   it("Creates a DAI, WEth, and WBtc SubMarket", async () => 
   {
     await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
-    .accounts({ tokenMintAddress: daiMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
+    .accounts({ tokenReserve: getTokenReservePDA(daiMint.publicKey), tokenMintAddress: daiMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
     await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
-    .accounts({ tokenMintAddress: wethMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
+    .accounts({ tokenReserve: getTokenReservePDA(wethMint.publicKey), tokenMintAddress: wethMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
     await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
-    .accounts({ tokenMintAddress: wbtcMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
+    .accounts({ tokenReserve: getTokenReservePDA(wbtcMint.publicKey), tokenMintAddress: wbtcMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
 
     const daiSubMarket = await program.account.subMarket.fetch(getSubMarketPDA(3, programProviderPublicKey, testSubMarketIndex))
     assert(daiSubMarket.owner.toBase58() == programProviderPublicKeyString)
@@ -3240,27 +3807,27 @@ describe("lending_protocol", () =>
   it("Deposits SOL, USDC, DAI, WEth, BTC into Token Reserve", async () => 
   {
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, twoSol, null, null)
-    .accounts({ tokenMint: solTokenMintAddress, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(solTokenMintAddress), tokenMint: solTokenMintAddress, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
     .signers([successorWalletKeypair])
     .rpc()
     
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, supplierUSDCAmount, null, null)
-    .accounts({ tokenMint: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMint: usdcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
     .signers([successorWalletKeypair])
     .rpc()
 
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, daiDepositAmount, null, null)
-    .accounts({ tokenMint: daiMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(daiMint.publicKey), tokenMint: daiMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
     .signers([successorWalletKeypair])
     .rpc()
 
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, wethDepositAmount, null, null)
-    .accounts({ tokenMint: wethMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(wethMint.publicKey), tokenMint: wethMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
     .signers([successorWalletKeypair])
     .rpc()
 
     await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, wbtcDepositAmount, null, null)
-    .accounts({ tokenMint: wbtcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
+    .accounts({ tokenReserve: getTokenReservePDA(wbtcMint.publicKey), tokenMint: wbtcMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: successorWalletKeypair.publicKey })
     .signers([successorWalletKeypair])
     .rpc()
 
@@ -3419,6 +3986,7 @@ describe("lending_protocol", () =>
       daiHalfDepositAmount,
       false)
     .accounts({
+      tokenReserve: getTokenReservePDA(daiMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: daiMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -3432,6 +4000,7 @@ describe("lending_protocol", () =>
       wethHalfDepositAmount,
       false)
     .accounts({
+      tokenReserve: getTokenReservePDA(wethMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: wethMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -3445,6 +4014,7 @@ describe("lending_protocol", () =>
       wbtcHalfDepositAmount,
       false)
     .accounts({
+      tokenReserve: getTokenReservePDA(wbtcMint.publicKey),
       subMarketOwner: programProviderPublicKey,
       tokenMint: wbtcMint.publicKey,
       tokenProgram: TOKEN_2022_PROGRAM_ID,
@@ -3457,6 +4027,284 @@ describe("lending_protocol", () =>
     await sendVersionedTrasaction([withdrawWBtcInstruction], [successorWalletKeypair])
   })
 
+  it("Lists a Token Reserve and Deposits into It", async () => 
+  {
+    //Fund Lister Wallet
+    await airDropSol(listerWalletKeypair.publicKey)
+
+    listedMint = await Token.createMint
+    (
+      program.provider.connection,
+      testingWalletKeypair, //Payer for the mint creation
+      programProviderPublicKey, //Mint authority (who can mint tokens)
+      null, //Freeze authority (opttional)
+      listedTokenDecimalAmount, //Decimals for the listed token
+      TOKEN_2022_PROGRAM_ID //SPL Token program ID
+    )
+
+    const listerWalletListedTokenATA = await deriveATA(listerWalletKeypair.publicKey, listedMint.publicKey)
+    await createATAForWallet(listerWalletKeypair, listedMint.publicKey, listerWalletListedTokenATA)
+    await mintTokenToWallet(listedMint.publicKey, listerWalletListedTokenATA)
+
+    await program.methods.listTokenReserve(usdQuotePriceId)
+    .accounts({ tokenMint: listedMint.publicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: listerWalletKeypair.publicKey })
+    .signers([listerWalletKeypair])
+    .rpc()
+
+    //Listings start with a 100% collateral haircut
+    const listedTokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(listedMint.publicKey))
+    listedTokenId = listedTokenReserve.tokenId
+    assert(listedTokenReserve.collateralHaircutRate == 10000)
+    assert(listedTokenReserve.listingVetoDeadlineTimeStamp.gt(bnZero))
+    assert(!listedTokenReserve.listingVetoed)
+
+    await program.methods.createSubMarket(testSubMarketIndex, subMarketFeeRate8Percent, null)
+    .accounts({ tokenReserve: getTokenReservePDA(listedMint.publicKey), tokenMintAddress: listedMint.publicKey, feeCollectorAddress: programProviderPublicKey }).rpc()
+
+    const listerLookUpTableAddress = await initLookUpTable()
+
+    await program.methods.depositTokens(testSubMarketIndex, testUserAccountIndex, listedTokenDepositAmount, accountName, listerLookUpTableAddress)
+    .accounts({ tokenReserve: getTokenReservePDA(listedMint.publicKey), tokenMint: listedMint.publicKey, subMarketOwner: programProviderPublicKey, tokenProgram: TOKEN_2022_PROGRAM_ID, signer: listerWalletKeypair.publicKey })
+    .signers([listerWalletKeypair])
+    .rpc()
+
+    const lendingUserTabAccount = await program.account.lendingUserTabAccount.fetch(getLendingUserTabAccountPDA
+    (
+      listedTokenId,
+      programProviderPublicKey,
+      testSubMarketIndex,
+      listerWalletKeypair.publicKey,
+      testUserAccountIndex
+    ))
+    assert(lendingUserTabAccount.depositedAmount.eq(listedTokenDepositAmount))
+  })
+
+  it("Vetoes the Listed Token Reserve and Verifies its Deposits Don't Count Toward User Health", async () => 
+  {
+    await program.methods.vetoTokenReserveListing()
+    .accounts({ tokenMintAddress: listedMint.publicKey })
+    .rpc()
+
+    const listedTokenReserve = await program.account.tokenReserve.fetch(getTokenReservePDA(listedMint.publicKey))
+    assert(listedTokenReserve.listingVetoed)
+    assert(listedTokenReserve.collateralHaircutRate == 10000)
+    assert(listedTokenReserve.globalLimit.eq(bnZero))
+
+    const listedTokenTestPriceDataPayload = getTestPriceDataPayload(
+    [
+      { tokenId: listedTokenId, price: 100, exponent: -2 } //$1.00 USD
+    ])
+    const [updatePricesTransaction, priceRemainingAccount] = await generateOracleTransactionAndRemainingPriceAccount(listedTokenTestPriceDataPayload, listerWalletKeypair.publicKey)
+
+    const refreshingRemainingAccounts =
+    [
+      //Price Account
+      priceRemainingAccount,
+
+      //Token Reserves
+      {
+        pubkey: getTokenReservePDA(listedMint.publicKey),
+        isSigner: false,
+        isWritable: true
+      },
+
+      //Sets of Tabs, Submarkets, and Monthly Statement Accounts
+      {
+        pubkey: getLendingUserTabAccountPDA(listedTokenId, programProviderPublicKey, testSubMarketIndex, listerWalletKeypair.publicKey, testUserAccountIndex),
+        isSigner: false,
+        isWritable: true
+      },
+      {
+        pubkey: getSubMarketPDA(listedTokenId, programProviderPublicKey, testSubMarketIndex),
+        isSigner: false,
+        isWritable: true
+      },
+      {
+        pubkey: getlendingUserMonthlyStatementAccountPDA(newStatementMonth, newStatementYear, listedTokenId, programProviderPublicKey, testSubMarketIndex, listerWalletKeypair.publicKey, testUserAccountIndex),
+        isSigner: false,
+        isWritable: true
+      }
+    ]
+
+    const refreshUserHealthAndTokenReservesInstruction = await program.methods.refreshUserHealthChunkAndTokenReserves(testUserAccountIndex, 1, 1, false)
+    .accounts({ lendingUserOwner: listerWalletKeypair.publicKey, signer: listerWalletKeypair.publicKey })
+    .remainingAccounts(refreshingRemainingAccounts)
+    .signers([listerWalletKeypair])
+    .instruction()
+
+    await program.provider.connection.sendRawTransaction(updatePricesTransaction.serialize(), { skipPreflight: false })
+    await sendVersionedTrasaction([refreshUserHealthAndTokenReservesInstruction], [listerWalletKeypair])
+
+    const lendingUserAccount = await program.account.lendingUserAccount.fetch(getLendingUserAccountPDA(listerWalletKeypair.publicKey, testUserAccountIndex))
+    assert(lendingUserAccount.totalDepositedUsdValue.eq(bnZero))
+  })
+
+  it("Verifies you can't Borrow from a Vetoed Token Reserve", async () => 
+  {
+    var errorMessage = ""
+
+    try
+    {
+      await program.methods.borrowTokens(
+        testSubMarketIndex,
+        testUserAccountIndex,
+        listedTokenDepositAmount,
+        false)
+      .accounts({
+        tokenReserve: getTokenReservePDA(listedMint.publicKey),
+        subMarketOwner: programProviderPublicKey,
+        tokenMint: listedMint.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        signer: listerWalletKeypair.publicKey })
+      .signers([listerWalletKeypair])
+      .rpc()
+    }
+    catch(error: any)
+    {
+      errorMessage = error.error.errorMessage
+    }
+
+    assert(errorMessage == errors.tokenReserveListingVetoedErrorMsg)
+  })
+
+  it("Opens and Manages a Position Held by an Integrating Program's PDA", async () => 
+  {
+    //Fund Vault Authority Wallet
+    await airDropSol(vaultAuthorityWalletKeypair.publicKey)
+
+    const [vaultPDA] = PublicKey.findProgramAddressSync
+    (
+      [
+        new TextEncoder().encode("vault"),
+        vaultAuthorityWalletKeypair.publicKey.toBuffer()
+      ],
+      vaultProgram.programId
+    )
+
+    //The vault PDA pays rent for the accounts its first deposit opens
+    await vaultProgram.methods.fundVault(new anchor.BN(LAMPORTS_PER_SOL))
+    .accounts({ authority: vaultAuthorityWalletKeypair.publicKey })
+    .signers([vaultAuthorityWalletKeypair])
+    .rpc()
+
+    //Create the vault PDA's USDC ATA and mint USDC to it
+    const vaultUSDCATA = await deriveATA(vaultPDA, usdcMint.publicKey, true)
+    await programProvider.sendAndConfirm(new Transaction().add
+    (
+      Token.createAssociatedTokenAccountInstruction
+      (
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        TOKEN_2022_PROGRAM_ID,
+        usdcMint.publicKey,
+        vaultUSDCATA,
+        vaultPDA,
+        programProviderPublicKey
+      )
+    ))
+    await mintTokenToWallet(usdcMint.publicKey, vaultUSDCATA)
+
+    const vaultLookUpTableAddress = await initLookUpTable()
+    const tokenReserveUSDCATA = await deriveATA(getTokenReservePDA(usdcMint.publicKey), usdcMint.publicKey, true)
+    const vaultLendingUserAccountPDA = getLendingUserAccountPDA(vaultPDA, testUserAccountIndex)
+    const vaultUSDCLendingUserTabAccountPDA = getLendingUserTabAccountPDA
+    (
+      usdcTestPriceDataPayload.data[0].tokenId,
+      programProviderPublicKey,
+      testSubMarketIndex,
+      vaultPDA,
+      testUserAccountIndex
+    )
+
+    await vaultProgram.methods.deposit(testSubMarketIndex, testUserAccountIndex, vaultUSDCDepositAmount, vaultLookUpTableAddress)
+    .accounts({
+      vaultAta: vaultUSDCATA,
+      subMarketOwner: programProviderPublicKey,
+      lendingProtocol: getLendingProtocolPDA(),
+      lendingStats: getLendingStatsPDA(),
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
+      subMarket: getSubMarketPDA(usdcTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex),
+      lendingUserAccount: vaultLendingUserAccountPDA,
+      lendingUserTabAccount: vaultUSDCLendingUserTabAccountPDA,
+      tokenReserveAta: tokenReserveUSDCATA,
+      tokenMint: usdcMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      authority: vaultAuthorityWalletKeypair.publicKey })
+    .signers([vaultAuthorityWalletKeypair])
+    .rpc()
+
+    //The position is owned by the vault PDA
+    const vaultLendingUserAccount = await program.account.lendingUserAccount.fetch(vaultLendingUserAccountPDA)
+    assert(vaultLendingUserAccount.owner.toBase58() == vaultPDA.toBase58())
+
+    var vaultUSDCLendingUserTabAccount = await program.account.lendingUserTabAccount.fetch(vaultUSDCLendingUserTabAccountPDA)
+    assert(vaultUSDCLendingUserTabAccount.depositedAmount.eq(vaultUSDCDepositAmount))
+
+    //Withdraw the whole position, including any interest it earned
+    await vaultProgram.methods.withdraw(testSubMarketIndex, testUserAccountIndex, bnZero, true)
+    .accounts({
+      vaultAta: vaultUSDCATA,
+      subMarketOwner: programProviderPublicKey,
+      lendingProtocol: getLendingProtocolPDA(),
+      lendingStats: getLendingStatsPDA(),
+      priceValidator: getOraclePriceValidatorPDA(),
+      tokenReserve: getTokenReservePDA(usdcMint.publicKey),
+      subMarket: getSubMarketPDA(usdcTestPriceDataPayload.data[0].tokenId, programProviderPublicKey, testSubMarketIndex),
+      lendingUserAccount: vaultLendingUserAccountPDA,
+      lendingUserTabAccount: vaultUSDCLendingUserTabAccountPDA,
+      tokenReserveAta: tokenReserveUSDCATA,
+      tokenMint: usdcMint.publicKey,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      authority: vaultAuthorityWalletKeypair.publicKey })
+    .signers([vaultAuthorityWalletKeypair])
+    .rpc()
+
+    vaultUSDCLendingUserTabAccount = await program.account.lendingUserTabAccount.fetch(vaultUSDCLendingUserTabAccountPDA)
+    assert(vaultUSDCLendingUserTabAccount.depositedAmount.eq(bnZero))
+
+    const vaultUSDCATABalance = await program.provider.connection.getTokenAccountBalance(vaultUSDCATA)
+    assert(Number(vaultUSDCATABalance.value.amount) >= mintAmount)
+  })
+
+  it("Verifies E-Mode Category and Asset Category Changes Have to Go Through the Timelock While it's On", async () => 
+  {
+    await program.methods.createEModeCategory(eModeAssetCategory, eModeLoanToValueRate90Percent, eModeLiquidationThresholdRate95Percent)
+    .rpc()
+
+    await program.methods.updateTimelockDelaySeconds(new anchor.BN(86_400))
+    .rpc()
+
+    var eModeErrorMessage = ""
+
+    try
+    {
+      await program.methods.updateEModeCategory(eModeAssetCategory, eModeLoanToValueRate90Percent + 100, eModeLiquidationThresholdRate95Percent)
+      .rpc()
+    }
+    catch(error: any)
+    {
+      eModeErrorMessage = error.error.errorMessage
+    }
+
+    assert(eModeErrorMessage == errors.timelockRequiredErrorMsg)
+
+    var assetCategoryErrorMessage = ""
+
+    try
+    {
+      await program.methods.updateTokenReserveAssetCategory(eModeAssetCategory)
+      .accounts({ tokenReserve: getTokenReservePDA(usdcMint.publicKey), tokenMintAddress: usdcMint.publicKey })
+      .rpc()
+    }
+    catch(error: any)
+    {
+      assetCategoryErrorMessage = error.error.errorMessage
+    }
+
+    assert(assetCategoryErrorMessage == errors.timelockRequiredErrorMsg)
+  })
+
   async function airDropSol(walletPublicKey: PublicKey)
   {
     let token_airdrop = await program.provider.connection.requestAirdrop(walletPublicKey, 
@@ -3622,14 +4470,15 @@ describe("lending_protocol", () =>
     await timeOutFunction(1)
   }
 
-  async function generateOracleTransactionAndRemainingPriceAccount(payload: PriceDataPayload, lendingUserAdress: PublicKey): Promise<[Transaction, AccountMeta]>
+  //The payload's slot is stamped from the cluster's current slot, plus slotOffset so staleness tests can post prices from the past (negative) or the future (positive)
+  async function generateOracleTransactionAndRemainingPriceAccount(payload: PriceDataPayload, lendingUserAdress: PublicKey, slotOffset: number = 0): Promise<[Transaction, AccountMeta]>
   {
     let transaction = new Transaction
     const latestBlockhash = await program.provider.connection.getLatestBlockhash()
     transaction.recentBlockhash = latestBlockhash.blockhash
     transaction.feePayer = priceValidatorKeypair.publicKey
 
-    payload.slot = new anchor.BN(await program.provider.connection.getSlot())
+    payload.slot = new anchor.BN(await program.provider.connection.getSlot() + slotOffset)
 
     const createTempOraclePriceDataInstruction = await program.methods.createTempOraclePriceData(payload)
       .accounts({ lendingUserAddress: lendingUserAdress, signer: priceValidatorKeypair.publicKey })
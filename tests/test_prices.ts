@@ -1,81 +1,78 @@
 import * as anchor from "@coral-xyz/anchor"
 import { runInsolventTest } from "./test_settings"
-import type { PriceDataPayload } from "./types"
+import type { PriceDataPayload, TestPrice } from "./types"
 
-export const solTestPriceDataPayload: PriceDataPayload = 
+//Builds an Oracle price payload from typed prices, normalizing each price * 10^exponent to the program's 18 decimal layout.
+//The slot is stamped when the Oracle transaction is generated
+export function getTestPriceDataPayload(prices: TestPrice[]): PriceDataPayload
 {
-  data: 
-  [{
-    tokenId: 1,
-    normalizedPrice18Decimals: new anchor.BN("100000000000000000000") //$100.00 USD
-  }],
-  slot: new anchor.BN(0)
-}
+  return {
+    data: prices.map(testPrice =>
+    {
+      if(testPrice.exponent > 0 || testPrice.exponent < -18)
+        throw new Error(`Test price exponent ${testPrice.exponent} for Token ID ${testPrice.tokenId} has to be between -18 and 0`)
 
-export const solAndUSDCTestPriceDataPayload: PriceDataPayload = 
-{
-  data: 
-  [{
-    tokenId: 1,
-    normalizedPrice18Decimals: new anchor.BN("100000000000000000000") //$100.00 USD
-  },
-  {
-    tokenId: 2,
-    normalizedPrice18Decimals: new anchor.BN("1000000000000000000") //$1.00 USD
-  }],
-  slot: new anchor.BN(0)
+      return {
+        tokenId: testPrice.tokenId,
+        normalizedPrice18Decimals: new anchor.BN(testPrice.price).mul(new anchor.BN(10).pow(new anchor.BN(18 + testPrice.exponent)))
+      }
+    }),
+    slot: new anchor.BN(0)
+  }
 }
 
-export const solCantLiquidatePriceDataPayload: PriceDataPayload =
-{
-  data: 
-  [{
-    tokenId: 1,
-    normalizedPrice18Decimals: new anchor.BN("875000001000000000000") //$875.000001 USD
-  }],
-  slot: new anchor.BN(0)
-}
+export const solTestPriceDataPayload: PriceDataPayload = getTestPriceDataPayload(
+[
+  { tokenId: 1, price: 10000, exponent: -2 } //$100.00 USD
+])
+
+export const solAndUSDCTestPriceDataPayload: PriceDataPayload = getTestPriceDataPayload(
+[
+  { tokenId: 1, price: 10000, exponent: -2 }, //$100.00 USD
+  { tokenId: 2, price: 100, exponent: -2 } //$1.00 USD
+])
+
+export const solCantLiquidatePriceDataPayload: PriceDataPayload = getTestPriceDataPayload(
+[
+  { tokenId: 1, price: 875000001, exponent: -6 } //$875.000001 USD
+])
 
 export var solLiquidatePriceWithUSDCDataPayload: PriceDataPayload
 if(!runInsolventTest)
 {
-  solLiquidatePriceWithUSDCDataPayload =
-  {
-    data: 
-    [{
-      tokenId: 1,
-      normalizedPrice18Decimals: new anchor.BN("87500000000000000000") //$87.50 USD
-    },
-    {
-      tokenId: 2,
-      normalizedPrice18Decimals: new anchor.BN("1000000000000000000") //$1.00 USD
-    }],
-    slot: new anchor.BN(0)
-  }
+  solLiquidatePriceWithUSDCDataPayload = getTestPriceDataPayload(
+  [
+    { tokenId: 1, price: 8750, exponent: -2 }, //$87.50 USD
+    { tokenId: 2, price: 100, exponent: -2 } //$1.00 USD
+  ])
 }
 else
 {
-  solLiquidatePriceWithUSDCDataPayload =
-  {
-    data: 
-    [{
-      tokenId: 1,
-      normalizedPrice18Decimals: new anchor.BN("70000000000000000000") //$70.00 USD
-    },
-    {
-      tokenId: 2,
-      normalizedPrice18Decimals: new anchor.BN("1000000000000000000") //$1.00 USD
-    }],
-    slot: new anchor.BN(0)
-  }
+  solLiquidatePriceWithUSDCDataPayload = getTestPriceDataPayload(
+  [
+    { tokenId: 1, price: 7000, exponent: -2 }, //$70.00 USD
+    { tokenId: 2, price: 100, exponent: -2 } //$1.00 USD
+  ])
 }
 
-export const usdcTestPriceDataPayload: PriceDataPayload = 
+export const usdcTestPriceDataPayload: PriceDataPayload = getTestPriceDataPayload(
+[
+  { tokenId: 2, price: 100, exponent: -2 } //$1.00 USD
+])
+
+export const solTestTokenId = 1
+export const usdcTestTokenId = 2
+export const volatileTestTokenId = 3
+
+//SOL, USDC, and a volatile token priced in one payload for multi asset health tests. Pass overrides to move any of them, ie a crash in the volatile token
+export function getMultiAssetTestPriceDataPayload(overrides: TestPrice[] = []): PriceDataPayload
 {
-  data: 
-  [{
-    tokenId: 2,
-    normalizedPrice18Decimals: new anchor.BN("1000000000000000000") //$1.00 USD
-  }],
-  slot: new anchor.BN(0)
+  const defaultPrices: TestPrice[] =
+  [
+    { tokenId: solTestTokenId, price: 10000, exponent: -2 }, //$100.00 USD
+    { tokenId: usdcTestTokenId, price: 100, exponent: -2 }, //$1.00 USD
+    { tokenId: volatileTestTokenId, price: 25, exponent: -4 } //$0.0025 USD
+  ]
+
+  return getTestPriceDataPayload(defaultPrices.map(defaultPrice => overrides.find(override => override.tokenId == defaultPrice.tokenId) ?? defaultPrice))
 }
\ No newline at end of file
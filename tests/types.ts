@@ -10,4 +10,11 @@ export type VerifiedPriceData =
 {
   tokenId: number; //u8
   normalizedPrice18Decimals: anchor.BN //u128
+}
+
+export type TestPrice =
+{
+  tokenId: number; //u8
+  price: number | string; //Price mantissa, ie 10000 with an exponent of -2 is $100.00
+  exponent: number //Between -18 and 0
 }
\ No newline at end of file